@@ -50,6 +50,13 @@ function SiteDetail() {
                 <div className="grid grid-cols-1 sm:grid-cols-[160px_1fr] text-sm">
                     <Row label="Site ID" value={site.site_id} mono />
                     {site.module_id && <Row label="Module ID" value={site.module_id} mono />}
+                    <Row label="Admin">
+                        {site.admin ? (
+                            <HashLink hash={site.admin} to={`/account/${site.admin}`} />
+                        ) : (
+                            <span className="text-blocker-text-muted">None (immutable)</span>
+                        )}
+                    </Row>
                     <Row label="Deploy Tx">
                         <HashLink hash={site.deploy_tx} to={`/tx/${site.deploy_tx}`} />
                     </Row>
@@ -79,6 +86,43 @@ function SiteDetail() {
                 </div>
             </div>
 
+            {/* Upgrade history */}
+            {site.upgrades.length > 0 && (
+                <div className="bg-blocker-surface border border-blocker-border rounded-lg overflow-x-auto mb-6">
+                    <div className="px-4 py-3 border-b border-blocker-border">
+                        <h2 className="text-sm font-medium text-blocker-text-primary">Upgrades ({site.upgrades.length})</h2>
+                    </div>
+                    <table className="w-full text-sm">
+                        <thead>
+                            <tr className="text-blocker-text-muted text-xs uppercase tracking-wider border-b border-blocker-border">
+                                <th className="text-left px-2 sm:px-4 py-2.5 font-medium">Tx Hash</th>
+                                <th className="text-left px-2 sm:px-4 py-2.5 font-medium hidden sm:table-cell">From Module</th>
+                                <th className="text-left px-2 sm:px-4 py-2.5 font-medium">To Module</th>
+                                <th className="text-right px-2 sm:px-4 py-2.5 font-medium">Block</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {[...site.upgrades].reverse().map((u) => (
+                                <tr key={u.tx_hash} className="border-b border-blocker-border last:border-0 hover:bg-blocker-surface-hover">
+                                    <td className="px-2 sm:px-4 py-2.5">
+                                        <HashLink hash={u.tx_hash} to={`/tx/${u.tx_hash}`} truncate={6} />
+                                    </td>
+                                    <td className="px-2 sm:px-4 py-2.5 font-mono text-xs hidden sm:table-cell">
+                                        {u.previous_module_id ? truncateHash(u.previous_module_id, 6) : <span className="text-blocker-text-muted">-</span>}
+                                    </td>
+                                    <td className="px-2 sm:px-4 py-2.5 font-mono text-xs">{truncateHash(u.module_id, 6)}</td>
+                                    <td className="px-2 sm:px-4 py-2.5 text-right">
+                                        <Link to={`/block/${u.block_height}`} className="text-blocker-accent hover:text-blocker-accent-hover">
+                                            {Number(u.block_height)}
+                                        </Link>
+                                    </td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </div>
+            )}
+
             {/* Transaction list */}
             <div className="bg-blocker-surface border border-blocker-border rounded-lg overflow-x-auto">
                 <div className="px-4 py-3 border-b border-blocker-border">
@@ -38,6 +38,14 @@ function TxDetail() {
                     <Row label="Type">
                         <span className={txTypeColor(tx.tx_type)}>{tx.tx_type}</span>
                     </Row>
+                    <Row label="Status">
+                        <span className={tx.success ? 'text-green-400' : 'text-red-400'}>
+                            {tx.success ? 'Success' : 'Failed'}
+                        </span>
+                    </Row>
+                    {tx.error && <Row label="Error" value={tx.error} mono />}
+                    <Row label="Fuel Used" value={String(tx.fuel_used)} />
+                    <Row label="KV Bytes Written" value={String(tx.kv_bytes_written)} />
                     {tx.function_sig && (
                         <Row label="Function signature" value={tx.function_sig} mono />
                     )}
@@ -51,6 +59,7 @@ function TxDetail() {
                             <HashLink hash={tx.target_site} to={`/site/${tx.target_site}`} />
                         </Row>
                     )}
+                    <Row label="Version" value={String(tx.version)} />
                     <Row label="Nonce" value={tx.nonce} mono />
                     <Row label="Recent Block Height" value={String(tx.recent_block_height)} />
                 </div>
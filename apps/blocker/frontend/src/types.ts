@@ -23,9 +23,35 @@ export interface TxDetail {
     sender: string | null;
     tx_type: string;
     target_site: string | null;
+    version: number;
     nonce: string;
     recent_block_height: number;
     function_sig: string | null;
+    success: boolean;
+    error: string | null;
+    fuel_used: number;
+    kv_bytes_written: number;
+    host_calls: HostCallCounts;
+}
+
+export interface HostCallCounts {
+    kv_insert: number;
+    kv_get: number;
+    kv_scan: number;
+    log: number;
+    register_static_route: number;
+    message_sender: number;
+    block_time: number;
+    call_site: number;
+    emit_event: number;
+    storage_usage: number;
+    random_seed: number;
+    block_height: number;
+    previous_block_hash: number;
+    tx_hash: number;
+    site_id: number;
+    module_id: number;
+    precompiles: number;
 }
 
 export interface SiteDetail {
@@ -35,6 +61,15 @@ export interface SiteDetail {
     block_height: number;
     domain: string | null;
     tx_count: number;
+    admin: string | null;
+    upgrades: SiteUpgrade[];
+}
+
+export interface SiteUpgrade {
+    tx_hash: string;
+    block_height: number;
+    previous_module_id: string | null;
+    module_id: string;
 }
 
 export interface DomainInfo {
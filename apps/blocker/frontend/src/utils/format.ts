@@ -27,6 +27,8 @@ export function txTypeColor(txType: string): string {
         case 'DeployStoredModule': return 'text-green-400';
         case 'RegisterDomain': return 'text-amber-400';
         case 'AddModule': return 'text-cyan-400';
+        case 'Evidence': return 'text-red-400';
+        case 'UpgradeSite': return 'text-violet-400';
         default: return 'text-blocker-text-secondary';
     }
 }
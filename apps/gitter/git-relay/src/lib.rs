@@ -28,7 +28,8 @@ pub async fn run(relay_key_path: PathBuf) -> Result<()> {
         let keystore_path = tmp.path().join("keystore.bin");
         let mut node = tokio::spawn(async move {
             let _tmp = tmp;
-            vastrum_node::start_node_production(keystore_path, true).await
+            vastrum_node::start_node_production(keystore_path, true, RoundTimeoutConfig::default())
+                .await
         });
         tokio::select! {
             _ = wait_for_rpc_server() => {
@@ -108,5 +109,6 @@ use std::time::Duration;
 use vastrum_git_lib::ContractAbiClient;
 use vastrum_git_lib::config::GITTER_DOMAIN;
 use vastrum_native_lib::NativeHttpClient;
+use vastrum_node::consensus::round_timer::RoundTimeoutConfig;
 use vastrum_shared_types::crypto::ed25519;
 use vastrum_shared_types::ports::HTTP_RPC_PORT;
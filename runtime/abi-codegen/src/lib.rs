@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use vastrum_runtime_shared::calculate_function_selector;
+use vastrum_runtime_shared::{calculate_event_topic, calculate_function_selector};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use syn::{
@@ -34,7 +34,7 @@ pub enum AbiType {
     BTreeSet(Box<AbiType>),
 
     // KV types (Vastrum-specific)
-    KvMap { key: Box<AbiType>, value: Box<AbiType> },
+    KvMap { key: Box<AbiType>, value: Box<AbiType>, iterable: bool },
     KvVec { elem: Box<AbiType> },
     KvBTree { key: Box<AbiType>, value: Box<AbiType> },
     KvVecBTree { sort: Box<AbiType>, value: Box<AbiType> },
@@ -130,10 +130,14 @@ impl AbiType {
                 let inner_tokens = inner.to_tokens();
                 quote! { ::std::collections::BTreeSet<#inner_tokens> }
             }
-            AbiType::KvMap { key, value } => {
+            AbiType::KvMap { key, value, iterable } => {
                 let k = key.to_tokens();
                 let v = value.to_tokens();
-                quote! { KvMap<#k, #v> }
+                if *iterable {
+                    quote! { KvMap<#k, #v, Iterable> }
+                } else {
+                    quote! { KvMap<#k, #v> }
+                }
             }
             AbiType::KvVec { elem } => {
                 let e = elem.to_tokens();
@@ -184,10 +188,9 @@ impl AbiType {
     /// Convert AbiType to a native type TokenStream (for state reading)
     pub fn to_native_tokens(&self) -> TokenStream {
         match self {
-            AbiType::KvMap { key, value } => {
-                let k = key.to_tokens();
-                let v = value.to_tokens();
-                quote! { vastrum_abi::__private::vastrum_native_types::KvMap<#k, #v> }
+            AbiType::KvMap { key, value, iterable } => {
+                let generics = native_kvmap_generics(key, value, *iterable);
+                quote! { vastrum_abi::__private::vastrum_native_types::KvMap #generics }
             }
             AbiType::KvVec { elem } => {
                 let e = elem.to_tokens();
@@ -270,10 +273,17 @@ pub fn syn_type_to_abi_type(ty: &Type) -> Result<AbiType, std::string::String> {
                         "BTreeSet" if generics.len() == 1 => {
                             Ok(AbiType::BTreeSet(Box::new(generics[0].clone())))
                         }
-                        "KvMap" if generics.len() == 2 => Ok(AbiType::KvMap {
-                            key: Box::new(generics[0].clone()),
-                            value: Box::new(generics[1].clone()),
-                        }),
+                        "KvMap" if generics.len() == 2 || generics.len() == 3 => {
+                            let iterable = matches!(
+                                generics.get(2),
+                                Some(AbiType::Defined { name, .. }) if name == "Iterable"
+                            );
+                            Ok(AbiType::KvMap {
+                                key: Box::new(generics[0].clone()),
+                                value: Box::new(generics[1].clone()),
+                                iterable,
+                            })
+                        }
                         "KvVec" if generics.len() == 1 => {
                             Ok(AbiType::KvVec { elem: Box::new(generics[0].clone()) })
                         }
@@ -344,6 +354,7 @@ pub struct ContractInfo {
     pub constructor: ConstructorInfo,
     pub methods: Vec<MethodInfo>,
     pub custom_types: Vec<TypeDef>,
+    pub events: Vec<EventInfo>,
 }
 
 /// Field with name and type
@@ -360,6 +371,19 @@ pub struct MethodInfo {
     pub params: Vec<FieldInfo>,
     pub is_public: bool,
     pub requires_auth: bool,
+    /// Read only method, the client simulates it instead of sending a transaction
+    pub is_view: bool,
+    /// Unit methods return the empty tuple
+    pub returns: AbiType,
+}
+
+/// Contract event struct
+#[derive(Debug, Clone)]
+pub struct EventInfo {
+    pub name: std::string::String,
+    pub fields: Vec<FieldInfo>,
+    /// Fields marked #[topic], events can be queried by their value
+    pub topic_fields: Vec<FieldInfo>,
 }
 
 /// User-defined type (struct or enum) from source
@@ -373,17 +397,20 @@ pub struct ContractDef {
     pub state: ItemStruct,
     pub methods: ItemImpl,
     pub custom_types: Vec<UserType>,
+    pub events: Vec<ItemStruct>,
 }
 
 /// Collected types from directory
 struct CollectedTypes {
     structs: HashMap<std::string::String, ItemStruct>,
     enums: HashMap<std::string::String, ItemEnum>,
+    events: Vec<ItemStruct>,
 }
 
 /// Recursively collect all structs and enums from .rs files in a directory
 fn collect_types_from_dir(dir: &Path) -> CollectedTypes {
-    let mut collected = CollectedTypes { structs: HashMap::new(), enums: HashMap::new() };
+    let mut collected =
+        CollectedTypes { structs: HashMap::new(), enums: HashMap::new(), events: Vec::new() };
     collect_types_recursive(dir, &mut collected);
     collected
 }
@@ -401,7 +428,9 @@ fn collect_types_recursive(dir: &Path, collected: &mut CollectedTypes) {
                     for item in file.items {
                         match item {
                             syn::Item::Struct(s) => {
-                                if !has_attribute(&s.attrs, "contract_state") {
+                                if has_attribute(&s.attrs, "contract_event") {
+                                    collected.events.push(s);
+                                } else if !has_attribute(&s.attrs, "contract_state") {
                                     collected.structs.insert(s.ident.to_string(), s);
                                 }
                             }
@@ -441,10 +470,12 @@ pub fn parse_contract_source(src_dir: &Path) -> Result<ContractDef, std::string:
 
     let state = state_struct.ok_or("No #[contract_state] found")?;
     let methods = methods_impl.ok_or("No #[contract_methods] found")?;
-    let collected = collect_types_from_dir(src_dir);
+    let mut collected = collect_types_from_dir(src_dir);
     let custom_types = extract_referenced_user_types(&state, &methods, &collected);
+    //sorted so the generated abi does not depend on directory order
+    collected.events.sort_by_key(|e| e.ident.to_string());
 
-    Ok(ContractDef { state, methods, custom_types })
+    Ok(ContractDef { state, methods, custom_types, events: collected.events })
 }
 
 /// Check if an attribute list contains a specific attribute
@@ -452,6 +483,16 @@ fn has_attribute(attrs: &[Attribute], name: &str) -> bool {
     attrs.iter().any(|a| a.path().is_ident(name))
 }
 
+/// Generic arguments of a native KvMap, the key storage marker is only spelled out for iterable maps
+fn native_kvmap_generics(key: &AbiType, value: &AbiType, iterable: bool) -> TokenStream {
+    let k = key.to_tokens();
+    let v = value.to_tokens();
+    if iterable {
+        return quote! { <#k, #v, vastrum_abi::__private::vastrum_native_types::Iterable> };
+    }
+    return quote! { <#k, #v> };
+}
+
 /// Known built-in types that should not be considered custom types
 const KNOWN_TYPES: &[&str] = &[
     "String",
@@ -460,6 +501,8 @@ const KNOWN_TYPES: &[&str] = &[
     "BTreeMap",
     "BTreeSet",
     "KvMap",
+    "Hashed",
+    "Iterable",
     "KvVec",
     "KvBTree",
     "KvVecBTree",
@@ -510,6 +553,11 @@ fn extract_referenced_user_types(
         }
     }
 
+    // Event fields are decoded by the client too
+    for event in &collected.events {
+        collect_type_names_from_struct_fields(event, &mut referenced);
+    }
+
     // Recursively collect nested types from custom structs and enums
     loop {
         let mut new_types = HashSet::new();
@@ -659,13 +707,25 @@ pub fn contract_def_to_info(def: &ContractDef) -> ContractInfo {
 
             if has_attribute(&method.attrs, "constructor") {
                 constructor = Some(ConstructorInfo { params });
+            } else if has_attribute(&method.attrs, "migrate") {
+                //only run by the node on upgrade, not callable by clients
+                continue;
             } else {
                 let requires_auth = has_attribute(&method.attrs, "authenticated");
+                let is_view = has_attribute(&method.attrs, "view");
+                let returns = match &method.sig.output {
+                    ReturnType::Default => AbiType::Tuple(vec![]),
+                    ReturnType::Type(_, ty) => syn_type_to_abi_type(ty).unwrap_or_else(|e| {
+                        panic!("Failed to parse return type of method '{}': {}", method_name, e)
+                    }),
+                };
                 methods.push(MethodInfo {
                     name: method_name,
                     params,
                     is_public: matches!(method.vis, Visibility::Public(_)),
                     requires_auth,
+                    is_view,
+                    returns,
                 });
             }
         }
@@ -680,7 +740,29 @@ pub fn contract_def_to_info(def: &ContractDef) -> ContractInfo {
         custom_types.push(user_type_to_type_def(ut));
     }
 
-    ContractInfo { state_name, state_fields, constructor, methods, custom_types }
+    let mut events = Vec::new();
+    for event in &def.events {
+        events.push(event_to_event_info(event));
+    }
+
+    ContractInfo { state_name, state_fields, constructor, methods, custom_types, events }
+}
+
+/// Convert a #[contract_event] struct to EventInfo
+fn event_to_event_info(event: &ItemStruct) -> EventInfo {
+    let name = event.ident.to_string();
+    let DefinedFields::Named(fields) = syn_fields_to_defined_fields(&event.fields) else {
+        panic!("contract_event '{}' requires named fields", name);
+    };
+    let mut topic_fields = Vec::new();
+    if let syn::Fields::Named(named) = &event.fields {
+        for (field, info) in named.named.iter().zip(&fields) {
+            if has_attribute(&field.attrs, "topic") {
+                topic_fields.push(info.clone());
+            }
+        }
+    }
+    EventInfo { name, fields, topic_fields }
 }
 
 /// Convert UserType (syn) to TypeDef
@@ -753,6 +835,7 @@ fn syn_fields_to_defined_fields(fields: &syn::Fields) -> DefinedFields {
 /// Generate ABI client code from ContractInfo
 fn generate_abi_code_from_info(contract: &ContractInfo) -> TokenStream {
     let custom_types_code = generate_custom_types_from_info(&contract.custom_types);
+    let events_code = generate_events_from_info(&contract.events);
     let state_code = generate_field_getters_from_info(&contract.state_name, &contract.state_fields);
     let client_code = generate_client_code(contract);
 
@@ -761,6 +844,7 @@ fn generate_abi_code_from_info(contract: &ContractInfo) -> TokenStream {
         pub use vastrum_abi::__private::vastrum_rpc_client::{RpcProvider, SentTxBehavior};
 
         #custom_types_code
+        #events_code
         #state_code
         #client_code
     }
@@ -783,6 +867,55 @@ fn generate_custom_types_from_info(custom_types: &[TypeDef]) -> TokenStream {
     quote! { #(#type_defs)* }
 }
 
+/// Generate event structs with their topic and a decoder for contract events
+fn generate_events_from_info(events: &[EventInfo]) -> TokenStream {
+    let sha256_digest =
+        quote! { vastrum_abi::__private::vastrum_shared_types::crypto::sha256::Sha256Digest };
+    let mut event_defs = Vec::new();
+
+    for event in events {
+        let ident = format_ident!("{}", event.name);
+        let struct_def = generate_struct_def(&ident, &DefinedFields::Named(event.fields.clone()));
+        let topic = calculate_event_topic(&event.name);
+
+        let mut topic_fns = Vec::new();
+        for f in &event.topic_fields {
+            let fn_name = format_ident!("{}_topic", f.name);
+            let ty = f.ty.to_tokens();
+            topic_fns.push(quote! {
+                /// Topic of events with this field value
+                pub fn #fn_name(value: &#ty) -> #sha256_digest {
+                    vastrum_abi::__private::vastrum_shared_types::crypto::sha256::sha256_hash(
+                        &vastrum_abi::__private::borsh::to_vec(value).unwrap()
+                    )
+                }
+            });
+        }
+
+        event_defs.push(quote! {
+            #struct_def
+
+            impl #ident {
+                pub const TOPIC: [u8; 32] = [#(#topic),*];
+
+                /// Decodes a contract event emitted as this event
+                pub fn decode_event(
+                    event: &vastrum_abi::__private::vastrum_shared_types::types::execution::event::ContractEvent
+                ) -> Option<Self> {
+                    if event.topics.first()?.to_bytes() != Self::TOPIC {
+                        return None;
+                    }
+                    vastrum_abi::__private::borsh::from_slice(&event.data).ok()
+                }
+
+                #(#topic_fns)*
+            }
+        });
+    }
+
+    quote! { #(#event_defs)* }
+}
+
 /// Generate a struct definition
 fn generate_struct_def(ident: &proc_macro2::Ident, fields: &DefinedFields) -> TokenStream {
     match fields {
@@ -904,6 +1037,7 @@ fn generate_client_code(contract: &ContractInfo) -> TokenStream {
         }
     }
     let method_impls = generate_async_methods_from_info(&pub_methods);
+    let event_methods = generate_event_methods_from_info(&contract.events);
     let deploy_method = generate_deploy_method(&contract.constructor);
 
     quote! {
@@ -935,6 +1069,8 @@ fn generate_client_code(contract: &ContractInfo) -> TokenStream {
             #deploy_method
 
             #method_impls
+
+            #event_methods
         }
     }
 }
@@ -1026,6 +1162,11 @@ fn generate_async_methods_from_info(methods: &[&MethodInfo]) -> TokenStream {
             }
         };
 
+        if method.is_view {
+            impls.push(generate_view_method(method, &signature_params, &calldata_build));
+            continue;
+        }
+
         let call_expr = if method.requires_auth {
             quote! { self.client.make_authenticated_call(calldata).await }
         } else {
@@ -1045,6 +1186,122 @@ fn generate_async_methods_from_info(methods: &[&MethodInfo]) -> TokenStream {
     quote! { #(#impls)* }
 }
 
+/// Generates a view method that simulates the call and decodes what it returned
+/// Methods returning Result<T, E> give Ok(Err(E)) when the method returned Err
+fn generate_view_method(
+    method: &MethodInfo,
+    signature_params: &[TokenStream],
+    calldata_build: &TokenStream,
+) -> TokenStream {
+    let method_name = format_ident!("{}", method.name);
+    let rpc_error = quote! { vastrum_abi::__private::vastrum_rpc_client::RpcError };
+    let tx_outcome = quote! { vastrum_abi::__private::vastrum_shared_types::types::execution::receipt::TxOutcome };
+
+    let (return_type, decode) = match &method.returns {
+        AbiType::Defined { name, generics } if name == "Result" && generics.len() == 2 => {
+            let ok_ty = generics[0].to_tokens();
+            let err_ty = generics[1].to_tokens();
+            let decode = quote! {
+                if response.reverted {
+                    let error = vastrum_abi::__private::borsh::from_slice::<#err_ty>(&response.return_data)
+                        .map_err(|e| #rpc_error(e.to_string()))?;
+                    return Ok(Err(error));
+                }
+                match response.outcome {
+                    #tx_outcome::Success => vastrum_abi::__private::borsh::from_slice::<#ok_ty>(&response.return_data)
+                        .map(Ok)
+                        .map_err(|e| #rpc_error(e.to_string())),
+                    #tx_outcome::Failure { message } => Err(#rpc_error(message)),
+                }
+            };
+            (quote! { Result<#ok_ty, #err_ty> }, decode)
+        }
+        returns => {
+            let ty = returns.to_tokens();
+            let decode = quote! {
+                match response.outcome {
+                    #tx_outcome::Success => vastrum_abi::__private::borsh::from_slice::<#ty>(&response.return_data)
+                        .map_err(|e| #rpc_error(e.to_string())),
+                    #tx_outcome::Failure { message } => Err(#rpc_error(message)),
+                }
+            };
+            (ty, decode)
+        }
+    };
+
+    quote! {
+        pub async fn #method_name(&self, #(#signature_params),*) -> Result<#return_type, #rpc_error> {
+            use vastrum_abi::__private::vastrum_rpc_client::RpcProvider;
+            #calldata_build
+            let response = self.client.simulate_call(calldata).await?;
+            #decode
+        }
+    }
+}
+
+/// Generates methods querying proven events, by event type and by each #[topic] field
+fn generate_event_methods_from_info(events: &[EventInfo]) -> TokenStream {
+    let rpc_error = quote! { vastrum_abi::__private::vastrum_rpc_client::RpcError };
+    let mut impls = Vec::new();
+
+    for event in events {
+        let ident = format_ident!("{}", event.name);
+        let snake_name = to_snake_case(&event.name);
+        let decode = quote! {
+            let events = self.client.get_events(topic, from_height, to_height).await?;
+            Ok(events.iter().filter_map(|e| Some((e.height, #ident::decode_event(&e.event)?))).collect())
+        };
+
+        let method_name = format_ident!("{}_events", snake_name);
+        impls.push(quote! {
+            /// Events in from_height..=to_height with the block height, oldest first
+            pub async fn #method_name(&self, from_height: u64, to_height: u64) -> Result<Vec<(u64, #ident)>, #rpc_error> {
+                use vastrum_abi::__private::vastrum_rpc_client::RpcProvider;
+                let topic = vastrum_abi::__private::vastrum_shared_types::crypto::sha256::Sha256Digest::from(#ident::TOPIC);
+                #decode
+            }
+        });
+
+        for f in &event.topic_fields {
+            let method_name = format_ident!("{}_events_by_{}", snake_name, f.name);
+            let topic_fn = format_ident!("{}_topic", f.name);
+            let field_name = format_ident!("{}", f.name);
+            let (param, value) = if matches!(f.ty, AbiType::String) {
+                (quote! { #field_name: impl Into<String> }, quote! { &#field_name.into() })
+            } else {
+                let ty = f.ty.to_tokens();
+                (quote! { #field_name: #ty }, quote! { &#field_name })
+            };
+            impls.push(quote! {
+                /// Events in from_height..=to_height with this field value, oldest first
+                pub async fn #method_name(&self, #param, from_height: u64, to_height: u64) -> Result<Vec<(u64, #ident)>, #rpc_error> {
+                    use vastrum_abi::__private::vastrum_rpc_client::RpcProvider;
+                    let topic = #ident::#topic_fn(#value);
+                    #decode
+                }
+            });
+        }
+    }
+
+    quote! { #(#impls)* }
+}
+
+/// Converts PascalCase to snake_case
+fn to_snake_case(s: &str) -> std::string::String {
+    let mut result = std::string::String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
 /// Generates state struct and reader using typed field info
 fn generate_field_getters_from_info(struct_name: &str, fields: &[FieldInfo]) -> TokenStream {
     let native_struct_name = format_ident!("Native{}", struct_name);
@@ -1067,12 +1324,11 @@ fn generate_field_getters_from_info(struct_name: &str, fields: &[FieldInfo]) ->
 
         // Deserialization type and construction based on AbiType
         match &field.ty {
-            AbiType::KvMap { key, value } => {
+            AbiType::KvMap { key, value, iterable } => {
                 field_types.push(quote! { u64 });
-                let k = key.to_tokens();
-                let v = value.to_tokens();
+                let generics = native_kvmap_generics(key, value, *iterable);
                 field_constructions.push(quote! {
-                    #field_name: vastrum_abi::__private::vastrum_native_types::KvMap::<#k, #v>::new(#tuple_field, client.clone())
+                    #field_name: vastrum_abi::__private::vastrum_native_types::KvMap:: #generics ::new(#tuple_field, client.clone())
                 });
             }
             AbiType::KvVec { elem } => {
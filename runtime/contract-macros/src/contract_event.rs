@@ -0,0 +1,60 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ItemStruct;
+use vastrum_runtime_shared::calculate_event_topic;
+
+//the event name is always the first topic, MAX_EVENT_TOPICS is 4
+const MAX_TOPIC_FIELDS: usize = 3;
+
+pub fn generate_event(item: proc_macro::TokenStream) -> TokenStream2 {
+    let mut input_struct: ItemStruct = match syn::parse(item) {
+        Ok(s) => s,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let syn::Fields::Named(fields) = &mut input_struct.fields else {
+        return syn::Error::new_spanned(
+            &input_struct,
+            "contract_event requires a struct with named fields",
+        )
+        .to_compile_error();
+    };
+
+    // Strip #[topic] markers, they are not real attributes
+    let mut topic_fields = Vec::new();
+    for field in fields.named.iter_mut() {
+        let is_topic = field.attrs.iter().any(|a| a.path().is_ident("topic"));
+        if is_topic {
+            field.attrs.retain(|a| !a.path().is_ident("topic"));
+            topic_fields.push(field.ident.clone().unwrap());
+        }
+    }
+    if topic_fields.len() > MAX_TOPIC_FIELDS {
+        return syn::Error::new_spanned(
+            &input_struct,
+            format!("contract_event supports at most {MAX_TOPIC_FIELDS} #[topic] fields"),
+        )
+        .to_compile_error();
+    }
+
+    let struct_name = &input_struct.ident;
+    let topic = calculate_event_topic(&struct_name.to_string());
+
+    quote! {
+        #[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone)]
+        #input_struct
+
+        impl #struct_name {
+            pub const TOPIC: [u8; 32] = [#(#topic),*];
+
+            /// Emit the event, indexed under its name and the hash of each #[topic] field
+            pub fn emit(&self) {
+                let topics = [
+                    Self::TOPIC,
+                    #(vastrum_runtime_lib::runtime::sha256(&borsh::to_vec(&self.#topic_fields).unwrap())),*
+                ];
+                vastrum_runtime_lib::runtime::emit_event(&topics, &borsh::to_vec(self).unwrap());
+            }
+        }
+    }
+}
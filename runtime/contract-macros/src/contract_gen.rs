@@ -2,13 +2,39 @@ use crate::utils::{calculate_function_selector, to_pascal_case};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::{FnArg, ImplItem, ItemImpl, ItemStruct, Pat, Type, Visibility};
+use syn::{FnArg, ImplItem, ItemImpl, ItemStruct, Pat, ReturnType, Type, Visibility};
 
 /// Parsed method information for contract code generation
 struct ParsedMethod {
     method_name: syn::Ident,
     param_fields: Vec<TokenStream2>,
     param_names: Vec<syn::Ident>,
+    returns: MethodReturn,
+    is_view: bool,
+}
+
+/// How the generated entry point handles what a method returns
+enum MethodReturn {
+    Unit,
+    /// Value is borsh encoded as return data
+    Value,
+    /// Ok is borsh encoded as return data, Err reverts with the borsh encoded error
+    Result,
+}
+
+fn parse_method_return(output: &ReturnType) -> MethodReturn {
+    let ReturnType::Type(_, ty) = output else {
+        return MethodReturn::Unit;
+    };
+    match &**ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => MethodReturn::Unit,
+        Type::Path(type_path)
+            if type_path.path.segments.last().is_some_and(|s| s.ident == "Result") =>
+        {
+            MethodReturn::Result
+        }
+        _ => MethodReturn::Value,
+    }
 }
 
 pub fn generate_state(item: TokenStream) -> TokenStream2 {
@@ -75,10 +101,14 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
     // Parse and validate functions and constructor
     let mut parsed_methods = Vec::new();
     let mut parsed_constructor: Option<ParsedMethod> = None;
+    let mut parsed_migrate: Option<ParsedMethod> = None;
 
     for method in &pub_methods {
         let method_name = method.sig.ident.clone();
+        let returns = parse_method_return(&method.sig.output);
         let is_constructor = method.attrs.iter().any(|a| a.path().is_ident("constructor"));
+        let is_migrate = method.attrs.iter().any(|a| a.path().is_ident("migrate"));
+        let is_view = method.attrs.iter().any(|a| a.path().is_ident("view"));
 
         let mut has_self = false;
         let mut param_fields = Vec::new();
@@ -86,7 +116,14 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
 
         for arg in &method.sig.inputs {
             match arg {
-                FnArg::Receiver(_) => {
+                FnArg::Receiver(receiver) => {
+                    if is_view && (receiver.reference.is_none() || receiver.mutability.is_some()) {
+                        return syn::Error::new_spanned(
+                            receiver,
+                            "#[view] methods must take &self",
+                        )
+                        .to_compile_error();
+                    }
                     has_self = true;
                 }
                 FnArg::Typed(pat_type) => {
@@ -124,7 +161,25 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
                 )
                 .to_compile_error();
             }
-            parsed_constructor = Some(ParsedMethod { method_name, param_fields, param_names });
+            parsed_constructor =
+                Some(ParsedMethod { method_name, param_fields, param_names, returns, is_view });
+        } else if is_migrate {
+            if has_self {
+                return syn::Error::new_spanned(
+                    &method.sig,
+                    "migrate must be an associated function (`fn(...) -> Self`), not take self",
+                )
+                .to_compile_error();
+            }
+            if parsed_migrate.is_some() {
+                return syn::Error::new_spanned(
+                    &input_impl,
+                    "at most one method may have #[migrate]",
+                )
+                .to_compile_error();
+            }
+            parsed_migrate =
+                Some(ParsedMethod { method_name, param_fields, param_names, returns, is_view });
         } else {
             // Regular methods have to take self, reject static methods
             if !has_self {
@@ -135,7 +190,13 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
                 .to_compile_error();
             }
 
-            parsed_methods.push(ParsedMethod { method_name, param_fields, param_names });
+            parsed_methods.push(ParsedMethod {
+                method_name,
+                param_fields,
+                param_names,
+                returns,
+                is_view,
+            });
         }
     }
 
@@ -163,12 +224,46 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
         };
         param_structs.push(param_struct);
 
+        let call = quote! { contract.#method_name(#(params.#param_names),*) };
+        //the host rejects state changes for the rest of a view, including the sites it calls
+        let enter_view = if method.is_view {
+            quote! { runtime::enter_view(); }
+        } else {
+            quote! {}
+        };
+        //views cannot change state so there is nothing to save
+        let save = if method.is_view {
+            quote! {}
+        } else {
+            quote! { contract.__save(); }
+        };
+        //state is only saved if the method did not return Err
+        let handle_return = match method.returns {
+            MethodReturn::Unit => quote! {
+                #call;
+                #save
+            },
+            MethodReturn::Value => quote! {
+                let value = #call;
+                #save
+                runtime::set_return_data(&borsh::to_vec(&value).unwrap());
+            },
+            MethodReturn::Result => quote! {
+                match #call {
+                    Ok(value) => {
+                        #save
+                        runtime::set_return_data(&borsh::to_vec(&value).unwrap());
+                    }
+                    Err(error) => runtime::revert(&borsh::to_vec(&error).unwrap()),
+                }
+            },
+        };
         let handler_fn = quote! {
             fn #handler_name(params_bytes: &[u8]) {
+                #enter_view
                 let mut contract = #struct_name::__load();
                 let params: #params_struct_name = borsh::from_slice(params_bytes).unwrap();
-                contract.#method_name(#(params.#param_names),*);
-                contract.__save();
+                #handle_return
             }
         };
         handler_fns.push(handler_fn);
@@ -182,44 +277,12 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
 
     // Generate constructor entrypoint
     let constructor_code = if let Some(ctor) = &parsed_constructor {
-        let method_name = &ctor.method_name;
-        let method_name_str = method_name.to_string();
-        let param_fields = &ctor.param_fields;
-        let param_names = &ctor.param_names;
-
-        let params_struct_name =
-            format_ident!("__External{}Params", to_pascal_case(&method_name_str));
-
-        param_structs.push(quote! {
-            #[derive(borsh::BorshDeserialize)]
-            struct #params_struct_name {
-                #(#param_fields),*
-            }
-        });
-
-        if param_names.is_empty() {
-            quote! {
-                #[unsafe(no_mangle)]
-                pub extern "C" fn construct(ptr: *const u8, len: u32) {
-                    __setup_panic_hook();
-                    let contract = #struct_name::#method_name();
-                    contract.__save();
-                }
-            }
-        } else {
-            quote! {
-                #[unsafe(no_mangle)]
-                pub extern "C" fn construct(ptr: *const u8, len: u32) {
-                    __setup_panic_hook();
-                    let params_bytes = if len == 0 { &[] as &[u8] } else {
-                        unsafe { core::slice::from_raw_parts(ptr, len as usize) }
-                    };
-                    let params: #params_struct_name = borsh::from_slice(params_bytes).unwrap();
-                    let contract = #struct_name::#method_name(#(params.#param_names),*);
-                    contract.__save();
-                }
-            }
-        }
+        generate_static_entry_point(
+            &format_ident!("construct"),
+            ctor,
+            &struct_name,
+            &mut param_structs,
+        )
     } else {
         // Auto generate default constructor
         quote! {
@@ -232,6 +295,18 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
         }
     };
 
+    // Generate migrate entrypoint, run when a site is upgraded to this module
+    let migrate_code = if let Some(migrate) = &parsed_migrate {
+        generate_static_entry_point(
+            &format_ident!("migrate"),
+            migrate,
+            &struct_name,
+            &mut param_structs,
+        )
+    } else {
+        quote! {}
+    };
+
     // Generate dispatch function
     let dispatch_fn = quote! {
         fn dispatch(input: &[u8]) {
@@ -275,7 +350,7 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
                     let location = info.location()
                         .map(|l| format!(" at {}:{}:{}", l.file(), l.line(), l.column()))
                         .unwrap_or_default();
-                    runtime::log(&format!("PANIC: {}{}", msg, location));
+                    runtime::panic(&format!("{}{}", msg, location));
                 }));
             });
         }
@@ -308,5 +383,65 @@ pub fn generate_impl(item: TokenStream) -> TokenStream2 {
         #makecall_fn
 
         #constructor_code
+
+        #migrate_code
+    }
+}
+
+/// Entry point for an associated function building the state, e.g. the constructor
+fn generate_static_entry_point(
+    entry_point: &syn::Ident,
+    method: &ParsedMethod,
+    struct_name: &syn::Ident,
+    param_structs: &mut Vec<TokenStream2>,
+) -> TokenStream2 {
+    let method_name = &method.method_name;
+    let method_name_str = method_name.to_string();
+    let param_fields = &method.param_fields;
+    let param_names = &method.param_names;
+
+    let params_struct_name = format_ident!("__External{}Params", to_pascal_case(&method_name_str));
+
+    param_structs.push(quote! {
+        #[derive(borsh::BorshDeserialize)]
+        struct #params_struct_name {
+            #(#param_fields),*
+        }
+    });
+
+    let construct = match method.returns {
+        MethodReturn::Result => quote! {
+            let contract = match #struct_name::#method_name(#(params.#param_names),*) {
+                Ok(contract) => contract,
+                Err(error) => runtime::revert(&borsh::to_vec(&error).unwrap()),
+            };
+        },
+        _ => quote! {
+            let contract = #struct_name::#method_name(#(params.#param_names),*);
+        },
+    };
+
+    if param_names.is_empty() {
+        quote! {
+            #[unsafe(no_mangle)]
+            pub extern "C" fn #entry_point(ptr: *const u8, len: u32) {
+                __setup_panic_hook();
+                #construct
+                contract.__save();
+            }
+        }
+    } else {
+        quote! {
+            #[unsafe(no_mangle)]
+            pub extern "C" fn #entry_point(ptr: *const u8, len: u32) {
+                __setup_panic_hook();
+                let params_bytes = if len == 0 { &[] as &[u8] } else {
+                    unsafe { core::slice::from_raw_parts(ptr, len as usize) }
+                };
+                let params: #params_struct_name = borsh::from_slice(params_bytes).unwrap();
+                #construct
+                contract.__save();
+            }
+        }
     }
 }
@@ -1,3 +1,4 @@
+mod contract_event;
 mod contract_gen;
 mod contract_type;
 mod utils;
@@ -24,6 +25,15 @@ pub fn authenticated(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Marker attribute for read only methods.
+/// View methods take `&self` and never save state, the host traps on any write they make.
+/// The generated ABI client runs them with `simulate_call` and returns the value instead
+/// of sending a transaction.
+#[proc_macro_attribute]
+pub fn view(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
 /// Marker attribute for the constructor method.
 /// The constructor is called once when a new site is deployed
 #[proc_macro_attribute]
@@ -31,9 +41,25 @@ pub fn constructor(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Marker attribute for the migrate method.
+/// Migrate is an associated function returning the new state, it is called once when a site
+/// is upgraded to this module. The old state can be read with `runtime::previous_state`.
+#[proc_macro_attribute]
+pub fn migrate(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
 /// Attribute macro for contract types (structs/enums used in contract state or methods).
 /// Automatically derives BorshSerialize, BorshDeserialize, Clone, and Default.
 #[proc_macro_attribute]
 pub fn contract_type(_attr: TokenStream, item: TokenStream) -> TokenStream {
     contract_type::contract_type(item)
 }
+
+/// Attribute macro for contract events.
+/// Derives BorshSerialize and BorshDeserialize and adds `emit()`. Events are indexed under
+/// the hash of the struct name and of each field marked `#[topic]`, at most three.
+#[proc_macro_attribute]
+pub fn contract_event(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    contract_event::generate_event(item).into()
+}
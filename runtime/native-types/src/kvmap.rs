@@ -1,30 +1,35 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use vastrum_rpc_client::{RpcClient, RpcProvider};
+use vastrum_rpc_client::{RpcClient, RpcError, RpcProvider};
+use vastrum_shared_types::limits::MAX_KV_SCAN_LIMIT;
 use sha2::{Digest, Sha256};
 use std::fmt;
 use std::io;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-pub struct KvMap<K, V> {
+/// Key storage of the contract side KvMap, only Iterable maps can list their entries
+pub struct Hashed;
+pub struct Iterable;
+
+pub struct KvMap<K, V, S = Hashed> {
     nonce: u64,
     client: Arc<RpcClient>,
-    _phantom: PhantomData<(K, V)>,
+    _phantom: PhantomData<(K, V, S)>,
 }
 
-impl<K, V> Clone for KvMap<K, V> {
+impl<K, V, S> Clone for KvMap<K, V, S> {
     fn clone(&self) -> Self {
         Self { nonce: self.nonce, client: self.client.clone(), _phantom: PhantomData }
     }
 }
 
-impl<K, V> fmt::Debug for KvMap<K, V> {
+impl<K, V, S> fmt::Debug for KvMap<K, V, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KvMap").field("nonce", &self.nonce).finish_non_exhaustive()
     }
 }
 
-impl<K, V> BorshDeserialize for KvMap<K, V> {
+impl<K, V, S> BorshDeserialize for KvMap<K, V, S> {
     fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
         //to pass rpc client to child kv data types as they need it to get()
         //very complicated but works
@@ -34,7 +39,7 @@ impl<K, V> BorshDeserialize for KvMap<K, V> {
     }
 }
 
-impl<K, V> KvMap<K, V>
+impl<K, V, S> KvMap<K, V, S>
 where
     K: BorshSerialize,
     V: BorshDeserialize,
@@ -63,3 +68,63 @@ where
         return self.get(key).await.is_some();
     }
 }
+
+impl<K, V> KvMap<K, V, Iterable>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshDeserialize,
+{
+    /// All entries in key hash order, same order as the runtime-lib KvMap iter()
+    /// The key list is read unproven, keys and values are proven at one height
+    /// Entries set before the map became iterable are only listed once they are set again
+    pub async fn iter(&self) -> Result<Vec<(K, V)>, RpcError> {
+        let Some(height) = self.client.get_latest_block_height().await else {
+            return Err(RpcError("latest block height unavailable".into()));
+        };
+        let prefix = format!("n.{}.k.", self.nonce);
+        let mut entries = Vec::new();
+        let mut start = None;
+        loop {
+            let keys =
+                self.client.scan_keys(prefix.clone(), start, MAX_KV_SCAN_LIMIT, false).await?;
+            let mut futs = Vec::new();
+            for entry_key in &keys {
+                futs.push(self.read_entry(&prefix, entry_key, height));
+            }
+            let fetched = futures::future::try_join_all(futs).await?;
+            entries.extend(fetched.into_iter().flatten());
+            if keys.len() < MAX_KV_SCAN_LIMIT as usize {
+                return Ok(entries);
+            }
+            //the smallest key after the last one, scan starts are inclusive
+            start = keys.last().map(|entry_key| format!("{entry_key}\0"));
+        }
+    }
+
+    //None if the entry was set after height or removed since, the key list is read at the latest state
+    async fn read_entry(
+        &self,
+        prefix: &str,
+        entry_key: &str,
+        height: u64,
+    ) -> Result<Option<(K, V)>, RpcError> {
+        let hash = &entry_key[prefix.len()..];
+        let client = &self.client;
+        let Some(key_bytes) = client.read_key_value_at_height(entry_key.into(), height).await?
+        else {
+            return Ok(None);
+        };
+        let data_key = format!("n.{}.{}", self.nonce, hash);
+        let Some(bytes) = client.read_key_value_at_height(data_key, height).await? else {
+            return Ok(None);
+        };
+        let Ok(key) = borsh::from_slice(&key_bytes) else {
+            return Err(RpcError(format!("failed to decode key {entry_key}")));
+        };
+        let value = crate::with_deser_client(client, || borsh::from_slice(&bytes).ok());
+        let Some(value) = value else {
+            return Err(RpcError(format!("failed to decode value of {entry_key}")));
+        };
+        return Ok(Some((key, value)));
+    }
+}
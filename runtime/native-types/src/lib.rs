@@ -4,7 +4,7 @@ mod kvvec;
 mod kvvecbtree;
 
 pub use kvbtree::KvBTree;
-pub use kvmap::KvMap;
+pub use kvmap::{Hashed, Iterable, KvMap};
 pub use kvvec::KvVec;
 pub use kvvecbtree::KvVecBTree;
 
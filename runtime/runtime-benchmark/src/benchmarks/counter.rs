@@ -1,8 +1,10 @@
 use crate::helpers::*;
 use crate::{RUNS, TXS_PER_BATCH};
 use vastrum_shared_types::crypto::{ed25519::PrivateKey, sha256::Sha256Digest};
+use vastrum_shared_types::genesis::genesis_chain_id;
+use vastrum_shared_types::limits::DEFAULT_TX_FUEL_LIMIT;
 use vastrum_shared_types::transactioning::transaction_generator::{
-    build_call_transaction, build_deploy_new_module_transaction,
+    TxEnvelope, build_call_transaction, build_deploy_new_module_transaction,
 };
 use std::io::Write;
 
@@ -19,9 +21,14 @@ pub fn run() {
             txs.push(build_call_transaction(
                 site_ids[i % site_ids.len()],
                 calldata,
-                i as u64,
                 PrivateKey::from_seed(i as u64),
-                ctx.height,
+                TxEnvelope {
+                    nonce: i as u64,
+                    recent_block_height: ctx.height,
+                    chain_id: genesis_chain_id(),
+                    pow_difficulty: 0,
+                    fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+                },
             ));
         }
         execute_block(&mut ctx.execution, &mut ctx.height, txs)
@@ -46,9 +53,15 @@ fn deploy_unique_contracts(ctx: &mut BenchContext, count: usize) -> Vec<Sha256Di
             let tx = build_deploy_new_module_transaction(
                 unique_wasm,
                 vec![],
-                i as u64,
+                None,
                 PrivateKey::from_seed(i as u64),
-                ctx.height + 1,
+                TxEnvelope {
+                    nonce: i as u64,
+                    recent_block_height: ctx.height + 1,
+                    chain_id: genesis_chain_id(),
+                    pow_difficulty: 0,
+                    fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+                },
             );
             site_ids.push(tx.calculate_txhash());
             deploy_txs.push(tx);
@@ -16,8 +16,19 @@ impl BenchContext {
 
         // Deploy contract
         let wasm_bytes = std::fs::read("contract/out/contract.wasm").expect("failed to read wasm");
-        let deploy_tx =
-            build_deploy_new_module_transaction(wasm_bytes, vec![], 0, PrivateKey::from_seed(0), 0);
+        let deploy_tx = build_deploy_new_module_transaction(
+            wasm_bytes,
+            vec![],
+            None,
+            PrivateKey::from_seed(0),
+            TxEnvelope {
+                nonce: 0,
+                recent_block_height: 0,
+                chain_id: genesis_chain_id(),
+                pow_difficulty: 0,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
+        );
         let site_id = deploy_tx.calculate_txhash();
         execute_block(&mut execution, &mut height, vec![deploy_tx]);
 
@@ -46,9 +57,14 @@ pub fn build_txs(
         txs.push(build_call_transaction(
             site_id,
             calldata,
-            i as u64,
             PrivateKey::from_seed(i as u64),
-            height,
+            TxEnvelope {
+                nonce: i as u64,
+                recent_block_height: height,
+                chain_id: genesis_chain_id(),
+                pow_difficulty: 0,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
         ));
     }
     txs
@@ -66,8 +82,10 @@ pub fn execute_block(
         previous_block_hash: Sha256Digest::from([0u8; 32]),
         timestamp: 0,
         previous_block_state_root: Sha256Digest::default(),
+        pow_difficulty: 0,
+        grandparent_finalization: BlockFinalization::default(),
     };
-    let finalized = FinalizedBlock { block, votes: BTreeMap::new(), round: 0 };
+    let finalized = FinalizedBlock { block, votes: CertificateVotes::default(), round: 0 };
     let start = Instant::now();
     execution.execute_block(finalized);
     start.elapsed()
@@ -147,13 +165,17 @@ use vastrum_native_lib::deployers::build::build_contract;
 use vastrum_runtime_shared::calculate_function_selector;
 use vastrum_shared_types::{
     crypto::{ed25519::PrivateKey, sha256::Sha256Digest},
+    genesis::genesis_chain_id,
+    limits::DEFAULT_TX_FUEL_LIMIT,
     transactioning::transaction_generator::{
-        build_call_transaction, build_deploy_new_module_transaction,
+        TxEnvelope, build_call_transaction, build_deploy_new_module_transaction,
+    },
+    types::{
+        consensus::{BlockFinalization, CertificateVotes},
+        execution::transaction::Transaction,
     },
-    types::execution::transaction::Transaction,
 };
 use std::{
-    collections::BTreeMap,
     io::Write,
     sync::Arc,
     time::{Duration, Instant},
@@ -1,36 +1,65 @@
 use crate::runtime;
 use borsh::{BorshDeserialize, BorshSerialize};
-use sha2::{Digest, Sha256};
 use std::marker::PhantomData;
 
+/// Whether a KvMap keeps the plain key of every entry next to its value
+pub trait KeyStorage {
+    const STORES_KEYS: bool;
+}
+
+/// Values are only stored under their key hash, the map can not be iterated
+#[derive(Clone)]
+pub struct Hashed;
+
+/// Plain keys are stored too so the map can be iterated,
+/// each set costs an extra read and the key bytes
+#[derive(Clone)]
+pub struct Iterable;
+
+impl KeyStorage for Hashed {
+    const STORES_KEYS: bool = false;
+}
+
+impl KeyStorage for Iterable {
+    const STORES_KEYS: bool = true;
+}
+
+/// Key storage only changes which entries are written, a KvMap<K, V> can become a
+/// KvMap<K, V, Iterable> in an upgrade, its earlier entries are listed once they are set again
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
-pub struct KvMap<K, V> {
+pub struct KvMap<K, V, S = Hashed> {
     nonce: u64,
     #[borsh(skip)]
-    _phantom: PhantomData<(K, V)>,
+    _phantom: PhantomData<(K, V, S)>,
 }
 
 //always allocate next_nonce() to ensure kvmap nonce wont get lost in certain nested cases
 //where would expect not to need to get and set to store newly gained nonce state
-impl<K, V> Default for KvMap<K, V> {
+impl<K, V, S> Default for KvMap<K, V, S> {
     fn default() -> Self {
         Self { nonce: runtime::next_nonce(), _phantom: PhantomData }
     }
 }
 
-impl<K, V> KvMap<K, V>
+impl<K, V, S> KvMap<K, V, S>
 where
     K: BorshSerialize,
     V: BorshSerialize + BorshDeserialize,
+    S: KeyStorage,
 {
     pub fn new() -> Self {
         return Self::default();
     }
 
+    fn key_hash(&self, key: &K) -> String {
+        let hash = runtime::sha256(&borsh::to_vec(key).unwrap());
+        //lowercase hex, keys must stay identical to the native-types KvMap
+        let hash: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+        return hash;
+    }
+
     fn data_key(&self, key: &K) -> String {
-        let hash = Sha256::digest(borsh::to_vec(key).unwrap());
-        let key = format!("n.{}.{:x}", self.nonce, hash);
-        return key;
+        return data_key(self.nonce, &self.key_hash(key));
     }
 
     pub fn get(&self, key: &K) -> Option<V> {
@@ -43,11 +72,23 @@ where
     }
 
     pub fn set(&self, key: &K, value: V) {
-        runtime::kv_insert(&self.data_key(key), &borsh::to_vec(&value).unwrap());
+        let hash = self.key_hash(key);
+        if S::STORES_KEYS {
+            //reading is cheaper than rewriting the plain key on every update
+            let plain_key = plain_key_entry(self.nonce, &hash);
+            if runtime::kv_get(&plain_key).is_empty() {
+                runtime::kv_insert(&plain_key, &borsh::to_vec(key).unwrap());
+            }
+        }
+        runtime::kv_insert(&data_key(self.nonce, &hash), &borsh::to_vec(&value).unwrap());
     }
 
     pub fn remove(&self, key: &K) {
-        runtime::kv_delete(&self.data_key(key));
+        let hash = self.key_hash(key);
+        if S::STORES_KEYS {
+            runtime::kv_delete(&plain_key_entry(self.nonce, &hash));
+        }
+        runtime::kv_delete(&data_key(self.nonce, &hash));
     }
 
     pub fn contains(&self, key: &K) -> bool {
@@ -55,3 +96,74 @@ where
         return contains_value;
     }
 }
+
+impl<K, V> KvMap<K, V, Iterable>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// All entries in key hash order, read in pages as the iterator advances.
+    /// Entries set before the map became iterable are only listed once they are set again.
+    pub fn iter(&self) -> KvMapIter<'_, K, V> {
+        return KvMapIter {
+            map: self,
+            page: Vec::new().into_iter(),
+            next_start: None,
+            done: false,
+        };
+    }
+}
+
+pub struct KvMapIter<'a, K, V> {
+    map: &'a KvMap<K, V, Iterable>,
+    page: std::vec::IntoIter<(String, Vec<u8>)>,
+    next_start: Option<String>,
+    done: bool,
+}
+
+impl<K, V> Iterator for KvMapIter<'_, K, V>
+where
+    K: BorshDeserialize,
+    V: BorshDeserialize,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let prefix = plain_key_prefix(self.map.nonce);
+        loop {
+            if let Some((entry_key, key_bytes)) = self.page.next() {
+                let hash = &entry_key[prefix.len()..];
+                let value = runtime::kv_get(&data_key(self.map.nonce, hash));
+                if value.is_empty() {
+                    continue;
+                }
+                let key = borsh::from_slice(&key_bytes).unwrap();
+                return Some((key, borsh::from_slice(&value).unwrap()));
+            }
+            if self.done {
+                return None;
+            }
+            let page = runtime::kv_scan(&prefix, self.next_start.as_deref(), ITER_PAGE_SIZE);
+            self.done = page.len() < ITER_PAGE_SIZE as usize;
+            //the smallest key after the last one, scan starts are inclusive
+            self.next_start = page.last().map(|(entry_key, _)| format!("{entry_key}\0"));
+            self.page = page.into_iter();
+        }
+    }
+}
+
+//scans are charged per requested entry, so pages are kept well below MAX_KV_SCAN_LIMIT
+const ITER_PAGE_SIZE: u32 = 32;
+
+fn data_key(nonce: u64, hash: &str) -> String {
+    return format!("n.{}.{}", nonce, hash);
+}
+
+//values are stored under the key hash, the plain key is kept next to it so the map can be iterated
+fn plain_key_entry(nonce: u64, hash: &str) -> String {
+    return format!("{}{}", plain_key_prefix(nonce), hash);
+}
+
+fn plain_key_prefix(nonce: u64) -> String {
+    return format!("n.{}.k.", nonce);
+}
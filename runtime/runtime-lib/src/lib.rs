@@ -5,9 +5,10 @@ mod kvvecbtree;
 pub mod runtime;
 
 pub use kvbtree::KvBTree;
-pub use kvmap::KvMap;
+pub use kvmap::{Hashed, Iterable, KeyStorage, KvMap, KvMapIter};
 pub use kvvec::KvVec;
 pub use kvvecbtree::KvVecBTree;
 pub use runtime::Ed25519Verify;
+pub use vastrum_runtime_shared::CallSiteError;
 pub use vastrum_runtime_shared::Ed25519PublicKey;
 pub use vastrum_runtime_shared::Ed25519Signature;
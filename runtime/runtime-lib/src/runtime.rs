@@ -1,10 +1,14 @@
 use vastrum_runtime_shared::{
-    Ed25519PublicKey, Ed25519Signature, GetMessageSenderResponse, KeyValueInsertCall,
-    KeyValueReadCall, KeyValueReadResponse, LogCall, RegisterStaticRouteCall,
+    CallSiteCall, CallSiteError, CallSiteResponse, Ed25519BatchVerifyCall, Ed25519PublicKey,
+    Ed25519Signature, Ed25519VerifyCall, EmitEventCall, GetDigestResponse,
+    GetMessageSenderResponse, GetRandomSeedResponse, KeyValueInsertCall, KeyValueReadCall,
+    KeyValueReadResponse, KeyValueScanCall, KeyValueScanResponse, LogCall, RegisterStaticRouteCall,
+    Secp256k1RecoverCall, X25519Call, calculate_function_selector,
 };
 use vastrum_bindings_guest::runtime_raw;
 
 /// Get the message senders public key.
+/// When called from another site this is the calling site id.
 pub fn message_sender() -> Ed25519PublicKey {
     let bytes = runtime_raw::message_sender();
     let response: GetMessageSenderResponse = borsh::from_slice(&bytes).unwrap();
@@ -18,6 +22,48 @@ pub fn block_time() -> u64 {
     return block_time;
 }
 
+/// Get the height of the block the transaction is in.
+pub fn block_height() -> u64 {
+    let block_height = runtime_raw::block_height();
+    return block_height;
+}
+
+/// Get the hash of the block before the one the transaction is in.
+pub fn previous_block_hash() -> [u8; 32] {
+    return read_digest(runtime_raw::previous_block_hash());
+}
+
+/// Get the hash of the current transaction, the same for every site it calls.
+pub fn tx_hash() -> [u8; 32] {
+    return read_digest(runtime_raw::tx_hash());
+}
+
+/// Get the id of the site this code runs as.
+pub fn self_site_id() -> [u8; 32] {
+    return read_digest(runtime_raw::site_id());
+}
+
+/// Get the id of the module the current site runs, it changes when the site is upgraded.
+pub fn module_id() -> [u8; 32] {
+    return read_digest(runtime_raw::module_id());
+}
+
+fn read_digest(bytes: Vec<u8>) -> [u8; 32] {
+    let response: GetDigestResponse = borsh::from_slice(&bytes).unwrap();
+    return response.digest;
+}
+
+/// Random seed of the current transaction, each domain gives an independent value.
+/// The block proposer can bias it, do not use it alone for high value draws.
+pub fn random_seed(domain: &str) -> [u8; 32] {
+    let bytes = runtime_raw::random_seed();
+    let response: GetRandomSeedResponse = borsh::from_slice(&bytes).unwrap();
+    let mut data = response.seed.to_vec();
+    data.extend_from_slice(domain.as_bytes());
+    let seed = sha256(&data);
+    return seed;
+}
+
 /// Register a static route with brotli compressed HTML content.
 pub fn register_static_route(route: &str, content: &[u8]) {
     let route = route.to_string();
@@ -27,6 +73,7 @@ pub fn register_static_route(route: &str, content: &[u8]) {
 }
 
 /// Insert a keyvalue pair into storage.
+/// Traps if the write grows the site past the storage quota of the chain.
 pub fn kv_insert(key: &str, value: &[u8]) {
     let args = KeyValueInsertCall { key: key.to_string(), value: value.to_vec() };
     runtime_raw::kv_insert(&borsh::to_vec(&args).unwrap());
@@ -38,6 +85,12 @@ pub fn kv_delete(key: &str) {
     kv_insert(key, &[]);
 }
 
+/// Bytes the current site holds in storage, keys included.
+pub fn storage_usage() -> u64 {
+    let storage_usage = runtime_raw::storage_usage();
+    return storage_usage;
+}
+
 /// Read a value from storage by key.
 pub fn kv_get(key: &str) -> Vec<u8> {
     let args = KeyValueReadCall { key: key.to_string() };
@@ -50,21 +103,101 @@ pub fn kv_get(key: &str) -> Vec<u8> {
     return value;
 }
 
+/// Entries with keys starting with prefix in ascending byte order, from start (inclusive) on.
+/// At most limit entries, limit can not exceed MAX_KV_SCAN_LIMIT. Fuel is charged per requested entry.
+pub fn kv_scan(prefix: &str, start: Option<&str>, limit: u32) -> Vec<(String, Vec<u8>)> {
+    let args = KeyValueScanCall {
+        prefix: prefix.to_string(),
+        start: start.map(|start| start.to_string()),
+        limit,
+    };
+    let bytes = runtime_raw::kv_scan(&borsh::to_vec(&args).unwrap());
+    let response: KeyValueScanResponse = borsh::from_slice(&bytes).unwrap();
+    return response.entries;
+}
+
+/// Same as kv_scan in descending order, from start (inclusive) down.
+pub fn kv_scan_reverse(prefix: &str, start: Option<&str>, limit: u32) -> Vec<(String, Vec<u8>)> {
+    let args = KeyValueScanCall {
+        prefix: prefix.to_string(),
+        start: start.map(|start| start.to_string()),
+        limit,
+    };
+    let bytes = runtime_raw::kv_scan_reverse(&borsh::to_vec(&args).unwrap());
+    let response: KeyValueScanResponse = borsh::from_slice(&bytes).unwrap();
+    return response.entries;
+}
+
+/// Call a method on another site, blocking until it returns.
+/// Returns the borsh encoded return value, if the called site fails its state changes are reverted.
+pub fn call_site(site_id: [u8; 32], calldata: &[u8]) -> Result<Vec<u8>, CallSiteError> {
+    let args = CallSiteCall { site_id, calldata: calldata.to_vec() };
+    let bytes = runtime_raw::call_site(&borsh::to_vec(&args).unwrap());
+    let response: CallSiteResponse = borsh::from_slice(&bytes).unwrap();
+    return response.result;
+}
+
+/// Encode calldata for a site method, params are the method arguments as a tuple.
+pub fn site_calldata(method_name: &str, params: &impl borsh::BorshSerialize) -> Vec<u8> {
+    let mut calldata = calculate_function_selector(method_name).to_vec();
+    calldata.extend(borsh::to_vec(params).unwrap());
+    return calldata;
+}
+
+/// Set the borsh encoded return value of the current method.
+pub fn set_return_data(data: &[u8]) {
+    runtime_raw::set_return_data(data);
+}
+
+/// Make the rest of the current method read only, the host traps on any state change.
+/// Called at the start of `#[view]` methods.
+pub fn enter_view() {
+    runtime_raw::enter_view();
+}
+
+/// Abort the current method with a borsh encoded error, its state changes are reverted.
+pub fn revert(error: &[u8]) -> ! {
+    runtime_raw::revert(error)
+}
+
+/// Trap with a panic message, it is recorded as the failure message of the transaction.
+/// Called by the panic hook `#[contract]` installs.
+pub fn panic(message: &str) -> ! {
+    runtime_raw::panic(message.as_bytes())
+}
+
 /// Log debug message
 pub fn log(message: &str) {
     let args = LogCall { message: message.to_string() };
     runtime_raw::log(&borsh::to_vec(&args).unwrap());
 }
 
+/// Emit an event indexed under each topic, it is dropped if the method reverts.
+/// Use `#[contract_event]` structs instead of calling this directly.
+pub fn emit_event(topics: &[[u8; 32]], data: &[u8]) {
+    let args = EmitEventCall { topics: topics.to_vec(), data: data.to_vec() };
+    runtime_raw::emit_event(&borsh::to_vec(&args).unwrap());
+}
+
 /// Verify an Ed25519 signature
 pub fn verify_ed25519(pub_key: &Ed25519PublicKey, msg: &[u8], sig: &Ed25519Signature) -> bool {
-    let Ok(pk) = ed25519_compact::PublicKey::from_slice(&pub_key.bytes) else {
-        return false;
-    };
-    let Ok(signature) = ed25519_compact::Signature::from_slice(&sig.bytes) else {
-        return false;
-    };
-    pk.verify(msg, &signature).is_ok()
+    let args = Ed25519VerifyCall { pub_key: *pub_key, message: msg.to_vec(), signature: *sig };
+    return runtime_raw::ed25519_verify(&borsh::to_vec(&args).unwrap());
+}
+
+/// Verify many Ed25519 signatures at once, false if any of them is invalid.
+/// Costs the same fuel per signature as verify_ed25519 but runs faster on the host.
+pub fn verify_ed25519_batch(items: &[(&Ed25519PublicKey, &[u8], &Ed25519Signature)]) -> bool {
+    let items = items
+        .iter()
+        .map(|(pub_key, msg, sig)| Ed25519VerifyCall {
+            pub_key: **pub_key,
+            message: msg.to_vec(),
+            signature: **sig,
+        })
+        .collect();
+    let args = Ed25519BatchVerifyCall { items };
+    return runtime_raw::ed25519_batch_verify(&borsh::to_vec(&args).unwrap());
 }
 
 pub trait Ed25519Verify {
@@ -79,8 +212,56 @@ impl Ed25519Verify for Ed25519PublicKey {
 
 /// Compute SHA256 hash of data.
 pub fn sha256(data: &[u8]) -> [u8; 32] {
-    use sha2::{Digest, Sha256};
-    Sha256::digest(data).into()
+    return runtime_raw::sha256(data);
+}
+
+/// Compute Keccak256 hash of data, the Ethereum variant without SHA3 padding.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    return runtime_raw::keccak256(data);
+}
+
+/// Compute BLAKE3 hash of data.
+pub fn blake3(data: &[u8]) -> [u8; 32] {
+    return runtime_raw::blake3(data);
+}
+
+/// Recover the secp256k1 public key (x || y, uncompressed without prefix) that signed message_hash.
+/// Signature is r || s with recovery_id 0 or 1, high s signatures are rejected.
+pub fn secp256k1_recover(
+    message_hash: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+) -> Option<[u8; 64]> {
+    let args =
+        Secp256k1RecoverCall { message_hash: *message_hash, signature: *signature, recovery_id };
+    return runtime_raw::secp256k1_recover(&borsh::to_vec(&args).unwrap());
+}
+
+/// Recover the Ethereum address that signed message_hash.
+/// Signature is r || s || v, v may be 0/1 or 27/28.
+pub fn ecrecover(message_hash: &[u8; 32], signature: &[u8; 65]) -> Option<[u8; 20]> {
+    let recovery_id = match signature[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return None,
+    };
+    let rs: [u8; 64] = signature[..64].try_into().unwrap();
+    let pub_key = secp256k1_recover(message_hash, &rs, recovery_id)?;
+    let hash = keccak256(&pub_key);
+    let address: [u8; 20] = hash[12..].try_into().unwrap();
+    return Some(address);
+}
+
+/// X25519 Diffie-Hellman, None if public_key is a low order point.
+pub fn x25519(secret: &[u8; 32], public_key: &[u8; 32]) -> Option<[u8; 32]> {
+    let args = X25519Call { secret: *secret, public_key: *public_key };
+    return runtime_raw::x25519(&borsh::to_vec(&args).unwrap());
+}
+
+/// State saved by the module the site was upgraded from, for use in `#[migrate]`.
+/// T has to match the borsh layout of the previous state struct.
+pub fn previous_state<T: borsh::BorshDeserialize>() -> T {
+    borsh::from_slice(&kv_get("__state")).unwrap()
 }
 
 /// Get next unique nonce for KvMap/KvVec namespace allocation.
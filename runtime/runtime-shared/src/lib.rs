@@ -1,5 +1,5 @@
 mod selector;
 mod types;
 
-pub use selector::calculate_function_selector;
+pub use selector::{calculate_event_topic, calculate_function_selector};
 pub use types::*;
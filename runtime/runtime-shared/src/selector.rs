@@ -7,3 +7,8 @@ pub fn calculate_function_selector(name: &str) -> [u8; 8] {
     function_selector.copy_from_slice(&hash[..8]);
     return function_selector;
 }
+
+/// Computes the SHA256 hash of the event name, the first topic of every event it emits.
+pub fn calculate_event_topic(name: &str) -> [u8; 32] {
+    return Sha256::digest(name.as_bytes()).into();
+}
@@ -15,6 +15,17 @@ pub struct GetMessageSenderResponse {
     pub sender: Ed25519PublicKey,
 }
 
+/// Response of the host calls returning a 32 byte hash or id
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GetDigestResponse {
+    pub digest: [u8; 32],
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GetRandomSeedResponse {
+    pub seed: [u8; 32],
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct RegisterStaticRouteCall {
     pub route: String,
@@ -37,7 +48,74 @@ pub struct KeyValueReadResponse {
     pub value: Vec<u8>,
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct KeyValueScanCall {
+    pub prefix: String,
+    /// Inclusive, the scan starts at the first key with the prefix when None
+    pub start: Option<String>,
+    pub limit: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct KeyValueScanResponse {
+    /// Key value pairs in byte order of the keys, descending for reverse scans
+    pub entries: Vec<(String, Vec<u8>)>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct LogCall {
     pub message: String,
 }
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct EmitEventCall {
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CallSiteCall {
+    pub site_id: [u8; 32],
+    pub calldata: Vec<u8>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CallSiteResponse {
+    /// Borsh encoded return value of the called method
+    pub result: Result<Vec<u8>, CallSiteError>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum CallSiteError {
+    /// The called method returned Err, holds the borsh encoded error
+    Reverted(Vec<u8>),
+    /// The site could not be called or it trapped
+    Failed(String),
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Ed25519VerifyCall {
+    pub pub_key: Ed25519PublicKey,
+    pub message: Vec<u8>,
+    pub signature: Ed25519Signature,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Ed25519BatchVerifyCall {
+    pub items: Vec<Ed25519VerifyCall>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Secp256k1RecoverCall {
+    pub message_hash: [u8; 32],
+    /// r || s
+    pub signature: [u8; 64],
+    /// 0 or 1
+    pub recovery_id: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct X25519Call {
+    pub secret: [u8; 32],
+    pub public_key: [u8; 32],
+}
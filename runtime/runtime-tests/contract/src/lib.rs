@@ -1,8 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
 use vastrum_contract_macros::{
-    authenticated, constructor, contract_methods, contract_state, contract_type,
+    authenticated, constructor, contract_event, contract_methods, contract_state, contract_type,
+    migrate, view,
+};
+use vastrum_runtime_lib::{
+    Ed25519PublicKey, Ed25519Signature, Iterable, KvBTree, KvMap, KvVec, KvVecBTree,
 };
-use vastrum_runtime_lib::{Ed25519PublicKey, KvBTree, KvMap, KvVec, KvVecBTree};
 
 #[contract_type]
 struct UserData {
@@ -69,12 +72,19 @@ struct PrimitiveFields {
     btree: BTreeMap<String, u64>,
 }
 
+#[contract_event]
+struct CounterIncreased {
+    #[topic]
+    key: String,
+    amount: u64,
+}
+
 #[contract_state]
 struct Contract {
     counter: u32,
     message: String,
     user_data: UserData,
-    kvmap: KvMap<String, u64>,
+    kvmap: KvMap<String, u64, Iterable>,
     kvvec: KvVec<String>,
     kvvec_struct: KvVec<ForumPost>,
     kvbtree: KvBTree<u64, String>,
@@ -100,6 +110,13 @@ impl Contract {
         Self { message: initial_message, ..Self::default() }
     }
 
+    #[migrate]
+    pub fn migrate_message(message: String) -> Self {
+        let mut contract: Self = runtime::previous_state();
+        contract.message = message;
+        contract
+    }
+
     pub fn add_to_counter(&mut self, amount: u32) {
         self.counter += amount;
     }
@@ -131,6 +148,23 @@ impl Contract {
         runtime::log(&format!("Block time: {}", time));
     }
 
+    pub fn draw_random(&mut self, domain: String) {
+        self.message = format!("{:02x?}", runtime::random_seed(&domain));
+    }
+
+    pub fn compare_random_domains(&mut self, a: String, b: String) {
+        let same = runtime::random_seed(&a) == runtime::random_seed(&b);
+        self.message = if same { "same".to_string() } else { "different".to_string() };
+    }
+
+    pub fn record_tx_context(&mut self) {
+        runtime::kv_insert("ctx.block_height", &runtime::block_height().to_le_bytes());
+        runtime::kv_insert("ctx.previous_block_hash", &runtime::previous_block_hash());
+        runtime::kv_insert("ctx.tx_hash", &runtime::tx_hash());
+        runtime::kv_insert("ctx.site_id", &runtime::self_site_id());
+        runtime::kv_insert("ctx.module_id", &runtime::module_id());
+    }
+
     pub fn add_page(&self, route: String, brotli_html_content: Vec<u8>) {
         runtime::register_static_route(&route, &brotli_html_content);
     }
@@ -285,6 +319,120 @@ impl Contract {
         panic!("intentional panic after writes");
     }
 
+    pub fn write_then_err(&mut self, key: String, value: u64) -> Result<u64, String> {
+        self.kvmap.set(&key, value);
+        self.counter += 1;
+        Err(format!("rejected write to {key}"))
+    }
+
+    pub fn emit_counter_increased(&mut self, key: String, amount: u64) {
+        CounterIncreased { key, amount }.emit();
+    }
+
+    pub fn emit_then_panic(&mut self, key: String, amount: u64) {
+        CounterIncreased { key, amount }.emit();
+        panic!("intentional panic after emitting");
+    }
+
+    #[view]
+    pub fn kvmap_lookup(&self, key: String) -> Option<u64> {
+        self.kvmap.get(&key)
+    }
+
+    //map writes through &self, the host has to reject it
+    #[view]
+    pub fn kvmap_set_in_view(&self, key: String, value: u64) -> u64 {
+        self.kvmap.set(&key, value);
+        value
+    }
+
+    #[view]
+    pub fn storage_used(&self) -> u64 {
+        runtime::storage_usage()
+    }
+
+    #[view]
+    pub fn precompile_hashes(&self, data: Vec<u8>) -> Vec<Vec<u8>> {
+        let hashes = [runtime::sha256(&data), runtime::keccak256(&data), runtime::blake3(&data)];
+        hashes.iter().map(|hash| hash.to_vec()).collect()
+    }
+
+    #[view]
+    pub fn verify_each_ed25519(
+        &self,
+        pub_key: Ed25519PublicKey,
+        messages: Vec<Vec<u8>>,
+        signatures: Vec<Ed25519Signature>,
+    ) -> Vec<bool> {
+        let pairs = messages.iter().zip(signatures.iter());
+        pairs
+            .map(|(message, signature)| runtime::verify_ed25519(&pub_key, message, signature))
+            .collect()
+    }
+
+    #[view]
+    pub fn verify_batch_ed25519(
+        &self,
+        pub_key: Ed25519PublicKey,
+        messages: Vec<Vec<u8>>,
+        signatures: Vec<Ed25519Signature>,
+    ) -> bool {
+        let items: Vec<_> = messages
+            .iter()
+            .zip(signatures.iter())
+            .map(|(message, signature)| (&pub_key, message.as_slice(), signature))
+            .collect();
+        runtime::verify_ed25519_batch(&items)
+    }
+
+    #[view]
+    pub fn ecrecover_address(&self, message_hash: Vec<u8>, signature: Vec<u8>) -> Option<Vec<u8>> {
+        let message_hash: [u8; 32] = message_hash.try_into().unwrap();
+        let signature: [u8; 65] = signature.try_into().unwrap();
+        runtime::ecrecover(&message_hash, &signature).map(|address| address.to_vec())
+    }
+
+    #[view]
+    pub fn x25519_shared_secret(&self, secret: Vec<u8>, public_key: Vec<u8>) -> Option<Vec<u8>> {
+        let secret: [u8; 32] = secret.try_into().unwrap();
+        let public_key: [u8; 32] = public_key.try_into().unwrap();
+        runtime::x25519(&secret, &public_key).map(|shared| shared.to_vec())
+    }
+
+    #[view]
+    pub fn kv_scan_keys(
+        &self,
+        prefix: String,
+        start: Option<String>,
+        limit: u32,
+        reverse: bool,
+    ) -> Vec<String> {
+        let entries = if reverse {
+            runtime::kv_scan_reverse(&prefix, start.as_deref(), limit)
+        } else {
+            runtime::kv_scan(&prefix, start.as_deref(), limit)
+        };
+        entries.into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[view]
+    pub fn kvmap_keys(&self) -> Vec<String> {
+        self.kvmap.iter().map(|(key, _)| key).collect()
+    }
+
+    #[view]
+    pub fn kvmap_entries(&self) -> BTreeMap<String, u64> {
+        self.kvmap.iter().collect()
+    }
+
+    #[view]
+    pub fn counter_below(&self, limit: u32) -> Result<u32, String> {
+        if self.counter >= limit {
+            return Err(format!("counter {} is not below {limit}", self.counter));
+        }
+        Ok(self.counter)
+    }
+
     #[authenticated]
     pub fn auth_record_sender(&mut self) {
         self.last_authenticated_sender = runtime::message_sender();
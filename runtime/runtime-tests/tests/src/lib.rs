@@ -9,17 +9,25 @@ mod tests {
     mod batch_db;
     mod blockchain_indexer;
     mod domain;
+    mod events;
     mod kv_delete;
     mod kv_history;
+    mod kv_scan;
     mod kvbtree;
     mod kvmap;
     mod kvvec;
     mod kvvecbtree;
     mod nested_kv;
     mod page_serving;
+    mod precompiles;
     mod primitive_types;
+    mod random_seed;
     mod rollback;
     mod state_basics;
+    mod storage_usage;
+    mod tx_context;
+    mod upgrade;
+    mod views;
 
     use vastrum_shared_types::crypto::ed25519;
     use vastrum_shared_types::crypto::sha256::Sha256Digest;
@@ -110,6 +110,8 @@ async fn test_block_txs() {
     tx_poller.await_confirmation().await;
 
     let detail: TxDetail = read_indexer_json(&tx_key(&tx_hash)).await.unwrap();
+    assert!(detail.success);
+    assert!(detail.error.is_none());
     let height = detail.block_height;
     let txs: Vec<TxSummary> = read_indexer_json(&block_txs_key(height)).await.unwrap();
     let indexed_tx = txs.first().unwrap();
@@ -0,0 +1,42 @@
+use super::*;
+use vastrum_native_lib::NativeHttpClient;
+
+async fn get_height() -> u64 {
+    NativeHttpClient::new().get_latest_block_height().await.unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_events_queried_by_type_and_topic() {
+    let ctx = TestContext::new().await;
+    let from_height = get_height().await;
+
+    ctx.client.emit_counter_increased("alice", 1).await.await_confirmation().await;
+    ctx.client.emit_counter_increased("bob", 2).await.await_confirmation().await;
+    ctx.client.emit_counter_increased("alice", 3).await.await_confirmation().await;
+    let to_height = get_height().await;
+
+    let events = ctx.client.counter_increased_events(from_height, to_height).await.unwrap();
+    let amounts: Vec<u64> = events.iter().map(|(_, e)| e.amount).collect();
+    assert_eq!(amounts, vec![1, 2, 3]);
+    assert!(events.windows(2).all(|w| w[0].0 <= w[1].0), "events should be oldest first");
+
+    let alice =
+        ctx.client.counter_increased_events_by_key("alice", from_height, to_height).await.unwrap();
+    let amounts: Vec<u64> = alice.iter().map(|(_, e)| e.amount).collect();
+    assert_eq!(amounts, vec![1, 3]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reverted_call_emits_no_events() {
+    let ctx = TestContext::new().await;
+    let from_height = get_height().await;
+
+    ctx.client.emit_then_panic("carol", 7).await.await_confirmation().await;
+    let to_height = get_height().await;
+
+    let events =
+        ctx.client.counter_increased_events_by_key("carol", from_height, to_height).await.unwrap();
+    assert!(events.is_empty());
+}
@@ -0,0 +1,77 @@
+use super::*;
+use vastrum_native_lib::NativeHttpClient;
+
+#[tokio::test]
+#[serial]
+async fn test_kv_scan_prefix_order_and_paging() {
+    let ctx = TestContext::new().await;
+    for key in ["c", "a", "b", "ab"] {
+        ctx.client.kv_insert_raw(key, vec![1]).await.await_confirmation().await;
+    }
+    let prefix = "n.raw.".to_string();
+
+    let keys = ctx.client.kv_scan_keys(prefix.clone(), None, 10, false).await.unwrap();
+    assert_eq!(keys, vec!["n.raw.a", "n.raw.ab", "n.raw.b", "n.raw.c"]);
+
+    let page = ctx.client.kv_scan_keys(prefix.clone(), None, 2, false).await.unwrap();
+    assert_eq!(page, vec!["n.raw.a", "n.raw.ab"]);
+    let start = Some("n.raw.ab".to_string());
+    let page = ctx.client.kv_scan_keys(prefix.clone(), start, 2, false).await.unwrap();
+    assert_eq!(page, vec!["n.raw.ab", "n.raw.b"]);
+
+    let reversed = ctx.client.kv_scan_keys(prefix.clone(), None, 10, true).await.unwrap();
+    assert_eq!(reversed, vec!["n.raw.c", "n.raw.b", "n.raw.ab", "n.raw.a"]);
+    let start = Some("n.raw.b".to_string());
+    let page = ctx.client.kv_scan_keys(prefix.clone(), start, 2, true).await.unwrap();
+    assert_eq!(page, vec!["n.raw.b", "n.raw.ab"]);
+
+    let narrowed = ctx.client.kv_scan_keys("n.raw.a".to_string(), None, 10, false).await.unwrap();
+    assert_eq!(narrowed, vec!["n.raw.a", "n.raw.ab"]);
+
+    ctx.client.kv_delete_raw("ab").await.await_confirmation().await;
+    let keys = ctx.client.kv_scan_keys(prefix.clone(), None, 10, false).await.unwrap();
+    assert_eq!(keys, vec!["n.raw.a", "n.raw.b", "n.raw.c"]);
+
+    let http = NativeHttpClient::new();
+    let payload =
+        ScanKeysPayload { site_id: ctx.site_id, prefix, start: None, limit: 10, reverse: true };
+    let response = http.scan_keys(payload).await.unwrap();
+    assert_eq!(response.keys, vec!["n.raw.c", "n.raw.b", "n.raw.a"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_kv_scan_over_limit_traps() {
+    let ctx = TestContext::new().await;
+    let result =
+        ctx.client.kv_scan_keys("n.".to_string(), None, MAX_KV_SCAN_LIMIT + 1, false).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_kvmap_iter_in_contract_and_native() {
+    let ctx = TestContext::new().await;
+    assert!(ctx.client.kvmap_entries().await.unwrap().is_empty());
+
+    ctx.client.kvmap_set("alice", 100).await.await_confirmation().await;
+    ctx.client.kvmap_set("bob", 50).await.await_confirmation().await;
+    ctx.client.kvmap_set("carol", 7).await.await_confirmation().await;
+    ctx.client.kvmap_set("alice", 101).await.await_confirmation().await;
+    ctx.client.kvmap_remove("bob").await.await_confirmation().await;
+
+    let expected = BTreeMap::from([("alice".to_string(), 101), ("carol".to_string(), 7)]);
+    assert_eq!(ctx.client.kvmap_entries().await.unwrap(), expected);
+
+    let state = ctx.client.state().await;
+    assert_eq!(state.kvmap.get(&"bob".to_string()).await, None);
+    let native = state.kvmap.iter().await.unwrap();
+    assert_eq!(native.iter().cloned().collect::<BTreeMap<_, _>>(), expected);
+    //both sides walk the map in key hash order
+    let native_keys: Vec<String> = native.into_iter().map(|(key, _)| key).collect();
+    assert_eq!(ctx.client.kvmap_keys().await.unwrap(), native_keys);
+}
+
+use std::collections::BTreeMap;
+use vastrum_shared_types::limits::MAX_KV_SCAN_LIMIT;
+use vastrum_shared_types::types::rpc::types::ScanKeysPayload;
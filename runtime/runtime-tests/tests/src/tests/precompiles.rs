@@ -0,0 +1,103 @@
+use super::*;
+use k256::ecdsa::SigningKey;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sha3::{Digest, Keccak256};
+use vastrum_shared_types::crypto::sha256::sha256_hash;
+use vastrum_shared_types::crypto::x25519;
+
+#[tokio::test]
+#[serial]
+async fn test_hash_precompiles_match_reference() {
+    let ctx = TestContext::new().await;
+
+    let hashes = ctx.client.precompile_hashes(vec![]).await.unwrap();
+    assert_eq!(hashes[0], sha256_hash(&[]).to_bytes());
+    assert_eq!(hashes[1], hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
+    assert_eq!(hashes[2], hex("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"));
+
+    let data = b"vastrum precompiles".to_vec();
+    let hashes = ctx.client.precompile_hashes(data.clone()).await.unwrap();
+    assert_eq!(hashes[0], sha256_hash(&data).to_bytes());
+    assert_eq!(hashes[1], Keccak256::digest(&data).to_vec());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ed25519_verify_and_batch_verify() {
+    let ctx = TestContext::new().await;
+    let key = ed25519::PrivateKey::from_seed(7);
+    let messages: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 40]).collect();
+    let mut signatures: Vec<_> = messages.iter().map(|message| key.sign(message)).collect();
+
+    let each = ctx
+        .client
+        .verify_each_ed25519(key.public_key(), messages.clone(), signatures.clone())
+        .await
+        .unwrap();
+    assert_eq!(each, vec![true; 4]);
+    let batch = ctx
+        .client
+        .verify_batch_ed25519(key.public_key(), messages.clone(), signatures.clone())
+        .await
+        .unwrap();
+    assert!(batch);
+
+    //one signature over the wrong message fails it alone and the whole batch
+    signatures[2] = key.sign(b"something else");
+    let each = ctx
+        .client
+        .verify_each_ed25519(key.public_key(), messages.clone(), signatures.clone())
+        .await
+        .unwrap();
+    assert_eq!(each, vec![true, true, false, true]);
+    let batch =
+        ctx.client.verify_batch_ed25519(key.public_key(), messages, signatures).await.unwrap();
+    assert!(!batch);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ecrecover_returns_signer_address() {
+    let ctx = TestContext::new().await;
+    let signing_key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let message_hash = Keccak256::digest(b"vastrum ecrecover").to_vec();
+    let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&message_hash).unwrap();
+    let mut signature = signature.to_bytes().to_vec();
+    signature.push(27 + recovery_id.to_byte());
+
+    let pub_key = signing_key.verifying_key().to_encoded_point(false);
+    let expected = Keccak256::digest(&pub_key.as_bytes()[1..])[12..].to_vec();
+    let address = ctx.client.ecrecover_address(message_hash.clone(), signature.clone()).await;
+    assert_eq!(address.unwrap(), Some(expected.clone()));
+
+    //recovering against another message gives some other address
+    let other_hash = Keccak256::digest(b"other message").to_vec();
+    let address = ctx.client.ecrecover_address(other_hash, signature).await.unwrap();
+    assert_ne!(address, Some(expected));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_x25519_matches_native_exchange() {
+    let ctx = TestContext::new().await;
+    let alice = x25519::PrivateKey::from_seed(1);
+    let bob = x25519::PrivateKey::from_seed(2);
+    let expected = alice.diffie_hellman(bob.public_key());
+
+    let shared = ctx
+        .client
+        .x25519_shared_secret(alice.to_bytes().to_vec(), bob.public_key().to_bytes().to_vec())
+        .await
+        .unwrap();
+    assert_eq!(shared, Some(expected.to_vec()));
+
+    //the identity point is low order, the exchange is rejected
+    let shared =
+        ctx.client.x25519_shared_secret(alice.to_bytes().to_vec(), vec![0; 32]).await.unwrap();
+    assert_eq!(shared, None);
+}
+
+fn hex(value: &str) -> Vec<u8> {
+    let bytes = (0..value.len()).step_by(2);
+    return bytes.map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap()).collect();
+}
@@ -0,0 +1,25 @@
+use super::*;
+
+#[tokio::test]
+#[serial]
+async fn test_random_seed_differs_per_tx() {
+    let ctx = TestContext::new().await;
+
+    ctx.client.draw_random("lottery").await.await_confirmation().await;
+    let first = ctx.client.state().await.message;
+    ctx.client.draw_random("lottery").await.await_confirmation().await;
+    let second = ctx.client.state().await.message;
+    assert!(!first.is_empty());
+    assert_ne!(first, second);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_random_seed_domains_are_independent() {
+    let ctx = TestContext::new().await;
+
+    ctx.client.compare_random_domains("winner", "winner").await.await_confirmation().await;
+    assert_eq!(ctx.client.state().await.message, "same");
+    ctx.client.compare_random_domains("winner", "shuffle").await.await_confirmation().await;
+    assert_eq!(ctx.client.state().await.message, "different");
+}
@@ -1,4 +1,6 @@
 use super::*;
+use vastrum_rpc_client::RpcClient;
+use vastrum_shared_types::types::execution::receipt::TxOutcome;
 
 #[tokio::test]
 #[serial]
@@ -16,13 +18,21 @@ async fn test_panic_reverts_state() {
     assert_eq!(state.kvvec.length().await, 1);
 
     // Call method that writes state then panics - all writes should be reverted
-    ctx.client.write_then_panic("alice", 999).await.await_confirmation().await;
+    let sent = ctx.client.write_then_panic("alice", 999).await;
+    sent.await_confirmation().await;
 
     let state = ctx.client.state().await;
     assert_eq!(state.kvmap.get(&"alice".to_string()).await, Some(10));
     assert_eq!(state.counter, 5);
     assert_eq!(state.kvvec.length().await, 1);
     assert_eq!(state.kvvec.get(0).await, Some("baseline".to_string()));
+
+    // Proven receipt carries the panic message rather than the trap it ends in
+    let receipt = RpcClient::new(ctx.site_id).get_tx_receipt(sent.tx_hash()).await.unwrap();
+    let TxOutcome::Failure { message } = receipt.outcome else {
+        panic!("panicking call should fail");
+    };
+    assert!(message.starts_with("intentional panic after writes at "), "{message}");
 }
 
 #[tokio::test]
@@ -49,3 +59,19 @@ async fn test_contract_works_after_panic() {
     assert_eq!(state.kvvec.length().await, 2);
     assert_eq!(state.kvvec.get(1).await, Some("after panic".to_string()));
 }
+
+#[tokio::test]
+#[serial]
+async fn test_err_result_reverts_state() {
+    let ctx = TestContext::new().await;
+
+    ctx.client.kvmap_set("alice", 10).await.await_confirmation().await;
+    ctx.client.add_to_counter(5).await.await_confirmation().await;
+
+    // Method that writes state then returns Err - writes should be reverted
+    ctx.client.write_then_err("alice", 999).await.await_confirmation().await;
+
+    let state = ctx.client.state().await;
+    assert_eq!(state.kvmap.get(&"alice".to_string()).await, Some(10));
+    assert_eq!(state.counter, 5);
+}
@@ -0,0 +1,35 @@
+use super::*;
+use vastrum_native_lib::NativeHttpClient;
+
+#[tokio::test]
+#[serial]
+async fn test_storage_usage_tracks_writes_and_deletes() {
+    let ctx = TestContext::new().await;
+    let http = NativeHttpClient::new();
+    let initial = http.get_storage_usage(ctx.site_id).await.unwrap();
+    assert_eq!(initial.quota_bytes, genesis_site_storage_quota());
+    assert!(initial.used_bytes > 0, "constructor state should count towards usage");
+
+    ctx.client.kv_insert_raw("blob", vec![7; 1000]).await.await_confirmation().await;
+    let after_insert = http.get_storage_usage(ctx.site_id).await.unwrap().used_bytes;
+    assert!(after_insert >= initial.used_bytes + 1000);
+    assert_eq!(ctx.client.storage_used().await.unwrap(), after_insert);
+
+    ctx.client.kv_delete_raw("blob").await.await_confirmation().await;
+    let after_delete = http.get_storage_usage(ctx.site_id).await.unwrap().used_bytes;
+    assert_eq!(after_delete, initial.used_bytes);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reverted_writes_do_not_count() {
+    let ctx = TestContext::new().await;
+    let http = NativeHttpClient::new();
+    let before = http.get_storage_usage(ctx.site_id).await.unwrap().used_bytes;
+
+    ctx.client.write_then_panic("ghost", 1).await.await_confirmation().await;
+    let after = http.get_storage_usage(ctx.site_id).await.unwrap().used_bytes;
+    assert_eq!(after, before);
+}
+
+use vastrum_shared_types::genesis::genesis_site_storage_quota;
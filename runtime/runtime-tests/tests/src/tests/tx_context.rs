@@ -0,0 +1,25 @@
+use super::*;
+use vastrum_native_lib::NativeHttpClient;
+use vastrum_shared_types::crypto::sha256::sha256_hash;
+use vastrum_shared_types::types::rpc::types::TxStatus;
+
+#[tokio::test]
+#[serial]
+async fn test_contract_reads_tx_context() {
+    let ctx = TestContext::new().await;
+    let http = NativeHttpClient::new();
+
+    let poller = ctx.client.record_tx_context().await;
+    let TxStatus::Included { height } = poller.await_confirmation().await else {
+        panic!("record_tx_context was not included");
+    };
+    let read = |key: &str| http.get_key_value(ctx.site_id, key.to_string());
+
+    let block_height = read("ctx.block_height").await.unwrap();
+    assert_eq!(u64::from_le_bytes(block_height.try_into().unwrap()), height);
+    assert_eq!(read("ctx.tx_hash").await.unwrap(), poller.tx_hash().to_bytes());
+    assert_eq!(read("ctx.site_id").await.unwrap(), ctx.site_id.to_bytes());
+    let module_id = sha256_hash(&std::fs::read("../contract/out/contract.wasm").unwrap());
+    assert_eq!(read("ctx.module_id").await.unwrap(), module_id.to_bytes());
+    assert_ne!(read("ctx.previous_block_hash").await.unwrap(), [0; 32]);
+}
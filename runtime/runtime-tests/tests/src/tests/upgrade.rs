@@ -0,0 +1,64 @@
+use super::*;
+use vastrum_native_lib::NativeHttpClient;
+use vastrum_native_lib::deployers::deploy::{
+    deploy_upgradeable_module, poll_until_site_id_deployed, upgrade_site,
+};
+use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::crypto::sha256::sha256_hash;
+use vastrum_shared_types::indexer::types::SiteDetail;
+use vastrum_shared_types::indexer::{indexed_blockchain_site_id, site_detail_key};
+use vastrum_shared_types::types::rpc::types::TxStatus;
+
+const WASM_PATH: &str = "../contract/out/contract.wasm";
+
+async fn deploy_upgradeable(admin: &ed25519::PrivateKey) -> (ContractAbiClient, Sha256Digest) {
+    ensure_network();
+    let constructor_calldata = ("init".to_string(),).encode();
+    let site_id =
+        deploy_upgradeable_module(WASM_PATH, constructor_calldata, admin.public_key()).await;
+    poll_until_site_id_deployed(site_id).await;
+    let module_id = sha256_hash(&std::fs::read(WASM_PATH).unwrap());
+    return (ContractAbiClient::new(site_id), module_id);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_upgrade_runs_migrate_and_is_indexed() {
+    let admin = ed25519::PrivateKey::from_seed(333);
+    let (client, module_id) = deploy_upgradeable(&admin).await;
+    let site_id = client.site_id();
+
+    let migrate_calldata = ("migrated".to_string(),).encode();
+    let status = upgrade_site(site_id, module_id, migrate_calldata, admin.clone())
+        .await
+        .await_confirmation()
+        .await;
+    assert!(matches!(status, TxStatus::Included { .. }), "upgrade should succeed: {status:?}");
+    assert_eq!(client.state().await.message, "migrated");
+
+    let bytes = NativeHttpClient::new()
+        .get_key_value(indexed_blockchain_site_id(), site_detail_key(&site_id.to_string()))
+        .await
+        .unwrap();
+    let detail: SiteDetail = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(detail.admin, Some(admin.public_key().to_string()));
+    assert_eq!(detail.upgrades.len(), 1);
+    assert_eq!(detail.upgrades[0].module_id, module_id.to_string());
+    assert_eq!(detail.module_id, Some(module_id.to_string()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_upgrade_rejected_for_other_key() {
+    let admin = ed25519::PrivateKey::from_seed(333);
+    let (client, module_id) = deploy_upgradeable(&admin).await;
+
+    let other = ed25519::PrivateKey::from_seed(444);
+    let migrate_calldata = ("migrated".to_string(),).encode();
+    let status = upgrade_site(client.site_id(), module_id, migrate_calldata, other)
+        .await
+        .await_confirmation()
+        .await;
+    assert!(matches!(status, TxStatus::Failed { .. }), "upgrade should fail: {status:?}");
+    assert_eq!(client.state().await.message, "init");
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+#[tokio::test]
+#[serial]
+async fn test_view_returns_value() {
+    let ctx = TestContext::new().await;
+
+    assert_eq!(ctx.client.kvmap_lookup("alice").await.unwrap(), None);
+    ctx.client.kvmap_set("alice", 10).await.await_confirmation().await;
+    assert_eq!(ctx.client.kvmap_lookup("alice").await.unwrap(), Some(10));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_view_err_is_decoded() {
+    let ctx = TestContext::new().await;
+
+    ctx.client.add_to_counter(5).await.await_confirmation().await;
+    assert_eq!(ctx.client.counter_below(10).await.unwrap(), Ok(5));
+    assert_eq!(
+        ctx.client.counter_below(5).await.unwrap(),
+        Err("counter 5 is not below 5".to_string())
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_view_cannot_write() {
+    let ctx = TestContext::new().await;
+
+    let error = ctx.client.kvmap_set_in_view("alice", 10).await.unwrap_err();
+    assert!(error.0.contains("view methods cannot change state"), "{error:?}");
+    assert_eq!(ctx.client.kvmap_lookup("alice").await.unwrap(), None);
+}
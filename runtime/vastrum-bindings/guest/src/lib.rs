@@ -4,10 +4,32 @@ mod raw {
     unsafe extern "C" {
         pub fn message_sender(out_ptr: *mut u32, out_len: *mut u32);
         pub fn block_time() -> u64;
+        pub fn block_height() -> u64;
+        pub fn previous_block_hash(out_ptr: *mut u32, out_len: *mut u32);
+        pub fn tx_hash(out_ptr: *mut u32, out_len: *mut u32);
+        pub fn site_id(out_ptr: *mut u32, out_len: *mut u32);
+        pub fn module_id(out_ptr: *mut u32, out_len: *mut u32);
+        pub fn random_seed(out_ptr: *mut u32, out_len: *mut u32);
         pub fn kv_insert(ptr: *const u8, len: u32);
+        pub fn storage_usage() -> u64;
         pub fn kv_get(ptr: *const u8, len: u32, out_ptr: *mut u32, out_len: *mut u32);
+        pub fn kv_scan(ptr: *const u8, len: u32, out_ptr: *mut u32, out_len: *mut u32);
+        pub fn kv_scan_reverse(ptr: *const u8, len: u32, out_ptr: *mut u32, out_len: *mut u32);
         pub fn log(ptr: *const u8, len: u32);
+        pub fn emit_event(ptr: *const u8, len: u32);
         pub fn register_static_route(ptr: *const u8, len: u32);
+        pub fn call_site(ptr: *const u8, len: u32, out_ptr: *mut u32, out_len: *mut u32);
+        pub fn set_return_data(ptr: *const u8, len: u32);
+        pub fn enter_view();
+        pub fn revert(ptr: *const u8, len: u32);
+        pub fn panic(ptr: *const u8, len: u32);
+        pub fn sha256(ptr: *const u8, len: u32, out_ptr: *mut u8);
+        pub fn keccak256(ptr: *const u8, len: u32, out_ptr: *mut u8);
+        pub fn blake3(ptr: *const u8, len: u32, out_ptr: *mut u8);
+        pub fn ed25519_verify(ptr: *const u8, len: u32) -> u32;
+        pub fn ed25519_batch_verify(ptr: *const u8, len: u32) -> u32;
+        pub fn secp256k1_recover(ptr: *const u8, len: u32, out_ptr: *mut u8) -> u32;
+        pub fn x25519(ptr: *const u8, len: u32, out_ptr: *mut u8) -> u32;
     }
 }
 
@@ -34,6 +56,55 @@ pub mod runtime_raw {
         unsafe { super::raw::block_time() }
     }
 
+    pub fn block_height() -> u64 {
+        unsafe { super::raw::block_height() }
+    }
+
+    pub fn previous_block_hash() -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::previous_block_hash(&mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn tx_hash() -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::tx_hash(&mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn site_id() -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::site_id(&mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn module_id() -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::module_id(&mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn random_seed() -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::random_seed(&mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
     pub fn kv_get(args: &[u8]) -> Vec<u8> {
         let mut out_ptr: u32 = 0;
         let mut out_len: u32 = 0;
@@ -43,17 +114,124 @@ pub mod runtime_raw {
         }
     }
 
+    pub fn kv_scan(args: &[u8]) -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::kv_scan(args.as_ptr(), args.len() as u32, &mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn kv_scan_reverse(args: &[u8]) -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::kv_scan_reverse(
+                args.as_ptr(),
+                args.len() as u32,
+                &mut out_ptr,
+                &mut out_len,
+            );
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
     pub fn kv_insert(args: &[u8]) {
         unsafe { super::raw::kv_insert(args.as_ptr(), args.len() as u32) }
     }
 
+    pub fn storage_usage() -> u64 {
+        unsafe { super::raw::storage_usage() }
+    }
+
     pub fn log(args: &[u8]) {
         unsafe { super::raw::log(args.as_ptr(), args.len() as u32) }
     }
 
+    pub fn emit_event(args: &[u8]) {
+        unsafe { super::raw::emit_event(args.as_ptr(), args.len() as u32) }
+    }
+
     pub fn register_static_route(args: &[u8]) {
         unsafe { super::raw::register_static_route(args.as_ptr(), args.len() as u32) }
     }
+
+    pub fn call_site(args: &[u8]) -> Vec<u8> {
+        let mut out_ptr: u32 = 0;
+        let mut out_len: u32 = 0;
+        unsafe {
+            super::raw::call_site(args.as_ptr(), args.len() as u32, &mut out_ptr, &mut out_len);
+            super::read_output(out_ptr, out_len)
+        }
+    }
+
+    pub fn set_return_data(data: &[u8]) {
+        unsafe { super::raw::set_return_data(data.as_ptr(), data.len() as u32) }
+    }
+
+    pub fn enter_view() {
+        unsafe { super::raw::enter_view() }
+    }
+
+    pub fn revert(data: &[u8]) -> ! {
+        unsafe { super::raw::revert(data.as_ptr(), data.len() as u32) }
+        //the host traps on revert
+        unreachable!()
+    }
+
+    pub fn panic(message: &[u8]) -> ! {
+        unsafe { super::raw::panic(message.as_ptr(), message.len() as u32) }
+        //the host traps on panic, called from the panic hook so trap directly instead of panicking again
+        core::arch::wasm32::unreachable()
+    }
+
+    pub fn sha256(data: &[u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        unsafe { super::raw::sha256(data.as_ptr(), data.len() as u32, out.as_mut_ptr()) }
+        out
+    }
+
+    pub fn keccak256(data: &[u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        unsafe { super::raw::keccak256(data.as_ptr(), data.len() as u32, out.as_mut_ptr()) }
+        out
+    }
+
+    pub fn blake3(data: &[u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        unsafe { super::raw::blake3(data.as_ptr(), data.len() as u32, out.as_mut_ptr()) }
+        out
+    }
+
+    pub fn ed25519_verify(args: &[u8]) -> bool {
+        unsafe { super::raw::ed25519_verify(args.as_ptr(), args.len() as u32) == 1 }
+    }
+
+    pub fn ed25519_batch_verify(args: &[u8]) -> bool {
+        unsafe { super::raw::ed25519_batch_verify(args.as_ptr(), args.len() as u32) == 1 }
+    }
+
+    pub fn secp256k1_recover(args: &[u8]) -> Option<[u8; 64]> {
+        let mut out = [0u8; 64];
+        let recovered = unsafe {
+            super::raw::secp256k1_recover(args.as_ptr(), args.len() as u32, out.as_mut_ptr())
+        };
+        if recovered != 1 {
+            return None;
+        }
+        Some(out)
+    }
+
+    pub fn x25519(args: &[u8]) -> Option<[u8; 32]> {
+        let mut out = [0u8; 32];
+        let exchanged =
+            unsafe { super::raw::x25519(args.as_ptr(), args.len() as u32, out.as_mut_ptr()) };
+        if exchanged != 1 {
+            return None;
+        }
+        Some(out)
+    }
 }
 
 //stubs for rust analyzer
@@ -65,16 +243,82 @@ pub mod runtime_raw {
     pub fn block_time() -> u64 {
         unimplemented!()
     }
+    pub fn block_height() -> u64 {
+        unimplemented!()
+    }
+    pub fn previous_block_hash() -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn tx_hash() -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn site_id() -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn module_id() -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn random_seed() -> Vec<u8> {
+        unimplemented!()
+    }
     pub fn kv_get(_args: &[u8]) -> Vec<u8> {
         unimplemented!()
     }
+    pub fn kv_scan(_args: &[u8]) -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn kv_scan_reverse(_args: &[u8]) -> Vec<u8> {
+        unimplemented!()
+    }
     pub fn kv_insert(_args: &[u8]) {
         unimplemented!()
     }
+    pub fn storage_usage() -> u64 {
+        unimplemented!()
+    }
     pub fn log(_args: &[u8]) {
         unimplemented!()
     }
+    pub fn emit_event(_args: &[u8]) {
+        unimplemented!()
+    }
     pub fn register_static_route(_args: &[u8]) {
         unimplemented!()
     }
+    pub fn call_site(_args: &[u8]) -> Vec<u8> {
+        unimplemented!()
+    }
+    pub fn set_return_data(_data: &[u8]) {
+        unimplemented!()
+    }
+    pub fn enter_view() {
+        unimplemented!()
+    }
+    pub fn revert(_data: &[u8]) -> ! {
+        unimplemented!()
+    }
+    pub fn panic(_message: &[u8]) -> ! {
+        unimplemented!()
+    }
+    pub fn sha256(_data: &[u8]) -> [u8; 32] {
+        unimplemented!()
+    }
+    pub fn keccak256(_data: &[u8]) -> [u8; 32] {
+        unimplemented!()
+    }
+    pub fn blake3(_data: &[u8]) -> [u8; 32] {
+        unimplemented!()
+    }
+    pub fn ed25519_verify(_args: &[u8]) -> bool {
+        unimplemented!()
+    }
+    pub fn ed25519_batch_verify(_args: &[u8]) -> bool {
+        unimplemented!()
+    }
+    pub fn secp256k1_recover(_args: &[u8]) -> Option<[u8; 64]> {
+        unimplemented!()
+    }
+    pub fn x25519(_args: &[u8]) -> Option<[u8; 32]> {
+        unimplemented!()
+    }
 }
@@ -1,5 +1,12 @@
-use vastrum_shared_types::limits::MAX_WASM_HOST_BUFFER_SIZE;
-use wasmtime::{AsContext, AsContextMut, Caller, Linker, Module, Store, TypedFunc};
+mod precompiles;
+
+use std::sync::Arc;
+use vastrum_runtime_shared::KeyValueScanCall;
+use vastrum_shared_types::limits::{
+    HOST_BYTE_FUEL, HOST_CALL_BASE_FUEL, KV_SCAN_ENTRY_FUEL, KV_WRITE_BYTE_FUEL, MAX_KV_SCAN_LIMIT,
+    MAX_PANIC_MESSAGE_SIZE, MAX_RETURN_DATA_SIZE, MAX_WASM_HOST_BUFFER_SIZE,
+};
+use wasmtime::{AsContext, AsContextMut, Caller, Linker, Module, Store, Trap, TypedFunc};
 
 pub fn call_contract<T: HostRuntime + 'static>(
     linker: &Linker<T>,
@@ -19,19 +26,105 @@ pub fn construct_contract<T: HostRuntime + 'static>(
     invoke_entry_point(linker, store, module, "construct", constructor_params)
 }
 
-pub trait HostRuntime {
-    fn message_sender(&self) -> Vec<u8>;
-    fn block_time(&self) -> u64;
-    fn kv_insert(&mut self, args: &[u8]);
-    fn kv_get(&self, args: &[u8]) -> Vec<u8>;
+/// Runs the migrate entry point of a module a site was just upgraded to
+pub fn migrate_contract<T: HostRuntime + 'static>(
+    linker: &Linker<T>,
+    store: &mut Store<T>,
+    module: &Module,
+    migrate_params: &[u8],
+) -> wasmtime::Result<()> {
+    invoke_entry_point(linker, store, module, "migrate", migrate_params)
+}
+
+/// Migrate is optional, modules without it are upgraded to as is
+pub fn has_migrate_entry_point(module: &Module) -> bool {
+    module.get_export("migrate").is_some()
+}
+
+pub trait HostRuntime: Sized {
+    fn message_sender(&mut self) -> Vec<u8>;
+    fn block_time(&mut self) -> u64;
+    fn block_height(&mut self) -> u64;
+    fn previous_block_hash(&mut self) -> Vec<u8>;
+    fn tx_hash(&mut self) -> Vec<u8>;
+    fn site_id(&mut self) -> Vec<u8>;
+    fn module_id(&mut self) -> Vec<u8>;
+    fn random_seed(&mut self) -> Vec<u8>;
+    /// Errors trap the contract, writes growing the site past its storage quota are rejected
+    fn kv_insert(&mut self, args: &[u8]) -> wasmtime::Result<()>;
+    fn storage_usage(&mut self) -> u64;
+    fn kv_get(&mut self, args: &[u8]) -> Vec<u8>;
+    /// Encoded KeyValueScanResponse, the limit is already checked against MAX_KV_SCAN_LIMIT
+    fn kv_scan(&mut self, call: KeyValueScanCall, reverse: bool) -> Vec<u8>;
     fn log(&mut self, args: &[u8]);
+    /// Errors trap the contract, events over the protocol limits are rejected
+    /// Returns the number of topics the event is stored under
+    fn emit_event(&mut self, args: &[u8]) -> wasmtime::Result<u64>;
     fn register_static_route(&mut self, args: &[u8]);
+    fn set_return_data(&mut self, data: Vec<u8>);
+    /// Make the rest of the current site call read only, called sites inherit it
+    fn enter_view(&mut self);
+    fn in_view(&self) -> bool;
+    /// Called once for every crypto precompile call
+    fn record_precompile_call(&mut self);
+
+    /// Linker used to instantiate called sites in the same store
+    fn linker(&self) -> Arc<Linker<Self>>;
+    /// Switch the runtime context to the called site, or return an encoded failure response
+    fn enter_site_call(&mut self, args: &[u8]) -> Result<SiteCallTarget, Vec<u8>>;
+    /// Switch back to the calling site and return the encoded response for it
+    fn exit_site_call(&mut self, result: wasmtime::Result<()>) -> Vec<u8>;
+}
+
+pub struct SiteCallTarget {
+    pub module: Module,
+    pub calldata: Vec<u8>,
 }
 
+/// Trap raised when a contract method returns Err, the error is left as return data
+#[derive(Debug)]
+pub struct ContractRevert;
+
+impl std::fmt::Display for ContractRevert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "method returned an error")
+    }
+}
+
+impl std::error::Error for ContractRevert {}
+
+/// Trap raised by the panic hook of a contract, carries the panic message and location
+#[derive(Debug)]
+pub struct ContractPanic {
+    pub message: String,
+}
+
+impl std::fmt::Display for ContractPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ContractPanic {}
+
 pub fn add_to_linker<T: HostRuntime + 'static>(linker: &mut Linker<T>) -> wasmtime::Result<()> {
-    linker.func_wrap("vastrum", "block_time", |caller: Caller<'_, T>| -> u64 {
-        caller.data().block_time()
-    })?;
+    linker.func_wrap(
+        "vastrum",
+        "block_time",
+        |mut caller: Caller<'_, T>| -> Result<u64, wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            Ok(caller.data_mut().block_time())
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "storage_usage",
+        |mut caller: Caller<'_, T>| -> Result<u64, wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            Ok(caller.data_mut().storage_usage())
+        },
+    )?;
 
     linker.func_wrap(
         "vastrum",
@@ -40,7 +133,88 @@ pub fn add_to_linker<T: HostRuntime + 'static>(linker: &mut Linker<T>) -> wasmti
          out_ptr_ptr: u32,
          out_len_ptr: u32|
          -> Result<(), wasmtime::Error> {
-            let bytes = caller.data().message_sender();
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().message_sender();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "block_height",
+        |mut caller: Caller<'_, T>| -> Result<u64, wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            Ok(caller.data_mut().block_height())
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "previous_block_hash",
+        |mut caller: Caller<'_, T>,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().previous_block_hash();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "tx_hash",
+        |mut caller: Caller<'_, T>,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().tx_hash();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "site_id",
+        |mut caller: Caller<'_, T>,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().site_id();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "module_id",
+        |mut caller: Caller<'_, T>,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().module_id();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "random_seed",
+        |mut caller: Caller<'_, T>,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            let bytes = caller.data_mut().random_seed();
+            charge_fuel(&mut caller, byte_fuel(bytes.len() as u32, HOST_BYTE_FUEL))?;
             return_bytes_to_guest(&mut caller, &bytes, out_ptr_ptr, out_len_ptr)
         },
     )?;
@@ -49,9 +223,10 @@ pub fn add_to_linker<T: HostRuntime + 'static>(linker: &mut Linker<T>) -> wasmti
         "vastrum",
         "kv_insert",
         |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            deny_in_view(&caller)?;
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, KV_WRITE_BYTE_FUEL))?;
             let buf = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
-            caller.data_mut().kv_insert(&buf);
-            Ok(())
+            caller.data_mut().kv_insert(&buf)
         },
     )?;
 
@@ -64,58 +239,240 @@ pub fn add_to_linker<T: HostRuntime + 'static>(linker: &mut Linker<T>) -> wasmti
          out_ptr_ptr: u32,
          out_len_ptr: u32|
          -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
             let args = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
-            let value = caller.data().kv_get(&args);
+            let value = caller.data_mut().kv_get(&args);
+            charge_fuel(&mut caller, byte_fuel(value.len() as u32, HOST_BYTE_FUEL))?;
             return_bytes_to_guest(&mut caller, &value, out_ptr_ptr, out_len_ptr)
         },
     )?;
 
+    linker.func_wrap(
+        "vastrum",
+        "kv_scan",
+        |caller: Caller<'_, T>,
+         ptr: u32,
+         len: u32,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            kv_scan(caller, ptr, len, out_ptr_ptr, out_len_ptr, false)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "kv_scan_reverse",
+        |caller: Caller<'_, T>,
+         ptr: u32,
+         len: u32,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            kv_scan(caller, ptr, len, out_ptr_ptr, out_len_ptr, true)
+        },
+    )?;
+
     linker.func_wrap(
         "vastrum",
         "log",
         |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
             let buf = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
             caller.data_mut().log(&buf);
             Ok(())
         },
     )?;
 
+    linker.func_wrap(
+        "vastrum",
+        "emit_event",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            deny_in_view(&caller)?;
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
+            let buf = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
+            //a copy of the event is written under each of its topics
+            let copies = caller.data_mut().emit_event(&buf)?;
+            charge_fuel(&mut caller, copies * byte_fuel(len, KV_WRITE_BYTE_FUEL))
+        },
+    )?;
+
     linker.func_wrap(
         "vastrum",
         "register_static_route",
         |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            deny_in_view(&caller)?;
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, KV_WRITE_BYTE_FUEL))?;
             let buf = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
             caller.data_mut().register_static_route(&buf);
             Ok(())
         },
     )?;
 
+    linker.func_wrap(
+        "vastrum",
+        "set_return_data",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            let data = read_return_data(&mut caller, ptr, len)?;
+            caller.data_mut().set_return_data(data);
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "enter_view",
+        |mut caller: Caller<'_, T>| -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL)?;
+            caller.data_mut().enter_view();
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "revert",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            let data = read_return_data(&mut caller, ptr, len)?;
+            caller.data_mut().set_return_data(data);
+            Err(wasmtime::Error::new(ContractRevert))
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "panic",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> Result<(), wasmtime::Error> {
+            let len = len.min(MAX_PANIC_MESSAGE_SIZE);
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
+            let buf = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
+            let message = String::from_utf8_lossy(&buf).into_owned();
+            Err(wasmtime::Error::new(ContractPanic { message }))
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "call_site",
+        |mut caller: Caller<'_, T>,
+         ptr: u32,
+         len: u32,
+         out_ptr_ptr: u32,
+         out_len_ptr: u32|
+         -> Result<(), wasmtime::Error> {
+            charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
+            let args = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
+            let response = match caller.data_mut().enter_site_call(&args) {
+                Ok(SiteCallTarget { module, calldata }) => {
+                    let linker = caller.data().linker();
+                    let result =
+                        invoke_entry_point(&linker, &mut caller, &module, "makecall", &calldata);
+                    let out_of_fuel = is_out_of_fuel(&result);
+                    let response = caller.data_mut().exit_site_call(result);
+                    //the caller could not continue anyway, fail the whole call stack
+                    if out_of_fuel {
+                        return Err(wasmtime::Error::new(Trap::OutOfFuel));
+                    }
+                    response
+                }
+                Err(response) => response,
+            };
+            charge_fuel(&mut caller, byte_fuel(response.len() as u32, HOST_BYTE_FUEL))?;
+            return_bytes_to_guest(&mut caller, &response, out_ptr_ptr, out_len_ptr)
+        },
+    )?;
+
+    precompiles::add_precompiles_to_linker(linker)?;
+
     Ok(())
 }
 
 fn invoke_entry_point<T: HostRuntime + 'static>(
     linker: &Linker<T>,
-    store: &mut Store<T>,
+    mut store: impl AsContextMut<Data = T>,
     module: &Module,
     entry_point: &str,
     calldata: &[u8],
 ) -> wasmtime::Result<()> {
-    let instance = linker.instantiate(&mut *store, module)?;
-    let func = instance.get_typed_func::<(u32, u32), ()>(&mut *store, entry_point)?;
+    let instance = linker.instantiate(&mut store, module)?;
+    let func = instance.get_typed_func::<(u32, u32), ()>(&mut store, entry_point)?;
 
     if calldata.is_empty() {
-        func.call(&mut *store, (0, 0))?;
+        func.call(&mut store, (0, 0))?;
     } else {
-        let alloc = get_instance_alloc(&instance, store)?;
-        let ptr = alloc.call(&mut *store, calldata.len() as u32)?;
-        let memory = get_instance_memory(&instance, store)?;
-        memory.write(&mut *store, ptr as usize, calldata)?;
-        func.call(&mut *store, (ptr, calldata.len() as u32))?;
+        let alloc = get_instance_alloc(&instance, &mut store)?;
+        let ptr = alloc.call(&mut store, calldata.len() as u32)?;
+        let memory = get_instance_memory(&instance, &mut store)?;
+        memory.write(&mut store, ptr as usize, calldata)?;
+        func.call(&mut store, (ptr, calldata.len() as u32))?;
+    }
+
+    Ok(())
+}
+
+fn kv_scan<T: HostRuntime>(
+    mut caller: Caller<'_, T>,
+    ptr: u32,
+    len: u32,
+    out_ptr_ptr: u32,
+    out_len_ptr: u32,
+    reverse: bool,
+) -> Result<(), wasmtime::Error> {
+    charge_fuel(&mut caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL))?;
+    let args = read_bytes_from_guest_memory(&mut caller, ptr, len)?;
+    let call: KeyValueScanCall = borsh::from_slice(&args)?;
+    if call.limit > MAX_KV_SCAN_LIMIT {
+        return Err(wasmtime::Error::msg("kv scan limit too large"));
     }
+    //charged for the requested entries, a scan that finds fewer is not refunded
+    charge_fuel(&mut caller, call.limit as u64 * KV_SCAN_ENTRY_FUEL)?;
+    let response = caller.data_mut().kv_scan(call, reverse);
+    charge_fuel(&mut caller, byte_fuel(response.len() as u32, HOST_BYTE_FUEL))?;
+    return_bytes_to_guest(&mut caller, &response, out_ptr_ptr, out_len_ptr)
+}
 
+/// State changing host calls trap inside `#[view]` methods
+fn deny_in_view<T: HostRuntime>(caller: &Caller<'_, T>) -> Result<(), wasmtime::Error> {
+    if caller.data().in_view() {
+        return Err(wasmtime::Error::msg("view methods cannot change state"));
+    }
     Ok(())
 }
 
+fn is_out_of_fuel(result: &wasmtime::Result<()>) -> bool {
+    let Err(e) = result else {
+        return false;
+    };
+    matches!(e.downcast_ref::<Trap>(), Some(Trap::OutOfFuel))
+}
+
+fn byte_fuel(len: u32, fuel_per_byte: u64) -> u64 {
+    len as u64 * fuel_per_byte
+}
+
+/// Host work is charged from the same fuel budget as wasm instructions
+fn charge_fuel<T>(caller: &mut Caller<'_, T>, fuel: u64) -> Result<(), wasmtime::Error> {
+    let remaining = caller.get_fuel()?;
+    if remaining < fuel {
+        caller.set_fuel(0)?;
+        return Err(wasmtime::Error::new(Trap::OutOfFuel));
+    }
+    caller.set_fuel(remaining - fuel)?;
+    Ok(())
+}
+
+fn read_return_data<T>(
+    caller: &mut Caller<'_, T>,
+    ptr: u32,
+    len: u32,
+) -> Result<Vec<u8>, wasmtime::Error> {
+    if len > MAX_RETURN_DATA_SIZE {
+        return Err(wasmtime::Error::msg("return data too large"));
+    }
+    charge_fuel(caller, HOST_CALL_BASE_FUEL + byte_fuel(len, KV_WRITE_BYTE_FUEL))?;
+    read_bytes_from_guest_memory(caller, ptr, len)
+}
+
 fn read_bytes_from_guest_memory<T>(
     caller: &mut Caller<'_, T>,
     ptr: u32,
@@ -190,9 +547,9 @@ fn get_guest_memory<T>(caller: &mut Caller<'_, T>) -> Result<wasmtime::Memory, w
 
 fn get_instance_memory<T>(
     instance: &wasmtime::Instance,
-    store: &mut Store<T>,
+    store: impl AsContextMut<Data = T>,
 ) -> Result<wasmtime::Memory, wasmtime::Error> {
-    let Some(memory) = instance.get_memory(&mut *store, "memory") else {
+    let Some(memory) = instance.get_memory(store, "memory") else {
         return Err(wasmtime::Error::msg("missing memory export"));
     };
     Ok(memory)
@@ -200,8 +557,8 @@ fn get_instance_memory<T>(
 
 fn get_instance_alloc<T>(
     instance: &wasmtime::Instance,
-    store: &mut Store<T>,
+    store: impl AsContextMut<Data = T>,
 ) -> Result<TypedFunc<u32, u32>, wasmtime::Error> {
-    let alloc = instance.get_typed_func::<u32, u32>(&mut *store, "__alloc")?;
+    let alloc = instance.get_typed_func::<u32, u32>(store, "__alloc")?;
     Ok(alloc)
 }
@@ -0,0 +1,136 @@
+use crate::{
+    HostRuntime, byte_fuel, charge_fuel, read_bytes_from_guest_memory, write_bytes_to_guest_memory,
+};
+use k256::ecdsa::VerifyingKey;
+use sha3::{Digest, Keccak256};
+use vastrum_runtime_shared::{
+    Ed25519BatchVerifyCall, Ed25519VerifyCall, Secp256k1RecoverCall, X25519Call,
+};
+use vastrum_shared_types::crypto::sha256::sha256_hash;
+use vastrum_shared_types::crypto::{ed25519, x25519};
+use vastrum_shared_types::limits::{
+    ED25519_VERIFY_FUEL, HASH_PRECOMPILE_FUEL, HOST_BYTE_FUEL, HOST_CALL_BASE_FUEL,
+    SECP256K1_RECOVER_FUEL, X25519_FUEL,
+};
+use wasmtime::{Caller, Linker};
+
+//native implementations of common crypto so modules do not have to compile it into wasm
+//fixed size outputs are written straight into a guest buffer, no allocation needed
+pub fn add_precompiles_to_linker<T: HostRuntime + 'static>(
+    linker: &mut Linker<T>,
+) -> wasmtime::Result<()> {
+    linker.func_wrap(
+        "vastrum",
+        "sha256",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32, out_ptr: u32| -> wasmtime::Result<()> {
+            let data = read_precompile_args(&mut caller, ptr, len, HASH_PRECOMPILE_FUEL)?;
+            let hash = sha256_hash(&data).to_bytes();
+            write_bytes_to_guest_memory(&mut caller, out_ptr, &hash)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "keccak256",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32, out_ptr: u32| -> wasmtime::Result<()> {
+            let data = read_precompile_args(&mut caller, ptr, len, HASH_PRECOMPILE_FUEL)?;
+            let hash: [u8; 32] = Keccak256::digest(&data).into();
+            write_bytes_to_guest_memory(&mut caller, out_ptr, &hash)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "blake3",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32, out_ptr: u32| -> wasmtime::Result<()> {
+            let data = read_precompile_args(&mut caller, ptr, len, HASH_PRECOMPILE_FUEL)?;
+            let hash: [u8; 32] = blake3::hash(&data).into();
+            write_bytes_to_guest_memory(&mut caller, out_ptr, &hash)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "ed25519_verify",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> wasmtime::Result<u32> {
+            let args = read_precompile_args(&mut caller, ptr, len, ED25519_VERIFY_FUEL)?;
+            let call: Ed25519VerifyCall = borsh::from_slice(&args)?;
+            let pub_key = ed25519::PublicKey::from(call.pub_key);
+            let valid = pub_key.verify_signature(&call.message, call.signature.into());
+            Ok(valid as u32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "ed25519_batch_verify",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32| -> wasmtime::Result<u32> {
+            let args = read_precompile_args(&mut caller, ptr, len, 0)?;
+            let call: Ed25519BatchVerifyCall = borsh::from_slice(&args)?;
+            //the fixed cost is per signature, charged once the batch size is known
+            charge_fuel(&mut caller, call.items.len() as u64 * ED25519_VERIFY_FUEL)?;
+            let items: Vec<(ed25519::PublicKey, Vec<u8>, ed25519::Signature)> = call
+                .items
+                .into_iter()
+                .map(|item| (item.pub_key.into(), item.message, item.signature.into()))
+                .collect();
+            let valid = ed25519::verify_batch_messages(&items);
+            Ok(valid as u32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "secp256k1_recover",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32, out_ptr: u32| -> wasmtime::Result<u32> {
+            let args = read_precompile_args(&mut caller, ptr, len, SECP256K1_RECOVER_FUEL)?;
+            let call: Secp256k1RecoverCall = borsh::from_slice(&args)?;
+            let Some(pub_key) = secp256k1_recover(&call) else {
+                return Ok(0);
+            };
+            write_bytes_to_guest_memory(&mut caller, out_ptr, &pub_key)?;
+            Ok(1)
+        },
+    )?;
+
+    linker.func_wrap(
+        "vastrum",
+        "x25519",
+        |mut caller: Caller<'_, T>, ptr: u32, len: u32, out_ptr: u32| -> wasmtime::Result<u32> {
+            let args = read_precompile_args(&mut caller, ptr, len, X25519_FUEL)?;
+            let call: X25519Call = borsh::from_slice(&args)?;
+            let secret = x25519::PrivateKey::from_bytes(call.secret);
+            let shared = secret.diffie_hellman(x25519::PublicKey::from_bytes(call.public_key));
+            //low order public keys give an all zero secret, reject them like a failed exchange
+            if shared == [0u8; 32] {
+                return Ok(0);
+            }
+            write_bytes_to_guest_memory(&mut caller, out_ptr, &shared)?;
+            Ok(1)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn read_precompile_args<T: HostRuntime>(
+    caller: &mut Caller<'_, T>,
+    ptr: u32,
+    len: u32,
+    fixed_fuel: u64,
+) -> wasmtime::Result<Vec<u8>> {
+    charge_fuel(caller, HOST_CALL_BASE_FUEL + byte_fuel(len, HOST_BYTE_FUEL) + fixed_fuel)?;
+    caller.data_mut().record_precompile_call();
+    read_bytes_from_guest_memory(caller, ptr, len)
+}
+
+/// Uncompressed public key without the 0x04 prefix, x || y
+fn secp256k1_recover(call: &Secp256k1RecoverCall) -> Option<[u8; 64]> {
+    let signature = k256::ecdsa::Signature::from_slice(&call.signature).ok()?;
+    let recovery_id = k256::ecdsa::RecoveryId::from_byte(call.recovery_id)?;
+    let key =
+        VerifyingKey::recover_from_prehash(&call.message_hash, &signature, recovery_id).ok()?;
+    let point = key.to_encoded_point(false);
+    let pub_key: [u8; 64] = point.as_bytes()[1..].try_into().ok()?;
+    return Some(pub_key);
+}
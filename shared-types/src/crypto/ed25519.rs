@@ -125,6 +125,54 @@ impl Signature {
     }
 }
 
+/// Verifies all signatures in one multiscalar multiplication, false if any signature is invalid
+pub fn verify_batch(items: &[(PublicKey, Sha256Digest, Signature)]) -> bool {
+    let mut verifier = ed25519_consensus::batch::Verifier::new();
+    for (pub_key, message, signature) in items {
+        verifier.queue((pub_key.verifying_key(), signature.signature, &message.encode()));
+    }
+    let rng = TranscriptRng { seed: sha256_hash(&borsh::to_vec(items).unwrap()), counter: 0 };
+    return verifier.verify(rng).is_ok();
+}
+
+/// Same as verify_batch for arbitrary messages, used by the contract ed25519 precompile
+pub fn verify_batch_messages(items: &[(PublicKey, Vec<u8>, Signature)]) -> bool {
+    let mut verifier = ed25519_consensus::batch::Verifier::new();
+    for (pub_key, message, signature) in items {
+        verifier.queue((pub_key.verifying_key(), signature.signature, message));
+    }
+    let rng = TranscriptRng { seed: sha256_hash(&borsh::to_vec(items).unwrap()), counter: 0 };
+    return verifier.verify(rng).is_ok();
+}
+
+//batch verification coefficients derived from a hash of the whole batch instead of os randomness
+//so verification is deterministic and works in wasm, signers can not predict the coefficients
+//without fixing every signature in the batch first
+struct TranscriptRng {
+    seed: Sha256Digest,
+    counter: u64,
+}
+impl rand_core_06::RngCore for TranscriptRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core_06::impls::next_u32_via_fill(self)
+    }
+    fn next_u64(&mut self) -> u64 {
+        rand_core_06::impls::next_u64_via_fill(self)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let block = sha256_hash(&(self.seed, self.counter).encode());
+            self.counter += 1;
+            chunk.copy_from_slice(&block.to_bytes()[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core_06::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+impl rand_core_06::CryptoRng for TranscriptRng {}
+
 impl BorshSerialize for PrivateKey {
     fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         let bytes = self.key.to_bytes();
@@ -165,7 +213,7 @@ impl BorshDeserialize for Signature {
 
 #[allow(unused_imports)]
 use crate::borsh::*;
-use crate::crypto::sha256::Sha256Digest;
+use crate::crypto::sha256::{Sha256Digest, sha256_hash};
 use borsh::BorshDeserialize;
 use borsh::BorshSerialize;
 use serde::Deserialize;
@@ -38,6 +38,16 @@ impl Sha256Digest {
     pub fn to_bytes(&self) -> [u8; 32] {
         return self.data;
     }
+    pub fn leading_zero_bits(&self) -> u32 {
+        let mut bits = 0;
+        for byte in self.data {
+            if byte != 0 {
+                return bits + byte.leading_zeros();
+            }
+            bits += 8;
+        }
+        return bits;
+    }
     pub fn from_rng() -> Sha256Digest {
         let mut bytes = [0u8; 32];
         rand::fill(&mut bytes);
@@ -14,9 +14,12 @@ pub struct ValidatorInfo {
 /// Data injected by the node into the served HTML via `window.__frontendData`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FrontendData {
+    pub chain_id: u64,
     pub rpc_nodes: Vec<RpcNodeEndpoint>,
     pub helios_checkpoint: String,
-    pub genesis_validators: HashMap<u64, ValidatorInfo>,
+    /// Validators of validators_epoch, clients extend them to later epochs with epoch handoffs
+    pub validators_epoch: u64,
+    pub validators: HashMap<u64, ValidatorInfo>,
     pub total_validator_stake: u64,
 }
 
@@ -1,9 +1,15 @@
 use crate::frontend::frontend_data::ValidatorInfo;
+use crate::limits::DEFAULT_SITE_STORAGE_QUOTA_BYTES;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GenesisConfig {
+    pub chain_id: u64,
+    /// Bytes a site may hold in its kv store, writes that grow a site past it trap
+    #[serde(default = "default_site_storage_quota_bytes")]
+    pub site_storage_quota_bytes: u64,
     pub validators: Vec<GenesisValidator>,
     pub bootstrap_peers: Vec<GenesisBootstrapPeer>,
     pub rpc_nodes: Vec<GenesisRpcNode>,
@@ -29,12 +35,23 @@ pub struct GenesisRpcNode {
     pub fingerprint: String,
 }
 
-pub fn genesis_config() -> GenesisConfig {
-    let json = if std::env::var("VASTRUM_LOCALNET").is_ok() {
-        include_str!("../genesis-dev.json")
-    } else {
-        include_str!("../genesis.json")
-    };
+static GENESIS_CONFIG: OnceLock<GenesisConfig> = OnceLock::new();
+static GENESIS_CONFIG_DEV: OnceLock<GenesisConfig> = OnceLock::new();
+
+/// Parsed once per network, localnet is still selected by VASTRUM_LOCALNET at call time
+pub fn genesis_config() -> &'static GenesisConfig {
+    if std::env::var("VASTRUM_LOCALNET").is_ok() {
+        return GENESIS_CONFIG_DEV
+            .get_or_init(|| parse_genesis(include_str!("../genesis-dev.json")));
+    }
+    return GENESIS_CONFIG.get_or_init(|| parse_genesis(include_str!("../genesis.json")));
+}
+
+fn default_site_storage_quota_bytes() -> u64 {
+    return DEFAULT_SITE_STORAGE_QUOTA_BYTES;
+}
+
+fn parse_genesis(json: &str) -> GenesisConfig {
     serde_json::from_str(json).expect("invalid genesis json")
 }
 
@@ -67,3 +84,11 @@ pub struct GenesisEpochState {
 pub fn genesis_epoch_state() -> GenesisEpochState {
     return genesis_config().to_genesis_epoch_state();
 }
+
+pub fn genesis_chain_id() -> u64 {
+    return genesis_config().chain_id;
+}
+
+pub fn genesis_site_storage_quota() -> u64 {
+    return genesis_config().site_storage_quota_bytes;
+}
@@ -1,4 +1,7 @@
 use crate::crypto::{ed25519, encryption::CipherText, sha256::Sha256Digest, x25519};
+use crate::types::execution::event::BlockEvent;
+use crate::types::execution::receipt::TxReceipt;
+use crate::types::rpc::types::TxStatus;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -17,6 +20,11 @@ pub enum RpcMethod {
     OpenExternalUrl,
     GetLatestBlockHeight,
     GetSitePrivateKey,
+    GetTxStatus,
+    GetTxReceipt,
+    SimulateCall,
+    GetEvents,
+    ScanKeys,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcMethodHostToIFrame {
@@ -152,6 +160,58 @@ pub struct GetTXHashIsConfirmedResponse {
     pub is_finalized: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTxStatusRequest {
+    pub tx_hash: Sha256Digest,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTxStatusResponse {
+    pub status: TxStatus,
+}
+
+/// Receipt verified against the state root by the host
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTxReceiptRequest {
+    pub tx_hash: Sha256Digest,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTxReceiptResponse {
+    pub receipt: TxReceipt,
+}
+
+/// Response is the node's SimulateCallResponse
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SimulateCallRequest {
+    #[serde(with = "crate::types::rpc::serde_base64::base64_vec")]
+    pub call_data: Vec<u8>,
+}
+
+/// Events of the current site, verified against the state root by the host
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEventsRequest {
+    pub topic: Sha256Digest,
+    pub from_height: u64,
+    pub to_height: u64,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEventsResponse {
+    /// Oldest first
+    pub events: Vec<BlockEvent>,
+}
+
+/// Keys of the current site in order, the key list itself is not proven
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScanKeysRequest {
+    pub prefix: String,
+    pub start: Option<String>,
+    pub limit: u32,
+    pub reverse: bool,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScanKeysResponse {
+    pub keys: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetCurrentPath {}
 #[derive(Serialize, Deserialize, Debug)]
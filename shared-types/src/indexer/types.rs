@@ -1,3 +1,4 @@
+use crate::types::execution::receipt::HostCallCounts;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -27,19 +28,39 @@ pub struct TxDetail {
     pub sender: Option<String>,
     pub tx_type: String,
     pub target_site: Option<String>,
+    pub version: u8,
     pub nonce: String,
     pub recent_block_height: u64,
     pub function_sig: Option<String>,
+    /// From the tx receipt, false if execution failed and was reverted
+    pub success: bool,
+    pub error: Option<String>,
+    pub fuel_used: u64,
+    pub kv_bytes_written: u64,
+    pub host_calls: HostCallCounts,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SiteDetail {
     pub site_id: String,
+    /// Current module of the site
     pub module_id: Option<String>,
     pub deploy_tx: String,
     pub block_height: u64,
     pub domain: Option<String>,
     pub tx_count: u64,
+    pub admin: Option<String>,
+    /// Successful upgrades, oldest first
+    #[serde(default)]
+    pub upgrades: Vec<SiteUpgrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SiteUpgrade {
+    pub tx_hash: String,
+    pub block_height: u64,
+    pub previous_module_id: Option<String>,
+    pub module_id: String,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
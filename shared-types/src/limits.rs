@@ -2,12 +2,66 @@ pub const MAX_TRANSACTIONS_PER_BLOCK: usize = 1000;
 pub const MAX_BLOCK_SIZE: usize = 4 * 1024 * 1024; //4mb
 pub const VALIDITY_WINDOW: u64 = 300;
 
+//pow difficulty is the number of leading zero bits a transaction pow hash needs
+//it steps up after a block at least POW_RAISE_FULLNESS_PERCENT full and down below POW_LOWER_FULLNESS_PERCENT
+pub const MAX_POW_DIFFICULTY: u8 = 32;
+pub const POW_RAISE_FULLNESS_PERCENT: usize = 50;
+pub const POW_LOWER_FULLNESS_PERCENT: usize = 25;
+
+//validator set changes take effect every EPOCH_LENGTH blocks
+pub const EPOCH_LENGTH: u64 = 1000;
+
+//block at height h commits to the state root after executing block h - STATE_ROOT_DELAY
+//so block h + 1 can be proposed while block h is still being finalized and executed
+pub const STATE_ROOT_DELAY: u64 = 2;
+
 pub const MAX_TRANSACTION_SIZE: usize = 4 * 1024 * 1024; //4mb
 pub const MAX_DECOMPRESSED_CALLDATA_SIZE: usize = 16 * 1024 * 1024; //16mb
 
 pub const MAX_WASM_MODULE_SIZE: usize = 1 * 1024 * 1024; //1mb
 pub const MAX_WASM_MEMORY: usize = 256 * 1024 * 1024; //256mb
 pub const MAX_WASM_HOST_BUFFER_SIZE: u32 = MAX_WASM_MEMORY as u32;
+//total over every instance a transaction creates, each site call instantiates the callee
+pub const MAX_TX_WASM_MEMORY: usize = 512 * 1024 * 1024; //512mb
+
+//fuel meters contract execution deterministically, roughly one unit per wasm instruction
+//host calls are charged a base cost plus a cost per byte moved across the host boundary
+pub const DEFAULT_TX_FUEL_LIMIT: u64 = 10_000_000;
+pub const MAX_TX_FUEL_LIMIT: u64 = 1_000_000_000;
+pub const MAX_BLOCK_FUEL: u64 = 10_000_000_000;
+pub const HOST_CALL_BASE_FUEL: u64 = 1_000;
+pub const HOST_BYTE_FUEL: u64 = 10;
+pub const KV_WRITE_BYTE_FUEL: u64 = 100;
+//kv scans are charged per requested entry up front, a seek and a value read each
+pub const KV_SCAN_ENTRY_FUEL: u64 = 1_000;
+pub const MAX_KV_SCAN_LIMIT: u32 = 256;
+
+//crypto precompiles are charged a fixed cost on top of the base and byte cost
+//priced well below running the same code as wasm, roughly by native time per call
+pub const HASH_PRECOMPILE_FUEL: u64 = 2_000;
+pub const ED25519_VERIFY_FUEL: u64 = 50_000; //per signature, also in batches
+pub const SECP256K1_RECOVER_FUEL: u64 = 80_000;
+pub const X25519_FUEL: u64 = 40_000;
+
+//storage usage counts encoded key + value bytes of every kv entry of a site
+//writes that grow a site past the quota trap, shrinking writes are always allowed
+//the quota is set in the genesis config, this is used when it is not given
+pub const DEFAULT_SITE_STORAGE_QUOTA_BYTES: u64 = 1024 * 1024 * 1024; //1gb
+
+//sites can call other sites synchronously, a site may not appear twice in the call stack
+pub const MAX_SITE_CALL_DEPTH: usize = 8;
+pub const MAX_SITE_CALLS_PER_TX: usize = 64;
+
+//method return values are committed to state in tx receipts
+pub const MAX_RETURN_DATA_SIZE: u32 = 64 * 1024; //64kb
+
+//longer panic messages are cut, receipts keep only the start of them anyway
+pub const MAX_PANIC_MESSAGE_SIZE: u32 = 2 * 1024; //2kb
+
+//events are committed to state per block, indexed by site and topic
+pub const MAX_EVENT_TOPICS: usize = 4;
+pub const MAX_EVENT_DATA_SIZE: usize = 16 * 1024; //16kb
+pub const MAX_EVENTS_PER_TX: usize = 256;
 
 pub const KV_RETENTION_WINDOW: u64 = 64;
 
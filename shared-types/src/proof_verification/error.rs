@@ -12,4 +12,10 @@ pub enum ProofVerificationError {
         "future timestamp: block timestamp {block_ts} is {ahead}s ahead (max {MAX_PROOF_FUTURE_SECS}s)"
     )]
     FutureTimestamp { block_ts: u64, ahead: u64 },
+    #[error("incomplete event range: {0}")]
+    IncompleteEvents(&'static str),
+    #[error("invalid epoch handoff: {0}")]
+    InvalidEpochHandoff(&'static str),
+    #[error("validator set of epoch {0} is not known")]
+    UnknownEpoch(u64),
 }
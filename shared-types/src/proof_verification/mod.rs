@@ -1,5 +1,10 @@
 mod error;
+mod validator_sets;
 mod verify;
 
 pub use error::ProofVerificationError;
-pub use verify::{verify_keyvalue_proof, verify_page_proof};
+pub use validator_sets::{EpochValidators, KnownValidatorSets, epoch_for_height};
+pub use verify::{
+    verify_epoch_handoff, verify_events_proof, verify_keyvalue_proof, verify_page_proof,
+    verify_receipt_proof,
+};
@@ -0,0 +1,190 @@
+/// Validators proofs of blocks in epoch are verified against
+#[derive(Clone, Debug)]
+pub struct EpochValidators {
+    pub epoch: u64,
+    pub validators: HashMap<u64, ValidatorInfo>,
+    pub total_stake: u64,
+}
+
+impl EpochValidators {
+    pub fn from_validator_set(
+        epoch: u64,
+        validator_set: &PersistedValidatorSet,
+    ) -> EpochValidators {
+        let mut validators = HashMap::new();
+        let mut total_stake = 0;
+        for (validator_index, validator) in validator_set.validators.iter().enumerate() {
+            let validator_index = validator_index as u64;
+            validators.insert(
+                validator_index,
+                ValidatorInfo {
+                    validator_index,
+                    pub_key: validator.pub_key.to_bytes(),
+                    stake: validator.stake,
+                },
+            );
+            total_stake += validator.stake;
+        }
+        return EpochValidators { epoch, validators, total_stake };
+    }
+}
+
+/// Validator sets a client trusts, starting from a trusted set (genesis or frontend data)
+/// and extended one epoch at a time through verified epoch handoffs
+#[derive(Clone, Debug)]
+pub struct KnownValidatorSets {
+    sets: BTreeMap<u64, EpochValidators>,
+}
+
+impl KnownValidatorSets {
+    pub fn new(trusted: EpochValidators) -> KnownValidatorSets {
+        return KnownValidatorSets { sets: BTreeMap::from([(trusted.epoch, trusted)]) };
+    }
+
+    pub fn get(&self, epoch: u64) -> Option<&EpochValidators> {
+        return self.sets.get(&epoch);
+    }
+
+    pub fn latest_epoch(&self) -> u64 {
+        return *self.sets.keys().next_back().unwrap();
+    }
+
+    /// Epoch whose handoff has to be added before the validators of epoch are known
+    /// errors if epoch is before the trusted set, sets are never walked backwards
+    pub fn next_missing_epoch(&self, epoch: u64) -> Result<Option<u64>, ProofVerificationError> {
+        if self.sets.contains_key(&epoch) {
+            return Ok(None);
+        }
+        let latest_epoch = self.latest_epoch();
+        if epoch < latest_epoch {
+            return Err(ProofVerificationError::UnknownEpoch(epoch));
+        }
+        return Ok(Some(latest_epoch + 1));
+    }
+
+    /// Verifies handoff against the latest known set and adds the set it hands off to
+    pub fn add_handoff(
+        &mut self,
+        handoff: &EpochHandoff,
+        chain_id: u64,
+    ) -> Result<(), ProofVerificationError> {
+        let latest = &self.sets[&self.latest_epoch()];
+        if handoff.epoch != latest.epoch + 1 {
+            return Err(ProofVerificationError::InvalidEpochHandoff(
+                "does not follow the latest known epoch",
+            ));
+        }
+        verify_epoch_handoff(handoff, chain_id, &latest.validators, latest.total_stake)?;
+        let next = EpochValidators::from_validator_set(handoff.epoch, &handoff.validator_set);
+        self.sets.insert(handoff.epoch, next);
+        return Ok(());
+    }
+}
+
+pub fn epoch_for_height(height: u64) -> u64 {
+    return height / EPOCH_LENGTH;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ed25519;
+    use crate::crypto::sha256::Sha256Digest;
+    use crate::types::consensus::{BlockHeader, CertificateVotes, PersistedValidator};
+    use crate::types::rpc::types::StateProof;
+
+    fn validator_set(seeds: &[u8]) -> PersistedValidatorSet {
+        let validators = seeds
+            .iter()
+            .map(|seed| PersistedValidator {
+                pub_key: ed25519::PrivateKey::from_seed(*seed as u64).public_key(),
+                p2p_key: ed25519::PrivateKey::from_seed(*seed as u64 + 100).public_key(),
+                stake: 1,
+            })
+            .collect();
+        return PersistedValidatorSet { validators };
+    }
+
+    fn handoff(epoch: u64, block_height: u64) -> EpochHandoff {
+        //empty proof, tests here fail before the merkle check
+        let proof = borsh::from_slice(&[0, 0, 0, 0, 0]).unwrap();
+        let block_header = BlockHeader {
+            height: block_height,
+            previous_block_hash: Sha256Digest::default(),
+            timestamp: 0,
+            previous_block_state_root: Sha256Digest::default(),
+            pow_difficulty: 0,
+            transactions_hash: Sha256Digest::default(),
+            grandparent_finalization_hash: Sha256Digest::default(),
+        };
+        let state_proof = StateProof {
+            proof,
+            block_header,
+            round: 0,
+            finalization_votes: CertificateVotes::default(),
+        };
+        return EpochHandoff { epoch, validator_set: validator_set(&[4]), state_proof };
+    }
+
+    fn known_genesis() -> KnownValidatorSets {
+        return KnownValidatorSets::new(EpochValidators::from_validator_set(
+            0,
+            &validator_set(&[1, 2, 3]),
+        ));
+    }
+
+    #[test]
+    fn test_from_validator_set_indexes_by_position() {
+        let validators = EpochValidators::from_validator_set(5, &validator_set(&[1, 2, 3]));
+        assert_eq!(validators.epoch, 5);
+        assert_eq!(validators.total_stake, 3);
+        let expected = ed25519::PrivateKey::from_seed(2).public_key().to_bytes();
+        assert_eq!(validators.validators[&1].pub_key, expected);
+    }
+
+    #[test]
+    fn test_next_missing_epoch() {
+        let known = known_genesis();
+        assert_eq!(known.next_missing_epoch(0).unwrap(), None);
+        assert_eq!(known.next_missing_epoch(3).unwrap(), Some(1));
+
+        let known =
+            KnownValidatorSets::new(EpochValidators::from_validator_set(4, &validator_set(&[1])));
+        assert!(matches!(
+            known.next_missing_epoch(2),
+            Err(ProofVerificationError::UnknownEpoch(2))
+        ));
+    }
+
+    #[test]
+    fn test_handoff_must_follow_latest_epoch() {
+        let mut known = known_genesis();
+        let result = known.add_handoff(&handoff(2, 2 * EPOCH_LENGTH - 1), 0);
+        assert!(matches!(result, Err(ProofVerificationError::InvalidEpochHandoff(_))));
+        assert_eq!(known.latest_epoch(), 0);
+    }
+
+    #[test]
+    fn test_handoff_must_be_proven_by_last_block_of_previous_epoch() {
+        let mut known = known_genesis();
+        for block_height in [EPOCH_LENGTH - 2, EPOCH_LENGTH, 0] {
+            let result = known.add_handoff(&handoff(1, block_height), 0);
+            assert!(matches!(result, Err(ProofVerificationError::InvalidEpochHandoff(_))));
+        }
+    }
+
+    #[test]
+    fn test_handoff_without_votes_rejected() {
+        let mut known = known_genesis();
+        let result = known.add_handoff(&handoff(1, EPOCH_LENGTH - 1), 0);
+        assert!(matches!(result, Err(ProofVerificationError::InsufficientStake { .. })));
+        assert!(known.get(1).is_none());
+    }
+}
+
+use super::{ProofVerificationError, verify_epoch_handoff};
+use crate::frontend::frontend_data::ValidatorInfo;
+use crate::limits::EPOCH_LENGTH;
+use crate::types::consensus::PersistedValidatorSet;
+use crate::types::rpc::types::EpochHandoff;
+use std::collections::{BTreeMap, HashMap};
@@ -2,6 +2,7 @@ pub fn verify_keyvalue_proof(
     response: &GetKeyValueResponse,
     site_id: Sha256Digest,
     key: &str,
+    chain_id: u64,
     validators: &HashMap<u64, ValidatorInfo>,
     total_stake: u64,
     current_unix_timestamp: u64,
@@ -15,6 +16,7 @@ pub fn verify_keyvalue_proof(
         block_hash,
         proof.block_header.height,
         proof.round,
+        chain_id,
         validators,
         total_stake,
     )?;
@@ -39,6 +41,129 @@ pub fn verify_keyvalue_proof(
     }
 }
 
+pub fn verify_receipt_proof(
+    response: &GetTxReceiptResponse,
+    tx_hash: Sha256Digest,
+    chain_id: u64,
+    validators: &HashMap<u64, ValidatorInfo>,
+    total_stake: u64,
+    current_unix_timestamp: u64,
+) -> Result<(), ProofVerificationError> {
+    let proof = &response.state_proof;
+
+    let block_hash = proof.block_header.calculate_hash();
+
+    verify_finalization_votes(
+        &proof.finalization_votes,
+        block_hash,
+        proof.block_header.height,
+        proof.round,
+        chain_id,
+        validators,
+        total_stake,
+    )?;
+
+    check_proof_staleness(proof, current_unix_timestamp)?;
+
+    let storage_key = tx_hash.encode();
+    let jmt_key_input =
+        JmtKeyInput { cf_namespace: cf_to_namespace_byte("receipt"), key: &storage_key };
+    let key_hash = KeyHash::with::<Sha256>(&borsh::to_vec(&jmt_key_input).unwrap());
+
+    let root = RootHash(proof.block_header.previous_block_state_root.to_bytes());
+    let value_hash = Sha256::digest(response.receipt.encode());
+    return Ok(proof.proof.verify_existence(root, key_hash, value_hash.as_slice())?);
+}
+
+/// Checks the event blocks are proven and no block in request.from_height..=response.to_height is left out
+/// Returns the height to continue from if the node cut the page short
+pub fn verify_events_proof(
+    response: &GetEventsResponse,
+    request: &GetEventsPayload,
+    chain_id: u64,
+    validators: &HashMap<u64, ValidatorInfo>,
+    total_stake: u64,
+    current_unix_timestamp: u64,
+) -> Result<Option<u64>, ProofVerificationError> {
+    let proof = &response.state_proof;
+
+    let block_hash = proof.block_header.calculate_hash();
+
+    verify_finalization_votes(
+        &proof.finalization_votes,
+        block_hash,
+        proof.block_header.height,
+        proof.round,
+        chain_id,
+        validators,
+        total_stake,
+    )?;
+
+    check_proof_staleness(proof, current_unix_timestamp)?;
+
+    let &GetEventsPayload { site_id, topic, from_height, to_height } = request;
+    if response.to_height > to_height {
+        return Err(ProofVerificationError::IncompleteEvents("range above the requested heights"));
+    }
+
+    let root = RootHash(proof.block_header.previous_block_state_root.to_bytes());
+    let head_key_hash = event_key_hash(&event_head_key(site_id, topic));
+    match response.head {
+        Some(head) => {
+            let value_hash = Sha256::digest(head.encode());
+            proof.proof.verify_existence(root, head_key_hash, value_hash.as_slice())?;
+        }
+        None => proof.proof.verify_nonexistence(root, head_key_hash)?,
+    }
+
+    //newest block in the range, pointed to by the first block above it or the head
+    let mut expected = match &response.anchor {
+        Some(anchor) => {
+            if anchor.height <= response.to_height {
+                return Err(ProofVerificationError::IncompleteEvents("anchor inside the range"));
+            }
+            verify_event_block(anchor, site_id, topic, root)?;
+            anchor.block.prev_height
+        }
+        None => response.head,
+    };
+    if expected.is_some_and(|height| height > response.to_height) {
+        return Err(ProofVerificationError::IncompleteEvents("blocks above the range left out"));
+    }
+
+    for proved in &response.blocks {
+        let in_range = expected.is_some_and(|height| height >= from_height);
+        if !in_range || expected != Some(proved.height) {
+            return Err(ProofVerificationError::IncompleteEvents("event blocks do not chain"));
+        }
+        verify_event_block(proved, site_id, topic, root)?;
+        expected = proved.block.prev_height;
+    }
+    let remaining = expected.filter(|height| *height >= from_height);
+    //a page has to make progress for the caller to continue from it
+    if remaining.is_some() && response.blocks.is_empty() {
+        return Err(ProofVerificationError::IncompleteEvents("page without event blocks"));
+    }
+    return Ok(remaining);
+}
+
+fn verify_event_block(
+    proved: &ProvedEventBlock,
+    site_id: Sha256Digest,
+    topic: Sha256Digest,
+    root: RootHash,
+) -> Result<(), ProofVerificationError> {
+    let key_hash = event_key_hash(&event_block_key(site_id, topic, proved.height));
+    let value_hash = Sha256::digest(proved.block.encode());
+    return Ok(proved.proof.verify_existence(root, key_hash, value_hash.as_slice())?);
+}
+
+fn event_key_hash(storage_key: &[u8]) -> KeyHash {
+    let jmt_key_input =
+        JmtKeyInput { cf_namespace: cf_to_namespace_byte("event"), key: storage_key };
+    return KeyHash::with::<Sha256>(&borsh::to_vec(&jmt_key_input).unwrap());
+}
+
 //TODO currently does not verify domain lookups and site_ids
 //currently domain lookup is done server side, to do this would need to do domain lookup locally and then request server
 //or server attaches proof of domain lookup
@@ -47,6 +172,7 @@ pub fn verify_keyvalue_proof(
 //to verify site_id
 pub fn verify_page_proof(
     response: &PageResponse,
+    chain_id: u64,
     validators: &HashMap<u64, ValidatorInfo>,
     total_stake: u64,
     current_unix_timestamp: u64,
@@ -61,6 +187,7 @@ pub fn verify_page_proof(
         block_hash,
         proof.block_header.height,
         proof.round,
+        chain_id,
         validators,
         total_stake,
     )?;
@@ -87,6 +214,47 @@ pub fn verify_page_proof(
     }
 }
 
+/// Checks the validator set of handoff.epoch is in the state committed by the last block
+/// of the previous epoch, finalized by the previous epoch's validators
+/// no staleness check, handoffs are replayed long after they were finalized
+pub fn verify_epoch_handoff(
+    handoff: &EpochHandoff,
+    chain_id: u64,
+    validators: &HashMap<u64, ValidatorInfo>,
+    total_stake: u64,
+) -> Result<(), ProofVerificationError> {
+    let proof = &handoff.state_proof;
+
+    let is_last_block_of_previous_epoch = handoff.epoch > 0
+        && proof.block_header.height.checked_add(1) == handoff.epoch.checked_mul(EPOCH_LENGTH);
+    if !is_last_block_of_previous_epoch {
+        return Err(ProofVerificationError::InvalidEpochHandoff(
+            "not proven by the last block of the previous epoch",
+        ));
+    }
+
+    let block_hash = proof.block_header.calculate_hash();
+
+    verify_finalization_votes(
+        &proof.finalization_votes,
+        block_hash,
+        proof.block_header.height,
+        proof.round,
+        chain_id,
+        validators,
+        total_stake,
+    )?;
+
+    let storage_key = handoff.epoch.encode();
+    let jmt_key_input =
+        JmtKeyInput { cf_namespace: cf_to_namespace_byte("epoch"), key: &storage_key };
+    let key_hash = KeyHash::with::<Sha256>(&borsh::to_vec(&jmt_key_input).unwrap());
+
+    let root = RootHash(proof.block_header.previous_block_state_root.to_bytes());
+    let value_hash = Sha256::digest(handoff.validator_set.encode());
+    return Ok(proof.proof.verify_existence(root, key_hash, value_hash.as_slice())?);
+}
+
 fn check_proof_staleness(
     proof: &StateProof,
     current_unix_timestamp: u64,
@@ -107,33 +275,21 @@ fn check_proof_staleness(
 }
 
 fn verify_finalization_votes(
-    votes: &[(u64, ed25519::Signature)],
+    votes: &CertificateVotes,
     block_hash: Sha256Digest,
     height: u64,
     round: u64,
+    chain_id: u64,
     validators: &HashMap<u64, ValidatorInfo>,
     total_stake: u64,
 ) -> Result<(), ProofVerificationError> {
-    let mut seen_validators = std::collections::HashSet::new();
-    let mut verified_stake = 0;
-
-    for (validator_index, signature) in votes {
-        //validator_already_signed
-        if !seen_validators.insert(validator_index) {
-            continue;
-        }
-        let Some(validator) = validators.get(validator_index) else {
-            continue;
-        };
-        let vote_data =
-            ValidatorVoteData { vote_type: VoteType::Finalize(block_hash), height, round };
-        let Some(pub_key) = ed25519::PublicKey::try_from_bytes(validator.pub_key) else {
-            continue;
-        };
-        if pub_key.verify_sig(vote_data.calculate_hash(), *signature) {
-            verified_stake += validator.stake;
-        }
-    }
+    let vote_data =
+        ValidatorVoteData { chain_id, vote_type: VoteType::Finalize(block_hash), height, round };
+    let verified_stake = votes.verified_stake(vote_data.calculate_hash(), |validator_index| {
+        let validator = validators.get(&validator_index)?;
+        let pub_key = ed25519::PublicKey::try_from_bytes(validator.pub_key)?;
+        return Some((pub_key, validator.stake));
+    });
 
     //66%
     if verified_stake * 3 <= total_stake * 2 {
@@ -150,12 +306,175 @@ use crate::borsh::BorshExt;
 use crate::crypto::ed25519;
 use crate::crypto::sha256::Sha256Digest;
 use crate::frontend::frontend_data::ValidatorInfo;
-use crate::limits::{MAX_PROOF_AGE_SECS, MAX_PROOF_FUTURE_SECS};
-use crate::types::consensus::{ValidatorVoteData, VoteType};
-use crate::types::rpc::types::{GetKeyValueResponse, PageResponse, StateProof};
+use crate::limits::{EPOCH_LENGTH, MAX_PROOF_AGE_SECS, MAX_PROOF_FUTURE_SECS};
+use crate::types::consensus::{CertificateVotes, ValidatorVoteData, VoteType};
+use crate::types::rpc::types::{
+    EpochHandoff, GetEventsPayload, GetEventsResponse, GetKeyValueResponse, GetTxReceiptResponse,
+    PageResponse, ProvedEventBlock, StateProof,
+};
 use crate::types::storage::{
-    JmtKeyInput, Page, PageStorageKey, SiteKvStorageKey, cf_to_namespace_byte,
+    JmtKeyInput, Page, PageStorageKey, SiteKvStorageKey, cf_to_namespace_byte, event_block_key,
+    event_head_key,
 };
 use jmt::{KeyHash, RootHash};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::consensus::BlockHeader;
+    use crate::types::execution::event::EventBlock;
+    use jmt::JellyfishMerkleTree;
+    use jmt::mock::MockTreeStore;
+    use std::collections::BTreeMap;
+
+    const CHAIN_ID: u64 = 7;
+
+    //event blocks of one site topic committed to a state tree, chained oldest to newest
+    struct EventChain {
+        store: MockTreeStore,
+        root: RootHash,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        blocks: BTreeMap<u64, EventBlock>,
+    }
+
+    impl EventChain {
+        fn new(heights: &[u64]) -> EventChain {
+            let site_id = Sha256Digest::from([1; 32]);
+            let topic = Sha256Digest::from([2; 32]);
+            let mut blocks = BTreeMap::new();
+            let mut prev_height = None;
+            for height in heights {
+                blocks.insert(*height, EventBlock { prev_height, events: vec![] });
+                prev_height = Some(*height);
+            }
+            let mut values = vec![];
+            if let Some(head) = prev_height {
+                values.push((event_key_hash(&event_head_key(site_id, topic)), head.encode()));
+            }
+            for (height, block) in &blocks {
+                let key = event_block_key(site_id, topic, *height);
+                values.push((event_key_hash(&key), block.encode()));
+            }
+            //the tree holds value hashes, like the node state tree
+            let values = values.into_iter().map(|(key, v)| (key, Some(Sha256::digest(v).to_vec())));
+            let store = MockTreeStore::default();
+            let (root, batch) =
+                JellyfishMerkleTree::<_, Sha256>::new(&store).put_value_set(values, 0).unwrap();
+            store.write_tree_update_batch(batch).unwrap();
+            return EventChain { store, root, site_id, topic, blocks };
+        }
+
+        fn proved(&self, height: u64) -> ProvedEventBlock {
+            let key = event_key_hash(&event_block_key(self.site_id, self.topic, height));
+            let tree = JellyfishMerkleTree::<_, Sha256>::new(&self.store);
+            let (_, proof) = tree.get_with_proof(key, 0).unwrap();
+            return ProvedEventBlock { height, block: self.blocks[&height].clone(), proof };
+        }
+
+        fn response(
+            &self,
+            to_height: u64,
+            anchor: Option<u64>,
+            blocks: &[u64],
+        ) -> GetEventsResponse {
+            let head_key = event_key_hash(&event_head_key(self.site_id, self.topic));
+            let tree = JellyfishMerkleTree::<_, Sha256>::new(&self.store);
+            let (_, proof) = tree.get_with_proof(head_key, 0).unwrap();
+            let block_header = BlockHeader {
+                height: 10,
+                previous_block_hash: Sha256Digest::default(),
+                timestamp: 0,
+                previous_block_state_root: Sha256Digest::from(self.root.0),
+                pow_difficulty: 0,
+                transactions_hash: Sha256Digest::default(),
+                grandparent_finalization_hash: Sha256Digest::default(),
+            };
+            let vote_type = VoteType::Finalize(block_header.calculate_hash());
+            let vote = ValidatorVoteData { chain_id: CHAIN_ID, vote_type, height: 10, round: 0 };
+            let signature = validator_key().sign_hash(vote.calculate_hash());
+            let finalization_votes =
+                CertificateVotes::from_votes(&BTreeMap::from([(0, signature)]));
+            let state_proof = StateProof { proof, block_header, round: 0, finalization_votes };
+            return GetEventsResponse {
+                to_height,
+                head: self.blocks.keys().last().copied(),
+                anchor: anchor.map(|height| self.proved(height)),
+                blocks: blocks.iter().map(|height| self.proved(*height)).collect(),
+                state_proof,
+            };
+        }
+
+        fn verify(
+            &self,
+            response: &GetEventsResponse,
+            from_height: u64,
+            to_height: u64,
+        ) -> Result<Option<u64>, ProofVerificationError> {
+            let validator = ValidatorInfo {
+                validator_index: 0,
+                pub_key: validator_key().public_key().to_bytes(),
+                stake: 1,
+            };
+            let validators = HashMap::from([(0, validator)]);
+            let request = GetEventsPayload {
+                site_id: self.site_id,
+                topic: self.topic,
+                from_height,
+                to_height,
+            };
+            verify_events_proof(response, &request, CHAIN_ID, &validators, 1, 0)
+        }
+    }
+
+    fn validator_key() -> ed25519::PrivateKey {
+        ed25519::PrivateKey::from_seed(1)
+    }
+
+    fn assert_incomplete(result: Result<Option<u64>, ProofVerificationError>, reason: &str) {
+        match result {
+            Err(ProofVerificationError::IncompleteEvents(message)) => assert_eq!(message, reason),
+            other => panic!("expected incomplete events, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn complete_range_verifies() {
+        let chain = EventChain::new(&[2, 5, 9]);
+        assert_eq!(chain.verify(&chain.response(10, None, &[9, 5, 2]), 1, 10).unwrap(), None);
+        assert_eq!(chain.verify(&chain.response(6, Some(9), &[5]), 3, 6).unwrap(), None);
+        //a page cut short continues below its last block
+        assert_eq!(chain.verify(&chain.response(10, None, &[9]), 1, 10).unwrap(), Some(5));
+    }
+
+    #[test]
+    fn omitted_block_rejected() {
+        let chain = EventChain::new(&[2, 5, 9]);
+        let response = chain.response(10, None, &[9, 2]);
+        assert_incomplete(chain.verify(&response, 1, 10), "event blocks do not chain");
+    }
+
+    #[test]
+    fn anchor_inside_range_rejected() {
+        let chain = EventChain::new(&[2, 5, 9]);
+        //block 9 left out by anchoring at 5
+        let response = chain.response(10, Some(5), &[2]);
+        assert_incomplete(chain.verify(&response, 1, 10), "anchor inside the range");
+    }
+
+    #[test]
+    fn to_height_above_request_rejected() {
+        let chain = EventChain::new(&[2, 5, 9]);
+        let response = chain.response(10, None, &[9, 5, 2]);
+        assert_incomplete(chain.verify(&response, 1, 6), "range above the requested heights");
+    }
+
+    #[test]
+    fn empty_page_with_remaining_blocks_rejected() {
+        let chain = EventChain::new(&[2, 5, 9]);
+        let response = chain.response(10, None, &[]);
+        assert_incomplete(chain.verify(&response, 1, 10), "page without event blocks");
+    }
+}
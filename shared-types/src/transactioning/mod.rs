@@ -1,2 +1,3 @@
 pub mod compression;
+pub mod pow;
 pub mod transaction_generator;
@@ -0,0 +1,176 @@
+/// Pow difficulty of the block after parent, derived only from the finalized parent
+pub fn next_pow_difficulty(
+    parent_pow_difficulty: u8,
+    parent_transactions: usize,
+    parent_size: usize,
+) -> u8 {
+    let transactions_fullness = parent_transactions * 100 / MAX_TRANSACTIONS_PER_BLOCK;
+    let size_fullness = parent_size * 100 / MAX_BLOCK_SIZE;
+    let fullness = transactions_fullness.max(size_fullness);
+
+    if fullness >= POW_RAISE_FULLNESS_PERCENT {
+        return parent_pow_difficulty.saturating_add(1).min(MAX_POW_DIFFICULTY);
+    }
+    if fullness < POW_LOWER_FULLNESS_PERCENT {
+        return parent_pow_difficulty.saturating_sub(1);
+    }
+    return parent_pow_difficulty;
+}
+
+//expected attempts at a difficulty are 2^difficulty, mining gives up after this many times that
+//so a nonce is missed with probability e^-32
+const POW_ATTEMPT_MARGIN: u64 = 32;
+
+/// Most nonces tried before mining at pow_difficulty gives up
+pub fn pow_attempt_budget(pow_difficulty: u8) -> u64 {
+    return 1u64
+        .checked_shl(pow_difficulty as u32)
+        .unwrap_or(u64::MAX)
+        .saturating_mul(POW_ATTEMPT_MARGIN);
+}
+
+pub enum PowStep {
+    Found(u64),
+    Pending,
+    Exhausted,
+}
+
+/// Resumable nonce search, callers that must stay responsive run it in steps and yield between
+pub struct PowMiner {
+    pub_key: ed25519::PublicKey,
+    recent_block_height: u64,
+    pow_difficulty: u8,
+    next_nonce: u64,
+    attempts_left: u64,
+}
+
+impl PowMiner {
+    pub fn new(
+        pub_key: ed25519::PublicKey,
+        recent_block_height: u64,
+        start_nonce: u64,
+        pow_difficulty: u8,
+    ) -> PowMiner {
+        let attempts_left = pow_attempt_budget(pow_difficulty);
+        return PowMiner {
+            pub_key,
+            recent_block_height,
+            pow_difficulty,
+            next_nonce: start_nonce,
+            attempts_left,
+        };
+    }
+
+    /// Tries up to max_attempts nonces
+    pub fn step(&mut self, max_attempts: u64) -> PowStep {
+        for _ in 0..max_attempts.min(self.attempts_left) {
+            let nonce = self.next_nonce;
+            self.next_nonce = nonce.wrapping_add(1);
+            self.attempts_left -= 1;
+            let pow = ProofOfWorkStruct {
+                nonce,
+                pub_key: self.pub_key,
+                recent_block_height: self.recent_block_height,
+            };
+            if pow.calculate_hash().leading_zero_bits() >= self.pow_difficulty as u32 {
+                return PowStep::Found(nonce);
+            }
+        }
+        if self.attempts_left == 0 {
+            return PowStep::Exhausted;
+        }
+        return PowStep::Pending;
+    }
+}
+
+/// First nonce from start_nonce whose pow hash meets pow_difficulty, None once the attempt budget
+/// is spent, blocks until then so async callers step a PowMiner instead
+pub fn mine_pow_nonce(
+    pub_key: ed25519::PublicKey,
+    recent_block_height: u64,
+    start_nonce: u64,
+    pow_difficulty: u8,
+) -> Option<u64> {
+    let mut miner = PowMiner::new(pub_key, recent_block_height, start_nonce, pow_difficulty);
+    match miner.step(u64::MAX) {
+        PowStep::Found(nonce) => return Some(nonce),
+        PowStep::Pending | PowStep::Exhausted => return None,
+    }
+}
+
+use crate::crypto::ed25519;
+use crate::limits::{
+    MAX_BLOCK_SIZE, MAX_POW_DIFFICULTY, MAX_TRANSACTIONS_PER_BLOCK, POW_LOWER_FULLNESS_PERCENT,
+    POW_RAISE_FULLNESS_PERCENT,
+};
+use crate::types::execution::transaction::ProofOfWorkStruct;
+
+#[cfg(test)]
+mod tests {
+    use super::{PowMiner, PowStep, mine_pow_nonce, next_pow_difficulty, pow_attempt_budget};
+    use crate::crypto::ed25519;
+    use crate::limits::{MAX_BLOCK_SIZE, MAX_POW_DIFFICULTY, MAX_TRANSACTIONS_PER_BLOCK};
+    use crate::types::execution::transaction::ProofOfWorkStruct;
+
+    #[test]
+    fn test_difficulty_follows_block_fullness() {
+        assert_eq!(next_pow_difficulty(4, MAX_TRANSACTIONS_PER_BLOCK, 0), 5);
+        assert_eq!(next_pow_difficulty(4, 0, MAX_BLOCK_SIZE / 2), 5);
+        assert_eq!(next_pow_difficulty(4, MAX_TRANSACTIONS_PER_BLOCK / 3, 0), 4);
+        assert_eq!(next_pow_difficulty(4, 0, 0), 3);
+    }
+
+    #[test]
+    fn test_difficulty_clamped() {
+        assert_eq!(next_pow_difficulty(0, 0, 0), 0);
+        assert_eq!(
+            next_pow_difficulty(MAX_POW_DIFFICULTY, MAX_TRANSACTIONS_PER_BLOCK, 0),
+            MAX_POW_DIFFICULTY
+        );
+    }
+
+    #[test]
+    fn test_mined_nonce_meets_difficulty() {
+        let pub_key = ed25519::PrivateKey::from_seed(1).public_key();
+        let nonce = mine_pow_nonce(pub_key, 10, 0, 8).unwrap();
+        let pow = ProofOfWorkStruct { nonce, pub_key, recent_block_height: 10 };
+        assert!(pow.calculate_hash().leading_zero_bits() >= 8);
+    }
+
+    #[test]
+    fn test_stepped_miner_finds_same_nonce() {
+        let pub_key = ed25519::PrivateKey::from_seed(1).public_key();
+        let expected = mine_pow_nonce(pub_key, 10, 0, 8).unwrap();
+        let mut miner = PowMiner::new(pub_key, 10, 0, 8);
+        let mut steps = 0;
+        let nonce = loop {
+            steps += 1;
+            match miner.step(16) {
+                PowStep::Found(nonce) => break nonce,
+                PowStep::Pending => continue,
+                PowStep::Exhausted => panic!("budget exhausted"),
+            }
+        };
+        assert_eq!(nonce, expected);
+        assert_eq!(steps, expected / 16 + 1);
+    }
+
+    #[test]
+    fn test_mining_gives_up_after_budget() {
+        let pub_key = ed25519::PrivateKey::from_seed(1).public_key();
+        let mut miner = PowMiner::new(pub_key, 10, 0, MAX_POW_DIFFICULTY);
+        let mut attempts = 0;
+        //shrink the budget so the test stays fast, 32 zero bits in 64 attempts is not expected
+        miner.attempts_left = 64;
+        loop {
+            match miner.step(16) {
+                PowStep::Found(_) => panic!("32 zero bits within 64 attempts"),
+                PowStep::Pending => attempts += 16,
+                PowStep::Exhausted => break,
+            }
+        }
+        assert_eq!(attempts, 48);
+        assert_eq!(pow_attempt_budget(0), 32);
+        assert_eq!(pow_attempt_budget(8), 256 * 32);
+    }
+}
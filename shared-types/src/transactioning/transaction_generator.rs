@@ -1,16 +1,48 @@
+/// Fields every transaction carries next to its calldata
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxEnvelope {
+    pub nonce: u64,
+    pub recent_block_height: u64,
+    pub chain_id: u64,
+    pub pow_difficulty: u8,
+    /// Only spent by transactions that run a contract, the others are always built with 0
+    pub fuel_limit: u64,
+}
+
+impl TxEnvelope {
+    fn without_fuel(self) -> TxEnvelope {
+        return TxEnvelope { fuel_limit: 0, ..self };
+    }
+}
+
 fn build_and_validate_transaction(
     tx_data: &TransactionData,
     private_key: &ed25519::PrivateKey,
-    nonce: u64,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
+    let TxEnvelope { nonce, recent_block_height, chain_id, pow_difficulty, fuel_limit } = envelope;
+    //nonce is signed, so pow is mined before signing
+    let nonce =
+        mine_pow_nonce(private_key.public_key(), recent_block_height, nonce, pow_difficulty)
+            .expect("no pow nonce found within attempt budget");
     let compressed = compress_calldata(&tx_data.encode());
+    let signing_data = TransactionSigningData {
+        version: TRANSACTION_VERSION,
+        chain_id,
+        calldata_hash: sha256::sha256_hash(&compressed),
+        nonce,
+        recent_block_height,
+        fuel_limit,
+    };
     let tx = Transaction {
+        version: TRANSACTION_VERSION,
+        chain_id,
         pub_key: private_key.public_key(),
-        signature: private_key.sign_hash(sha256::sha256_hash(&compressed)),
+        signature: private_key.sign_hash(signing_data.calculate_hash()),
         calldata: compressed,
         nonce,
         recent_block_height,
+        fuel_limit,
     };
     let tx_bytes = tx.encode();
     assert!(
@@ -25,16 +57,14 @@ fn build_and_validate_transaction(
 pub fn build_call_transaction(
     site_id: Sha256Digest,
     calldata: Vec<u8>,
-    nonce: u64,
     private_key: ed25519::PrivateKey,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
     let tx_data = TransactionData {
         transaction_type: TransactionType::Call,
         calldata: SiteCall { site_id, calldata }.encode(),
     };
-    let transaction =
-        build_and_validate_transaction(&tx_data, &private_key, nonce, recent_block_height);
+    let transaction = build_and_validate_transaction(&tx_data, &private_key, envelope);
     return transaction;
 }
 
@@ -54,69 +84,119 @@ fn assert_wasm_module_size(data: &[u8]) {
 pub fn build_deploy_new_module_transaction(
     wasm_data: Vec<u8>,
     constructor_calldata: Vec<u8>,
-    nonce: u64,
+    admin: Option<ed25519::PublicKey>,
     private_key: ed25519::PrivateKey,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
     assert_wasm_module_size(&wasm_data);
-    let deploy_call = DeployNewModuleCall { wasm_data, constructor_calldata };
+    let deploy_call = DeployNewModuleCall { wasm_data, constructor_calldata, admin };
     let tx_data = TransactionData {
         transaction_type: TransactionType::DeployNewModule,
         calldata: deploy_call.encode(),
     };
-    let tx = build_and_validate_transaction(&tx_data, &private_key, nonce, recent_block_height);
+    let tx = build_and_validate_transaction(&tx_data, &private_key, envelope);
     return tx;
 }
 
 pub fn build_add_module_transaction(
     module_data: Vec<u8>,
-    nonce: u64,
     private_key: ed25519::PrivateKey,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
     assert_wasm_module_size(&module_data);
     let tx_data =
         TransactionData { transaction_type: TransactionType::AddModule, calldata: module_data };
-    let tx = build_and_validate_transaction(&tx_data, &private_key, nonce, recent_block_height);
+    let tx = build_and_validate_transaction(&tx_data, &private_key, envelope.without_fuel());
     return tx;
 }
 
 pub fn build_deploy_stored_module_transaction(
     module_id: Sha256Digest,
     constructor_calldata: Vec<u8>,
-    nonce: u64,
+    admin: Option<ed25519::PublicKey>,
     private_key: ed25519::PrivateKey,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
-    let deploy_call = DeployStoredModuleCall { module_id, constructor_calldata };
+    let deploy_call = DeployStoredModuleCall { module_id, constructor_calldata, admin };
     let tx_data = TransactionData {
         transaction_type: TransactionType::DeployStoredModule,
         calldata: deploy_call.encode(),
     };
-    let tx = build_and_validate_transaction(&tx_data, &private_key, nonce, recent_block_height);
+    let tx = build_and_validate_transaction(&tx_data, &private_key, envelope);
     return tx;
 }
 
+/// private_key has to be the admin key the site was deployed with
+pub fn build_upgrade_site_transaction(
+    site_id: Sha256Digest,
+    module_id: Sha256Digest,
+    migrate_calldata: Vec<u8>,
+    private_key: ed25519::PrivateKey,
+    envelope: TxEnvelope,
+) -> Transaction {
+    let upgrade_call = UpgradeSiteCall { site_id, module_id, migrate_calldata };
+    let tx_data = TransactionData {
+        transaction_type: TransactionType::UpgradeSite,
+        calldata: upgrade_call.encode(),
+    };
+    build_and_validate_transaction(&tx_data, &private_key, envelope)
+}
+
 pub fn build_register_domain_transaction(
     site_id: Sha256Digest,
     domain_name: String,
-    nonce: u64,
     private_key: ed25519::PrivateKey,
-    recent_block_height: u64,
+    envelope: TxEnvelope,
 ) -> Transaction {
     let domain_data = DomainData { site_id, domain_name };
     let tx_data = TransactionData {
         transaction_type: TransactionType::RegisterDomain,
         calldata: domain_data.encode(),
     };
-    build_and_validate_transaction(&tx_data, &private_key, nonce, recent_block_height)
+    build_and_validate_transaction(&tx_data, &private_key, envelope.without_fuel())
+}
+
+pub fn build_register_validator_transaction(
+    p2p_key: ed25519::PublicKey,
+    stake: u64,
+    epoch: u64,
+    approvals: Vec<(u64, ed25519::Signature)>,
+    private_key: ed25519::PrivateKey,
+    envelope: TxEnvelope,
+) -> Transaction {
+    let register_call = RegisterValidatorCall { p2p_key, stake, epoch, approvals };
+    let tx_data = TransactionData {
+        transaction_type: TransactionType::RegisterValidator,
+        calldata: register_call.encode(),
+    };
+    build_and_validate_transaction(&tx_data, &private_key, envelope.without_fuel())
+}
+
+pub fn build_exit_validator_transaction(
+    private_key: ed25519::PrivateKey,
+    envelope: TxEnvelope,
+) -> Transaction {
+    let tx_data =
+        TransactionData { transaction_type: TransactionType::ExitValidator, calldata: vec![] };
+    build_and_validate_transaction(&tx_data, &private_key, envelope.without_fuel())
+}
+
+/// evidence_data is the borsh encoded consensus evidence, opaque to clients
+pub fn build_evidence_transaction(
+    evidence_data: Vec<u8>,
+    private_key: ed25519::PrivateKey,
+    envelope: TxEnvelope,
+) -> Transaction {
+    let tx_data =
+        TransactionData { transaction_type: TransactionType::Evidence, calldata: evidence_data };
+    build_and_validate_transaction(&tx_data, &private_key, envelope.without_fuel())
 }
 
 use crate::{
     borsh::BorshExt,
     crypto::{ed25519, sha256, sha256::Sha256Digest},
     limits::{MAX_TRANSACTION_SIZE, MAX_WASM_MODULE_SIZE},
-    transactioning::compression::compress_calldata,
+    transactioning::{compression::compress_calldata, pow::mine_pow_nonce},
     types::{
         application::{
             deploy_new_module::DeployNewModuleCall,
@@ -124,8 +204,10 @@ use crate::{
             domaindata::DomainData,
             sitecall::SiteCall,
             transactiondata::{TransactionData, TransactionType},
+            upgrade_site::UpgradeSiteCall,
+            validator_registration::RegisterValidatorCall,
         },
-        execution::transaction::Transaction,
+        execution::transaction::{TRANSACTION_VERSION, Transaction, TransactionSigningData},
         rpc::types::SubmitTransactionPayload,
     },
 };
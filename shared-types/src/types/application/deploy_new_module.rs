@@ -2,8 +2,11 @@
 pub struct DeployNewModuleCall {
     pub wasm_data: Vec<u8>,
     pub constructor_calldata: Vec<u8>,
+    /// Key allowed to upgrade the site, None makes the site immutable
+    pub admin: Option<ed25519::PublicKey>,
 }
 
 #[allow(unused_imports)]
 use crate::borsh::*;
+use crate::crypto::ed25519;
 use borsh::{BorshDeserialize, BorshSerialize};
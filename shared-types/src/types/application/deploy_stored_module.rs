@@ -2,9 +2,11 @@
 pub struct DeployStoredModuleCall {
     pub module_id: Sha256Digest,
     pub constructor_calldata: Vec<u8>,
+    /// Key allowed to upgrade the site, None makes the site immutable
+    pub admin: Option<ed25519::PublicKey>,
 }
 
 #[allow(unused_imports)]
 use crate::borsh::*;
-use crate::crypto::sha256::Sha256Digest;
+use crate::crypto::{ed25519, sha256::Sha256Digest};
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -3,3 +3,5 @@ pub mod deploy_stored_module;
 pub mod domaindata;
 pub mod sitecall;
 pub mod transactiondata;
+pub mod upgrade_site;
+pub mod validator_registration;
@@ -5,6 +5,10 @@ pub enum TransactionType {
     AddModule,
     DeployStoredModule,
     RegisterDomain,
+    RegisterValidator,
+    ExitValidator,
+    Evidence,
+    UpgradeSite,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
@@ -0,0 +1,13 @@
+/// Points a site to an already stored module, must be signed by the site admin
+/// If the new module exports a migrate entry point it is called with migrate_calldata
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct UpgradeSiteCall {
+    pub site_id: Sha256Digest,
+    pub module_id: Sha256Digest,
+    pub migrate_calldata: Vec<u8>,
+}
+
+#[allow(unused_imports)]
+use crate::borsh::*;
+use crate::crypto::sha256::Sha256Digest;
+use borsh::{BorshDeserialize, BorshSerialize};
@@ -0,0 +1,33 @@
+/// Registers the transaction signer as validator from the next epoch snapshot
+/// There is no native token to bond, stake is the voting weight approved by the current validator set,
+/// capped below a third of the next epoch's stake
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct RegisterValidatorCall {
+    pub p2p_key: ed25519::PublicKey,
+    pub stake: u64,
+    pub epoch: u64,
+    pub approvals: Vec<(u64, ed25519::Signature)>,
+}
+
+/// Signed by current validators (by validator index) to approve a registration
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct ValidatorRegistrationApproval {
+    pub validator_pub_key: ed25519::PublicKey,
+    pub p2p_key: ed25519::PublicKey,
+    pub stake: u64,
+    pub epoch: u64,
+}
+
+impl ValidatorRegistrationApproval {
+    pub fn calculate_hash(&self) -> Sha256Digest {
+        sha256_hash(&self.encode())
+    }
+}
+
+#[allow(unused_imports)]
+use crate::borsh::*;
+use crate::crypto::{
+    ed25519,
+    sha256::{Sha256Digest, sha256_hash},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
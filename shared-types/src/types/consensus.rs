@@ -4,7 +4,9 @@ pub struct BlockHeader {
     pub previous_block_hash: Sha256Digest,
     pub timestamp: u64,
     pub previous_block_state_root: Sha256Digest,
+    pub pow_difficulty: u8,
     pub transactions_hash: Sha256Digest,
+    pub grandparent_finalization_hash: Sha256Digest,
 }
 
 impl BlockHeader {
@@ -20,8 +22,10 @@ pub enum VoteType {
     Skip,
 }
 
+//chain_id separates votes of different networks
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ValidatorVoteData {
+    pub chain_id: u64,
     pub vote_type: VoteType,
     pub height: u64,
     pub round: u64,
@@ -33,8 +37,177 @@ impl ValidatorVoteData {
     }
 }
 
+/// Compact certificate votes, bit i of signers is set if validator index i signed
+/// signatures are ordered by validator index
+#[derive(
+    BorshSerialize, BorshDeserialize, Clone, Debug, Default, Serialize, Deserialize, PartialEq,
+)]
+pub struct CertificateVotes {
+    pub signers: Vec<u8>,
+    pub signatures: Vec<ed25519::Signature>,
+}
+
+impl CertificateVotes {
+    pub fn from_votes(votes: &BTreeMap<u64, ed25519::Signature>) -> CertificateVotes {
+        let mut signers = Vec::new();
+        let mut signatures = Vec::with_capacity(votes.len());
+        for (validator_index, signature) in votes {
+            let byte = (*validator_index / 8) as usize;
+            if signers.len() <= byte {
+                signers.resize(byte + 1, 0);
+            }
+            signers[byte] |= 1 << (validator_index % 8);
+            signatures.push(*signature);
+        }
+        return CertificateVotes { signers, signatures };
+    }
+
+    pub fn signer_indices(&self) -> impl Iterator<Item = u64> + '_ {
+        self.signers.iter().enumerate().flat_map(|(byte_index, byte)| {
+            (0..8u64)
+                .filter(move |bit| byte & (1 << bit) != 0)
+                .map(move |bit| byte_index as u64 * 8 + bit)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, ed25519::Signature)> + '_ {
+        self.signer_indices().zip(self.signatures.iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Signer count in bitmap matches the amount of signatures
+    pub fn is_well_formed(&self) -> bool {
+        let signer_count: u32 = self.signers.iter().map(|b| b.count_ones()).sum();
+        return signer_count as usize == self.signatures.len();
+    }
+
+    /// Summed stake of signers with a valid signature over message
+    /// batch verified, falls back to verifying one by one if the batch contains an invalid signature
+    pub fn verified_stake(
+        &self,
+        message: Sha256Digest,
+        validator: impl Fn(u64) -> Option<(ed25519::PublicKey, u64)>,
+    ) -> u64 {
+        if !self.is_well_formed() {
+            return 0;
+        }
+        let mut items = Vec::with_capacity(self.len());
+        let mut stakes = Vec::with_capacity(self.len());
+        for (validator_index, signature) in self.iter() {
+            let Some((pub_key, stake)) = validator(validator_index) else {
+                continue;
+            };
+            items.push((pub_key, message, signature));
+            stakes.push(stake);
+        }
+        if ed25519::verify_batch(&items) {
+            return stakes.iter().sum();
+        }
+
+        let mut verified_stake = 0;
+        for ((pub_key, message, signature), stake) in items.iter().zip(stakes) {
+            if pub_key.verify_sig(*message, *signature) {
+                verified_stake += stake;
+            }
+        }
+        return verified_stake;
+    }
+}
+
+/// Votes that finalized a block and the round they were cast in
+#[derive(
+    Clone, Debug, Default, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct BlockFinalization {
+    pub round: u64,
+    pub votes: CertificateVotes,
+}
+
+impl BlockFinalization {
+    pub fn calculate_hash(&self) -> Sha256Digest {
+        sha256_hash(&self.encode())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct PersistedValidator {
+    pub pub_key: ed25519::PublicKey,
+    pub p2p_key: ed25519::PublicKey,
+    pub stake: u64,
+}
+
+/// Ordered validator set, position in validators is the validator index for the epoch
+#[derive(
+    Clone, Debug, Default, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct PersistedValidatorSet {
+    pub validators: Vec<PersistedValidator>,
+}
+
 #[allow(unused_imports)]
 use crate::borsh::*;
+use crate::crypto::ed25519;
 use crate::crypto::sha256::{Sha256Digest, sha256_hash};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{ed25519, sha256::sha256_hash};
+    use crate::types::consensus::CertificateVotes;
+    use std::collections::BTreeMap;
+
+    fn signed_votes(indices: &[u64], message: &[u8]) -> BTreeMap<u64, ed25519::Signature> {
+        let hash = sha256_hash(message);
+        let mut votes = BTreeMap::new();
+        for index in indices {
+            votes.insert(*index, ed25519::PrivateKey::from_seed(*index).sign_hash(hash));
+        }
+        return votes;
+    }
+
+    fn validator(index: u64) -> Option<(ed25519::PublicKey, u64)> {
+        Some((ed25519::PrivateKey::from_seed(index).public_key(), 10))
+    }
+
+    #[test]
+    fn test_signer_bitmap_roundtrip() {
+        let votes = signed_votes(&[0, 3, 9, 17], b"block");
+        let compact = CertificateVotes::from_votes(&votes);
+        assert_eq!(compact.signers.len(), 3);
+        assert!(compact.is_well_formed());
+        let roundtrip: BTreeMap<u64, ed25519::Signature> = compact.iter().collect();
+        assert_eq!(roundtrip, votes);
+    }
+
+    #[test]
+    fn test_batch_verified_stake() {
+        let votes = signed_votes(&[0, 1, 2, 3], b"block");
+        let compact = CertificateVotes::from_votes(&votes);
+        assert_eq!(compact.verified_stake(sha256_hash(b"block"), validator), 40);
+    }
+
+    #[test]
+    fn test_invalid_signature_only_drops_its_stake() {
+        let mut votes = signed_votes(&[0, 1, 2, 3], b"block");
+        votes.insert(2, ed25519::PrivateKey::from_seed(2).sign_hash(sha256_hash(b"other")));
+        let compact = CertificateVotes::from_votes(&votes);
+        assert_eq!(compact.verified_stake(sha256_hash(b"block"), validator), 30);
+    }
+
+    #[test]
+    fn test_malformed_bitmap_has_no_stake() {
+        let votes = signed_votes(&[0, 1, 2, 3], b"block");
+        let mut compact = CertificateVotes::from_votes(&votes);
+        compact.signers[0] |= 1 << 5;
+        assert_eq!(compact.verified_stake(sha256_hash(b"block"), validator), 0);
+    }
+}
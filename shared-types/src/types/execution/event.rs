@@ -0,0 +1,40 @@
+/// Event emitted by a contract, stored once under each of its topics
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ContractEvent {
+    pub tx_hash: Sha256Digest,
+    pub topics: Vec<Sha256Digest>,
+    /// Borsh encoded event struct
+    pub data: Vec<u8>,
+}
+
+/// Events a site emitted under one topic in one block, committed to the state tree
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EventBlock {
+    /// Previous height with events under the topic, chains blocks so a range can be proven complete
+    pub prev_height: Option<u64>,
+    /// In emission order
+    pub events: Vec<ContractEvent>,
+}
+
+/// Emitted by the node on the upgraded site under `site_upgraded_topic`, contracts cannot emit that topic
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SiteUpgraded {
+    pub previous_module_id: Sha256Digest,
+    pub module_id: Sha256Digest,
+}
+
+/// Reserved topic of `SiteUpgraded`, not a valid contract event name so derived topics never collide
+pub fn site_upgraded_topic() -> Sha256Digest {
+    return sha256_hash(b"vastrum::SiteUpgraded");
+}
+
+/// Event with the height of the block it was emitted in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BlockEvent {
+    pub height: u64,
+    pub event: ContractEvent,
+}
+
+use crate::crypto::sha256::{Sha256Digest, sha256_hash};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
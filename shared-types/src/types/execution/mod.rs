@@ -1 +1,3 @@
+pub mod event;
+pub mod receipt;
 pub mod transaction;
@@ -0,0 +1,55 @@
+/// Execution result of an included transaction, committed to the state tree keyed by tx hash
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TxReceipt {
+    pub height: u64,
+    pub outcome: TxOutcome,
+    /// Fuel consumed by contract execution including host calls, at most the tx fuel_limit
+    pub fuel_used: u64,
+    /// Key and value bytes written to site storage, counted even if the writes were reverted
+    pub kv_bytes_written: u64,
+    pub host_calls: HostCallCounts,
+    /// Borsh encoded return value of the called method, or its error if it returned Err
+    pub return_data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum TxOutcome {
+    Success,
+    /// State changes were reverted, message is the contract panic or trap
+    Failure {
+        message: String,
+    },
+}
+
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct HostCallCounts {
+    pub kv_insert: u32,
+    pub kv_get: u32,
+    pub kv_scan: u32,
+    pub log: u32,
+    pub register_static_route: u32,
+    pub message_sender: u32,
+    pub block_time: u32,
+    pub call_site: u32,
+    pub emit_event: u32,
+    pub storage_usage: u32,
+    pub random_seed: u32,
+    pub block_height: u32,
+    pub previous_block_hash: u32,
+    pub tx_hash: u32,
+    pub site_id: u32,
+    pub module_id: u32,
+    /// Calls to any of the crypto precompiles
+    pub precompiles: u32,
+}
+
+impl TxReceipt {
+    pub fn is_success(&self) -> bool {
+        self.outcome == TxOutcome::Success
+    }
+}
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
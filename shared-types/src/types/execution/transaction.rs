@@ -1,18 +1,28 @@
+/// Current transaction format, signature covers TransactionSigningData
+pub const TRANSACTION_VERSION: u8 = 2;
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq)]
 pub struct Transaction {
+    pub version: u8,
+    pub chain_id: u64,
     pub calldata: Vec<u8>,
     pub pub_key: ed25519::PublicKey,
     pub signature: ed25519::Signature,
     pub nonce: u64,
     pub recent_block_height: u64,
+    /// Fuel contract execution may consume before it is aborted
+    pub fuel_limit: u64,
 }
 impl fmt::Debug for Transaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Transaction")
+            .field("version", &self.version)
+            .field("chain_id", &self.chain_id)
             .field("pub_key", &self.pub_key)
             .field("signature", &self.signature)
             .field("nonce", &self.nonce)
             .field("recent_block_height", &self.recent_block_height)
+            .field("fuel_limit", &self.fuel_limit)
             .finish()
     }
 }
@@ -23,8 +33,10 @@ impl Transaction {
             pub_key: self.pub_key,
             recent_block_height: self.recent_block_height,
         };
-        let encoded = pow.encode();
-        return sha256::sha256_hash(&encoded);
+        return pow.calculate_hash();
+    }
+    pub fn meets_pow_difficulty(&self, pow_difficulty: u8) -> bool {
+        return self.calculate_pow_hash().leading_zero_bits() >= pow_difficulty as u32;
     }
     pub fn calculate_calldata_hash(&self) -> Sha256Digest {
         return sha256::sha256_hash(&self.calldata);
@@ -33,20 +45,55 @@ impl Transaction {
         let bytes = self.encode();
         return sha256::sha256_hash(&bytes);
     }
+    pub fn calculate_signing_hash(&self) -> Sha256Digest {
+        let signing_data = TransactionSigningData {
+            version: self.version,
+            chain_id: self.chain_id,
+            calldata_hash: self.calculate_calldata_hash(),
+            nonce: self.nonce,
+            recent_block_height: self.recent_block_height,
+            fuel_limit: self.fuel_limit,
+        };
+        return signing_data.calculate_hash();
+    }
     pub fn verify_signature(&self) -> bool {
-        let calldata_hash = self.calculate_calldata_hash();
-        self.pub_key.verify_sig(calldata_hash, self.signature)
+        if self.version != TRANSACTION_VERSION {
+            return false;
+        }
+        let signing_hash = self.calculate_signing_hash();
+        self.pub_key.verify_sig(signing_hash, self.signature)
     }
     pub fn verify_gas(&self) -> bool {
-        self.calldata.len() <= MAX_TRANSACTION_SIZE
+        self.calldata.len() <= MAX_TRANSACTION_SIZE && self.fuel_limit <= MAX_TX_FUEL_LIMIT
+    }
+}
+
+//nonce and recent_block_height are signed so a signed calldata cannot be rewrapped and replayed
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TransactionSigningData {
+    pub version: u8,
+    pub chain_id: u64,
+    pub calldata_hash: Sha256Digest,
+    pub nonce: u64,
+    pub recent_block_height: u64,
+    pub fuel_limit: u64,
+}
+impl TransactionSigningData {
+    pub fn calculate_hash(&self) -> Sha256Digest {
+        return sha256::sha256_hash(&self.encode());
     }
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ProofOfWorkStruct {
-    nonce: u64,
-    pub_key: PublicKey,
-    recent_block_height: u64,
+    pub nonce: u64,
+    pub pub_key: PublicKey,
+    pub recent_block_height: u64,
+}
+impl ProofOfWorkStruct {
+    pub fn calculate_hash(&self) -> Sha256Digest {
+        return sha256::sha256_hash(&self.encode());
+    }
 }
 #[allow(unused_imports)]
 use crate::borsh::*;
@@ -54,6 +101,59 @@ use crate::crypto::{
     ed25519::{self, PublicKey},
     sha256::{self, Sha256Digest},
 };
-use crate::limits::MAX_TRANSACTION_SIZE;
+use crate::limits::{MAX_TRANSACTION_SIZE, MAX_TX_FUEL_LIMIT};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::fmt;
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ed25519;
+    use crate::transactioning::transaction_generator::{
+        TxEnvelope, build_exit_validator_transaction,
+    };
+    use crate::types::execution::transaction::Transaction;
+
+    fn signed_tx() -> Transaction {
+        build_exit_validator_transaction(
+            ed25519::PrivateKey::from_seed(1),
+            TxEnvelope {
+                nonce: 7,
+                recent_block_height: 100,
+                chain_id: 1,
+                pow_difficulty: 0,
+                fuel_limit: 0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_signature_verifies() {
+        assert!(signed_tx().verify_signature());
+    }
+
+    #[test]
+    fn test_rewrapped_nonce_and_height_rejected() {
+        let mut tx = signed_tx();
+        tx.nonce = 8;
+        assert!(!tx.verify_signature());
+
+        let mut tx = signed_tx();
+        tx.recent_block_height = 200;
+        assert!(!tx.verify_signature());
+
+        let mut tx = signed_tx();
+        tx.fuel_limit += 1;
+        assert!(!tx.verify_signature());
+    }
+
+    #[test]
+    fn test_other_chain_and_version_rejected() {
+        let mut tx = signed_tx();
+        tx.chain_id = 2;
+        assert!(!tx.verify_signature());
+
+        let mut tx = signed_tx();
+        tx.version = 0;
+        assert!(!tx.verify_signature());
+    }
+}
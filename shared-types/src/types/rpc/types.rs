@@ -28,6 +28,9 @@ pub enum GetPageResult {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct GetLatestBlockHeightResponse {
     pub height: u64,
+    pub chain_id: u64,
+    /// Pow difficulty new transactions should be mined to
+    pub pow_difficulty: u8,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -42,7 +45,7 @@ pub struct StateProof {
     pub proof: jmt::proof::SparseMerkleProof<sha2::Sha256>,
     pub block_header: BlockHeader,
     pub round: u64,
-    pub finalization_votes: Vec<(u64, ed25519::Signature)>,
+    pub finalization_votes: CertificateVotes,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -58,6 +61,8 @@ pub enum ProvedReadError {
     ProofUnavailable,
     SiteNotFound,
     PageNotFound,
+    ReceiptNotFound,
+    EpochHandoffNotFound,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -86,6 +91,201 @@ pub struct GetTxHashIsIncludedResponse {
     pub included: bool,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetTxStatusPayload {
+    pub tx_hash: Sha256Digest,
+}
+
+/// Lifecycle of a transaction as seen by the queried node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum TxStatus {
+    /// Never seen, rejected on submission or evicted from the mempool
+    Unknown,
+    Pending,
+    Included {
+        height: u64,
+    },
+    /// Left the mempool after its recent_block_height fell outside the validity window
+    Expired,
+    /// Included in a block but execution failed, state changes were reverted
+    Failed {
+        height: u64,
+    },
+}
+
+impl TxStatus {
+    /// No later status is possible
+    pub fn is_final(&self) -> bool {
+        matches!(self, TxStatus::Included { .. } | TxStatus::Expired | TxStatus::Failed { .. })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetTxStatusResponse {
+    pub status: TxStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetTxReceiptPayload {
+    pub tx_hash: Sha256Digest,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetTxReceiptResponse {
+    pub receipt: TxReceipt,
+    pub state_proof: StateProof,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum GetTxReceiptResult {
+    Ok(Box<GetTxReceiptResponse>),
+    Err(ProvedReadError),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetEventsPayload {
+    pub site_id: Sha256Digest,
+    pub topic: Sha256Digest,
+    pub from_height: u64,
+    pub to_height: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ProvedEventBlock {
+    pub height: u64,
+    pub block: EventBlock,
+    /// Proven against the same state root as the response state_proof
+    pub proof: jmt::proof::SparseMerkleProof<sha2::Sha256>,
+}
+
+/// Event blocks of a site topic, newest first, with proofs that none in the range are left out
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetEventsResponse {
+    /// Requested to_height capped at the proven state height
+    pub to_height: u64,
+    /// Latest height with events under the topic at the proven state height
+    pub head: Option<u64>,
+    /// First event block above to_height, its prev_height shows nothing between is left out
+    pub anchor: Option<ProvedEventBlock>,
+    /// At most MAX_EVENT_BLOCKS_PAGE blocks, continue below the last one if the range is longer
+    pub blocks: Vec<ProvedEventBlock>,
+    /// Proof of head
+    pub state_proof: StateProof,
+}
+
+impl GetEventsResponse {
+    /// Events of the returned blocks, oldest first
+    pub fn into_events(self) -> Vec<BlockEvent> {
+        let mut events = Vec::new();
+        for proved in self.blocks.into_iter().rev() {
+            for event in proved.block.events {
+                events.push(BlockEvent { height: proved.height, event });
+            }
+        }
+        return events;
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum GetEventsResult {
+    Ok(Box<GetEventsResponse>),
+    Err(ProvedReadError),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetEpochHandoffPayload {
+    pub epoch: u64,
+}
+
+/// Validator set of epoch, proven against the last block of the previous epoch
+/// which is finalized by the previous epoch's validators
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EpochHandoff {
+    pub epoch: u64,
+    pub validator_set: PersistedValidatorSet,
+    pub state_proof: StateProof,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum GetEpochHandoffResult {
+    Ok(Box<EpochHandoff>),
+    Err(ProvedReadError),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetPendingTransactionsPayload {
+    pub offset: u64,
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct PendingTransaction {
+    pub tx_hash: Sha256Digest,
+    pub pub_key: ed25519::PublicKey,
+    pub recent_block_height: u64,
+    /// Leading zero bits of the pow hash
+    pub priority: u32,
+    pub size: u64,
+}
+
+/// Page of the mempool, highest priority first
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetPendingTransactionsResponse {
+    pub total: u64,
+    pub transactions: Vec<PendingTransaction>,
+}
+
+/// Run a site call read only, nothing it writes is persisted
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SimulateCallPayload {
+    pub site_id: Sha256Digest,
+    #[serde(with = "crate::types::rpc::serde_base64::base64_vec")]
+    pub calldata: Vec<u8>,
+    /// Sender the contract sees, defaults to the zero key
+    pub message_sender: Option<ed25519::PublicKey>,
+    /// Finalized height to run against, latest if not set
+    pub height_lock: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SimulatedWrite {
+    pub site_id: Sha256Digest,
+    pub key: String,
+    /// Empty if the key would be deleted
+    #[serde(with = "crate::types::rpc::serde_base64::base64_vec")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SimulateCallResponse {
+    /// Finalized height whose state the call ran against
+    pub height: u64,
+    pub outcome: TxOutcome,
+    /// Method returned Err, return_data holds the encoded error
+    pub reverted: bool,
+    #[serde(with = "crate::types::rpc::serde_base64::base64_vec")]
+    pub return_data: Vec<u8>,
+    pub fuel_used: u64,
+    pub logs: Vec<String>,
+    /// Site storage writes the call would make, empty if it failed
+    pub writes: Vec<SimulatedWrite>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum SimulateCallError {
+    OutsideRetentionWindow,
+    SiteNotFound,
+    ModuleUnavailable,
+    /// Too many simulations running on the node, retry later
+    Busy,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum SimulateCallResult {
+    Ok(SimulateCallResponse),
+    Err(SimulateCallError),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct ResolveDomainRequest {
     pub domain: String,
@@ -96,6 +296,35 @@ pub struct ResolveDomainResponse {
     pub site_id: Option<Sha256Digest>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetStorageUsagePayload {
+    pub site_id: Sha256Digest,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GetStorageUsageResponse {
+    /// Encoded key + value bytes of every kv entry of the site
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ScanKeysPayload {
+    pub site_id: Sha256Digest,
+    pub prefix: String,
+    /// Inclusive, scans from the edge of the prefix range if None
+    pub start: Option<String>,
+    /// Capped at MAX_KV_SCAN_LIMIT
+    pub limit: u32,
+    pub reverse: bool,
+}
+
+/// Keys in byte order, or reverse byte order, not covered by a state proof
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ScanKeysResponse {
+    pub keys: Vec<String>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct RpcRequest {
     pub id: u64,
@@ -132,6 +361,8 @@ pub struct EthProxyResponse {
 #[allow(unused_imports)]
 use crate::borsh::*;
 use crate::crypto::{ed25519, sha256::Sha256Digest};
-use crate::types::consensus::BlockHeader;
+use crate::types::consensus::{BlockHeader, CertificateVotes, PersistedValidatorSet};
+use crate::types::execution::event::{BlockEvent, EventBlock};
+use crate::types::execution::receipt::{TxOutcome, TxReceipt};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
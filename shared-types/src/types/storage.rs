@@ -27,6 +27,16 @@ impl PageStorageKey {
     }
 }
 
+/// Key of the EventBlock of a site topic at a height, big endian height keeps a topic in height order
+pub fn event_block_key(site_id: Sha256Digest, topic: Sha256Digest, height: u64) -> Vec<u8> {
+    return [event_head_key(site_id, topic).as_slice(), &height.to_be_bytes()].concat();
+}
+
+/// Key of the latest height a site emitted events under a topic
+pub fn event_head_key(site_id: Sha256Digest, topic: Sha256Digest) -> Vec<u8> {
+    return [site_id.to_bytes(), topic.to_bytes()].concat();
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Page {
     pub site_id: Sha256Digest,
@@ -46,6 +56,10 @@ pub fn cf_to_namespace_byte(cf: &str) -> u8 {
         "sitekv" => 1,
         "domain" => 2,
         "page" => 3,
+        "receipt" => 4,
+        "event" => 5,
+        "epoch" => 6,
+        "site_storage" => 7,
         other => panic!("unknown state CF in JMT namespace mapping: {other}"),
     }
 }
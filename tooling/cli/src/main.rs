@@ -18,6 +18,8 @@ enum Commands {
         keystore: Option<PathBuf>,
         #[arg(long)]
         rpc: bool,
+        #[command(flatten)]
+        round_timeout: node::RoundTimeoutArgs,
     },
     GenerateKeys {
         #[arg(long, default_value = "keystore.bin")]
@@ -50,7 +52,9 @@ async fn main() -> Result<()> {
             scaffold::initialize_new_project(name, template);
         }
         Commands::RunDev {} => start_run_dev().await,
-        Commands::StartNode { keystore, rpc } => node::start_node(keystore, rpc).await,
+        Commands::StartNode { keystore, rpc, round_timeout } => {
+            node::start_node(keystore, rpc, round_timeout).await
+        }
         Commands::GenerateKeys { output, wallet_key } => node::generate_keys(output, wallet_key)?,
         Commands::ShowKeys { keystore } => node::show_keys(keystore),
         Commands::VastrumGitClone { repo_name } => vastrum_git_clone(repo_name).await?,
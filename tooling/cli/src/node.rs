@@ -5,9 +5,44 @@ fn default_keystore_path() -> PathBuf {
         .join("keystore.bin")
 }
 
-pub async fn start_node(keystore: Option<PathBuf>, rpc: bool) {
+pub async fn start_node(keystore: Option<PathBuf>, rpc: bool, round_timeout: RoundTimeoutArgs) {
     let path = keystore.unwrap_or_else(default_keystore_path);
-    vastrum_node::start_node_production(path, rpc).await;
+    let round_timeout = round_timeout.to_config().unwrap_or_else(|e| e.exit());
+    vastrum_node::start_node_production(path, rpc, round_timeout).await;
+}
+
+/// Round timeout bounds in milliseconds, unset values use the node defaults
+#[derive(Args)]
+pub struct RoundTimeoutArgs {
+    #[arg(long)]
+    round_timeout_ms: Option<u64>,
+    #[arg(long)]
+    min_round_timeout_ms: Option<u64>,
+    #[arg(long)]
+    max_round_timeout_ms: Option<u64>,
+}
+
+impl RoundTimeoutArgs {
+    /// Rejects a min above the max, including against the default of the unset bound
+    fn to_config(&self) -> Result<RoundTimeoutConfig, clap::Error> {
+        let default = RoundTimeoutConfig::default();
+        let millis_or =
+            |millis: Option<u64>, default| millis.map_or(default, Duration::from_millis);
+        let config = RoundTimeoutConfig {
+            initial: millis_or(self.round_timeout_ms, default.initial),
+            min: millis_or(self.min_round_timeout_ms, default.min),
+            max: millis_or(self.max_round_timeout_ms, default.max),
+        };
+        if config.min > config.max {
+            let message = format!(
+                "min round timeout {}ms is above max round timeout {}ms\n",
+                config.min.as_millis(),
+                config.max.as_millis()
+            );
+            return Err(clap::Error::raw(ErrorKind::ArgumentConflict, message));
+        }
+        return Ok(config);
+    }
 }
 
 pub fn generate_keys(output: PathBuf, wallet_key: String) -> Result<()> {
@@ -33,6 +68,8 @@ pub fn show_keys(keystore: PathBuf) {
 }
 
 use anyhow::Result;
-use std::path::PathBuf;
+use clap::{Args, error::ErrorKind};
+use std::{path::PathBuf, time::Duration};
+use vastrum_node::consensus::round_timer::RoundTimeoutConfig;
 use vastrum_node::keystore::keyset::Keystore;
 use vastrum_shared_types::crypto::ed25519;
@@ -1,6 +1,8 @@
 #[derive(Parser)]
 #[command(about = "Generate validator keystores and genesis config")]
 struct Args {
+    #[arg(long, default_value = "1")]
+    chain_id: u64,
     #[arg(long)]
     validators: u64,
     #[arg(long, default_value = "0")]
@@ -11,25 +13,31 @@ struct Args {
     bootstrap_hosts: Vec<String>,
     #[arg(long, value_delimiter = ',')]
     rpc_hosts: Vec<String>,
+    #[arg(long, default_value_t = DEFAULT_SITE_STORAGE_QUOTA_BYTES)]
+    site_storage_quota_bytes: u64,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
     generate_genesis(
+        args.chain_id,
         args.validators,
         args.rpc_nodes,
         args.output_dir,
         args.bootstrap_hosts,
         args.rpc_hosts,
+        args.site_storage_quota_bytes,
     )
 }
 
 fn generate_genesis(
+    chain_id: u64,
     validators: u64,
     rpc_nodes: u64,
     output_dir: PathBuf,
     bootstrap_hosts: Vec<String>,
     rpc_hosts: Vec<String>,
+    site_storage_quota_bytes: u64,
 ) -> Result<()> {
     std::fs::create_dir_all(&output_dir)?;
 
@@ -70,6 +78,8 @@ fn generate_genesis(
     }
 
     let config = GenesisConfig {
+        chain_id,
+        site_storage_quota_bytes,
         validators: genesis_validators,
         bootstrap_peers: genesis_bootstrap_peers,
         rpc_nodes: genesis_rpc_nodes,
@@ -124,3 +134,4 @@ use vastrum_shared_types::crypto::ed25519;
 use vastrum_shared_types::genesis::{
     GenesisBootstrapPeer, GenesisConfig, GenesisRpcNode, GenesisValidator,
 };
+use vastrum_shared_types::limits::DEFAULT_SITE_STORAGE_QUOTA_BYTES;
@@ -1,6 +1,19 @@
 pub async fn deploy_module(module_path: &str, constructor_calldata: Vec<u8>) -> Sha256Digest {
     let (site_id, tx) =
-        deploy_module_tx(module_path.to_string(), constructor_calldata).await.unwrap();
+        deploy_module_tx(module_path.to_string(), constructor_calldata, None).await.unwrap();
+    let http = NativeHttpClient::new();
+    http.submit_transaction(tx.encode()).await.unwrap();
+    return site_id;
+}
+
+/// Deploy a site that admin can later point to a new module with upgrade_site
+pub async fn deploy_upgradeable_module(
+    module_path: &str,
+    constructor_calldata: Vec<u8>,
+    admin: ed25519::PublicKey,
+) -> Sha256Digest {
+    let (site_id, tx) =
+        deploy_module_tx(module_path.to_string(), constructor_calldata, Some(admin)).await.unwrap();
     let http = NativeHttpClient::new();
     http.submit_transaction(tx.encode()).await.unwrap();
     return site_id;
@@ -23,20 +36,55 @@ pub async fn instantiate_module(
     return site_id;
 }
 
+/// Module has to be stored already, see add_module
+pub async fn upgrade_site(
+    site_id: Sha256Digest,
+    module_id: Sha256Digest,
+    migrate_calldata: Vec<u8>,
+    admin_key: ed25519::PrivateKey,
+) -> NativeTxPoller {
+    let http = NativeHttpClient::new();
+    let latest = http.get_latest_block().await.unwrap();
+
+    let tx = build_upgrade_site_transaction(
+        site_id,
+        module_id,
+        migrate_calldata,
+        admin_key,
+        TxEnvelope {
+            nonce: rand::random(),
+            recent_block_height: latest.height,
+            chain_id: genesis_chain_id(),
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: MAX_TX_FUEL_LIMIT,
+        },
+    );
+
+    let tx_hash = tx.calculate_txhash();
+    http.submit_transaction(tx.encode()).await.unwrap();
+    let sent_tx = NativeTxPoller::new(tx_hash);
+    return sent_tx;
+}
+
 pub async fn register_domain(
     site_id: Sha256Digest,
     domain_name: impl Into<String>,
 ) -> NativeTxPoller {
     let private_key = ed25519::PrivateKey::from_seed(0xcadfefe);
     let http = NativeHttpClient::new();
-    let recent_block_height = http.get_latest_block_height().await.unwrap();
+    let latest = http.get_latest_block().await.unwrap();
 
     let tx = build_register_domain_transaction(
         site_id,
         domain_name.into(),
-        rand::random(),
         private_key,
-        recent_block_height,
+        TxEnvelope {
+            nonce: rand::random(),
+            recent_block_height: latest.height,
+            chain_id: genesis_chain_id(),
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: 0,
+        },
     );
 
     let tx_hash = tx.calculate_txhash();
@@ -48,18 +96,25 @@ pub async fn register_domain(
 pub async fn deploy_module_tx(
     module_path: String,
     constructor_calldata: Vec<u8>,
+    admin: Option<ed25519::PublicKey>,
 ) -> Result<(Sha256Digest, Transaction), HttpError> {
     let http = NativeHttpClient::new();
     let private_key = ed25519::PrivateKey::from_seed(0xcadfefe);
     let wasm_data = std::fs::read(module_path).unwrap();
-    let recent_block_height = http.get_latest_block_height().await?;
+    let latest = http.get_latest_block().await?;
 
     let tx = build_deploy_new_module_transaction(
         wasm_data,
         constructor_calldata,
-        rand::random(),
+        admin,
         private_key,
-        recent_block_height,
+        TxEnvelope {
+            nonce: rand::random(),
+            recent_block_height: latest.height,
+            chain_id: genesis_chain_id(),
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: MAX_TX_FUEL_LIMIT,
+        },
     );
     let site_id = tx.calculate_txhash();
     return Ok((site_id, tx));
@@ -70,10 +125,19 @@ async fn add_module_tx(module_path: String) -> Result<(Sha256Digest, Transaction
     let module_data = std::fs::read(module_path).unwrap();
     let module_id = sha256_hash(&module_data);
     let http = NativeHttpClient::new();
-    let recent_block_height = http.get_latest_block_height().await?;
+    let latest = http.get_latest_block().await?;
 
-    let tx =
-        build_add_module_transaction(module_data, rand::random(), private_key, recent_block_height);
+    let tx = build_add_module_transaction(
+        module_data,
+        private_key,
+        TxEnvelope {
+            nonce: rand::random(),
+            recent_block_height: latest.height,
+            chain_id: genesis_chain_id(),
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: 0,
+        },
+    );
     return Ok((module_id, tx));
 }
 
@@ -83,14 +147,20 @@ async fn instantiate_module_tx(
 ) -> Result<(Sha256Digest, Transaction), HttpError> {
     let private_key = ed25519::PrivateKey::from_seed(0xcadfefe);
     let http = NativeHttpClient::new();
-    let recent_block_height = http.get_latest_block_height().await?;
+    let latest = http.get_latest_block().await?;
 
     let tx = build_deploy_stored_module_transaction(
         module_id,
         constructor_calldata,
-        rand::random(),
+        None,
         private_key,
-        recent_block_height,
+        TxEnvelope {
+            nonce: rand::random(),
+            recent_block_height: latest.height,
+            chain_id: genesis_chain_id(),
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: MAX_TX_FUEL_LIMIT,
+        },
     );
     let site_id = tx.calculate_txhash();
     return Ok((site_id, tx));
@@ -103,8 +173,8 @@ pub async fn poll_until_site_id_deployed(site_id: Sha256Digest) {
         }
         sleep(Duration::from_millis(5)).await;
     }
-    //need to wait 1 more block because state proof for this transactions only exists when the block after this is finalized
-    http.wait_for_next_block().await;
+    //state proof for this transaction only exists once STATE_ROOT_DELAY more blocks are finalized
+    http.wait_for_blocks(STATE_ROOT_DELAY).await;
 }
 
 use crate::error::HttpError;
@@ -115,9 +185,12 @@ use vastrum_shared_types::{
         ed25519,
         sha256::{Sha256Digest, sha256_hash},
     },
+    genesis::genesis_chain_id,
+    limits::{MAX_TX_FUEL_LIMIT, STATE_ROOT_DELAY},
     transactioning::transaction_generator::{
-        build_add_module_transaction, build_deploy_new_module_transaction,
+        TxEnvelope, build_add_module_transaction, build_deploy_new_module_transaction,
         build_deploy_stored_module_transaction, build_register_domain_transaction,
+        build_upgrade_site_transaction,
     },
     types::execution::transaction::Transaction,
 };
@@ -17,6 +17,11 @@ impl NativeHttpClient {
     }
 
     pub async fn get_latest_block_height(&self) -> Result<u64, HttpError> {
+        Ok(self.get_latest_block().await?.height)
+    }
+
+    /// Latest height together with the pow difficulty new transactions should be mined to
+    pub async fn get_latest_block(&self) -> Result<GetLatestBlockHeightResponse, HttpError> {
         let url = format!("{}/getlatestblockheight/", self.base_url);
 
         Ok(self
@@ -26,8 +31,7 @@ impl NativeHttpClient {
             .await?
             .error_for_status()?
             .json::<GetLatestBlockHeightResponse>()
-            .await?
-            .height)
+            .await?)
     }
 
     pub async fn submit_transaction(&self, tx_bytes: Vec<u8>) -> Result<(), HttpError> {
@@ -74,6 +78,117 @@ impl NativeHttpClient {
             .included)
     }
 
+    pub async fn get_tx_status(&self, tx_hash: Sha256Digest) -> Result<TxStatus, HttpError> {
+        let payload = GetTxStatusPayload { tx_hash };
+        let url = format!("{}/gettxstatus/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetTxStatusResponse>()
+            .await?
+            .status)
+    }
+
+    /// Unverified receipt with a state proof, RpcClient::get_tx_receipt verifies it
+    pub async fn get_tx_receipt(
+        &self,
+        tx_hash: Sha256Digest,
+    ) -> Result<GetTxReceiptResult, HttpError> {
+        let payload = GetTxReceiptPayload { tx_hash };
+        let url = format!("{}/gettxreceipt/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetTxReceiptResult>()
+            .await?)
+    }
+
+    /// Validator set of an epoch with its proof, caller verifies it with verify_epoch_handoff
+    pub async fn get_epoch_handoff(&self, epoch: u64) -> Result<GetEpochHandoffResult, HttpError> {
+        let payload = GetEpochHandoffPayload { epoch };
+        let url = format!("{}/getepochhandoff/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetEpochHandoffResult>()
+            .await?)
+    }
+
+    /// Event blocks of a site topic with proofs, caller verifies them with verify_events_proof
+    pub async fn get_events(
+        &self,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        from_height: u64,
+        to_height: u64,
+    ) -> Result<GetEventsResult, HttpError> {
+        let payload = GetEventsPayload { site_id, topic, from_height, to_height };
+        let url = format!("{}/getevents/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetEventsResult>()
+            .await?)
+    }
+
+    /// Page of the node's mempool, highest priority first
+    pub async fn get_pending_transactions(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<GetPendingTransactionsResponse, HttpError> {
+        let payload = GetPendingTransactionsPayload { offset, limit };
+        let url = format!("{}/getpendingtransactions/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetPendingTransactionsResponse>()
+            .await?)
+    }
+
+    /// Runs a site call against finalized state without submitting it, nothing is persisted
+    pub async fn simulate_call(
+        &self,
+        payload: SimulateCallPayload,
+    ) -> Result<SimulateCallResult, HttpError> {
+        let url = format!("{}/simulatecall/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<SimulateCallResult>()
+            .await?)
+    }
+
     pub async fn get_page(
         &self,
         site_identifier: String,
@@ -112,10 +227,10 @@ impl NativeHttpClient {
         }
     }
 
-    pub async fn wait_for_next_block(&self) {
+    pub async fn wait_for_blocks(&self, count: u64) {
         let height = self.get_latest_block_height().await.unwrap_or(0);
         loop {
-            if self.get_latest_block_height().await.unwrap_or(0) > height {
+            if self.get_latest_block_height().await.unwrap_or(0) >= height + count {
                 return;
             }
             tokio::time::sleep(std::time::Duration::from_millis(5)).await;
@@ -138,6 +253,39 @@ impl NativeHttpClient {
             .site_id)
     }
 
+    pub async fn get_storage_usage(
+        &self,
+        site_id: Sha256Digest,
+    ) -> Result<GetStorageUsageResponse, HttpError> {
+        let payload = GetStorageUsagePayload { site_id };
+        let url = format!("{}/getstorageusage/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GetStorageUsageResponse>()
+            .await?)
+    }
+
+    /// Site keys in byte order from start, unproven, read values with get_key_value_response
+    pub async fn scan_keys(&self, payload: ScanKeysPayload) -> Result<ScanKeysResponse, HttpError> {
+        let url = format!("{}/scankeys/", self.base_url);
+
+        Ok(self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ScanKeysResponse>()
+            .await?)
+    }
+
     pub async fn get_key_value_response(
         &self,
         site_id: Sha256Digest,
@@ -209,9 +357,13 @@ use vastrum_shared_types::{
     crypto::sha256::Sha256Digest,
     ports::HTTP_RPC_PORT,
     types::rpc::types::{
+        GetEpochHandoffPayload, GetEpochHandoffResult, GetEventsPayload, GetEventsResult,
         GetKeyValuePayload, GetKeyValueResult, GetLatestBlockHeightResponse, GetPagePayload,
-        GetPageResult, GetSiteIDIsDeployed, GetSiteIDIsDeployedResponse, GetTxHashIsIncluded,
-        GetTxHashIsIncludedResponse, ResolveDomainRequest, ResolveDomainResponse,
-        SubmitTransactionPayload,
+        GetPageResult, GetPendingTransactionsPayload, GetPendingTransactionsResponse,
+        GetSiteIDIsDeployed, GetSiteIDIsDeployedResponse, GetStorageUsagePayload,
+        GetStorageUsageResponse, GetTxHashIsIncluded, GetTxHashIsIncludedResponse,
+        GetTxReceiptPayload, GetTxReceiptResult, GetTxStatusPayload, GetTxStatusResponse,
+        ResolveDomainRequest, ResolveDomainResponse, ScanKeysPayload, ScanKeysResponse,
+        SimulateCallPayload, SimulateCallResult, SubmitTransactionPayload, TxStatus,
     },
 };
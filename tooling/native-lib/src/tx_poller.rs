@@ -11,19 +11,29 @@ impl NativeTxPoller {
         self.tx_hash
     }
 
-    pub async fn await_confirmation(&self) {
+    /// Polls until the transaction is included, failed or expired, returns the last seen status on timeout
+    pub async fn await_confirmation(&self) -> TxStatus {
         let http = NativeHttpClient::new();
 
+        let mut status = TxStatus::Unknown;
         for _ in 0..2400 {
-            if http.get_tx_hash_inclusion_state(self.tx_hash).await.unwrap_or(false) {
-                //wait one more block so state proof exists for this transactions (state proofs are delayed 1 block)
-                http.wait_for_next_block().await;
-                return;
+            if let Ok(latest) = http.get_tx_status(self.tx_hash).await {
+                status = latest;
+            }
+            if status.is_final() {
+                if status != TxStatus::Expired {
+                    //wait so state proof exists for this transaction (state proofs are delayed STATE_ROOT_DELAY blocks)
+                    http.wait_for_blocks(STATE_ROOT_DELAY).await;
+                }
+                return status;
             }
             tokio::time::sleep(std::time::Duration::from_millis(5)).await;
         }
+        return status;
     }
 }
 
 use crate::NativeHttpClient;
 use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::limits::STATE_ROOT_DELAY;
+use vastrum_shared_types::types::rpc::types::TxStatus;
@@ -5,19 +5,19 @@ pub async fn get_current_path() -> String {
     return path;
 }
 
+/// Resolves to "included", "failed", "expired", or on timeout "pending" or "unknown"
 #[wasm_bindgen]
-pub async fn await_tx_inclusion(tx_hash_hex: String) {
+pub async fn await_tx_inclusion(tx_hash_hex: String) -> String {
     let digest = Sha256Digest::from_string(&tx_hash_hex).unwrap();
-    if vastrum_frontend_lib::get_tx_hash_inclusion_state(digest).await {
-        return;
-    }
-    TimeoutFuture::new(5).await;
-    for _ in 0..240 {
-        if vastrum_frontend_lib::get_tx_hash_inclusion_state(digest).await {
-            return;
-        }
-        TimeoutFuture::new(500).await;
-    }
+    let status = vastrum_frontend_lib::await_tx_status(digest).await;
+    let name = match status {
+        TxStatus::Unknown => "unknown",
+        TxStatus::Pending => "pending",
+        TxStatus::Included { .. } => "included",
+        TxStatus::Expired => "expired",
+        TxStatus::Failed { .. } => "failed",
+    };
+    return name.to_string();
 }
 #[wasm_bindgen]
 pub async fn update_current_path(path: String, replace: bool) {
@@ -38,10 +38,10 @@ pub async fn send_eth_rpc_request(request: JsValue) -> Result<JsValue, JsError>
     return Ok(js_value);
 }
 
-use gloo_timers::future::TimeoutFuture;
 use serde::Serialize;
 use vastrum_shared_types::crypto::sha256::Sha256Digest;
 use vastrum_shared_types::iframerpc::types::EthRPCRequest;
+use vastrum_shared_types::types::rpc::types::TxStatus;
 use vastrum_frontend_lib::make_eth_rpc_request;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::prelude::*;
@@ -35,6 +35,19 @@ impl RpcProvider for IFrameRpcClient {
         return Some(res.value);
     }
 
+    async fn read_key_value_at_height(
+        &self,
+        key: String,
+        height: u64,
+    ) -> Result<Option<Vec<u8>>, RpcError> {
+        let res = vastrum_frontend_lib::get_key_value_at_height(key, height).await;
+        let res = res.ok_or_else(|| RpcError("get_key_value_at_height failed".into()))?;
+        if res.value.is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some(res.value));
+    }
+
     async fn get_latest_block_height(&self) -> Option<u64> {
         let height = Some(vastrum_frontend_lib::get_latest_block_height().await);
         return height;
@@ -45,6 +58,42 @@ impl RpcProvider for IFrameRpcClient {
         return Ok(state);
     }
 
+    async fn get_tx_status(&self, hash: Sha256Digest) -> Result<TxStatus, RpcError> {
+        let status = vastrum_frontend_lib::get_tx_status(hash).await;
+        return Ok(status);
+    }
+
+    async fn get_tx_receipt(&self, hash: Sha256Digest) -> Result<TxReceipt, RpcError> {
+        let receipt = vastrum_frontend_lib::get_tx_receipt(hash).await;
+        return receipt.ok_or_else(|| RpcError("get_tx_receipt failed".into()));
+    }
+
+    async fn simulate_call(&self, calldata: Vec<u8>) -> Result<SimulateCallResponse, RpcError> {
+        let response = vastrum_frontend_lib::simulate_call(calldata).await;
+        return response.ok_or_else(|| RpcError("simulate_call failed".into()));
+    }
+
+    async fn scan_keys(
+        &self,
+        prefix: String,
+        start: Option<String>,
+        limit: u32,
+        reverse: bool,
+    ) -> Result<Vec<String>, RpcError> {
+        let keys = vastrum_frontend_lib::scan_keys(prefix, start, limit, reverse).await;
+        return keys.ok_or_else(|| RpcError("scan_keys failed".into()));
+    }
+
+    async fn get_events(
+        &self,
+        topic: Sha256Digest,
+        from_height: u64,
+        to_height: u64,
+    ) -> Result<Vec<BlockEvent>, RpcError> {
+        let events = vastrum_frontend_lib::get_events(topic, from_height, to_height).await;
+        return events.ok_or_else(|| RpcError("get_events failed".into()));
+    }
+
     async fn make_call(&self, calldata: Vec<u8>) -> IFrameSentTx {
         let res = vastrum_frontend_lib::make_call(calldata).await;
         let sent_tx = IFrameSentTx::new(res.tx_hash);
@@ -78,26 +127,20 @@ impl SentTxBehavior for IFrameSentTx {
         return state;
     }
 
-    async fn await_confirmation(&self) {
-        if self.check_if_included().await {
-            wait_for_next_block().await;
-            return;
-        }
-        TimeoutFuture::new(5).await;
-        for _ in 0..240 {
-            if self.check_if_included().await {
-                wait_for_next_block().await;
-                return;
-            }
-            TimeoutFuture::new(500).await;
+    async fn await_confirmation(&self) -> TxStatus {
+        let status = vastrum_frontend_lib::await_tx_status(self.tx_hash).await;
+        if matches!(status, TxStatus::Included { .. } | TxStatus::Failed { .. }) {
+            wait_for_state_proof().await;
         }
+        return status;
     }
 }
 
-async fn wait_for_next_block() {
+//state proofs are delayed STATE_ROOT_DELAY blocks
+async fn wait_for_state_proof() {
     let height = vastrum_frontend_lib::get_latest_block_height().await;
     for _ in 0..240 {
-        if vastrum_frontend_lib::get_latest_block_height().await > height {
+        if vastrum_frontend_lib::get_latest_block_height().await >= height + STATE_ROOT_DELAY {
             return;
         }
         TimeoutFuture::new(500).await;
@@ -107,3 +150,7 @@ async fn wait_for_next_block() {
 use crate::{RpcError, RpcProvider, SentTxBehavior};
 use gloo_timers::future::TimeoutFuture;
 use vastrum_shared_types::crypto::{ed25519, sha256::Sha256Digest};
+use vastrum_shared_types::limits::STATE_ROOT_DELAY;
+use vastrum_shared_types::types::execution::event::BlockEvent;
+use vastrum_shared_types::types::execution::receipt::TxReceipt;
+use vastrum_shared_types::types::rpc::types::{SimulateCallResponse, TxStatus};
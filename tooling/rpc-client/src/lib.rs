@@ -13,6 +13,14 @@ pub trait RpcProvider: Sized {
         height: u64,
     ) -> impl Future<Output = Option<Vec<u8>>>;
 
+    /// Proven value at height, Ok(None) if the key is not set
+    /// Fails if the value could not be fetched or its proof does not verify
+    fn read_key_value_at_height(
+        &self,
+        key: String,
+        height: u64,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, RpcError>>;
+
     fn get_latest_block_height(&self) -> impl Future<Output = Option<u64>>;
 
     fn get_tx_hash_inclusion_state(
@@ -20,6 +28,39 @@ pub trait RpcProvider: Sized {
         hash: Sha256Digest,
     ) -> impl Future<Output = Result<bool, RpcError>>;
 
+    fn get_tx_status(&self, hash: Sha256Digest)
+    -> impl Future<Output = Result<TxStatus, RpcError>>;
+
+    /// Proven receipt of an included transaction, available a few blocks after inclusion
+    fn get_tx_receipt(
+        &self,
+        hash: Sha256Digest,
+    ) -> impl Future<Output = Result<TxReceipt, RpcError>>;
+
+    /// Runs the call read only against the latest finalized state, no transaction is sent
+    fn simulate_call(
+        &self,
+        calldata: Vec<u8>,
+    ) -> impl Future<Output = Result<SimulateCallResponse, RpcError>>;
+
+    /// Keys of the site starting with prefix in byte order, start is inclusive
+    /// The key list is not proven, values read through get_key_value are
+    fn scan_keys(
+        &self,
+        prefix: String,
+        start: Option<String>,
+        limit: u32,
+        reverse: bool,
+    ) -> impl Future<Output = Result<Vec<String>, RpcError>>;
+
+    /// Proven events the site emitted under topic in from_height..=to_height, oldest first
+    fn get_events(
+        &self,
+        topic: Sha256Digest,
+        from_height: u64,
+        to_height: u64,
+    ) -> impl Future<Output = Result<Vec<BlockEvent>, RpcError>>;
+
     fn make_call(&self, calldata: Vec<u8>) -> impl Future<Output = Self::SentTx>;
 
     fn make_authenticated_call(&self, calldata: Vec<u8>) -> impl Future<Output = Self::SentTx>;
@@ -31,7 +72,8 @@ pub struct RpcError(pub String);
 pub trait SentTxBehavior {
     fn tx_hash(&self) -> Sha256Digest;
     fn check_if_included(&self) -> impl Future<Output = bool>;
-    fn await_confirmation(&self) -> impl Future<Output = ()>;
+    /// Resolves once the transaction is included, failed or expired, or with the last seen status on timeout
+    fn await_confirmation(&self) -> impl Future<Output = TxStatus>;
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -55,4 +97,7 @@ pub type RpcClient = IFrameRpcClient;
 pub type SentTx = IFrameSentTx;
 
 use vastrum_shared_types::crypto::{ed25519, sha256::Sha256Digest};
+use vastrum_shared_types::types::execution::event::BlockEvent;
+use vastrum_shared_types::types::execution::receipt::TxReceipt;
+use vastrum_shared_types::types::rpc::types::{SimulateCallResponse, TxStatus};
 use std::future::Future;
@@ -3,13 +3,50 @@ pub struct NativeRpcClient {
     site_id: Sha256Digest,
     http: NativeHttpClient,
     account_key: Option<ed25519::PrivateKey>,
+    //starts at genesis, extended through verified epoch handoffs
+    validator_sets: Arc<Mutex<KnownValidatorSets>>,
+}
+
+impl NativeRpcClient {
+    /// Validators of the epoch block_height is in, walking epoch handoffs from the latest known epoch
+    async fn epoch_validators(&self, block_height: u64) -> Result<EpochValidators, RpcError> {
+        let epoch = epoch_for_height(block_height);
+        loop {
+            let next_missing = self.validator_sets.lock().unwrap().next_missing_epoch(epoch);
+            let next_epoch = match next_missing {
+                Ok(Some(next_epoch)) => next_epoch,
+                Ok(None) => {
+                    return Ok(self.validator_sets.lock().unwrap().get(epoch).unwrap().clone());
+                }
+                Err(e) => return Err(RpcError(format!("{e}"))),
+            };
+            let handoff = match self.http.get_epoch_handoff(next_epoch).await? {
+                GetEpochHandoffResult::Ok(handoff) => *handoff,
+                GetEpochHandoffResult::Err(e) => return Err(RpcError(format!("{e:?}"))),
+            };
+            let mut validator_sets = self.validator_sets.lock().unwrap();
+            //a concurrent call may have added it while this one was fetching
+            if validator_sets.latest_epoch() < handoff.epoch {
+                validator_sets
+                    .add_handoff(&handoff, genesis_chain_id())
+                    .map_err(|e| RpcError(format!("epoch handoff verification failed: {e}")))?;
+            }
+        }
+    }
 }
 
 impl RpcProvider for NativeRpcClient {
     type SentTx = NativeSentTx;
 
     fn new(site_id: Sha256Digest) -> Self {
-        Self { site_id, http: NativeHttpClient::new(), account_key: None }
+        let genesis = genesis_epoch_state();
+        let genesis_validators = EpochValidators {
+            epoch: 0,
+            validators: genesis.validators,
+            total_stake: genesis.total_stake,
+        };
+        let validator_sets = Arc::new(Mutex::new(KnownValidatorSets::new(genesis_validators)));
+        Self { site_id, http: NativeHttpClient::new(), account_key: None, validator_sets }
     }
 
     fn site_id(&self) -> Sha256Digest {
@@ -31,14 +68,22 @@ impl RpcProvider for NativeRpcClient {
                 return None;
             }
         };
-        let genesis = genesis_epoch_state();
+        let proof_height = response.state_proof.block_header.height;
+        let validators = match self.epoch_validators(proof_height).await {
+            Ok(validators) => validators,
+            Err(e) => {
+                eprintln!("validator set unavailable for key {key}: {e:?}");
+                return None;
+            }
+        };
         let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
         if let Err(e) = verify_keyvalue_proof(
             &response,
             self.site_id,
             &key,
-            &genesis.validators,
-            genesis.total_stake,
+            genesis_chain_id(),
+            &validators.validators,
+            validators.total_stake,
             now,
         ) {
             eprintln!("proof verification failed for key {key}: {e}");
@@ -51,32 +96,43 @@ impl RpcProvider for NativeRpcClient {
     }
 
     async fn get_key_value_at_height(&self, key: String, height: u64) -> Option<Vec<u8>> {
+        match self.read_key_value_at_height(key.clone(), height).await {
+            Ok(value) => return value,
+            Err(e) => {
+                eprintln!("get_key_value failed for key {key} at height {height}: {}", e.0);
+                return None;
+            }
+        }
+    }
+
+    async fn read_key_value_at_height(
+        &self,
+        key: String,
+        height: u64,
+    ) -> Result<Option<Vec<u8>>, RpcError> {
         let result =
-            self.http.get_key_value_response(self.site_id, key.clone(), Some(height)).await.ok()?;
+            self.http.get_key_value_response(self.site_id, key.clone(), Some(height)).await?;
         let response = match result {
             GetKeyValueResult::Ok(r) => r,
-            GetKeyValueResult::Err(e) => {
-                eprintln!("get_key_value failed for key {key} at height {height}: {e:?}");
-                return None;
-            }
+            GetKeyValueResult::Err(e) => return Err(RpcError(format!("{e:?}"))),
         };
-        let genesis = genesis_epoch_state();
+        let proof_height = response.state_proof.block_header.height;
+        let validators = self.epoch_validators(proof_height).await?;
         let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
-        if let Err(e) = verify_keyvalue_proof(
+        verify_keyvalue_proof(
             &response,
             self.site_id,
             &key,
-            &genesis.validators,
-            genesis.total_stake,
+            genesis_chain_id(),
+            &validators.validators,
+            validators.total_stake,
             now,
-        ) {
-            eprintln!("proof verification failed for key {key} at height {height}: {e}");
-            return None;
-        }
+        )
+        .map_err(|e| RpcError(format!("proof verification failed for key {key}: {e}")))?;
         if response.value.is_empty() {
-            return None;
+            return Ok(None);
         }
-        return Some(response.value);
+        return Ok(Some(response.value));
     }
 
     async fn get_latest_block_height(&self) -> Option<u64> {
@@ -89,18 +145,107 @@ impl RpcProvider for NativeRpcClient {
         return state;
     }
 
+    async fn get_tx_status(&self, hash: Sha256Digest) -> Result<TxStatus, RpcError> {
+        let status = Ok(self.http.get_tx_status(hash).await?);
+        return status;
+    }
+
+    async fn get_tx_receipt(&self, hash: Sha256Digest) -> Result<TxReceipt, RpcError> {
+        let response = match self.http.get_tx_receipt(hash).await? {
+            GetTxReceiptResult::Ok(r) => *r,
+            GetTxReceiptResult::Err(e) => return Err(RpcError(format!("{e:?}"))),
+        };
+        let validators = self.epoch_validators(response.state_proof.block_header.height).await?;
+        let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        verify_receipt_proof(
+            &response,
+            hash,
+            genesis_chain_id(),
+            &validators.validators,
+            validators.total_stake,
+            now,
+        )
+        .map_err(|e| RpcError(format!("receipt proof verification failed: {e}")))?;
+        return Ok(response.receipt);
+    }
+
+    async fn simulate_call(&self, calldata: Vec<u8>) -> Result<SimulateCallResponse, RpcError> {
+        //views that depend on the sender see the account key if one is set
+        let message_sender = self.account_key.as_ref().map(|key| key.public_key());
+        let payload = SimulateCallPayload {
+            site_id: self.site_id,
+            calldata,
+            message_sender,
+            height_lock: None,
+        };
+        return match self.http.simulate_call(payload).await? {
+            SimulateCallResult::Ok(response) => Ok(response),
+            SimulateCallResult::Err(e) => Err(RpcError(format!("{e:?}"))),
+        };
+    }
+
+    async fn scan_keys(
+        &self,
+        prefix: String,
+        start: Option<String>,
+        limit: u32,
+        reverse: bool,
+    ) -> Result<Vec<String>, RpcError> {
+        let payload = ScanKeysPayload { site_id: self.site_id, prefix, start, limit, reverse };
+        let response = self.http.scan_keys(payload).await?;
+        return Ok(response.keys);
+    }
+
+    async fn get_events(
+        &self,
+        topic: Sha256Digest,
+        from_height: u64,
+        to_height: u64,
+    ) -> Result<Vec<BlockEvent>, RpcError> {
+        //pages come newest first, each continuing below the previous one
+        let mut pages = Vec::new();
+        let mut page_to_height = Some(to_height);
+        while let Some(to_height) = page_to_height {
+            let result = self.http.get_events(self.site_id, topic, from_height, to_height).await?;
+            let response = match result {
+                GetEventsResult::Ok(r) => *r,
+                GetEventsResult::Err(e) => return Err(RpcError(format!("{e:?}"))),
+            };
+            let validators =
+                self.epoch_validators(response.state_proof.block_header.height).await?;
+            let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+            let request = GetEventsPayload { site_id: self.site_id, topic, from_height, to_height };
+            page_to_height = verify_events_proof(
+                &response,
+                &request,
+                genesis_chain_id(),
+                &validators.validators,
+                validators.total_stake,
+                now,
+            )
+            .map_err(|e| RpcError(format!("event proof verification failed: {e}")))?;
+            pages.push(response.into_events());
+        }
+        return Ok(pages.into_iter().rev().flatten().collect());
+    }
+
     async fn make_call(&self, calldata: Vec<u8>) -> NativeSentTx {
         let throwaway_private_key = ed25519::PrivateKey::from_rng();
         let nonce = rand::random();
 
-        let recent_block_height = self.http.get_latest_block_height().await.unwrap();
+        let latest = self.http.get_latest_block().await.unwrap();
 
         let transaction = build_call_transaction(
             self.site_id,
             calldata,
-            nonce,
             throwaway_private_key,
-            recent_block_height,
+            TxEnvelope {
+                nonce,
+                recent_block_height: latest.height,
+                chain_id: genesis_chain_id(),
+                pow_difficulty: latest.pow_difficulty,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
         );
         self.http.submit_transaction(transaction.encode()).await.unwrap();
 
@@ -114,15 +259,20 @@ impl RpcProvider for NativeRpcClient {
             .clone()
             .expect("Authenticated call requires key. Use .with_account_key()");
 
-        let recent_block_height = self.http.get_latest_block_height().await.unwrap();
+        let latest = self.http.get_latest_block().await.unwrap();
         let nonce = rand::random();
 
         let transaction = build_call_transaction(
             self.site_id,
             calldata,
-            nonce,
             account_private_key,
-            recent_block_height,
+            TxEnvelope {
+                nonce,
+                recent_block_height: latest.height,
+                chain_id: genesis_chain_id(),
+                pow_difficulty: latest.pow_difficulty,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
         );
         self.http.submit_transaction(transaction.encode()).await.unwrap();
 
@@ -151,11 +301,12 @@ impl SentTxBehavior for NativeSentTx {
         self.http.get_tx_hash_inclusion_state(self.tx_hash).await.unwrap_or(false)
     }
 
-    async fn await_confirmation(&self) {
-        NativeTxPoller::new(self.tx_hash).await_confirmation().await;
+    async fn await_confirmation(&self) -> TxStatus {
+        return NativeTxPoller::new(self.tx_hash).await_confirmation().await;
     }
 }
 
+use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 use crate::{RpcError, RpcProvider, SentTxBehavior};
@@ -169,8 +320,18 @@ impl From<vastrum_native_lib::error::HttpError> for RpcError {
 use vastrum_shared_types::{
     borsh::BorshExt,
     crypto::{ed25519, sha256::Sha256Digest},
-    genesis::genesis_epoch_state,
-    proof_verification::verify_keyvalue_proof,
-    transactioning::transaction_generator::build_call_transaction,
-    types::rpc::types::GetKeyValueResult,
+    genesis::{genesis_chain_id, genesis_epoch_state},
+    limits::DEFAULT_TX_FUEL_LIMIT,
+    proof_verification::{
+        EpochValidators, KnownValidatorSets, epoch_for_height, verify_events_proof,
+        verify_keyvalue_proof, verify_receipt_proof,
+    },
+    transactioning::transaction_generator::{TxEnvelope, build_call_transaction},
+    types::execution::event::BlockEvent,
+    types::execution::receipt::TxReceipt,
+    types::rpc::types::{
+        GetEpochHandoffResult, GetEventsPayload, GetEventsResult, GetKeyValueResult,
+        GetTxReceiptResult, ScanKeysPayload, SimulateCallPayload, SimulateCallResponse,
+        SimulateCallResult, TxStatus,
+    },
 };
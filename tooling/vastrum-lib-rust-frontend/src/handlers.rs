@@ -70,6 +70,67 @@ pub async fn get_tx_hash_inclusion_state(tx_hash: Sha256Digest) -> bool {
     }
 }
 
+pub async fn get_tx_status(tx_hash: Sha256Digest) -> TxStatus {
+    let params = GetTxStatusRequest { tx_hash };
+    match send_request::<_, GetTxStatusResponse>(params, RpcMethod::GetTxStatus).await {
+        Ok(r) => r.status,
+        Err(()) => TxStatus::Unknown,
+    }
+}
+
+/// Polls until the transaction is included, failed or expired, returns the last seen status on timeout
+pub async fn await_tx_status(tx_hash: Sha256Digest) -> TxStatus {
+    let mut status = get_tx_status(tx_hash).await;
+    if status.is_final() {
+        return status;
+    }
+    TimeoutFuture::new(5).await;
+    for _ in 0..240 {
+        status = get_tx_status(tx_hash).await;
+        if status.is_final() {
+            return status;
+        }
+        TimeoutFuture::new(500).await;
+    }
+    return status;
+}
+
+/// Proven receipt of an included transaction, None until its state proof is available or if the host could not verify it
+pub async fn get_tx_receipt(tx_hash: Sha256Digest) -> Option<TxReceipt> {
+    let params = GetTxReceiptRequest { tx_hash };
+    let res: GetTxReceiptResponse = send_request(params, RpcMethod::GetTxReceipt).await.ok()?;
+    return Some(res.receipt);
+}
+
+/// Runs the call read only as the site account, None if the node rejected it
+pub async fn simulate_call(call_data: Vec<u8>) -> Option<SimulateCallResponse> {
+    let params = SimulateCallRequest { call_data };
+    send_request(params, RpcMethod::SimulateCall).await.ok()
+}
+
+/// Proven events of the current site under topic, oldest first, None if the host could not verify them
+pub async fn get_events(
+    topic: Sha256Digest,
+    from_height: u64,
+    to_height: u64,
+) -> Option<Vec<BlockEvent>> {
+    let params = GetEventsRequest { topic, from_height, to_height };
+    let res: GetEventsResponse = send_request(params, RpcMethod::GetEvents).await.ok()?;
+    return Some(res.events);
+}
+
+/// Keys of the current site starting with prefix in byte order, None if the host request failed
+pub async fn scan_keys(
+    prefix: String,
+    start: Option<String>,
+    limit: u32,
+    reverse: bool,
+) -> Option<Vec<String>> {
+    let params = ScanKeysRequest { prefix, start, limit, reverse };
+    let res: ScanKeysResponse = send_request(params, RpcMethod::ScanKeys).await.ok()?;
+    return Some(res.keys);
+}
+
 pub async fn make_eth_rpc_request(request: EthRPCRequest) -> EthRPCResponse {
     let params = GetEthRPCRequest { request };
     let res: GetEthRPCResponse = send_request(params, RpcMethod::EthRpcRequest).await.unwrap();
@@ -101,17 +162,23 @@ pub fn navigate_to(params: &str) {
 }
 
 use crate::rpc::send_request;
+use gloo_timers::future::TimeoutFuture;
 use vastrum_shared_types::borsh::BorshExt;
 use vastrum_shared_types::crypto::ed25519;
 use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
 use vastrum_shared_types::iframerpc::types::{
     EthRPCRequest, EthRPCResponse, GetCurrentPath, GetCurrentPathResponse, GetEthRPCRequest,
-    GetEthRPCResponse, GetKeyValueBySiteIdRequest, GetKeyValueRequest, GetKeyValueResponse,
-    GetLatestBlockHeight, GetLatestBlockHeightResponse, GetPrivateKeyResponse, GetPrivateKeyRpc,
-    GetPrivateSalt, GetPrivateSaltResponse, GetPubKey, GetPubKeyResponse, GetTXHashIsConfirmed,
-    GetTXHashIsConfirmedResponse, MakeAuthCallRequest, MakeAuthCallResponse, MakeCallRequest,
-    MakeCallResponse, PageNavigationEventMessage, RpcMethod, UpdateCurrentPath,
+    GetEthRPCResponse, GetEventsRequest, GetEventsResponse, GetKeyValueBySiteIdRequest,
+    GetKeyValueRequest, GetKeyValueResponse, GetLatestBlockHeight, GetLatestBlockHeightResponse,
+    GetPrivateKeyResponse, GetPrivateKeyRpc, GetPrivateSalt, GetPrivateSaltResponse, GetPubKey,
+    GetPubKeyResponse, GetTXHashIsConfirmed, GetTXHashIsConfirmedResponse, GetTxReceiptRequest,
+    GetTxReceiptResponse, GetTxStatusRequest, GetTxStatusResponse, MakeAuthCallRequest,
+    MakeAuthCallResponse, MakeCallRequest, MakeCallResponse, PageNavigationEventMessage, RpcMethod,
+    ScanKeysRequest, ScanKeysResponse, SimulateCallRequest, UpdateCurrentPath,
     UpdateCurrentPathResponse,
 };
+use vastrum_shared_types::types::execution::event::BlockEvent;
+use vastrum_shared_types::types::execution::receipt::TxReceipt;
+use vastrum_shared_types::types::rpc::types::{SimulateCallResponse, TxStatus};
 use wasm_bindgen::prelude::*;
 use web_sys::{CustomEvent, CustomEventInit, window};
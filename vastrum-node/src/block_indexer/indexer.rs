@@ -44,16 +44,17 @@ fn index_transactions_in_block(db: &BatchDb, cert: &FinalizedBlock, height: u64)
             continue;
         };
 
-        let (detail, summary) = build_tx_records(
-            &tx_data,
-            &tx_hash,
-            &tx.pub_key,
-            height,
-            idx,
-            block.timestamp,
-            tx.nonce,
-            tx.recent_block_height,
-        );
+        //receipt is written during execution of this block
+        let receipt = db.read_tx_receipt(tx_hash);
+        #[cfg(madsim)]
+        let receipt = receipt.or_else(|| Some(simulated_receipt(height)));
+        //transactions of a block rejected during execution have no receipt
+        let Some(receipt) = receipt else {
+            tracing::warn!(?tx_hash, "no receipt for indexed transaction, skipping");
+            continue;
+        };
+        let (detail, summary) =
+            build_tx_records(&tx_data, &tx_hash, tx, receipt, height, idx, block.timestamp);
 
         // /tx/:hash
         store_tx_detail(db, &detail.tx_hash, &detail);
@@ -73,6 +74,11 @@ fn index_transactions_in_block(db: &BatchDb, cert: &FinalizedBlock, height: u64)
             track_site_deploy(db, &tx_hash, &tx_data, height);
         }
 
+        // /site/:id (module and upgrade history)
+        if tx_data.transaction_type == TransactionType::UpgradeSite && detail.success {
+            track_site_upgrade(db, &tx_hash, &tx_data.calldata, height);
+        }
+
         // /site/:id (domain field)
         if tx_data.transaction_type == TransactionType::RegisterDomain {
             track_domain_register(db, &tx_data.calldata, height);
@@ -80,7 +86,10 @@ fn index_transactions_in_block(db: &BatchDb, cert: &FinalizedBlock, height: u64)
 
         // /site/:id (tx history)
         if let Some(ref site) = detail.target_site {
-            if tx_data.transaction_type == TransactionType::Call {
+            if matches!(
+                tx_data.transaction_type,
+                TransactionType::Call | TransactionType::UpgradeSite
+            ) {
                 update_site_tx(db, site, &detail.tx_hash);
             }
         }
@@ -102,13 +111,13 @@ fn store_block_tx_summaries(db: &BatchDb, height: u64, summaries: &[TxSummary])
 fn build_tx_records(
     tx_data: &TransactionData,
     tx_hash: &Sha256Digest,
-    pub_key: &ed25519::PublicKey,
+    tx: &Transaction,
+    receipt: TxReceipt,
     block_height: u64,
     tx_index: usize,
     timestamp: u64,
-    nonce: u64,
-    recent_block_height: u64,
 ) -> (TxDetail, TxSummary) {
+    let pub_key = &tx.pub_key;
     let (tx_type, target_site, sender, function_sig) = match tx_data.transaction_type {
         TransactionType::Call => {
             let call = borsh::from_slice::<SiteCall>(&tx_data.calldata).ok();
@@ -123,6 +132,20 @@ fn build_tx_records(
         }
         TransactionType::RegisterDomain => ("RegisterDomain", None, None, None),
         TransactionType::AddModule => ("AddModule", None, None, None),
+        TransactionType::RegisterValidator => {
+            ("RegisterValidator", None, Some(pub_key.to_string()), None)
+        }
+        TransactionType::ExitValidator => ("ExitValidator", None, Some(pub_key.to_string()), None),
+        TransactionType::Evidence => ("Evidence", None, Some(pub_key.to_string()), None),
+        TransactionType::UpgradeSite => {
+            let call = borsh::from_slice::<UpgradeSiteCall>(&tx_data.calldata).ok();
+            ("UpgradeSite", call.map(|c| c.site_id.to_string()), Some(pub_key.to_string()), None)
+        }
+    };
+
+    let error = match receipt.outcome {
+        TxOutcome::Success => None,
+        TxOutcome::Failure { message } => Some(message),
     };
 
     let detail = TxDetail {
@@ -133,9 +156,15 @@ fn build_tx_records(
         sender: sender.clone(),
         tx_type: tx_type.into(),
         target_site: target_site.clone(),
-        nonce: nonce.to_string(),
-        recent_block_height,
+        version: tx.version,
+        nonce: tx.nonce.to_string(),
+        recent_block_height: tx.recent_block_height,
         function_sig: function_sig.clone(),
+        success: error.is_none(),
+        error,
+        fuel_used: receipt.fuel_used,
+        kv_bytes_written: receipt.kv_bytes_written,
+        host_calls: receipt.host_calls,
     };
 
     let summary = TxSummary {
@@ -149,6 +178,19 @@ fn build_tx_records(
     (detail, summary)
 }
 
+//madsim does not execute transactions so there is no receipt
+#[cfg(madsim)]
+fn simulated_receipt(height: u64) -> TxReceipt {
+    return TxReceipt {
+        height,
+        outcome: TxOutcome::Success,
+        fuel_used: 0,
+        kv_bytes_written: 0,
+        host_calls: vastrum_shared_types::types::execution::receipt::HostCallCounts::default(),
+        return_data: vec![],
+    };
+}
+
 fn extract_function_sig(calldata: &[u8]) -> Option<String> {
     if calldata.len() < 8 {
         return None;
@@ -183,13 +225,21 @@ fn track_site_deploy(db: &BatchDb, tx_hash: &Sha256Digest, tx_data: &Transaction
 }
 
 fn store_site_detail(db: &BatchDb, site_id: &str, tx_hash: &Sha256Digest, tx_data: &TransactionData, height: u64) {
-    let module_id = match tx_data.transaction_type {
+    let (module_id, admin) = match tx_data.transaction_type {
         TransactionType::DeployStoredModule => {
-            borsh::from_slice::<DeployStoredModuleCall>(&tx_data.calldata)
-                .ok()
-                .map(|c| c.module_id.to_string())
+            match borsh::from_slice::<DeployStoredModuleCall>(&tx_data.calldata) {
+                Ok(c) => (Some(c.module_id.to_string()), c.admin),
+                Err(_) => (None, None),
+            }
+        }
+        //module id is the hash of the wasm, same as when the node stores it
+        TransactionType::DeployNewModule => {
+            match borsh::from_slice::<DeployNewModuleCall>(&tx_data.calldata) {
+                Ok(c) => (Some(sha256_hash(&c.wasm_data).to_string()), c.admin),
+                Err(_) => (None, None),
+            }
         }
-        _ => None,
+        _ => (None, None),
     };
 
     let detail = SiteDetail {
@@ -199,11 +249,30 @@ fn store_site_detail(db: &BatchDb, site_id: &str, tx_hash: &Sha256Digest, tx_dat
         block_height: height,
         domain: None,
         tx_count: 0,
+        admin: admin.map(|a| a.to_string()),
+        upgrades: Vec::new(),
     };
 
     write_json(db, &site_detail_key(site_id), &detail);
 }
 
+fn track_site_upgrade(db: &BatchDb, tx_hash: &Sha256Digest, calldata: &[u8], height: u64) {
+    let Ok(upgrade) = borsh::from_slice::<UpgradeSiteCall>(calldata) else {
+        return;
+    };
+    let sk = site_detail_key(&upgrade.site_id.to_string());
+    if let Some(mut detail) = read_json::<SiteDetail>(db, &sk) {
+        let module_id = upgrade.module_id.to_string();
+        detail.upgrades.push(SiteUpgrade {
+            tx_hash: tx_hash.to_string(),
+            block_height: height,
+            previous_module_id: detail.module_id.replace(module_id.clone()),
+            module_id,
+        });
+        write_json(db, &sk, &detail);
+    }
+}
+
 fn append_to_sites_list(db: &BatchDb, site_id: &str) {
     let count: u64 = read_json(db, SITE_COUNT_KEY).unwrap_or(0);
     let page_key = sites_page_key(count / PAGE_SIZE);
@@ -278,12 +347,15 @@ use crate::consensus::types::FinalizedBlock;
 use crate::db::BatchDb;
 use serde::{Serialize, de::DeserializeOwned};
 use vastrum_shared_types::borsh::BorshExt;
-use vastrum_shared_types::crypto::ed25519;
-use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
 use vastrum_shared_types::indexer::types::*;
 use vastrum_shared_types::indexer::*;
 use vastrum_shared_types::transactioning::compression::decompress_calldata;
+use vastrum_shared_types::types::application::deploy_new_module::DeployNewModuleCall;
 use vastrum_shared_types::types::application::deploy_stored_module::DeployStoredModuleCall;
 use vastrum_shared_types::types::application::domaindata::DomainData;
 use vastrum_shared_types::types::application::sitecall::SiteCall;
 use vastrum_shared_types::types::application::transactiondata::{TransactionData, TransactionType};
+use vastrum_shared_types::types::application::upgrade_site::UpgradeSiteCall;
+use vastrum_shared_types::types::execution::receipt::{TxOutcome, TxReceipt};
+use vastrum_shared_types::types::execution::transaction::Transaction;
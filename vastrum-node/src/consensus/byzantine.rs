@@ -0,0 +1,69 @@
+//amount of old proposals kept around by ReplayOldProposals nodes
+const MAX_REPLAYED_PROPOSALS: usize = 20;
+
+/// Fault injection used by the consensus simulator, production nodes always run Honest
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ByzantineMode {
+    #[default]
+    Honest,
+    /// Leader signs and broadcasts a second, different block for every round it leads
+    ConflictingProposals,
+    /// Validator follows every vote with a conflicting vote for the same height and round
+    DoubleVote,
+    /// Validator justify votes for random block hashes it never received
+    VoteForUnseenBlocks,
+    /// Node never broadcasts the certificates it forms
+    WithholdCertificates,
+    /// Node rebroadcasts proposals from earlier heights and rounds every round
+    ReplayOldProposals,
+}
+
+pub struct ByzantineState {
+    pub mode: ByzantineMode,
+    last_acted_slot: Option<(u64, u64)>,
+    old_proposals: VecDeque<Proposal>,
+}
+
+impl ByzantineState {
+    pub fn new(mode: ByzantineMode) -> ByzantineState {
+        return ByzantineState { mode, last_acted_slot: None, old_proposals: VecDeque::new() };
+    }
+
+    pub fn is_honest(&self) -> bool {
+        self.mode == ByzantineMode::Honest
+    }
+
+    /// Returns true the first time it is called for a height and round
+    pub fn act_once(&mut self, height: u64, round: u64) -> bool {
+        let slot = Some((height, round));
+        if self.last_acted_slot == slot {
+            return false;
+        }
+        self.last_acted_slot = slot;
+        return true;
+    }
+
+    pub fn record_proposal(&mut self, proposal: Proposal) {
+        if self.old_proposals.contains(&proposal) {
+            return;
+        }
+        if self.old_proposals.len() >= MAX_REPLAYED_PROPOSALS {
+            self.old_proposals.pop_front();
+        }
+        self.old_proposals.push_back(proposal);
+    }
+
+    pub fn old_proposals(&self) -> impl Iterator<Item = &Proposal> {
+        self.old_proposals.iter()
+    }
+}
+
+pub fn random_block_hash() -> Sha256Digest {
+    let mut bytes = [0u8; 32];
+    rng::fill_bytes(&mut bytes);
+    return Sha256Digest::from(bytes);
+}
+
+use crate::{consensus::types::Proposal, utils::rng};
+use std::collections::VecDeque;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
@@ -0,0 +1,131 @@
+/// Signed proof that a validator equivocated, gossiped between nodes and included in blocks
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub enum Evidence {
+    DoubleVote(DoubleVoteEvidence),
+    DoubleProposal(DoubleProposalEvidence),
+}
+
+/// Two conflicting votes by the same validator for the same height and round
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct DoubleVoteEvidence {
+    pub first: ValidatorVote,
+    pub second: ValidatorVote,
+}
+
+/// Two proposals for different blocks signed by the same leader for the same height and round
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct DoubleProposalEvidence {
+    pub leader: ed25519::PublicKey,
+    pub first: SignedProposalHeader,
+    pub second: SignedProposalHeader,
+}
+
+impl Evidence {
+    pub fn calculate_hash(&self) -> Sha256Digest {
+        sha256_hash(&self.encode())
+    }
+
+    pub fn height(&self) -> u64 {
+        match self {
+            Evidence::DoubleVote(e) => e.first.height,
+            Evidence::DoubleProposal(e) => e.first.height,
+        }
+    }
+
+    pub fn round(&self) -> u64 {
+        match self {
+            Evidence::DoubleVote(e) => e.first.round,
+            Evidence::DoubleProposal(e) => e.first.round,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Evidence::DoubleVote(_) => "DoubleVote",
+            Evidence::DoubleProposal(_) => "DoubleProposal",
+        }
+    }
+
+    /// Returns the offending validator if the evidence is valid for the epoch covering its height
+    /// leader_seed is the block hash of height - 2, without it double proposals can not be verified
+    pub fn verify(
+        &self,
+        epoch_state: &EpochState,
+        leader_seed: Option<Sha256Digest>,
+        chain_id: u64,
+    ) -> Option<ValidatorData> {
+        match self {
+            Evidence::DoubleVote(e) => verify_double_vote(e, epoch_state, chain_id),
+            Evidence::DoubleProposal(e) => {
+                verify_double_proposal(e, epoch_state, leader_seed, chain_id)
+            }
+        }
+    }
+}
+
+fn verify_double_vote(
+    e: &DoubleVoteEvidence,
+    epoch_state: &EpochState,
+    chain_id: u64,
+) -> Option<ValidatorData> {
+    let same_validator = e.first.validator_index == e.second.validator_index;
+    let same_slot = e.first.height == e.second.height && e.first.round == e.second.round;
+    if !same_validator || !same_slot || !votes_conflict(&e.first.vote, &e.second.vote) {
+        return None;
+    }
+    let validator = epoch_state.validator_data(e.first.validator_index)?;
+    let first_signed = validator.pub_key.verify_sig(e.first.hash(chain_id), e.first.signature);
+    let second_signed = validator.pub_key.verify_sig(e.second.hash(chain_id), e.second.signature);
+    if !first_signed || !second_signed {
+        return None;
+    }
+    return Some(*validator);
+}
+
+fn verify_double_proposal(
+    e: &DoubleProposalEvidence,
+    epoch_state: &EpochState,
+    leader_seed: Option<Sha256Digest>,
+    chain_id: u64,
+) -> Option<ValidatorData> {
+    let same_slot = e.first.height == e.second.height && e.first.round == e.second.round;
+    let different_blocks = e.first.block_hash != e.second.block_hash;
+    if !same_slot || !different_blocks {
+        return None;
+    }
+    //only the leader of the slot may propose, anyone else signing proposals is not equivocating
+    let validator = slot_leader(epoch_state, leader_seed?, e.first.height, e.first.round)?;
+    if validator.pub_key != e.leader {
+        return None;
+    }
+    let first_hash = e.first.calculate_hash(chain_id);
+    let second_hash = e.second.calculate_hash(chain_id);
+    let first_signed = e.leader.verify_sig(first_hash, e.first.leader_signature);
+    let second_signed = e.leader.verify_sig(second_hash, e.second.leader_signature);
+    if !first_signed || !second_signed {
+        return None;
+    }
+    return Some(*validator);
+}
+
+//a validator may justify vote and then skip vote in the same round (timeout after justify)
+//but may never justify 2 blocks, or commit to 2 different outcomes (finalize a, finalize b or skip)
+pub fn votes_conflict(first: &VoteType, second: &VoteType) -> bool {
+    match (first, second) {
+        (VoteType::Justify(a), VoteType::Justify(b)) => a != b,
+        (VoteType::Justify(_), _) | (_, VoteType::Justify(_)) => false,
+        (a, b) => a != b,
+    }
+}
+
+use crate::consensus::{
+    types::{SignedProposalHeader, ValidatorVote, VoteType},
+    validator_state_machine::{EpochState, ValidatorData, slot_leader},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+#[allow(unused_imports)]
+use vastrum_shared_types::borsh::*;
+use vastrum_shared_types::crypto::{
+    ed25519,
+    sha256::{Sha256Digest, sha256_hash},
+};
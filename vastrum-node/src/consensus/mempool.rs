@@ -0,0 +1,207 @@
+/// Mempool owned by the state machine, read by rpc
+pub type SharedMempool = Arc<RwLock<Mempool>>;
+
+/// Pending transactions bounded in bytes and per sender
+//priority is the pow work a transaction carries, when full the lowest priority transaction is evicted oldest first
+//keys are free so the per sender quota alone does not stop a flood, past half full admission also needs extra work
+pub struct Mempool {
+    transactions: HashMap<Sha256Digest, MempoolEntry>,
+    by_priority: BTreeMap<(u32, u64), Sha256Digest>,
+    //highest priority first then arrival order, kept alongside eviction order so proposals never sort
+    by_proposal_order: BTreeMap<(Reverse<u32>, u64), Sha256Digest>,
+    //only one transaction per pow hash can ever be included
+    by_pow_hash: HashMap<Sha256Digest, Sha256Digest>,
+    by_recent_block_height: BTreeMap<(u64, u64), Sha256Digest>,
+    by_sender: HashMap<ed25519::PublicKey, BTreeMap<u64, Sha256Digest>>,
+    next_sequence: u64,
+    size: usize,
+    max_size: usize,
+}
+
+struct MempoolEntry {
+    transaction: Transaction,
+    //arrival order
+    sequence: u64,
+    priority: u32,
+    pow_hash: Sha256Digest,
+    size: usize,
+}
+
+impl Mempool {
+    pub fn new(max_size: usize) -> Mempool {
+        return Mempool {
+            transactions: HashMap::new(),
+            by_priority: BTreeMap::new(),
+            by_proposal_order: BTreeMap::new(),
+            by_pow_hash: HashMap::new(),
+            by_recent_block_height: BTreeMap::new(),
+            by_sender: HashMap::new(),
+            next_sequence: 0,
+            size: 0,
+            max_size,
+        };
+    }
+
+    pub fn new_shared(max_size: usize) -> SharedMempool {
+        return Arc::new(RwLock::new(Mempool::new(max_size)));
+    }
+
+    /// Total encoded size of all pending transactions
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn contains(&self, tx_hash: &Sha256Digest) -> bool {
+        self.transactions.contains_key(tx_hash)
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Least pow work a new transaction needs on top of the chain difficulty, rises from 0 at half full
+    pub fn admission_priority(&self) -> u32 {
+        let half = self.max_size / 2;
+        if self.size <= half {
+            return 0;
+        }
+        let pressure =
+            (self.size - half) as u64 * MEMPOOL_ADMISSION_BITS as u64 / half.max(1) as u64;
+        return pressure as u32;
+    }
+
+    /// False if already pending, its pow hash is already pending, sender is at its quota,
+    /// it lacks pow_difficulty plus the work admission needs or the mempool is full of higher priority transactions
+    pub fn insert(&mut self, transaction: Transaction, pow_difficulty: u8) -> bool {
+        let tx_hash = transaction.calculate_txhash();
+        if self.contains(&tx_hash) {
+            return false;
+        }
+        let pow_hash = transaction.calculate_pow_hash();
+        if self.by_pow_hash.contains_key(&pow_hash) {
+            return false;
+        }
+        let sender_count = self.by_sender.get(&transaction.pub_key).map_or(0, |s| s.len());
+        if sender_count >= MAX_MEMPOOL_TXS_PER_SENDER {
+            return false;
+        }
+        let size = transaction.encode().len();
+        if size > self.max_size {
+            return false;
+        }
+        let priority = pow_hash.leading_zero_bits();
+        if priority < pow_difficulty as u32 + self.admission_priority() {
+            return false;
+        }
+
+        //never evict for a transaction with less work than the evicted one
+        let mut freed = 0;
+        let mut to_evict = vec![];
+        for (&(evict_priority, _), hash) in &self.by_priority {
+            if self.size - freed + size <= self.max_size {
+                break;
+            }
+            if evict_priority > priority {
+                return false;
+            }
+            freed += self.transactions[hash].size;
+            to_evict.push(*hash);
+        }
+        for hash in to_evict {
+            self.remove(&hash);
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.by_priority.insert((priority, sequence), tx_hash);
+        self.by_proposal_order.insert((Reverse(priority), sequence), tx_hash);
+        self.by_pow_hash.insert(pow_hash, tx_hash);
+        self.by_recent_block_height.insert((transaction.recent_block_height, sequence), tx_hash);
+        self.by_sender.entry(transaction.pub_key).or_default().insert(sequence, tx_hash);
+        self.size += size;
+        self.transactions
+            .insert(tx_hash, MempoolEntry { transaction, sequence, priority, pow_hash, size });
+        return true;
+    }
+
+    pub fn remove(&mut self, tx_hash: &Sha256Digest) -> Option<Transaction> {
+        let entry = self.transactions.remove(tx_hash)?;
+        let transaction = entry.transaction;
+        self.by_priority.remove(&(entry.priority, entry.sequence));
+        self.by_proposal_order.remove(&(Reverse(entry.priority), entry.sequence));
+        self.by_pow_hash.remove(&entry.pow_hash);
+        self.by_recent_block_height.remove(&(transaction.recent_block_height, entry.sequence));
+        if let Some(sender) = self.by_sender.get_mut(&transaction.pub_key) {
+            sender.remove(&entry.sequence);
+            if sender.is_empty() {
+                self.by_sender.remove(&transaction.pub_key);
+            }
+        }
+        self.size -= entry.size;
+        return Some(transaction);
+    }
+
+    /// Drops the transactions carrying pow hashes a finalized block spent
+    pub fn remove_spent(&mut self, pow_hashes: &[Sha256Digest]) {
+        for pow_hash in pow_hashes {
+            if let Some(tx_hash) = self.by_pow_hash.get(pow_hash).copied() {
+                self.remove(&tx_hash);
+            }
+        }
+    }
+
+    /// Drops transactions whose recent_block_height is outside VALIDITY_WINDOW for the block at next_height
+    pub fn remove_expired(&mut self, next_height: u64) -> Vec<Sha256Digest> {
+        let min_recent_block_height = next_height.saturating_sub(VALIDITY_WINDOW);
+        let expired: Vec<Sha256Digest> = self
+            .by_recent_block_height
+            .range(..(min_recent_block_height, 0))
+            .map(|(_, hash)| *hash)
+            .collect();
+        for hash in &expired {
+            self.remove(hash);
+        }
+        return expired;
+    }
+
+    /// Reverse of eviction order, highest priority and most recent first
+    pub fn pending_page(&self, offset: usize, limit: usize) -> Vec<PendingTransaction> {
+        let mut page = Vec::with_capacity(limit.min(self.transactions.len()));
+        let by_priority = self.by_priority.values().rev();
+        for hash in by_priority.skip(offset).take(limit) {
+            let entry = &self.transactions[hash];
+            page.push(PendingTransaction {
+                tx_hash: *hash,
+                pub_key: entry.transaction.pub_key,
+                recent_block_height: entry.transaction.recent_block_height,
+                priority: entry.priority,
+                size: entry.size as u64,
+            });
+        }
+        return page;
+    }
+
+    /// Highest priority first then arrival order
+    //tx hash plays no part in ordering so it cannot be ground to get to the front
+    pub fn ordered_for_proposal(&self) -> impl Iterator<Item = &Transaction> {
+        return self.by_proposal_order.values().map(|hash| &self.transactions[hash].transaction);
+    }
+}
+
+use crate::utils::limits::{MAX_MEMPOOL_TXS_PER_SENDER, MEMPOOL_ADMISSION_BITS};
+use parking_lot::RwLock;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use vastrum_shared_types::{
+    borsh::BorshExt,
+    crypto::{ed25519, sha256::Sha256Digest},
+    limits::VALIDITY_WINDOW,
+    types::{execution::transaction::Transaction, rpc::types::PendingTransaction},
+};
+
+#[cfg(test)]
+#[path = "mempool_tests.rs"]
+mod tests;
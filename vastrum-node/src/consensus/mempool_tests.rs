@@ -0,0 +1,264 @@
+use super::*;
+use vastrum_shared_types::limits::DEFAULT_TX_FUEL_LIMIT;
+use vastrum_shared_types::transactioning::transaction_generator::{
+    TxEnvelope, build_call_transaction,
+};
+use vastrum_shared_types::types::execution::transaction::ProofOfWorkStruct;
+
+//transaction whose pow hash has exactly priority leading zero bits
+fn tx_with_priority(seed: u64, recent_block_height: u64, priority: u32) -> Transaction {
+    let private_key = ed25519::PrivateKey::from_seed(seed);
+    let pub_key = private_key.public_key();
+    let nonce = (0..)
+        .find(|&nonce| {
+            let pow = ProofOfWorkStruct { nonce, pub_key, recent_block_height };
+            pow.calculate_hash().leading_zero_bits() == priority
+        })
+        .unwrap();
+    build_call_transaction(
+        Sha256Digest::default(),
+        vec![1, 2, 3],
+        private_key,
+        TxEnvelope {
+            nonce,
+            recent_block_height,
+            chain_id: 0,
+            pow_difficulty: 0,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
+    )
+}
+
+//pow hash has no leading zero bits, lowest possible priority
+fn low_priority_tx(seed: u64, recent_block_height: u64) -> Transaction {
+    return tx_with_priority(seed, recent_block_height, 0);
+}
+
+fn high_priority_tx(seed: u64) -> Transaction {
+    let private_key = ed25519::PrivateKey::from_seed(seed);
+    build_call_transaction(
+        Sha256Digest::default(),
+        vec![1, 2, 3],
+        private_key,
+        TxEnvelope {
+            nonce: 0,
+            recent_block_height: 0,
+            chain_id: 0,
+            pow_difficulty: 8,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
+    )
+}
+
+fn tx_size() -> usize {
+    low_priority_tx(1, 0).encode().len()
+}
+
+#[test]
+fn test_duplicate_rejected() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let tx = low_priority_tx(1, 0);
+    assert!(mempool.insert(tx.clone(), 0));
+    assert!(!mempool.insert(tx, 0));
+}
+
+#[test]
+fn test_sender_quota() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let private_key = ed25519::PrivateKey::from_seed(1);
+    for nonce in 0..MAX_MEMPOOL_TXS_PER_SENDER as u64 {
+        let tx = build_call_transaction(
+            Sha256Digest::default(),
+            vec![],
+            private_key.clone(),
+            TxEnvelope {
+                nonce,
+                recent_block_height: 0,
+                chain_id: 0,
+                pow_difficulty: 0,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
+        );
+        assert!(mempool.insert(tx, 0));
+    }
+    let over_quota = build_call_transaction(
+        Sha256Digest::default(),
+        vec![],
+        private_key,
+        TxEnvelope {
+            nonce: u64::MAX,
+            recent_block_height: 0,
+            chain_id: 0,
+            pow_difficulty: 0,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
+    );
+    assert!(!mempool.insert(over_quota, 0), "sender over quota should be rejected");
+    assert!(mempool.insert(low_priority_tx(2, 0), 0), "other senders are not affected");
+}
+
+//a full mempool admits only transactions carrying MEMPOOL_ADMISSION_BITS
+#[test]
+fn test_full_mempool_evicts_oldest_lowest_priority() {
+    let mut mempool = Mempool::new(tx_size() * 2);
+    let oldest = tx_with_priority(1, 0, MEMPOOL_ADMISSION_BITS);
+    let newer = tx_with_priority(2, 0, MEMPOOL_ADMISSION_BITS);
+    mempool.insert(oldest.clone(), 0);
+    mempool.insert(newer.clone(), 0);
+
+    assert!(mempool.insert(tx_with_priority(3, 0, MEMPOOL_ADMISSION_BITS), 0));
+    assert!(!mempool.contains(&oldest.calculate_txhash()), "oldest should be evicted");
+    assert!(mempool.contains(&newer.calculate_txhash()));
+    assert_eq!(mempool.size(), tx_size() * 2);
+}
+
+#[test]
+fn test_full_mempool_keeps_higher_priority() {
+    let mut mempool = Mempool::new(tx_size() * 2);
+    mempool.insert(tx_with_priority(1, 0, MEMPOOL_ADMISSION_BITS + 1), 0);
+    mempool.insert(tx_with_priority(2, 0, MEMPOOL_ADMISSION_BITS + 1), 0);
+
+    let less_work = tx_with_priority(3, 0, MEMPOOL_ADMISSION_BITS);
+    assert!(!mempool.insert(less_work, 0), "less work should not evict more work");
+
+    let mut mempool = Mempool::new(tx_size() * 2);
+    let low = tx_with_priority(1, 0, MEMPOOL_ADMISSION_BITS);
+    mempool.insert(low.clone(), 0);
+    mempool.insert(tx_with_priority(2, 0, MEMPOOL_ADMISSION_BITS + 1), 0);
+    assert!(mempool.insert(tx_with_priority(3, 0, MEMPOOL_ADMISSION_BITS + 1), 0));
+    assert!(!mempool.contains(&low.calculate_txhash()), "lowest priority should be evicted");
+}
+
+#[test]
+fn test_admission_priority_rises_past_half_full() {
+    let mut mempool = Mempool::new(tx_size() * 4);
+    assert!(mempool.insert(low_priority_tx(1, 0), 0));
+    assert!(mempool.insert(low_priority_tx(2, 0), 0));
+    assert_eq!(mempool.admission_priority(), 0);
+
+    assert!(mempool.insert(low_priority_tx(3, 0), 0));
+    assert_eq!(mempool.admission_priority(), MEMPOOL_ADMISSION_BITS / 2);
+
+    //flood from fresh keys is not stopped by the sender quota but by the rising admission work
+    assert!(!mempool.insert(low_priority_tx(4, 0), 0), "fresh key without extra work");
+    assert!(!mempool.insert(tx_with_priority(5, 0, MEMPOOL_ADMISSION_BITS / 2 - 1), 0));
+    assert!(mempool.insert(tx_with_priority(6, 0, MEMPOOL_ADMISSION_BITS / 2), 0));
+    assert_eq!(mempool.admission_priority(), MEMPOOL_ADMISSION_BITS);
+}
+
+#[test]
+fn test_admission_work_is_on_top_of_difficulty() {
+    let difficulty = 2;
+    let mut mempool = Mempool::new(tx_size() * 4);
+    assert!(!mempool.insert(tx_with_priority(1, 0, 1), difficulty), "below chain difficulty");
+    assert!(mempool.insert(tx_with_priority(2, 0, 2), difficulty));
+    assert!(mempool.insert(tx_with_priority(3, 0, 2), difficulty));
+    assert!(mempool.insert(tx_with_priority(4, 0, 2), difficulty));
+    assert_eq!(mempool.admission_priority(), MEMPOOL_ADMISSION_BITS / 2);
+
+    let extra = MEMPOOL_ADMISSION_BITS / 2;
+    let only_difficulty = tx_with_priority(5, 0, difficulty as u32);
+    assert!(!mempool.insert(only_difficulty, difficulty), "fresh key needs work past difficulty");
+    let short = tx_with_priority(6, 0, difficulty as u32 + extra - 1);
+    assert!(!mempool.insert(short, difficulty));
+    assert!(mempool.insert(tx_with_priority(7, 0, difficulty as u32 + extra), difficulty));
+}
+
+#[test]
+fn test_remove_expired() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let expiring = low_priority_tx(1, 10);
+    let valid = low_priority_tx(2, 11);
+    mempool.insert(expiring.clone(), 0);
+    mempool.insert(valid.clone(), 0);
+
+    let expired = mempool.remove_expired(11 + VALIDITY_WINDOW);
+    assert_eq!(expired, vec![expiring.calculate_txhash()]);
+    assert!(!mempool.contains(&expiring.calculate_txhash()));
+    assert!(mempool.contains(&valid.calculate_txhash()));
+    assert_eq!(mempool.size(), valid.encode().len());
+}
+
+#[test]
+fn test_proposal_order_highest_priority_first() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let low = low_priority_tx(1, 0);
+    let high = high_priority_tx(2);
+    let later_low = low_priority_tx(3, 0);
+    mempool.insert(low.clone(), 0);
+    mempool.insert(high.clone(), 0);
+    mempool.insert(later_low.clone(), 0);
+
+    let ordered: Vec<Sha256Digest> =
+        mempool.ordered_for_proposal().map(|tx| tx.calculate_txhash()).collect();
+    let expected =
+        vec![high.calculate_txhash(), low.calculate_txhash(), later_low.calculate_txhash()];
+    assert_eq!(ordered, expected);
+
+    mempool.remove(&high.calculate_txhash());
+    assert_eq!(mempool.ordered_for_proposal().count(), 2);
+}
+
+#[test]
+fn test_duplicate_pow_hash_rejected() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let private_key = ed25519::PrivateKey::from_seed(1);
+    let call = |calldata| {
+        build_call_transaction(
+            Sha256Digest::default(),
+            calldata,
+            private_key.clone(),
+            TxEnvelope {
+                nonce: 0,
+                recent_block_height: 0,
+                chain_id: 0,
+                pow_difficulty: 0,
+                fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+            },
+        )
+    };
+    assert!(mempool.insert(call(vec![1]), 0));
+    assert!(!mempool.insert(call(vec![2]), 0), "same pow hash with other calldata");
+}
+
+#[test]
+fn test_remove_spent() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let spent = low_priority_tx(1, 0);
+    let pending = low_priority_tx(2, 0);
+    mempool.insert(spent.clone(), 0);
+    mempool.insert(pending.clone(), 0);
+
+    mempool.remove_spent(&[spent.calculate_pow_hash(), Sha256Digest::from_u64(1)]);
+    assert!(!mempool.contains(&spent.calculate_txhash()));
+    assert!(mempool.contains(&pending.calculate_txhash()));
+    assert_eq!(mempool.size(), pending.encode().len());
+
+    //pow hash is free again once its transaction left
+    assert!(mempool.insert(spent, 0));
+}
+
+#[test]
+fn test_pending_page_highest_priority_first() {
+    let mut mempool = Mempool::new(MAX_MEMPOOL_SIZE_FOR_TEST);
+    let low = low_priority_tx(1, 0);
+    let high = high_priority_tx(2);
+    let later_low = low_priority_tx(3, 0);
+    mempool.insert(low.clone(), 0);
+    mempool.insert(high.clone(), 0);
+    mempool.insert(later_low.clone(), 0);
+    assert_eq!(mempool.transaction_count(), 3);
+
+    let first_page = mempool.pending_page(0, 2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page[0].tx_hash, high.calculate_txhash());
+    assert_eq!(first_page[0].size, high.encode().len() as u64);
+    assert_eq!(first_page[1].tx_hash, later_low.calculate_txhash());
+
+    let second_page = mempool.pending_page(2, 2);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].tx_hash, low.calculate_txhash());
+    assert!(mempool.pending_page(3, 2).is_empty());
+}
+
+const MAX_MEMPOOL_SIZE_FOR_TEST: usize = 1024 * 1024;
@@ -1,2 +1,7 @@
+#[cfg(madsim)]
+pub mod byzantine;
+pub mod evidence;
+pub mod mempool;
+pub mod round_timer;
 pub mod types;
 pub mod validator_state_machine;
@@ -0,0 +1,72 @@
+//timeout is kept at least this multiple of observed round latency
+//so jitter in a stable but slow network does not cause skips
+const TIMEOUT_LATENCY_MULTIPLIER: u32 = 2;
+
+/// Bounds for the adaptive round timeout
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundTimeoutConfig {
+    pub initial: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Default for RoundTimeoutConfig {
+    fn default() -> Self {
+        RoundTimeoutConfig {
+            initial: ROUND_TIMEOUT,
+            min: MIN_ROUND_TIMEOUT,
+            max: MAX_ROUND_TIMEOUT,
+        }
+    }
+}
+
+impl RoundTimeoutConfig {
+    /// Timeout that never adapts, used to force skip rounds in simulation
+    pub fn fixed(timeout: Duration) -> Self {
+        RoundTimeoutConfig { initial: timeout, min: timeout, max: timeout }
+    }
+}
+
+/// Round timeout doubles on every skipped round and halves on every finalization
+/// never going below a multiple of the observed time it takes a round to finalize
+pub struct RoundTimer {
+    config: RoundTimeoutConfig,
+    timeout: Duration,
+    observed_round_latency: Option<Duration>,
+}
+
+impl RoundTimer {
+    pub fn new(config: RoundTimeoutConfig) -> RoundTimer {
+        let timeout = config.initial.clamp(config.min, config.max);
+        return RoundTimer { config, timeout, observed_round_latency: None };
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn on_skip(&mut self) {
+        self.timeout = (self.timeout * 2).min(self.config.max);
+    }
+
+    /// round_latency is the time between entering the finalizing round and finalization
+    pub fn on_finalized(&mut self, round_latency: Duration) {
+        let observed = match self.observed_round_latency {
+            //exponential moving average, weight 1/4 for the new sample
+            Some(previous) => (previous * 3 + round_latency) / 4,
+            None => round_latency,
+        };
+        self.observed_round_latency = Some(observed);
+
+        let floor = observed * TIMEOUT_LATENCY_MULTIPLIER;
+        let shrunk = (self.timeout / 2).max(floor);
+        self.timeout = shrunk.clamp(self.config.min, self.config.max);
+    }
+}
+
+use crate::utils::limits::{MAX_ROUND_TIMEOUT, MIN_ROUND_TIMEOUT, ROUND_TIMEOUT};
+use std::time::Duration;
+
+#[cfg(test)]
+#[path = "round_timer_tests.rs"]
+mod tests;
@@ -0,0 +1,64 @@
+use super::*;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+fn config() -> RoundTimeoutConfig {
+    RoundTimeoutConfig { initial: secs(4), min: secs(2), max: secs(30) }
+}
+
+#[test]
+fn test_initial_timeout_clamped_to_bounds() {
+    let timer = RoundTimer::new(RoundTimeoutConfig { initial: secs(100), ..config() });
+    assert_eq!(timer.timeout(), secs(30));
+    let timer = RoundTimer::new(RoundTimeoutConfig { initial: secs(1), ..config() });
+    assert_eq!(timer.timeout(), secs(2));
+}
+
+#[test]
+fn test_skip_doubles_up_to_max() {
+    let mut timer = RoundTimer::new(config());
+    timer.on_skip();
+    assert_eq!(timer.timeout(), secs(8));
+    timer.on_skip();
+    assert_eq!(timer.timeout(), secs(16));
+    timer.on_skip();
+    assert_eq!(timer.timeout(), secs(30));
+    timer.on_skip();
+    assert_eq!(timer.timeout(), secs(30));
+}
+
+#[test]
+fn test_finalization_halves_down_to_min() {
+    let mut timer = RoundTimer::new(RoundTimeoutConfig { initial: secs(16), ..config() });
+    timer.on_finalized(Duration::ZERO);
+    assert_eq!(timer.timeout(), secs(8));
+    timer.on_finalized(Duration::ZERO);
+    timer.on_finalized(Duration::ZERO);
+    assert_eq!(timer.timeout(), secs(2));
+    timer.on_finalized(Duration::ZERO);
+    assert_eq!(timer.timeout(), secs(2));
+}
+
+#[test]
+fn test_finalization_keeps_multiple_of_observed_latency() {
+    let mut timer = RoundTimer::new(RoundTimeoutConfig { initial: secs(16), ..config() });
+    timer.on_finalized(secs(5));
+    assert_eq!(timer.timeout(), secs(10));
+    timer.on_finalized(secs(5));
+    assert_eq!(timer.timeout(), secs(10), "slow but stable network does not shrink further");
+
+    //moving average, a single fast round only moves the floor a quarter of the way
+    timer.on_finalized(secs(1));
+    assert_eq!(timer.timeout(), secs(8));
+}
+
+#[test]
+fn test_fixed_timeout_never_adapts() {
+    let mut timer = RoundTimer::new(RoundTimeoutConfig::fixed(secs(3)));
+    timer.on_skip();
+    assert_eq!(timer.timeout(), secs(3));
+    timer.on_finalized(secs(10));
+    assert_eq!(timer.timeout(), secs(3));
+}
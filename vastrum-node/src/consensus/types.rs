@@ -15,29 +15,44 @@ impl Proposal {
         round: u64,
         proposal_type: ProposalType,
         block: Block,
+        chain_id: u64,
         private_key: &ed25519::PrivateKey,
     ) -> Self {
-        let proposal_data = ProposalData { round, proposal_type, block: block.clone() };
+        let proposal_data = ProposalData {
+            chain_id,
+            height: block.height,
+            round,
+            proposal_type,
+            block_hash: block.calculate_hash(),
+        };
         let leader_signature = private_key.sign_hash(proposal_data.calculate_hash());
 
         let proposal = Proposal { leader_signature, round, proposal_type, block };
 
         return proposal;
     }
-    pub fn calculate_hash(&self) -> Sha256Digest {
-        let proposal_data = ProposalData {
+    pub fn calculate_hash(&self, chain_id: u64) -> Sha256Digest {
+        self.header().calculate_hash(chain_id)
+    }
+    /// Signed proposal without block body, enough to prove leader signed a block hash
+    pub fn header(&self) -> SignedProposalHeader {
+        SignedProposalHeader {
+            height: self.block.height,
             round: self.round,
             proposal_type: self.proposal_type,
-            block: self.block.clone(),
-        };
-        proposal_data.calculate_hash()
+            block_hash: self.block.calculate_hash(),
+            leader_signature: self.leader_signature,
+        }
     }
 }
+//leader signs block hash instead of full block so proposals can be used as compact evidence
 #[derive(BorshSerialize, BorshDeserialize)]
 struct ProposalData {
+    pub chain_id: u64,
+    pub height: u64,
     pub round: u64,
     pub proposal_type: ProposalType,
-    pub block: Block,
+    pub block_hash: Sha256Digest,
 }
 impl ProposalData {
     fn calculate_hash(&self) -> Sha256Digest {
@@ -45,6 +60,27 @@ impl ProposalData {
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct SignedProposalHeader {
+    pub height: u64,
+    pub round: u64,
+    pub proposal_type: ProposalType,
+    pub block_hash: Sha256Digest,
+    pub leader_signature: ed25519::Signature,
+}
+impl SignedProposalHeader {
+    pub fn calculate_hash(&self, chain_id: u64) -> Sha256Digest {
+        let proposal_data = ProposalData {
+            chain_id,
+            height: self.height,
+            round: self.round,
+            proposal_type: self.proposal_type,
+            block_hash: self.block_hash,
+        };
+        proposal_data.calculate_hash()
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
 pub struct Block {
     pub height: u64,
@@ -52,19 +88,31 @@ pub struct Block {
     pub previous_block_hash: Sha256Digest,
     pub timestamp: u64,
     pub previous_block_state_root: Sha256Digest,
+    pub pow_difficulty: u8,
+    /// Finalization of block height - 2, the newest block every proposer has seen finalized
+    /// since pipelined proposals are made before the parent finalizes, mixed into tx random seeds
+    pub grandparent_finalization: BlockFinalization,
 }
 
 impl Block {
     pub fn calculate_hash(&self) -> Sha256Digest {
-        let transactions_hash = sha256_hash(&borsh::to_vec(&self.transactions).unwrap());
-        let header = BlockHeader {
+        self.header().calculate_hash()
+    }
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
             height: self.height,
             previous_block_hash: self.previous_block_hash,
             timestamp: self.timestamp,
             previous_block_state_root: self.previous_block_state_root,
-            transactions_hash,
-        };
-        header.calculate_hash()
+            pow_difficulty: self.pow_difficulty,
+            transactions_hash: sha256_hash(&borsh::to_vec(&self.transactions).unwrap()),
+            grandparent_finalization_hash: self.grandparent_finalization.calculate_hash(),
+        }
+    }
+    /// Pow difficulty the next block requires, derived from how full this block is
+    pub fn next_pow_difficulty(&self) -> u8 {
+        let size = self.transactions.iter().map(|tx| tx.encode().len()).sum();
+        return next_pow_difficulty(self.pow_difficulty, self.transactions.len(), size);
     }
 }
 
@@ -85,17 +133,23 @@ impl ValidatorVote {
         height: u64,
         round: u64,
         validator_index: u64,
+        chain_id: u64,
         private_key: &ed25519::PrivateKey,
     ) -> Self {
-        let vote_data = ValidatorVoteData { vote_type: vote.clone(), height, round };
+        let vote_data = ValidatorVoteData { chain_id, vote_type: vote.clone(), height, round };
         let signature = private_key.sign_hash(vote_data.calculate_hash());
 
         ValidatorVote { vote, height, round, signature, validator_index }
     }
 
-    pub fn hash(&self) -> Sha256Digest {
-        ValidatorVoteData { vote_type: self.vote.clone(), height: self.height, round: self.round }
-            .calculate_hash()
+    pub fn hash(&self, chain_id: u64) -> Sha256Digest {
+        let vote_data = ValidatorVoteData {
+            chain_id,
+            vote_type: self.vote.clone(),
+            height: self.height,
+            round: self.round,
+        };
+        vote_data.calculate_hash()
     }
 }
 
@@ -109,14 +163,14 @@ pub enum Certificate {
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
 pub struct JustifyCertificate {
     pub block_hash: Sha256Digest,
-    pub votes: BTreeMap<ValidatorIndex, ed25519::Signature>,
+    pub votes: CertificateVotes,
     pub round: u64,
     pub height: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
 pub struct SkipCertificate {
-    pub votes: BTreeMap<ValidatorIndex, ed25519::Signature>,
+    pub votes: CertificateVotes,
     pub round: u64,
     pub height: u64,
 }
@@ -124,7 +178,7 @@ pub struct SkipCertificate {
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
 pub struct FinalizationCertificate {
     pub block_hash: Sha256Digest,
-    pub votes: BTreeMap<ValidatorIndex, ed25519::Signature>,
+    pub votes: CertificateVotes,
     pub round: u64,
     pub height: u64,
 }
@@ -132,7 +186,7 @@ pub struct FinalizationCertificate {
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
 pub struct FinalizedBlock {
     pub block: Block,
-    pub votes: BTreeMap<ValidatorIndex, ed25519::Signature>,
+    pub votes: CertificateVotes,
     pub round: u64,
 }
 
@@ -142,16 +196,18 @@ pub struct RoundSyncStateExternal {
     pub skip_certs: HashMap<u64, SkipCertificate>,
 }
 
-use crate::consensus::validator_state_machine::ValidatorIndex;
 use borsh::{BorshDeserialize, BorshSerialize};
 #[allow(unused_imports)]
 use vastrum_shared_types::borsh::*;
-use vastrum_shared_types::types::consensus::{BlockHeader, ValidatorVoteData};
+use vastrum_shared_types::types::consensus::{
+    BlockFinalization, BlockHeader, CertificateVotes, ValidatorVoteData,
+};
 use vastrum_shared_types::{
     crypto::{
         ed25519,
         sha256::{Sha256Digest, sha256_hash},
     },
+    transactioning::pow::next_pow_difficulty,
     types::execution::transaction::Transaction,
 };
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
@@ -32,16 +32,41 @@ impl ValidatorStateMachine {
         }
     }
     fn validator_actor(&mut self) {
+        #[cfg(madsim)]
+        if !self.byzantine.is_honest() {
+            self.byzantine_actor();
+        }
         let is_leader = self.is_leader(self.pub_key, self.current_height, self.current_round);
         let is_validator = self.has_stake_at_height(self.pub_key, self.current_height);
 
         if is_leader {
             self.execute_leader_role();
         }
+        let is_next_height_leader = self.is_leader(self.pub_key, self.current_height + 1, 0);
+        if is_next_height_leader {
+            self.execute_pipelined_leader_role();
+        }
         if is_validator {
             self.execute_validator_role();
         }
     }
+    //leader of the next height proposes as soon as current height has a justified block
+    //so the proposal is already gossiped when current height finalizes
+    fn execute_pipelined_leader_role(&mut self) {
+        let next_height = self.current_height + 1;
+        let state = self.local_validator_state(next_height, 0);
+        let has_not_proposed_block = state.leader_state == LeaderState::HasNotProposedBlock;
+
+        if has_not_proposed_block {
+            let Some(block_to_propose) = self.get_pipelined_block_to_propose() else {
+                return;
+            };
+            let state = self.local_validator_state(next_height, 0);
+            state.leader_state = LeaderState::HasProposedBlock;
+            self.networking.broadcast_proposal(&block_to_propose);
+            self.handle_proposal_received(block_to_propose);
+        }
+    }
     fn execute_leader_role(&mut self) {
         let state = self.local_validator_state(self.current_height, self.current_round);
         let has_not_proposed_block = state.leader_state == LeaderState::HasNotProposedBlock;
@@ -76,6 +101,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
 
@@ -91,15 +117,7 @@ impl ValidatorStateMachine {
         let state = self.local_validator_state(height, round);
         let has_not_commit_voted = state.commit_state == CommitState::NoVoteYet;
 
-        let deadline_expired;
-
-        //if high round, assume timeout is too low, decrease it.
-        let faulty_slot = round > 5;
-        if faulty_slot {
-            deadline_expired = self.entered_round_at + LONG_ROUND_TIMEOUT <= Instant::now()
-        } else {
-            deadline_expired = self.entered_round_at + ROUND_TIMEOUT <= Instant::now()
-        }
+        let deadline_expired = self.entered_round_at + self.round_timer.timeout() <= Instant::now();
         if has_not_commit_voted {
             let round_state = self.round_state(height, round);
             let exists_justified_block = round_state.justify_cert.is_some();
@@ -117,6 +135,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
                 let state = self.local_validator_state(height, round);
@@ -131,6 +150,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
                 let state = self.local_validator_state(height, round);
@@ -160,6 +180,9 @@ impl ValidatorStateMachine {
                 Certificate::Finalization(c) => self.handle_finalization_cert_received(c),
             }
         }
+        while let Ok(evidence) = self.evidence_rx.try_recv() {
+            self.handle_evidence(evidence);
+        }
     }
     fn handle_vote(&mut self, vote: ValidatorVote) -> HandleVoteResult {
         let height = vote.height;
@@ -181,7 +204,7 @@ impl ValidatorStateMachine {
         let Some(validator) = epoch_state.validator_data(validator_index).copied() else {
             return HandleVoteResult::ErrorCouldNotFindValidatorIndex;
         };
-        let is_valid_signature = validator.pub_key.verify_sig(vote.hash(), signature);
+        let is_valid_signature = validator.pub_key.verify_sig(vote.hash(self.chain_id), signature);
 
         if !is_valid_signature {
             return HandleVoteResult::ErrorInvalidSignature;
@@ -192,6 +215,7 @@ impl ValidatorStateMachine {
             VoteType::Justify(block_hash) => {
                 let already_voted = validator_state.justify_state != JustifyState::NoVoteYet;
                 if already_voted {
+                    self.check_double_vote(&vote);
                     return HandleVoteResult::ErrorValidatorHasAlreadyVotedInThisSlotRound;
                 }
 
@@ -207,6 +231,7 @@ impl ValidatorStateMachine {
             VoteType::Finalize(block_hash) => {
                 let already_voted = validator_state.commit_state != CommitState::NoVoteYet;
                 if already_voted {
+                    self.check_double_vote(&vote);
                     return HandleVoteResult::ErrorValidatorHasAlreadyVotedInThisSlotRound;
                 }
 
@@ -222,6 +247,7 @@ impl ValidatorStateMachine {
             VoteType::Skip => {
                 let already_voted = validator_state.commit_state != CommitState::NoVoteYet;
                 if already_voted {
+                    self.check_double_vote(&vote);
                     return HandleVoteResult::ErrorValidatorHasAlreadyVotedInThisSlotRound;
                 }
 
@@ -251,11 +277,23 @@ impl ValidatorStateMachine {
         }
         let signed_by_leader = self.proposal_signed_by_leader(&proposal);
         if signed_by_leader {
+            #[cfg(madsim)]
+            if self.byzantine.mode == ByzantineMode::ReplayOldProposals {
+                self.byzantine.record_proposal(proposal.clone());
+            }
             let state = self.round_state(height, round);
 
             let have_not_yet_received_block = state.leader_proposal.is_none();
             if have_not_yet_received_block {
                 state.leader_proposal = Some(proposal.clone());
+            } else {
+                let first = state.leader_proposal.as_ref().unwrap().header();
+                let second = proposal.header();
+                if first.block_hash != second.block_hash {
+                    let leader = self.leader(height, round).unwrap().pub_key;
+                    let evidence = DoubleProposalEvidence { leader, first, second };
+                    self.handle_evidence(Evidence::DoubleProposal(evidence));
+                }
             }
             let block = proposal.block;
             let block_hash = block.calculate_hash();
@@ -263,10 +301,193 @@ impl ValidatorStateMachine {
             self.update_consensus_for_slot(height, round);
         };
     }
-    //double voting protection enforced through BTreeMap<ValidatorIndex,
+    //fault injection for the consensus simulator, not compiled into real nodes
+    #[cfg(madsim)]
+    fn byzantine_actor(&mut self) {
+        let height = self.current_height;
+        let round = self.current_round;
+        let Some(validator_index) = self.validator_index(self.pub_key, height) else {
+            return;
+        };
+        match self.byzantine.mode {
+            ByzantineMode::Honest | ByzantineMode::WithholdCertificates => {}
+            ByzantineMode::ConflictingProposals => {
+                let is_leader = self.is_leader(self.pub_key, height, round);
+                let state = self.round_state(height, round);
+                let Some(proposal) = state.leader_proposal.clone() else {
+                    return;
+                };
+                if !is_leader || !self.byzantine.act_once(height, round) {
+                    return;
+                }
+                let mut block = proposal.block;
+                block.timestamp += 1;
+                let conflicting = Proposal::create_signed(
+                    round,
+                    proposal.proposal_type,
+                    block,
+                    self.chain_id,
+                    &self.private_key,
+                );
+                self.networking.broadcast_proposal(&conflicting);
+            }
+            ByzantineMode::DoubleVote => {
+                let state = self.local_validator_state(height, round);
+                let justify_state = state.justify_state;
+                let conflicting_commit = match state.commit_state {
+                    CommitState::Finalize(_) => VoteType::Skip,
+                    CommitState::Skip => VoteType::Finalize(random_block_hash()),
+                    _ => return,
+                };
+                if !self.byzantine.act_once(height, round) {
+                    return;
+                }
+                let mut conflicting_votes = vec![conflicting_commit];
+                if let JustifyState::VotedFor(_) = justify_state {
+                    conflicting_votes.push(VoteType::Justify(random_block_hash()));
+                }
+                for vote_type in conflicting_votes {
+                    let vote = ValidatorVote::create_signed(
+                        vote_type,
+                        height,
+                        round,
+                        validator_index,
+                        self.chain_id,
+                        &self.private_key,
+                    );
+                    self.networking.broadcast_vote(&vote);
+                }
+            }
+            ByzantineMode::VoteForUnseenBlocks => {
+                let state = self.local_validator_state(height, round);
+                if state.justify_state != JustifyState::NoVoteYet {
+                    return;
+                }
+                let block_hash = random_block_hash();
+                state.justify_state = JustifyState::VotedFor(block_hash);
+                let vote = ValidatorVote::create_signed(
+                    VoteType::Justify(block_hash),
+                    height,
+                    round,
+                    validator_index,
+                    self.chain_id,
+                    &self.private_key,
+                );
+                self.networking.broadcast_vote(&vote);
+                self.handle_vote(vote);
+            }
+            ByzantineMode::ReplayOldProposals => {
+                if !self.byzantine.act_once(height, round) {
+                    return;
+                }
+                for proposal in self.byzantine.old_proposals() {
+                    self.networking.broadcast_proposal(proposal);
+                }
+            }
+        }
+    }
+    //rebuild the vote already recorded for this validator and compare against the new vote
+    fn check_double_vote(&mut self, vote: &ValidatorVote) {
+        let height = vote.height;
+        let round = vote.round;
+        let validator_index = vote.validator_index;
+        let validator_state = self.validator_state(height, round, validator_index);
+
+        let previous_vote_type = match vote.vote {
+            VoteType::Justify(_) => match validator_state.justify_state {
+                JustifyState::VotedFor(block_hash) => VoteType::Justify(block_hash),
+                _ => return,
+            },
+            VoteType::Finalize(_) | VoteType::Skip => match validator_state.commit_state {
+                CommitState::Finalize(block_hash) => VoteType::Finalize(block_hash),
+                CommitState::Skip => VoteType::Skip,
+                _ => return,
+            },
+        };
+        if !votes_conflict(&previous_vote_type, &vote.vote) {
+            return;
+        }
+
+        let round_state = self.round_state(height, round);
+        let previous_signature = match previous_vote_type {
+            VoteType::Justify(block_hash) => round_state
+                .block_candidates
+                .get(&block_hash)
+                .and_then(|c| c.justify_votes.get(&validator_index)),
+            VoteType::Finalize(block_hash) => round_state
+                .block_candidates
+                .get(&block_hash)
+                .and_then(|c| c.finalize_votes.get(&validator_index)),
+            VoteType::Skip => round_state.skip_votes.get(&validator_index),
+        };
+        let Some(previous_signature) = previous_signature.copied() else {
+            return;
+        };
+        let first = ValidatorVote {
+            vote: previous_vote_type,
+            height,
+            round,
+            signature: previous_signature,
+            validator_index,
+        };
+        let evidence = DoubleVoteEvidence { first, second: vote.clone() };
+        self.handle_evidence(Evidence::DoubleVote(evidence));
+    }
+    //gossip valid evidence once and submit it as transaction so it is recorded on chain
+    //only verified evidence is remembered, so invalid copies can not shadow a valid one
+    fn handle_evidence(&mut self, evidence: Evidence) {
+        let evidence_hash = evidence.calculate_hash();
+        if self.seen_evidence.contains_key(&evidence_hash) {
+            return;
+        }
+        let Some(epoch_state) = self.epoch_state(evidence.height()) else {
+            return;
+        };
+        let leader_seed = self.evidence_leader_seed(evidence.height());
+        let Some(offender) = evidence.verify(epoch_state, leader_seed, self.chain_id) else {
+            return;
+        };
+        self.seen_evidence.insert(evidence_hash, evidence.height());
+        tracing::warn!(
+            "{} evidence against validator {} at height {} round {}",
+            evidence.kind(),
+            offender.pub_key,
+            evidence.height(),
+            evidence.round()
+        );
+        self.networking.broadcast_evidence(&evidence);
+
+        let already_included = self.db.check_evidence_included(evidence_hash);
+        let is_validator = self.has_stake_at_height(self.pub_key, self.current_height);
+        if is_validator && !already_included {
+            //pow is mined on the blocking pool so the consensus loop keeps voting meanwhile
+            //the transaction comes back through the mempool channel
+            let evidence_transaction_tx = self.evidence_transaction_tx.clone();
+            let private_key = self.private_key.clone();
+            let recent_block_height = self.latest_finalized_block.height;
+            let chain_id = self.chain_id;
+            let pow_difficulty = self.latest_finalized_block.next_pow_difficulty();
+            tokio::task::spawn_blocking(move || {
+                let nonce = rng::random_range(0..=u64::MAX);
+                let transaction = build_evidence_transaction(
+                    evidence.encode(),
+                    private_key,
+                    TxEnvelope {
+                        nonce,
+                        recent_block_height,
+                        chain_id,
+                        pow_difficulty,
+                        fuel_limit: 0,
+                    },
+                );
+                let _ = evidence_transaction_tx.send(transaction);
+            });
+        }
+    }
+    //double voting protection enforced through signer bitmap, each validator index signs at most once
     fn validate_cert_votes(
         &self,
-        votes: &BTreeMap<ValidatorIndex, ed25519::Signature>,
+        votes: &CertificateVotes,
         vote_type: VoteType,
         height: u64,
         round: u64,
@@ -277,25 +498,7 @@ impl ValidatorStateMachine {
         let Some(epoch_state) = self.epoch_state(height) else {
             return false;
         };
-        let threshold = (epoch_state.total_validator_stake * 2) / 3;
-        let mut valid_stake = 0;
-        for (validator_index, signature) in votes {
-            let Some(validator) = epoch_state.validator_data(*validator_index) else {
-                continue;
-            };
-            let expected_vote = ValidatorVote {
-                vote: vote_type.clone(),
-                height,
-                round,
-                signature: *signature,
-                validator_index: *validator_index,
-            };
-            if validator.pub_key.verify_sig(expected_vote.hash(), *signature) {
-                valid_stake += validator.stake;
-            }
-        }
-        let is_valid = valid_stake >= threshold;
-        return is_valid;
+        return votes_reach_threshold(epoch_state, self.chain_id, votes, vote_type, height, round);
     }
     fn handle_sync_finalized_block_received(&mut self, cert: FinalizedBlock) {
         let invalid_cert = !self.validate_cert_votes(
@@ -364,7 +567,7 @@ impl ValidatorStateMachine {
 
         let meets_skip_threshold = round_state.skip_stake >= skip_threshold;
         if meets_skip_threshold {
-            let votes = round_state.skip_votes.clone();
+            let votes = CertificateVotes::from_votes(&round_state.skip_votes);
             let cert = SkipCertificate { votes, round, height };
             round_state.skip_cert = Some(cert);
         }
@@ -375,7 +578,7 @@ impl ValidatorStateMachine {
             if meets_justification_threshold {
                 let cert = JustifyCertificate {
                     block_hash: candidate.block_hash,
-                    votes: candidate.justify_votes.clone(),
+                    votes: CertificateVotes::from_votes(&candidate.justify_votes),
                     round,
                     height,
                 };
@@ -387,7 +590,7 @@ impl ValidatorStateMachine {
             if meets_finalization_threshold && has_block {
                 let block = FinalizedBlock {
                     block: candidate.block.clone().unwrap(),
-                    votes: candidate.finalize_votes.clone(),
+                    votes: CertificateVotes::from_votes(&candidate.finalize_votes),
                     round,
                 };
                 slot_state.finalized_block = Some(block);
@@ -423,6 +626,7 @@ impl ValidatorStateMachine {
         if let Some(cert) = skip_cert {
             self.current_round += 1;
             self.entered_round_at = Instant::now();
+            self.round_timer.on_skip();
             self.sync_rounds_to_rpc();
 
             //https://docs.rs/commonware-consensus/latest/commonware_consensus/simplex/index.html#fetching-missing-certificates
@@ -445,15 +649,25 @@ impl ValidatorStateMachine {
             let finalized = slot.finalized_block.as_ref().unwrap().clone();
             self.broadcast_finalization_certificate(&finalized);
             self.latest_finalized_block = finalized.block.clone();
+            self.latest_finalized_block_hash = finalized.block.calculate_hash();
+
+            let finalized_in_current_round = finalized.round == self.current_round;
+            if finalized_in_current_round {
+                self.round_timer.on_finalized(self.entered_round_at.elapsed());
+            }
 
+            let spent_pow_hashes: Vec<Sha256Digest> =
+                finalized.block.transactions.iter().map(|tx| tx.calculate_pow_hash()).collect();
             self.execution.execute_block(finalized);
 
             self.entered_round_at = Instant::now();
             self.current_height += 1;
             self.current_round = 0;
             self.slot_state.retain(|&h, _| h >= self.current_height);
+            self.load_epoch_states();
+            prune_seen_evidence(&mut self.seen_evidence, self.current_height);
             self.clear_sync_rounds();
-            self.clean_mempool();
+            self.clean_mempool(&spent_pow_hashes);
         }
     }
 
@@ -496,6 +710,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
                 self.networking.broadcast_vote(&vote);
@@ -510,6 +725,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
                 self.networking.broadcast_vote(&vote);
@@ -520,6 +736,7 @@ impl ValidatorStateMachine {
                     height,
                     round,
                     validator_index,
+                    self.chain_id,
                     &self.private_key,
                 );
                 self.networking.broadcast_vote(&vote);
@@ -539,9 +756,17 @@ impl ValidatorStateMachine {
         }
     }
     fn broadcast_justify_certificate(&self, cert: JustifyCertificate) {
+        #[cfg(madsim)]
+        if self.byzantine.mode == ByzantineMode::WithholdCertificates {
+            return;
+        }
         self.networking.broadcast_certificate(Certificate::Justify(cert));
     }
     fn broadcast_finalization_certificate(&self, finalized: &FinalizedBlock) {
+        #[cfg(madsim)]
+        if self.byzantine.mode == ByzantineMode::WithholdCertificates {
+            return;
+        }
         let cert = FinalizationCertificate {
             block_hash: finalized.block.calculate_hash(),
             height: finalized.block.height,
@@ -551,6 +776,10 @@ impl ValidatorStateMachine {
         self.networking.broadcast_certificate(Certificate::Finalization(cert));
     }
     fn broadcast_skip_certificate(&self, cert: SkipCertificate) {
+        #[cfg(madsim)]
+        if self.byzantine.mode == ByzantineMode::WithholdCertificates {
+            return;
+        }
         self.networking.broadcast_certificate(Certificate::Skip(cert));
     }
 
@@ -700,28 +929,36 @@ impl ValidatorStateMachine {
         let state = self.round_state(height, round);
         return &mut state.local_validator_state;
     }
+    //stake weighted leader, seeded by hash of finalized block at height - 2
+    //so leaders are only known 2 slots in advance instead of forever
+    //
+    //not a vrf, leader of height - 2 can bias the seed by grinding its block contents
+    //(reorder/drop transactions) and try to get itself or a friend elected
+    //acceptable for now as it can only affect 1 slot per grinded block
     fn leader(&self, height: u64, round: u64) -> Option<ValidatorData> {
-        //not secure
-        //alts,
-        //actual vrf
-        //vulnerable vrf, just do blockhash
-        //queue based
-        //will probs do vrf in future, just do vulnerable "random" for now?
-        let epoch_state = self.epoch_state(height);
-
-        //leader selection assume index
-        if let Some(epoch_state) = epoch_state {
-            let amount_of_validators = epoch_state.validator_data.len() as u64;
-            let chosen_validator_index = (height + round) % amount_of_validators;
-
-            let leader_pub_key =
-                epoch_state.validator_index_to_key.get(&chosen_validator_index).unwrap();
-
-            let leader = epoch_state.validator_data.get(leader_pub_key);
-            return Some(*leader.unwrap());
+        let seed = self.leader_seed(height)?;
+        let epoch_state = self.epoch_state(height)?;
+        return slot_leader(epoch_state, seed, height, round).copied();
+    }
+    //only have seed for current and next height
+    //latest finalized block is height - 1 and its previous_block_hash is block hash of height - 2
+    fn leader_seed(&self, height: u64) -> Option<Sha256Digest> {
+        if height == self.current_height {
+            return Some(self.latest_finalized_block.previous_block_hash);
+        }
+        if height == self.current_height + 1 {
+            return Some(self.latest_finalized_block_hash);
         }
         return None;
     }
+    //evidence can be for an earlier height of the epoch, its seed is then read from disk
+    fn evidence_leader_seed(&self, height: u64) -> Option<Sha256Digest> {
+        if let Some(seed) = self.leader_seed(height) {
+            return Some(seed);
+        }
+        let previous_block = self.db.read_block(height.checked_sub(1)?)?;
+        return Some(previous_block.block.previous_block_hash);
+    }
     fn is_leader(&self, public_key: ed25519::PublicKey, height: u64, round: u64) -> bool {
         if let Some(leader) = self.leader(height, round) {
             return leader.pub_key == public_key;
@@ -764,46 +1001,144 @@ impl ValidatorStateMachine {
                 round,
                 ProposalType::Reproposal(justified_round),
                 block.clone(),
+                self.chain_id,
                 &self.private_key,
             );
             return Some(proposal);
         } else {
             //propose new block
-            let previous_block_hash = self.latest_finalized_block.calculate_hash();
-            let mut transactions = Vec::new();
-            let mut total_size = 0;
-            for tx in self.mempool.values() {
-                if transactions.len() >= MAX_TRANSACTIONS_PER_BLOCK {
-                    break;
-                }
-                if !self.execution.verify_pow(tx) {
-                    continue;
-                }
-                let tx_size = tx.encode().len();
-                if total_size + tx_size > MAX_BLOCK_SIZE {
-                    continue;
-                }
-                total_size += tx_size;
-                transactions.push(tx.clone());
-            }
+            let previous_block_hash = self.latest_finalized_block_hash;
+            let pow_difficulty = self.latest_finalized_block.next_pow_difficulty();
+            let transactions = self.select_mempool_transactions(&HashSet::new(), pow_difficulty);
 
             let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
 
-            let previous_block_state_root = self.execution.latest_state_root();
+            let previous_block_state_root = self.execution.state_root_for_block(height)?;
+            let grandparent_finalization = self.grandparent_finalization(height)?;
             let block = Block {
                 height,
                 transactions,
                 previous_block_hash,
                 timestamp,
                 previous_block_state_root,
+                pow_difficulty,
+                grandparent_finalization,
             };
 
-            let proposal =
-                Proposal::create_signed(round, ProposalType::Proposal, block, &self.private_key);
+            let proposal = Proposal::create_signed(
+                round,
+                ProposalType::Proposal,
+                block,
+                self.chain_id,
+                &self.private_key,
+            );
 
             return Some(proposal);
         }
     }
+    //block for current_height + 1 on top of the justified block at current_height
+    //justified block is the only block that can be finalized at current_height unless a later round justifies another
+    fn get_pipelined_block_to_propose(&mut self) -> Option<Proposal> {
+        let height = self.current_height;
+        let justify_cert = self.slot_state(height).latest_justify_cert()?.clone();
+        let round_state = self.round_state(height, justify_cert.round);
+        let candidate = round_state.block_candidates.get(&justify_cert.block_hash)?;
+        let parent = candidate.block.clone()?;
+
+        //parent is not executed yet, so its pow hashes are not marked as spent
+        let parent_pow_hashes: HashSet<Sha256Digest> =
+            parent.transactions.iter().map(|tx| tx.calculate_pow_hash()).collect();
+        let pow_difficulty = parent.next_pow_difficulty();
+        let transactions = self.select_mempool_transactions(&parent_pow_hashes, pow_difficulty);
+
+        let local_timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let timestamp = local_timestamp.max(parent.timestamp);
+
+        let previous_block_state_root = self.execution.state_root_for_block(height + 1)?;
+        let grandparent_finalization = self.grandparent_finalization(height + 1)?;
+        let block = Block {
+            height: height + 1,
+            transactions,
+            previous_block_hash: justify_cert.block_hash,
+            timestamp,
+            previous_block_state_root,
+            pow_difficulty,
+            grandparent_finalization,
+        };
+        let proposal = Proposal::create_signed(
+            0,
+            ProposalType::Proposal,
+            block,
+            self.chain_id,
+            &self.private_key,
+        );
+        return Some(proposal);
+    }
+    //pipelined proposals are made before the parent finalizes, so blocks carry the grandparent votes
+    //blocks 1 and 2 have none, the genesis block is not voted on
+    fn grandparent_finalization(&self, height: u64) -> Option<BlockFinalization> {
+        if height <= 2 {
+            return Some(BlockFinalization::default());
+        }
+        let grandparent = self.db.read_block(height - 2)?;
+        return Some(BlockFinalization { round: grandparent.round, votes: grandparent.votes });
+    }
+    //any certificate of the grandparent with enough stake is valid, the proposer picks which one
+    //the grandparent can be in the previous epoch, which is no longer loaded
+    fn grandparent_finalization_valid(&self, block: &Block) -> bool {
+        let finalization = &block.grandparent_finalization;
+        if block.height <= 2 {
+            return *finalization == BlockFinalization::default();
+        }
+        let grandparent_height = block.height - 2;
+        let Some(grandparent) = self.db.read_block(grandparent_height) else {
+            return false;
+        };
+        let epoch = epoch_for_height(grandparent_height);
+        let Some(validator_set) = self.db.read_epoch_validator_set(epoch) else {
+            return false;
+        };
+        return votes_reach_threshold(
+            &EpochState::from_validator_set(&validator_set),
+            self.chain_id,
+            &finalization.votes,
+            VoteType::Finalize(grandparent.block.calculate_hash()),
+            grandparent_height,
+            finalization.round,
+        );
+    }
+    fn select_mempool_transactions(
+        &self,
+        excluded_pow_hashes: &HashSet<Sha256Digest>,
+        pow_difficulty: u8,
+    ) -> Vec<Transaction> {
+        let mut transactions = Vec::new();
+        let mut total_size = 0;
+        let mut total_fuel: u64 = 0;
+        let mempool = self.mempool.read();
+        for tx in mempool.ordered_for_proposal() {
+            if transactions.len() >= MAX_TRANSACTIONS_PER_BLOCK {
+                break;
+            }
+            if !self.execution.verify_pow(tx) || !tx.meets_pow_difficulty(pow_difficulty) {
+                continue;
+            }
+            if excluded_pow_hashes.contains(&tx.calculate_pow_hash()) {
+                continue;
+            }
+            let tx_size = tx.encode().len();
+            if total_size + tx_size > MAX_BLOCK_SIZE {
+                continue;
+            }
+            if total_fuel.saturating_add(tx.fuel_limit) > MAX_BLOCK_FUEL {
+                continue;
+            }
+            total_size += tx_size;
+            total_fuel += tx.fuel_limit;
+            transactions.push(tx.clone());
+        }
+        return transactions;
+    }
     fn get_valid_proposal(&mut self, height: u64, round: u64) -> Option<Proposal> {
         let Some(proposal) = self.get_proposal(height, round).cloned() else {
             return None;
@@ -820,6 +1155,9 @@ impl ValidatorStateMachine {
         if block_size > MAX_BLOCK_SIZE {
             return None;
         }
+        if total_fuel_limit(&proposal.block.transactions) > MAX_BLOCK_FUEL {
+            return None;
+        }
 
         match proposal.proposal_type {
             ProposalType::Proposal => {
@@ -836,21 +1174,27 @@ impl ValidatorStateMachine {
                 let block = &proposal.block;
                 let prev_block = &self.latest_finalized_block;
 
-                let builds_on_latest = block.previous_block_hash == prev_block.calculate_hash();
+                let builds_on_latest =
+                    block.previous_block_hash == self.latest_finalized_block_hash;
                 let timestamp_increased_or_same = block.timestamp >= prev_block.timestamp;
                 let local_timestamp =
                     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
                 let timestamp_reasonable = block.timestamp < local_timestamp + 20;
 
                 let is_current_height = block.height == self.current_height;
-                let state_root_matches =
-                    block.previous_block_state_root == self.execution.latest_state_root();
+                let state_root_matches = Some(block.previous_block_state_root)
+                    == self.execution.state_root_for_block(block.height);
+                //difficulty follows from the finalized parent alone so every validator checks the same value
+                let pow_difficulty_valid = block.pow_difficulty == prev_block.next_pow_difficulty();
+                let grandparent_finalization_valid = self.grandparent_finalization_valid(block);
 
                 let is_valid = builds_on_latest
                     && timestamp_increased_or_same
                     && is_current_height
                     && timestamp_reasonable
-                    && state_root_matches;
+                    && state_root_matches
+                    && pow_difficulty_valid
+                    && grandparent_finalization_valid;
 
                 if is_valid {
                     return Some(proposal);
@@ -894,7 +1238,7 @@ impl ValidatorStateMachine {
         let Some(leader) = self.leader(height, round) else {
             return false;
         };
-        let hash = proposal.calculate_hash();
+        let hash = proposal.calculate_hash(self.chain_id);
         let signed_by_leader = leader.pub_key.verify_sig(hash, proposal.leader_signature);
 
         return signed_by_leader;
@@ -918,36 +1262,35 @@ impl ValidatorStateMachine {
     }
     //assumes signature verification handled by networking.rs ingress
     fn handle_new_mempool_tx(&mut self, transaction: Transaction) {
-        let tx_size = transaction.encode().len();
-        if self.mempool_size + tx_size > MAX_MEMPOOL_SIZE {
-            return;
-        }
         let is_valid_mempool_tx = self.valid_mempool_tx(&transaction);
         if is_valid_mempool_tx {
-            self.mempool_size += tx_size;
-            self.mempool.insert(transaction.calculate_txhash(), transaction);
+            let pow_difficulty = self.latest_finalized_block.next_pow_difficulty();
+            self.mempool.write().insert(transaction, pow_difficulty);
         }
     }
-    fn clean_mempool(&mut self) {
-        let mut tx_hash_to_remove = vec![];
-        for transaction in self.mempool.values() {
-            if !self.execution.verify_pow(transaction) {
-                tx_hash_to_remove.push(transaction.calculate_txhash());
-            }
-        }
-        for hash in tx_hash_to_remove {
-            if let Some(tx) = self.mempool.remove(&hash) {
-                self.mempool_size -= tx.encode().len();
-            }
-        }
+    //included transactions and any other transaction reusing their pow hash can never be included again
+    //expired transactions are recorded so rpc can report them instead of unknown
+    fn clean_mempool(&mut self, spent_pow_hashes: &[Sha256Digest]) {
+        let expired = {
+            let mut mempool = self.mempool.write();
+            mempool.remove_spent(spent_pow_hashes);
+            mempool.remove_expired(self.current_height)
+        };
+        self.db.set_txs_as_expired(&expired, self.current_height);
     }
 
     fn valid_mempool_tx(&self, transaction: &Transaction) -> bool {
         self.execution.verify_pow(transaction)
     }
 
-    fn epoch_state(&self, _height: u64) -> Option<&EpochState> {
-        return self.epoch_states.first();
+    fn epoch_state(&self, height: u64) -> Option<&EpochState> {
+        return self.epoch_states.get(&epoch_for_height(height));
+    }
+    fn load_epoch_states(&mut self) {
+        let changed = load_epoch_states(&self.db, self.current_height, &mut self.epoch_states);
+        if changed {
+            self.networking.set_validator_p2p_keys(validator_p2p_keys(&self.epoch_states));
+        }
     }
     fn validator_state(
         &mut self,
@@ -998,12 +1341,15 @@ impl ValidatorStateMachine {
         let previous_block_hash = Sha256Digest::from(*b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
         let timestamp = 0;
         let previous_block_state_root = Sha256Digest::default();
+        let pow_difficulty = 0;
         let block = Block {
             height,
             transactions,
             previous_block_hash,
             timestamp,
             previous_block_state_root,
+            pow_difficulty,
+            grandparent_finalization: BlockFinalization::default(),
         };
         return block;
     }
@@ -1116,6 +1462,7 @@ impl ValidatorStateMachine {
         let (transaction_tx, transactions_rx) = mpsc::unbounded_channel::<Transaction>();
         let (block_sync_tx, block_sync_rx) = mpsc::unbounded_channel::<FinalizedBlock>();
         let (cert_tx, cert_rx) = mpsc::unbounded_channel::<Certificate>();
+        let (evidence_tx, evidence_rx) = mpsc::unbounded_channel::<Evidence>();
 
         let current_round_for_sync = Arc::new(RwLock::new(RoundSyncStateExternal::default()));
 
@@ -1123,26 +1470,39 @@ impl ValidatorStateMachine {
         let p2p_key = config.keystore.p2p_key.clone();
         let dtls_key = config.keystore.dtls_key;
 
+        let is_fresh_db = db.read_epoch_validator_set(0).is_none();
+        if is_fresh_db {
+            let genesis_validator_set = config.genesis_epoch_state.to_validator_set();
+            db.write_epoch_validator_set(0, &genesis_validator_set);
+            db.write_pending_validator_set(&genesis_validator_set);
+        }
+
+        let mut epoch_states = BTreeMap::new();
+        load_epoch_states(&db, db.read_latest_finalized_height() + 1, &mut epoch_states);
+
         let networking = Networking::start(
             vote_tx.clone(),
             proposal_tx.clone(),
             cert_tx.clone(),
-            transaction_tx,
+            evidence_tx,
+            transaction_tx.clone(),
             p2p_key,
             config.peers,
             current_round_for_sync.clone(),
             db.clone(),
-            &config.genesis_epoch_state,
+            validator_p2p_keys(&epoch_states),
+            config.chain_id,
         )
         .await;
 
+        let mempool = Mempool::new_shared(MAX_MEMPOOL_SIZE);
         if config.run_rpc_node {
             start_rpc_node(
                 db.clone(),
                 networking.clone(),
+                mempool.clone(),
                 dtls_key,
                 config.rpc_nodes,
-                config.genesis_epoch_state.clone(),
             );
         }
 
@@ -1152,37 +1512,47 @@ impl ValidatorStateMachine {
         if is_restart {
             initial_state = Self::restore_from_db(&db);
         }
+        initial_state.execution.chain_id = config.chain_id;
         let current_height = initial_state.block.height + 1;
         let (restored_round, restored_slot_state, last_disk_justify_vote, last_disk_commit_vote) =
             Self::recover_consensus_state(&db, current_height);
 
-        ValidatorStateMachine {
+        let mut validator_state_machine = ValidatorStateMachine {
             last_sync_time: Instant::now(),
             last_time_pushed_votes: Instant::now(),
             current_height,
+            latest_finalized_block_hash: initial_state.block.calculate_hash(),
             latest_finalized_block: initial_state.block,
             current_round: restored_round,
-            epoch_states: vec![config.genesis_epoch_state],
+            epoch_states,
             pub_key: private_key.public_key(),
             private_key,
-            mempool: BTreeMap::new(),
-            mempool_size: 0,
+            chain_id: config.chain_id,
+            mempool,
             networking,
             vote_rx,
             proposal_rx,
             cert_rx,
             cert_tx,
+            evidence_rx,
+            evidence_transaction_tx: transaction_tx,
+            seen_evidence: HashMap::new(),
+            #[cfg(madsim)]
+            byzantine: ByzantineState::new(config.byzantine_mode),
             execution: initial_state.execution,
             transactions_rx,
             slot_state: restored_slot_state,
             entered_round_at: Instant::now(),
+            round_timer: RoundTimer::new(config.round_timeout),
             block_sync_rx,
             block_sync_tx,
             current_round_for_sync,
             last_disk_commit_vote,
             last_disk_justify_vote,
             db,
-        }
+        };
+        validator_state_machine.load_epoch_states();
+        return validator_state_machine;
     }
 }
 
@@ -1195,7 +1565,9 @@ pub struct ValidatorStateMachine {
     current_round: u64,
     current_height: u64,
     latest_finalized_block: Block,
+    latest_finalized_block_hash: Sha256Digest,
     entered_round_at: Instant,
+    round_timer: RoundTimer,
     slot_state: HashMap<SlotHeight, SlotState>,
 
     last_sync_time: Instant,
@@ -1206,12 +1578,13 @@ pub struct ValidatorStateMachine {
 
     pub_key: ed25519::PublicKey,
     private_key: ed25519::PrivateKey,
+    chain_id: u64,
 
     networking: Arc<Networking>,
     execution: Execution,
     db: Arc<Db>,
 
-    epoch_states: Vec<EpochState>,
+    epoch_states: BTreeMap<u64, EpochState>,
 
     vote_rx: UnboundedReceiver<ValidatorVote>,
     proposal_rx: UnboundedReceiver<Proposal>,
@@ -1221,10 +1594,17 @@ pub struct ValidatorStateMachine {
     block_sync_tx: UnboundedSender<FinalizedBlock>,
     cert_tx: UnboundedSender<Certificate>,
 
+    evidence_rx: UnboundedReceiver<Evidence>,
+    evidence_transaction_tx: UnboundedSender<Transaction>,
+    //evidence hash to evidence height
+    seen_evidence: HashMap<Sha256Digest, u64>,
+
+    #[cfg(madsim)]
+    byzantine: ByzantineState,
+
     current_round_for_sync: Arc<RwLock<RoundSyncStateExternal>>,
 
-    mempool: BTreeMap<Sha256Digest, Transaction>,
-    mempool_size: usize,
+    mempool: SharedMempool,
     transactions_rx: UnboundedReceiver<Transaction>,
 }
 
@@ -1262,6 +1642,144 @@ impl EpochState {
         self.validator_index_to_key.insert(validator_index, pub_key);
         self.total_validator_stake += stake;
     }
+    //pick validator with probability stake / total_validator_stake
+    pub fn stake_weighted_validator(&self, seed: Sha256Digest) -> Option<&ValidatorData> {
+        if self.total_validator_stake == 0 {
+            return None;
+        }
+        let mut target = uniform_below(seed, self.total_validator_stake);
+        for validator_public_key in self.validator_index_to_key.values() {
+            let validator = self.validator_data.get(validator_public_key).expect("invariant");
+            if target < validator.stake {
+                return Some(validator);
+            }
+            target -= validator.stake;
+        }
+        return None;
+    }
+    pub fn from_validator_set(validator_set: &PersistedValidatorSet) -> EpochState {
+        let mut epoch_state = EpochState::new();
+        for validator in &validator_set.validators {
+            epoch_state.add_registered_validator(
+                validator.pub_key,
+                validator.p2p_key,
+                validator.stake,
+            );
+        }
+        return epoch_state;
+    }
+    pub fn to_validator_set(&self) -> PersistedValidatorSet {
+        let mut validators = Vec::new();
+        for pub_key in self.validator_index_to_key.values() {
+            let validator = self.validator_data.get(pub_key).expect("invariant");
+            validators.push(PersistedValidator {
+                pub_key: validator.pub_key,
+                p2p_key: validator.p2p_key,
+                stake: validator.stake,
+            });
+        }
+        return PersistedValidatorSet { validators };
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct LeaderSeedData {
+    seed: Sha256Digest,
+    height: u64,
+    round: u64,
+}
+impl LeaderSeedData {
+    fn calculate_hash(&self) -> Sha256Digest {
+        sha256_hash(&self.encode())
+    }
+}
+
+/// Uniform value in 0..bound derived from seed
+/// draws in the incomplete last multiple of bound are rejected and the seed rehashed,
+/// plain `% bound` would favour low values
+pub fn uniform_below(seed: Sha256Digest, bound: u64) -> u64 {
+    let accepted_below = u64::MAX - (u64::MAX % bound);
+    let mut seed = seed;
+    loop {
+        let random = u64::from_le_bytes(seed.to_bytes()[..8].try_into().unwrap());
+        if random < accepted_below {
+            return random % bound;
+        }
+        seed = sha256_hash(&seed.to_bytes());
+    }
+}
+
+/// Leader of height and round, seed is the block hash of height - 2
+pub fn slot_leader(
+    epoch_state: &EpochState,
+    seed: Sha256Digest,
+    height: u64,
+    round: u64,
+) -> Option<&ValidatorData> {
+    let leader_seed_data = LeaderSeedData { seed, height, round };
+    return epoch_state.stake_weighted_validator(leader_seed_data.calculate_hash());
+}
+
+fn votes_reach_threshold(
+    epoch_state: &EpochState,
+    chain_id: u64,
+    votes: &CertificateVotes,
+    vote_type: VoteType,
+    height: u64,
+    round: u64,
+) -> bool {
+    let threshold = (epoch_state.total_validator_stake * 2) / 3;
+    let vote_data = ValidatorVoteData { chain_id, vote_type, height, round };
+    let vote_hash = vote_data.calculate_hash();
+    let valid_stake = votes.verified_stake(vote_hash, |validator_index| {
+        let validator = epoch_state.validator_data(validator_index)?;
+        return Some((validator.pub_key, validator.stake));
+    });
+    let is_valid = valid_stake >= threshold;
+    return is_valid;
+}
+
+pub fn epoch_for_height(height: u64) -> u64 {
+    return height / EPOCH_LENGTH;
+}
+
+/// Keeps the current and next epoch of current_height loaded, returns whether they changed
+/// the next epoch is written to db STATE_ROOT_DELAY blocks before the current epoch ends
+pub fn load_epoch_states(
+    db: &Db,
+    current_height: u64,
+    epoch_states: &mut BTreeMap<u64, EpochState>,
+) -> bool {
+    let current_epoch = epoch_for_height(current_height);
+    let loaded_before: Vec<u64> = epoch_states.keys().copied().collect();
+    for epoch in [current_epoch, current_epoch + 1] {
+        if epoch_states.contains_key(&epoch) {
+            continue;
+        }
+        if let Some(validator_set) = db.read_epoch_validator_set(epoch) {
+            epoch_states.insert(epoch, EpochState::from_validator_set(&validator_set));
+        }
+    }
+    epoch_states.retain(|&epoch, _| epoch >= current_epoch);
+    return !epoch_states.keys().copied().eq(loaded_before);
+}
+
+/// Forget evidence from epochs before the current one, its epoch state is no longer loaded
+/// so it can not be verified again anyway
+pub fn prune_seen_evidence(seen_evidence: &mut HashMap<Sha256Digest, u64>, current_height: u64) {
+    let current_epoch = epoch_for_height(current_height);
+    seen_evidence.retain(|_, &mut height| epoch_for_height(height) >= current_epoch);
+}
+
+//validators of the loaded epochs get the validator connection tier
+pub fn validator_p2p_keys(epoch_states: &BTreeMap<u64, EpochState>) -> HashSet<ed25519::PublicKey> {
+    let mut validator_p2p_keys = HashSet::new();
+    for epoch_state in epoch_states.values() {
+        for validator in epoch_state.validator_data.values() {
+            validator_p2p_keys.insert(validator.p2p_key);
+        }
+    }
+    return validator_p2p_keys;
 }
 
 pub type SlotHeight = u64;
@@ -1389,13 +1907,13 @@ pub struct LocalValidatorState {
     commit_state: CommitState,
     leader_state: LeaderState,
 }
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum JustifyState {
     NoVoteYet,
     VotedFor(Sha256Digest),
     Recovered,
 }
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum CommitState {
     NoVoteYet,
     Finalize(Sha256Digest),
@@ -1408,28 +1926,41 @@ pub struct NodeConfig {
     pub run_rpc_node: bool,
     pub genesis_epoch_state: EpochState,
     pub rpc_nodes: Vec<vastrum_shared_types::frontend::frontend_data::RpcNodeEndpoint>,
+    #[cfg(madsim)]
+    pub byzantine_mode: ByzantineMode,
+    pub round_timeout: RoundTimeoutConfig,
+    pub chain_id: u64,
 }
-use crate::utils::limits::{LONG_ROUND_TIMEOUT, ROUND_TIMEOUT};
+#[cfg(madsim)]
+use crate::consensus::byzantine::{ByzantineMode, ByzantineState, random_block_hash};
 use crate::{
+    consensus::evidence::{DoubleProposalEvidence, DoubleVoteEvidence, Evidence, votes_conflict},
+    consensus::mempool::{Mempool, SharedMempool},
+    consensus::round_timer::{RoundTimeoutConfig, RoundTimer},
     consensus::types::{
         Block, Certificate, FinalizationCertificate, FinalizedBlock, JustifyCertificate, Proposal,
         ProposalType, RoundSyncStateExternal, SkipCertificate, ValidatorVote, VoteType,
     },
     db::{
         Db,
+        epoch::{PersistedValidator, PersistedValidatorSet},
         round_state::PersistedRoundState,
         vote_state::{
             LatestCommitVote, LatestCommitVoteState, LatestJustifyVote, LatestJustifyVoteState,
         },
     },
-    execution::execution::Execution,
+    execution::execution::{Execution, total_fuel_limit},
     keystore::keyset::Keystore,
     p2p::{networking::Networking, peer_manager::KnownPeer},
     rpc::start::start_rpc_node,
-    utils::limits::{MAX_MEMPOOL_SIZE, MAX_ROUND_LOOKAHEAD, MAX_SLOT_LOOKAHEAD},
+    utils::{
+        limits::{MAX_MEMPOOL_SIZE, MAX_ROUND_LOOKAHEAD, MAX_SLOT_LOOKAHEAD},
+        rng,
+    },
 };
+use borsh::{BorshDeserialize, BorshSerialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -1442,7 +1973,18 @@ use tokio::{
 };
 use vastrum_shared_types::{
     borsh::BorshExt,
-    crypto::{ed25519, sha256::Sha256Digest},
-    limits::{MAX_BLOCK_SIZE, MAX_TRANSACTIONS_PER_BLOCK},
-    types::execution::transaction::Transaction,
+    crypto::{
+        ed25519,
+        sha256::{Sha256Digest, sha256_hash},
+    },
+    limits::{EPOCH_LENGTH, MAX_BLOCK_FUEL, MAX_BLOCK_SIZE, MAX_TRANSACTIONS_PER_BLOCK},
+    transactioning::transaction_generator::{TxEnvelope, build_evidence_transaction},
+    types::{
+        consensus::{BlockFinalization, CertificateVotes, ValidatorVoteData},
+        execution::transaction::Transaction,
+    },
 };
+
+#[cfg(test)]
+#[path = "validator_state_machine_tests.rs"]
+mod tests;
@@ -0,0 +1,90 @@
+use super::*;
+
+fn epoch_state_with_stakes(stakes: &[u64]) -> EpochState {
+    let mut epoch_state = EpochState::new();
+    for (seed, stake) in stakes.iter().enumerate() {
+        let pub_key = ed25519::PrivateKey::from_seed(seed as u64 + 1).public_key();
+        epoch_state.add_registered_validator(pub_key, pub_key, *stake);
+    }
+    return epoch_state;
+}
+
+#[test]
+fn test_stake_weighted_validator_deterministic() {
+    let epoch_state = epoch_state_with_stakes(&[10, 20, 30, 40]);
+    for i in 0..100u64 {
+        let seed = sha256_hash(&i.to_le_bytes());
+        let first = epoch_state.stake_weighted_validator(seed).unwrap().validator_index;
+        let second = epoch_state.stake_weighted_validator(seed).unwrap().validator_index;
+        assert_eq!(first, second);
+    }
+
+    //same set rebuilt from its persisted form picks the same leaders
+    let restored = EpochState::from_validator_set(&epoch_state.to_validator_set());
+    for i in 0..100u64 {
+        let seed = sha256_hash(&i.to_le_bytes());
+        let leader = epoch_state.stake_weighted_validator(seed).unwrap().validator_index;
+        let restored_leader = restored.stake_weighted_validator(seed).unwrap().validator_index;
+        assert_eq!(leader, restored_leader);
+    }
+}
+
+#[test]
+fn test_stake_weighted_validator_distribution() {
+    let stakes = [1, 2, 3, 4];
+    let epoch_state = epoch_state_with_stakes(&stakes);
+    let draws = 20_000;
+    let mut picks = [0u64; 4];
+    for i in 0..draws {
+        let validator =
+            epoch_state.stake_weighted_validator(sha256_hash(&i.to_le_bytes())).unwrap();
+        picks[validator.validator_index as usize] += 1;
+    }
+    for (validator_index, stake) in stakes.iter().enumerate() {
+        let expected = draws * stake / 10;
+        let picked = picks[validator_index];
+        //well within 5 standard deviations for 20k draws
+        assert!(picked.abs_diff(expected) < 400, "validator {validator_index}: {picked}");
+    }
+}
+
+#[test]
+fn test_stake_weighted_validator_no_stake() {
+    let epoch_state = EpochState::new();
+    assert!(epoch_state.stake_weighted_validator(Sha256Digest::from_u64(0)).is_none());
+}
+
+#[test]
+fn test_uniform_below_rejects_biased_draws() {
+    //with bound just over 2^63 close to half of all u64 draws fall in the biased tail
+    let bound = (1u64 << 63) + 1;
+    let mut resampled = 0;
+    for i in 0..1000u64 {
+        let seed = sha256_hash(&i.to_le_bytes());
+        let first_draw = u64::from_le_bytes(seed.to_bytes()[..8].try_into().unwrap());
+        let value = uniform_below(seed, bound);
+        assert!(value < bound);
+        if first_draw >= bound {
+            resampled += 1;
+            assert_ne!(value, first_draw % bound, "biased draw not resampled");
+        } else {
+            assert_eq!(value, first_draw);
+        }
+    }
+    assert!(resampled > 0);
+    assert_eq!(uniform_below(Sha256Digest::from_u64(7), 1), 0);
+}
+
+#[test]
+fn test_seen_evidence_pruned_with_its_epoch() {
+    let mut seen_evidence = HashMap::new();
+    seen_evidence.insert(Sha256Digest::from_u64(1), 1);
+    seen_evidence.insert(Sha256Digest::from_u64(2), EPOCH_LENGTH - 1);
+    seen_evidence.insert(Sha256Digest::from_u64(3), EPOCH_LENGTH);
+
+    prune_seen_evidence(&mut seen_evidence, EPOCH_LENGTH - 1);
+    assert_eq!(seen_evidence.len(), 3, "evidence of the current epoch is kept");
+
+    prune_seen_evidence(&mut seen_evidence, EPOCH_LENGTH);
+    assert_eq!(seen_evidence.keys().copied().collect::<Vec<_>>(), vec![Sha256Digest::from_u64(3)]);
+}
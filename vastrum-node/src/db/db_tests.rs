@@ -96,3 +96,238 @@ fn rollback_preserves_writes_before_begin() {
     assert_eq!(batch.get(cf::SITE_KV, b"pre2").unwrap(), b"b");
     assert!(batch.get(cf::SITE_KV, b"post").is_none());
 }
+
+#[test]
+fn nested_rollback_keeps_outer_scope_writes() {
+    let db = test_db("nested_rollback");
+    let batch = BatchDb::new(db);
+    batch.begin_revertable();
+    batch.put(cf::SITE_KV, b"outer", b"a".to_vec());
+    batch.begin_revertable();
+    batch.put(cf::SITE_KV, b"outer", b"b".to_vec());
+    batch.put(cf::SITE_KV, b"inner", b"c".to_vec());
+    batch.rollback_revertable();
+    assert_eq!(batch.get(cf::SITE_KV, b"outer").unwrap(), b"a");
+    assert!(batch.get(cf::SITE_KV, b"inner").is_none());
+    batch.commit_revertable();
+    assert_eq!(batch.get(cf::SITE_KV, b"outer").unwrap(), b"a");
+}
+
+#[test]
+fn nested_commit_is_reverted_with_outer_scope() {
+    let db = test_db("nested_commit");
+    let batch = BatchDb::new(db);
+    batch.begin_revertable();
+    batch.begin_revertable();
+    batch.put(cf::SITE_KV, b"inner", b"c".to_vec());
+    batch.commit_revertable();
+    assert_eq!(batch.get(cf::SITE_KV, b"inner").unwrap(), b"c");
+    batch.rollback_revertable();
+    assert!(batch.get(cf::SITE_KV, b"inner").is_none());
+}
+
+#[test]
+fn batch_at_height_reads_past_state() {
+    let db = test_db("batch_at_height");
+    let site_id = Sha256Digest::from_u64(1);
+    let module_ids = [Sha256Digest::from_u64(2), Sha256Digest::from_u64(3)];
+    for (height, module_id) in [(1, module_ids[0]), (2, module_ids[1])] {
+        let batch = BatchDb::new(Arc::clone(&db));
+        batch.write_kv("k", vec![height as u8], site_id);
+        batch.write_site(SiteData { site_id, module_id, admin: None });
+        batch.write_keyvalue_history_to_db(height, 10);
+        batch.commit();
+    }
+    let past = BatchDb::new_at_height(Arc::clone(&db), 1);
+    assert_eq!(past.read_kv("k", site_id), Some(vec![1]));
+    assert_eq!(past.read_site(site_id).unwrap().module_id, module_ids[0]);
+    let past = BatchDb::new_at_height(Arc::clone(&db), 0);
+    assert_eq!(past.read_kv("k", site_id), None);
+    assert_eq!(past.read_site(site_id), None);
+    let latest = BatchDb::new(db);
+    assert_eq!(latest.read_kv("k", site_id), Some(vec![2]));
+    assert_eq!(latest.read_site(site_id).unwrap().module_id, module_ids[1]);
+}
+
+#[test]
+fn receipt_is_committed_to_state_tree() {
+    let db = test_db("receipt_in_state_tree");
+    let batch = BatchDb::new(db);
+    let tx_hash = Sha256Digest::from_u64(7);
+    let receipt = TxReceipt {
+        height: 3,
+        outcome: TxOutcome::Failure { message: "out of bounds".to_string() },
+        fuel_used: 500,
+        kv_bytes_written: 12,
+        host_calls: HostCallCounts::default(),
+        return_data: vec![1, 2, 3],
+    };
+    batch.write_tx_receipt(tx_hash, &receipt);
+    assert_eq!(batch.read_tx_receipt(tx_hash), Some(receipt));
+    assert!(batch.check_tx_inclusion_state(tx_hash));
+    assert_eq!(batch.collect_jmt_updates().len(), 1);
+}
+
+#[test]
+fn event_blocks_chain_to_previous_height() {
+    let db = test_db("event_blocks_chain");
+    let site_id = Sha256Digest::from([1; 32]);
+    let topic = Sha256Digest::from([2; 32]);
+    let event = |n: u64| ContractEvent {
+        tx_hash: Sha256Digest::from_u64(n),
+        topics: vec![topic],
+        data: vec![n as u8],
+    };
+    for (height, events) in [(2, vec![event(1), event(2)]), (5, vec![event(3)])] {
+        let batch = BatchDb::new(Arc::clone(&db));
+        batch.write_event_block(site_id, topic, height, events);
+        batch.commit();
+    }
+
+    let block_2 = db.read_event_block(site_id, topic, 2).unwrap();
+    assert_eq!(block_2, EventBlock { prev_height: None, events: vec![event(1), event(2)] });
+    let block_5 = db.read_event_block(site_id, topic, 5).unwrap();
+    assert_eq!(block_5, EventBlock { prev_height: Some(2), events: vec![event(3)] });
+
+    assert_eq!(db.latest_event_height(site_id, topic, 4), Some(2));
+    assert_eq!(db.latest_event_height(site_id, topic, 1), None);
+    assert_eq!(db.next_event_height(site_id, topic, 2, 10), Some(5));
+    assert_eq!(db.next_event_height(site_id, topic, 5, 10), None);
+}
+
+#[test]
+fn storage_usage_follows_kv_writes() {
+    let db = test_db("storage_usage");
+    let batch = BatchDb::new(Arc::clone(&db));
+    let site_id = Sha256Digest::from([1; 32]);
+    //sitekv key plus the kv index key, site id and plain key
+    let key_len = SiteKvStorageKey::new(site_id, "key").encode().len() as u64 + 32 + 3;
+
+    batch.write_kv("key", vec![0; 10], site_id);
+    assert_eq!(batch.read_storage_usage(site_id), key_len + 10);
+    batch.write_kv("key", vec![0; 4], site_id);
+    assert_eq!(batch.read_storage_usage(site_id), key_len + 4);
+
+    batch.begin_revertable();
+    batch.write_kv("key", vec![0; 100], site_id);
+    batch.delete_kv("missing", site_id);
+    assert_eq!(batch.read_storage_usage(site_id), key_len + 100);
+    batch.rollback_revertable();
+    assert_eq!(batch.read_storage_usage(site_id), key_len + 4);
+
+    batch.commit();
+    assert_eq!(db.read_storage_usage(site_id), key_len + 4);
+    let batch = BatchDb::new(Arc::clone(&db));
+    batch.delete_kv("key", site_id);
+    batch.commit();
+    assert_eq!(db.read_storage_usage(site_id), 0);
+    assert_eq!(db.read_storage_usage(Sha256Digest::from([2; 32])), 0);
+}
+
+#[test]
+fn scan_kv_merges_pending_writes_in_key_order() {
+    let db = test_db("scan_kv");
+    let site_id = Sha256Digest::from([1; 32]);
+    let other_site_id = Sha256Digest::from([2; 32]);
+    let batch = BatchDb::new(Arc::clone(&db));
+    for key in ["a.1", "a.3", "a.5", "b.1"] {
+        batch.write_kv(key, key.as_bytes().to_vec(), site_id);
+    }
+    batch.write_kv("a.2", vec![1], other_site_id);
+    batch.commit();
+
+    let batch = BatchDb::new(Arc::clone(&db));
+    batch.write_kv("a.4", vec![4], site_id);
+    batch.begin_revertable();
+    batch.delete_kv("a.3", site_id);
+    batch.write_kv("a.0", vec![0], site_id);
+    let keys = |entries: Vec<(String, Vec<u8>)>| -> Vec<String> {
+        entries.into_iter().map(|(key, _)| key).collect()
+    };
+    assert_eq!(keys(batch.scan_kv(site_id, "a.", None, 10, false)), ["a.0", "a.1", "a.4", "a.5"]);
+    assert_eq!(keys(batch.scan_kv(site_id, "a.", Some("a.2"), 2, false)), ["a.4", "a.5"]);
+    assert_eq!(keys(batch.scan_kv(site_id, "a.", None, 2, true)), ["a.5", "a.4"]);
+    assert_eq!(keys(batch.scan_kv(site_id, "a.", Some("a.4"), 10, true)), ["a.4", "a.1", "a.0"]);
+    assert_eq!(
+        batch.scan_kv(site_id, "b.", None, 10, false),
+        [("b.1".to_string(), b"b.1".to_vec())]
+    );
+    batch.rollback_revertable();
+    assert_eq!(keys(batch.scan_kv(site_id, "a.", None, 10, false)), ["a.1", "a.3", "a.4", "a.5"]);
+
+    batch.commit();
+    assert_eq!(db.scan_kv_keys(site_id, "a.", Some("a.2"), 10, false), ["a.3", "a.4", "a.5"]);
+    assert_eq!(db.scan_kv_keys(site_id, "", None, 10, true), ["b.1", "a.5", "a.4", "a.3", "a.1"]);
+    assert_eq!(db.scan_kv_keys(other_site_id, "", None, 10, false), ["a.2"]);
+}
+
+#[test]
+fn expired_txs_pruned_after_validity_window() {
+    let db = test_db("expired_txs_pruned");
+    let expired = [Sha256Digest::from_u64(1), Sha256Digest::from_u64(2)];
+    db.set_txs_as_expired(&expired, 10);
+    db.set_txs_as_expired(&[Sha256Digest::from_u64(3)], 11);
+    assert_eq!(db.read_tx_expired_height(expired[0]), Some(10));
+
+    db.set_txs_as_expired(&[], 10 + VALIDITY_WINDOW - 1);
+    assert_eq!(db.read_tx_expired_height(expired[1]), Some(10));
+
+    db.set_txs_as_expired(&[], 10 + VALIDITY_WINDOW);
+    assert_eq!(db.read_tx_expired_height(expired[0]), None);
+    assert_eq!(db.read_tx_expired_height(expired[1]), None);
+    assert_eq!(db.read_tx_expired_height(Sha256Digest::from_u64(3)), Some(11));
+    assert!(db.get(cf::EXPIRED_TXS_PRUNE_INDEX, 10u64.to_be_bytes()).is_none());
+}
+
+#[test]
+fn read_site_decodes_layout_without_admin() {
+    let db = test_db("read_site_without_admin");
+    let site_id = Sha256Digest::from([1; 32]);
+    let module_id = Sha256Digest::from([2; 32]);
+    let mut v0 = site_id.encode();
+    v0.extend(module_id.encode());
+    db.put(cf::SITE, site_id.encode(), v0);
+    assert_eq!(db.read_site(site_id), Some(SiteData { site_id, module_id, admin: None }));
+
+    let admin = Some(ed25519::PrivateKey::from_seed(1).public_key());
+    db.write_site(SiteData { site_id, module_id, admin });
+    assert_eq!(BatchDb::new(db).read_site(site_id).unwrap().admin, admin);
+}
+
+#[cfg(not(madsim))]
+#[test]
+fn open_drops_legacy_column_families() {
+    let path = std::env::temp_dir().join("vastrum_batch_test_open_drops_legacy_cfs");
+    let _ = std::fs::remove_dir_all(&path);
+    let mut opts = rocksdb::Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    drop(rocksdb::DB::open_cf(&opts, &path, ["included_txs"]).unwrap());
+
+    drop(Db::open(&path));
+    let cfs = rocksdb::DB::list_cf(&opts, &path).unwrap();
+    assert!(!cfs.iter().any(|name| name == "included_txs"));
+    assert!(cfs.iter().any(|name| name == cf::RECEIPT));
+}
+
+#[cfg(not(madsim))]
+#[test]
+#[should_panic(expected = "remove it to resync the chain")]
+fn open_refuses_database_without_schema_version() {
+    let path = std::env::temp_dir().join("vastrum_batch_test_open_without_schema_version");
+    let db = Db::open_fresh(&path);
+    db.write_latest_height(10);
+    //as written before the schema version was stored
+    db.delete(cf::META, b"schema_version");
+    drop(db);
+    Db::open(&path);
+}
+
+use crate::execution::types::sitedata::SiteData;
+use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::crypto::ed25519;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::limits::VALIDITY_WINDOW;
+use vastrum_shared_types::types::execution::event::{ContractEvent, EventBlock};
+use vastrum_shared_types::types::execution::receipt::{HostCallCounts, TxOutcome, TxReceipt};
+use vastrum_shared_types::types::storage::SiteKvStorageKey;
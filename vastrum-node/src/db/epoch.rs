@@ -0,0 +1,73 @@
+//validator set for the next epoch, updated by register/exit validator transactions
+const PENDING_VALIDATOR_SET: &[u8] = b"pending_validator_set";
+
+impl Db {
+    pub fn read_epoch_validator_set(&self, epoch: u64) -> Option<PersistedValidatorSet> {
+        let bytes = self.get(cf::EPOCH, epoch.encode())?;
+        return Some(PersistedValidatorSet::decode(&bytes).unwrap());
+    }
+
+    pub fn write_epoch_validator_set(&self, epoch: u64, validator_set: &PersistedValidatorSet) {
+        self.put(cf::EPOCH, epoch.encode(), validator_set.encode());
+    }
+
+    pub fn write_pending_validator_set(&self, validator_set: &PersistedValidatorSet) {
+        self.put(cf::EPOCH, PENDING_VALIDATOR_SET, validator_set.encode());
+    }
+
+    pub fn read_epoch_handoff(&self, epoch: u64) -> Option<EpochHandoff> {
+        let bytes = self.get(cf::EPOCH_HANDOFF, epoch.encode())?;
+        return Some(EpochHandoff::decode(&bytes).unwrap());
+    }
+}
+
+impl BatchDb {
+    pub fn read_epoch_validator_set(&self, epoch: u64) -> Option<PersistedValidatorSet> {
+        let bytes = self.get(cf::EPOCH, epoch.encode())?;
+        return Some(PersistedValidatorSet::decode(&bytes).unwrap());
+    }
+
+    pub fn write_epoch_validator_set(&self, epoch: u64, validator_set: &PersistedValidatorSet) {
+        self.put(cf::EPOCH, epoch.encode(), validator_set.encode());
+    }
+
+    pub fn read_pending_validator_set(&self) -> Option<PersistedValidatorSet> {
+        let bytes = self.get(cf::EPOCH, PENDING_VALIDATOR_SET)?;
+        return Some(PersistedValidatorSet::decode(&bytes).unwrap());
+    }
+
+    pub fn write_pending_validator_set(&self, validator_set: &PersistedValidatorSet) {
+        self.put(cf::EPOCH, PENDING_VALIDATOR_SET, validator_set.encode());
+    }
+
+    /// With the last block of an epoch, keep the proof of the next validator set in the same batch
+    /// the proven jmt version is STATE_ROOT_DELAY blocks old, so already on disk
+    /// and it is pruned after KV_RETENTION_WINDOW
+    pub fn persist_epoch_handoff(&self, height: u64) {
+        let is_last_block_of_epoch = (height + 1) % EPOCH_LENGTH == 0;
+        if !is_last_block_of_epoch {
+            return;
+        }
+        let epoch = epoch_for_height(height + 1);
+        let Some(validator_set) = self.read_epoch_validator_set(epoch) else {
+            return;
+        };
+        let Some(state_height) = height.checked_sub(STATE_ROOT_DELAY) else {
+            return;
+        };
+        let Some(state_proof) =
+            self.db.generate_state_proof(cf::EPOCH, &epoch.encode(), state_height)
+        else {
+            return;
+        };
+        let handoff = EpochHandoff { epoch, validator_set, state_proof };
+        self.put(cf::EPOCH_HANDOFF, epoch.encode(), handoff.encode());
+    }
+}
+
+use super::{BatchDb, Db, cf};
+use crate::consensus::validator_state_machine::epoch_for_height;
+use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::limits::{EPOCH_LENGTH, STATE_ROOT_DELAY};
+pub use vastrum_shared_types::types::consensus::{PersistedValidator, PersistedValidatorSet};
+use vastrum_shared_types::types::rpc::types::EpochHandoff;
@@ -0,0 +1,69 @@
+use super::{BatchDb, Db, cf};
+use vastrum_shared_types::{
+    borsh::BorshExt,
+    crypto::sha256::Sha256Digest,
+    types::{
+        execution::event::{ContractEvent, EventBlock},
+        storage::{event_block_key, event_head_key},
+    },
+};
+
+impl Db {
+    pub fn read_event_block(
+        &self,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        height: u64,
+    ) -> Option<EventBlock> {
+        let value = self.get(cf::EVENT, event_block_key(site_id, topic, height))?;
+        EventBlock::decode(&value).ok()
+    }
+
+    /// Latest height at most max_height with events under the topic
+    pub fn latest_event_height(
+        &self,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        max_height: u64,
+    ) -> Option<u64> {
+        let entry = self.seek_prev(cf::EVENT, &event_block_key(site_id, topic, max_height))?;
+        return event_key_height(&entry.key, site_id, topic);
+    }
+
+    /// First height above after and at most max_height with events under the topic
+    pub fn next_event_height(
+        &self,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        after: u64,
+        max_height: u64,
+    ) -> Option<u64> {
+        let start = event_block_key(site_id, topic, after.checked_add(1)?);
+        let end = event_block_key(site_id, topic, max_height);
+        let entry = self.seek_forward_bounded(cf::EVENT, &start, &end)?;
+        return event_key_height(&entry.key, site_id, topic);
+    }
+}
+
+impl BatchDb {
+    /// Writes the events of a site topic at height as one block, chained to the previous block
+    pub fn write_event_block(
+        &self,
+        site_id: Sha256Digest,
+        topic: Sha256Digest,
+        height: u64,
+        events: Vec<ContractEvent>,
+    ) {
+        let head_key = event_head_key(site_id, topic);
+        let prev_height = self.get(cf::EVENT, &head_key).and_then(|v| u64::decode(&v).ok());
+        self.put(cf::EVENT, head_key, height.encode());
+        let block = EventBlock { prev_height, events };
+        self.put(cf::EVENT, event_block_key(site_id, topic, height), block.encode());
+    }
+}
+
+//head keys are the site topic prefix without a height
+fn event_key_height(key: &[u8], site_id: Sha256Digest, topic: Sha256Digest) -> Option<u64> {
+    let height_bytes = key.strip_prefix(event_head_key(site_id, topic).as_slice())?;
+    return Some(u64::from_be_bytes(height_bytes.try_into().ok()?));
+}
@@ -0,0 +1,21 @@
+use super::{BatchDb, Db, cf};
+use vastrum_shared_types::{borsh::BorshExt, crypto::sha256::Sha256Digest};
+
+impl Db {
+    pub fn check_evidence_included(&self, evidence_hash: Sha256Digest) -> bool {
+        let key = evidence_hash.encode();
+        self.get(cf::EVIDENCE, key).is_some()
+    }
+}
+
+impl BatchDb {
+    pub fn check_evidence_included(&self, evidence_hash: Sha256Digest) -> bool {
+        let key = evidence_hash.encode();
+        self.get(cf::EVIDENCE, key).is_some()
+    }
+
+    pub fn set_evidence_as_included(&self, evidence_hash: Sha256Digest, height: u64) {
+        let key = evidence_hash.encode();
+        self.put(cf::EVIDENCE, key, height.encode());
+    }
+}
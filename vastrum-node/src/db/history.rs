@@ -0,0 +1,121 @@
+//state the jmt commits to, but only by value hash, so old values are kept here for reads at past heights
+//receipts and events are written once under unique keys, they never have an old value to keep
+pub(super) const HISTORY_TRACKED_CFS: [&str; 6] =
+    [cf::SITE, cf::SITE_KV, cf::DOMAIN, cf::PAGE, cf::EPOCH, cf::SITE_STORAGE];
+
+//historykey, contains cf and storage key but also height
+//this allows rocksdb to sort by height
+struct HistoryKey<'a> {
+    cf_namespace: u8,
+    key: &'a [u8],
+    height: u64,
+}
+
+impl HistoryKey<'_> {
+    //key is length prefixed so no key is a prefix of another key's history
+    //in order to support rocksdb ordering on height need to_be_bytes
+    fn encode(self) -> Vec<u8> {
+        let mut buf = vec![self.cf_namespace];
+        buf.extend_from_slice(&(self.key.len() as u32).to_be_bytes());
+        buf.extend_from_slice(self.key);
+        buf.extend_from_slice(&self.height.to_be_bytes());
+        return buf;
+    }
+}
+
+fn history_key(cf: &str, key: &[u8], height: u64) -> Vec<u8> {
+    let cf_namespace = cf_to_namespace_byte(cf);
+    let history_key = HistoryKey { cf_namespace, key, height }.encode();
+    return history_key;
+}
+
+fn history_prefix_end(cf: &str, key: &[u8]) -> Vec<u8> {
+    history_key(cf, key, u64::MAX)
+}
+
+struct ChangedKey {
+    cf: String,
+    key: Vec<u8>,
+    old_value: Vec<u8>,
+}
+
+impl Db {
+    /// Value of a key in a history tracked cf at a height inside the retention window
+    pub fn read_at_height(&self, cf: &str, key: &[u8], height: u64) -> Option<Vec<u8>> {
+        let first_change_after = history_key(cf, key, height + 1);
+        let key_upper_bound = history_prefix_end(cf, key);
+
+        match self.seek_forward_bounded(cf::KV_HISTORY, &first_change_after, &key_upper_bound) {
+            //if find entry in kv_history, that means key was changed after this height
+            //either have case where key was empty at this height and then latest set
+            //or key was empty before and should return that value
+            Some(entry) => {
+                let key_was_empty_at_this_height = entry.value.is_empty();
+                if key_was_empty_at_this_height {
+                    return None;
+                } else {
+                    return Some(entry.value);
+                }
+            }
+            //no changes after this height, can return current value
+            None => {
+                return self.get(cf, key);
+            }
+        }
+    }
+}
+
+impl BatchDb {
+    pub fn write_keyvalue_history_to_db(&self, block_height: u64, retention: u64) {
+        let changed = self.collect_changed_keyvalues_this_batch();
+
+        let mut history_keys = vec![];
+        for entry in changed {
+            let hk = history_key(&entry.cf, &entry.key, block_height);
+            history_keys.push(hk.clone());
+            self.put(cf::KV_HISTORY, &hk, entry.old_value);
+        }
+
+        if !history_keys.is_empty() {
+            self.put(
+                cf::KV_HISTORY_PRUNE_INDEX,
+                block_height.to_be_bytes(),
+                borsh::to_vec(&history_keys).unwrap(),
+            );
+        }
+
+        self.prune_kv_history(block_height, retention);
+    }
+
+    fn prune_kv_history(&self, block_height: u64, retention: u64) {
+        let expired = block_height.saturating_sub(retention + 1);
+        if expired == 0 {
+            return;
+        }
+        let idx_key = expired.to_be_bytes();
+        if let Some(data) = self.get(cf::KV_HISTORY_PRUNE_INDEX, idx_key) {
+            if let Ok(old_keys) = borsh::from_slice::<Vec<Vec<u8>>>(&data) {
+                for hk in old_keys {
+                    self.delete(cf::KV_HISTORY, hk);
+                }
+            }
+            self.delete(cf::KV_HISTORY_PRUNE_INDEX, idx_key);
+        }
+    }
+
+    fn collect_changed_keyvalues_this_batch(&self) -> Vec<ChangedKey> {
+        let state = self.state.lock();
+        let mut changed = vec![];
+        for cf_key in state.pending.keys() {
+            if !HISTORY_TRACKED_CFS.contains(&cf_key.cf.as_str()) {
+                continue;
+            }
+            let old_value = self.db.get(&cf_key.cf, &cf_key.key).unwrap_or_default();
+            changed.push(ChangedKey { cf: cf_key.cf.clone(), key: cf_key.key.clone(), old_value });
+        }
+        return changed;
+    }
+}
+
+use crate::db::{BatchDb, Db, cf};
+use vastrum_shared_types::types::storage::cf_to_namespace_byte;
@@ -1,11 +1,18 @@
 const META_JMT_ROOT: &[u8] = b"jmt_root";
-const JMT_TRACKED_CFS: [&str; 4] = ["site", "sitekv", "domain", "page"];
+const META_STATE_ROOT_PREFIX: &[u8] = b"state_root";
+const JMT_TRACKED_CFS: [&str; 8] =
+    ["site", "sitekv", "domain", "page", "receipt", "event", "epoch", "site_storage"];
 
 //key format: key_hash (32 bytes) + version (8 bytes BE)
 fn jmt_value_key(key_hash: KeyHash, version: Version) -> Vec<u8> {
     [key_hash.0.as_slice(), &version.to_be_bytes()].concat()
 }
 
+//key format: prefix + version (8 bytes BE)
+fn state_root_key(version: Version) -> Vec<u8> {
+    [META_STATE_ROOT_PREFIX, &version.to_be_bytes()].concat()
+}
+
 impl TreeReader for Db {
     fn get_node_option(&self, node_key: &NodeKey) -> anyhow::Result<Option<Node>> {
         match self.get(cf::JMT_NODES, borsh::to_vec(node_key)?) {
@@ -68,6 +75,12 @@ impl BatchDb {
         self.persist_jmt_values(node_batch);
         self.persist_stale_node_keys(stale_nodes, version);
         self.put(cf::META, META_JMT_ROOT, root.to_bytes().into());
+        self.put(cf::META, state_root_key(version), root.to_bytes().into());
+    }
+
+    pub fn read_state_root_at(&self, version: u64) -> Option<Sha256Digest> {
+        let bytes = self.get(cf::META, state_root_key(version))?;
+        Some(Sha256Digest::from(<[u8; 32]>::try_from(bytes.as_slice()).ok()?))
     }
 
     fn persist_jmt_nodes(&self, node_batch: &NodeBatch) {
@@ -105,6 +118,7 @@ impl BatchDb {
         if expired == 0 {
             return;
         }
+        self.delete(cf::META, state_root_key(expired));
         let key = expired.to_be_bytes();
         if let Some(data) = self.get(cf::JMT_STALE, key) {
             if let Ok(node_keys) = borsh::from_slice::<Vec<Vec<u8>>>(&data) {
@@ -122,6 +136,11 @@ impl Db {
         let bytes = self.get(cf::META, META_JMT_ROOT)?;
         Some(Sha256Digest::from(<[u8; 32]>::try_from(bytes.as_slice()).ok()?))
     }
+
+    pub fn read_state_root_at(&self, version: u64) -> Option<Sha256Digest> {
+        let bytes = self.get(cf::META, state_root_key(version))?;
+        Some(Sha256Digest::from(<[u8; 32]>::try_from(bytes.as_slice()).ok()?))
+    }
 }
 
 #[cfg(not(madsim))]
@@ -132,34 +151,35 @@ impl Db {
         key: &[u8],
         state_height: u64,
     ) -> Option<StateProof> {
-        //state proof is delayed 1 block
-        let block_height = state_height.checked_add(1)?;
-        let jmt_version = state_height;
-
-        let jmt_key = JmtKeyInput { cf_namespace: cf_to_namespace_byte(cf), key };
-        let key_hash = KeyHash::with::<Sha256>(&borsh::to_vec(&jmt_key).unwrap());
-        let jmt = Sha256Jmt::new(self);
-        let (_stored_value, proof) = jmt.get_with_proof(key_hash, jmt_version).ok()?;
+        //state proof is delayed STATE_ROOT_DELAY blocks
+        let block_height = state_height.checked_add(STATE_ROOT_DELAY)?;
+        let proof = self.generate_key_proof(cf, key, state_height)?;
 
         let finalized = self.read_block(block_height)?;
-        let state_root = finalized.block.previous_block_state_root;
-
-        let block_header = BlockHeader {
-            height: block_height,
-            previous_block_hash: finalized.block.previous_block_hash,
-            timestamp: finalized.block.timestamp,
-            previous_block_state_root: state_root,
-            transactions_hash: sha256_hash(&borsh::to_vec(&finalized.block.transactions).unwrap()),
-        };
+        let block_header = finalized.block.header();
 
         let proof = StateProof {
             proof,
             block_header,
             round: finalized.round,
-            finalization_votes: finalized.votes.into_iter().collect(),
+            finalization_votes: finalized.votes,
         };
         return Some(proof);
     }
+
+    /// Merkle proof of a key at a jmt version, without the block header and votes proving the root
+    pub fn generate_key_proof(
+        &self,
+        cf: &str,
+        key: &[u8],
+        jmt_version: u64,
+    ) -> Option<SparseMerkleProof<Sha256>> {
+        let jmt_key = JmtKeyInput { cf_namespace: cf_to_namespace_byte(cf), key };
+        let key_hash = KeyHash::with::<Sha256>(&borsh::to_vec(&jmt_key).unwrap());
+        let jmt = Sha256Jmt::new(self);
+        let (_stored_value, proof) = jmt.get_with_proof(key_hash, jmt_version).ok()?;
+        return Some(proof);
+    }
 }
 
 #[cfg(madsim)]
@@ -172,13 +192,23 @@ impl Db {
     ) -> Option<StateProof> {
         None
     }
+
+    pub fn generate_key_proof(
+        &self,
+        _cf: &str,
+        _key: &[u8],
+        _jmt_version: u64,
+    ) -> Option<SparseMerkleProof<Sha256>> {
+        None
+    }
 }
 
 use crate::db::{BatchDb, Db, PendingOp, cf};
+use jmt::proof::SparseMerkleProof;
 use jmt::storage::{LeafNode, Node, NodeBatch, NodeKey, TreeReader};
 use jmt::{KeyHash, OwnedValue, Sha256Jmt, Version};
 use sha2::{Digest, Sha256};
-use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
-use vastrum_shared_types::types::consensus::BlockHeader;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::limits::STATE_ROOT_DELAY;
 use vastrum_shared_types::types::rpc::types::StateProof;
 use vastrum_shared_types::types::storage::{JmtKeyInput, cf_to_namespace_byte};
@@ -5,8 +5,13 @@ pub mod cf {
     pub const DOMAIN: &str = "domain";
     pub const MODULE: &str = "module";
     pub const SITE_KV: &str = "sitekv";
+    pub const SITE_STORAGE: &str = "site_storage";
+    pub const SITE_KV_INDEX: &str = "sitekv_index";
     pub const BLOCKCHAIN: &str = "blockchain";
-    pub const INCLUDED_TXS: &str = "included_txs";
+    pub const RECEIPT: &str = "receipt";
+    pub const EVENT: &str = "event";
+    pub const EXPIRED_TXS: &str = "expired_txs";
+    pub const EXPIRED_TXS_PRUNE_INDEX: &str = "expired_txs_index";
     pub const PAGE: &str = "page";
     pub const META: &str = "meta";
     pub const VOTE_STATE: &str = "vote_state";
@@ -15,6 +20,13 @@ pub mod cf {
     pub const JMT_STALE: &str = "jmt_stale";
     pub const KV_HISTORY: &str = "kv_history";
     pub const KV_HISTORY_PRUNE_INDEX: &str = "kv_history_index";
+    pub const EPOCH: &str = "epoch";
+    pub const EPOCH_HANDOFF: &str = "epoch_handoff";
+    pub const EVIDENCE: &str = "evidence";
+
+    //column families written by older node versions, dropped on open
+    //included_txs held a placeholder per tx and was replaced by receipt, its values carry no receipt to migrate
+    pub const LEGACY: &[&str] = &["included_txs"];
 }
 
 pub struct Db {
@@ -69,13 +81,18 @@ impl Db {
         db_opts.set_enable_pipelined_write(true);
         db_opts.set_use_fsync(false);
 
-        let cfs = vec![
+        let mut cfs = vec![
             ColumnFamilyDescriptor::new(cf::SITE, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::DOMAIN, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::MODULE, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::SITE_KV, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::SITE_STORAGE, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::SITE_KV_INDEX, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::BLOCKCHAIN, cf_opts.clone()),
-            ColumnFamilyDescriptor::new(cf::INCLUDED_TXS, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::RECEIPT, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EVENT, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EXPIRED_TXS, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EXPIRED_TXS_PRUNE_INDEX, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::PAGE, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::META, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::VOTE_STATE, cf_opts.clone()),
@@ -83,13 +100,32 @@ impl Db {
             ColumnFamilyDescriptor::new(cf::JMT_VALUES, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::JMT_STALE, cf_opts.clone()),
             ColumnFamilyDescriptor::new(cf::KV_HISTORY, cf_opts.clone()),
-            ColumnFamilyDescriptor::new(cf::KV_HISTORY_PRUNE_INDEX, cf_opts),
+            ColumnFamilyDescriptor::new(cf::KV_HISTORY_PRUNE_INDEX, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EPOCH, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EPOCH_HANDOFF, cf_opts.clone()),
+            ColumnFamilyDescriptor::new(cf::EVIDENCE, cf_opts.clone()),
         ];
 
-        Db {
-            rocks: Arc::new(DB::open_cf_descriptors(&db_opts, &path, cfs).unwrap()),
-            data_path: path,
+        //rocksdb refuses to open without every existing column family, so legacy ones are opened then dropped
+        let existing_cfs = DB::list_cf(&db_opts, &path).unwrap_or_default();
+        let legacy_cfs: Vec<&str> = cf::LEGACY
+            .iter()
+            .copied()
+            .filter(|name| existing_cfs.iter().any(|cf| cf == name))
+            .collect();
+        for name in &legacy_cfs {
+            cfs.push(ColumnFamilyDescriptor::new(*name, cf_opts.clone()));
+        }
+
+        let mut rocks = DB::open_cf_descriptors(&db_opts, &path, cfs).unwrap();
+        for name in legacy_cfs {
+            tracing::info!("dropping legacy column family {name}");
+            rocks.drop_cf(name).unwrap();
         }
+
+        let db = Db { rocks: Arc::new(rocks), data_path: path };
+        db.check_schema_version();
+        return db;
     }
 
     pub fn open_fresh(path: impl Into<PathBuf>) -> Db {
@@ -140,6 +176,37 @@ impl Db {
         }
     }
 
+    /// Up to limit entries starting with prefix, walking from key in either direction
+    pub fn scan_prefix(
+        &self,
+        cf: &str,
+        prefix: &[u8],
+        from: &[u8],
+        reverse: bool,
+        limit: usize,
+    ) -> Vec<DbEntry> {
+        let Some(cf_handle) = self.rocks.cf_handle(cf) else {
+            return vec![];
+        };
+        let direction =
+            if reverse { rocksdb::Direction::Reverse } else { rocksdb::Direction::Forward };
+        let iter = self.rocks.iterator_cf(&cf_handle, rocksdb::IteratorMode::From(from, direction));
+        let mut entries = vec![];
+        for item in iter {
+            if entries.len() >= limit {
+                break;
+            }
+            let Ok((key, value)) = item else {
+                break;
+            };
+            if !key.starts_with(prefix) {
+                break;
+            }
+            entries.push(DbEntry { key: key.to_vec(), value: value.to_vec() });
+        }
+        return entries;
+    }
+
     pub fn write_batch(&self, pending_writes: HashMap<CfKey, Vec<u8>>, deletes: &[CfKey]) {
         let mut wb = rocksdb::WriteBatch::default();
         for (CfKey { cf, key }, value) in pending_writes {
@@ -204,6 +271,29 @@ impl Db {
             .map(|(k, v)| DbEntry { key: k.key.clone(), value: v.clone() })
     }
 
+    pub fn scan_prefix(
+        &self,
+        cf: &str,
+        prefix: &[u8],
+        from: &[u8],
+        reverse: bool,
+        limit: usize,
+    ) -> Vec<DbEntry> {
+        let mem = self.mem.lock();
+        let mut entries: Vec<DbEntry> = mem
+            .iter()
+            .filter(|(k, _)| k.cf == cf && k.key.starts_with(prefix))
+            .filter(|(k, _)| is_scanned_from(&k.key, from, reverse))
+            .map(|(k, v)| DbEntry { key: k.key.clone(), value: v.clone() })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        if reverse {
+            entries.reverse();
+        }
+        entries.truncate(limit);
+        return entries;
+    }
+
     pub fn write_batch(&self, pending_writes: HashMap<CfKey, Vec<u8>>, deletes: &[CfKey]) {
         let mut mem = self.mem.lock();
         for (cf_key, value) in pending_writes {
@@ -221,6 +311,11 @@ impl Db {
     }
 }
 
+//whether a scan walking from `from` reaches key
+fn is_scanned_from(key: &[u8], from: &[u8], reverse: bool) -> bool {
+    if reverse { key <= from } else { key >= from }
+}
+
 pub struct DbEntry {
     pub key: Vec<u8>,
     pub value: Vec<u8>,
@@ -244,20 +339,23 @@ pub enum PendingOp {
 
 type PendingOps = BTreeMap<CfKey, PendingOp>;
 
-enum WriteMode {
-    Direct,
-    Revertable(PendingOps),
-}
-
 struct BatchState {
     pending: PendingOps,
-    write_mode: WriteMode,
+    //one layer per open revertable scope, nested scopes are pushed for cross site calls
+    revertable: Vec<PendingOps>,
 }
 
 impl BatchState {
     fn get_revertable(&self, cf: &str, key: &[u8]) -> Option<&PendingOp> {
-        let WriteMode::Revertable(rw) = &self.write_mode else { return None };
-        rw.get(&CfKey::new(cf, key))
+        let cf_key = CfKey::new(cf, key);
+        self.revertable.iter().rev().find_map(|layer| layer.get(&cf_key))
+    }
+
+    fn active_ops(&mut self) -> &mut PendingOps {
+        match self.revertable.last_mut() {
+            Some(layer) => layer,
+            None => &mut self.pending,
+        }
     }
 
     fn get_pending(&self, cf: &str, key: &[u8]) -> Option<&PendingOp> {
@@ -268,16 +366,26 @@ impl BatchState {
 pub struct BatchDb {
     db: Arc<Db>,
     state: Mutex<BatchState>,
+    //history tracked reads fall through to this finalized height instead of the latest state
+    read_height: Option<u64>,
 }
 
 impl BatchDb {
     pub fn new(db: Arc<Db>) -> Arc<Self> {
         Arc::new(BatchDb {
             db,
-            state: Mutex::new(BatchState {
-                pending: BTreeMap::new(),
-                write_mode: WriteMode::Direct,
-            }),
+            state: Mutex::new(BatchState { pending: BTreeMap::new(), revertable: Vec::new() }),
+            read_height: None,
+        })
+    }
+
+    /// Batch over the state at a past finalized height, used for simulating calls
+    /// Prefix scans still list the latest keys, scan_kv skips keys without a value at that height
+    pub fn new_at_height(db: Arc<Db>, height: u64) -> Arc<Self> {
+        Arc::new(BatchDb {
+            db,
+            state: Mutex::new(BatchState { pending: BTreeMap::new(), revertable: Vec::new() }),
+            read_height: Some(height),
         })
     }
 
@@ -302,34 +410,68 @@ impl BatchDb {
             };
         }
         drop(state);
+        if let Some(height) = self.read_height {
+            if HISTORY_TRACKED_CFS.contains(&cf) {
+                return self.db.read_at_height(cf, key, height);
+            }
+        }
         //then check if underlying rocksdb has key
         self.db.get(cf, key)
     }
 
-    pub fn put(&self, cf: &str, key: impl AsRef<[u8]>, value: Vec<u8>) {
-        let cf_key = CfKey::new(cf, key.as_ref());
-        let mut state = self.state.lock();
-        match &mut state.write_mode {
-            WriteMode::Revertable(revertable_ops) => {
-                revertable_ops.insert(cf_key, PendingOp::Write(value));
-            }
-            WriteMode::Direct => {
-                state.pending.insert(cf_key, PendingOp::Write(value));
+    /// Same as Db::scan_prefix, with the writes pending in this batch applied on top
+    pub fn scan_prefix(
+        &self,
+        cf: &str,
+        prefix: &[u8],
+        from: &[u8],
+        reverse: bool,
+        limit: usize,
+    ) -> Vec<DbEntry> {
+        let state = self.state.lock();
+        //later layers override earlier ones, same priority as get
+        let mut overlay = BTreeMap::new();
+        for layer in std::iter::once(&state.pending).chain(state.revertable.iter()) {
+            for (cf_key, op) in layer.range(CfKey::new(cf, prefix)..) {
+                if cf_key.cf != cf || !cf_key.key.starts_with(prefix) {
+                    break;
+                }
+                let value = match op {
+                    PendingOp::Write(v) => Some(v.clone()),
+                    PendingOp::Delete => None,
+                };
+                overlay.insert(cf_key.key.clone(), value);
             }
         }
+        drop(state);
+        overlay.retain(|key: &Vec<u8>, _| is_scanned_from(key, from, reverse));
+
+        //each pending delete can hide at most one stored entry, so this many always fill limit
+        let deletes = overlay.values().filter(|value| value.is_none()).count();
+        let stored = self.db.scan_prefix(cf, prefix, from, reverse, limit + deletes);
+        let mut merged: BTreeMap<Vec<u8>, Vec<u8>> =
+            stored.into_iter().map(|entry| (entry.key, entry.value)).collect();
+        for (key, value) in overlay {
+            match value {
+                Some(value) => merged.insert(key, value),
+                None => merged.remove(&key),
+            };
+        }
+        let entries = merged.into_iter().map(|(key, value)| DbEntry { key, value });
+        if reverse {
+            return entries.rev().take(limit).collect();
+        }
+        return entries.take(limit).collect();
+    }
+
+    pub fn put(&self, cf: &str, key: impl AsRef<[u8]>, value: Vec<u8>) {
+        let cf_key = CfKey::new(cf, key.as_ref());
+        self.state.lock().active_ops().insert(cf_key, PendingOp::Write(value));
     }
 
     pub fn delete(&self, cf: &str, key: impl AsRef<[u8]>) {
         let cf_key = CfKey::new(cf, key.as_ref());
-        let mut state = self.state.lock();
-        match &mut state.write_mode {
-            WriteMode::Revertable(revertable_ops) => {
-                revertable_ops.insert(cf_key, PendingOp::Delete);
-            }
-            WriteMode::Direct => {
-                state.pending.insert(cf_key, PendingOp::Delete);
-            }
-        }
+        self.state.lock().active_ops().insert(cf_key, PendingOp::Delete);
     }
 
     //Write this batch to disk atomically (no partial writes, crash safe)
@@ -351,24 +493,23 @@ impl BatchDb {
         self.db.write_batch(writes, &deletes);
     }
 
+    /// Open a revertable scope, scopes nest and are closed innermost first
     pub fn begin_revertable(&self) {
-        let mut state = self.state.lock();
-        let no_active_revertable_tx = matches!(state.write_mode, WriteMode::Direct);
-        debug_assert!(no_active_revertable_tx);
-        state.write_mode = WriteMode::Revertable(BTreeMap::new());
+        self.state.lock().revertable.push(BTreeMap::new());
     }
 
+    /// Close the innermost scope, keeping its writes in the enclosing scope
     pub fn commit_revertable(&self) {
         let mut state = self.state.lock();
-        let WriteMode::Revertable(rw) = std::mem::replace(&mut state.write_mode, WriteMode::Direct)
-        else {
+        let Some(layer) = state.revertable.pop() else {
             return;
         };
-        state.pending.extend(rw);
+        state.active_ops().extend(layer);
     }
 
+    /// Close the innermost scope, discarding its writes
     pub fn rollback_revertable(&self) {
-        self.state.lock().write_mode = WriteMode::Direct;
+        self.state.lock().revertable.pop();
     }
 
     pub fn inner_db(&self) -> &Db {
@@ -376,16 +517,24 @@ impl BatchDb {
     }
 }
 mod domain;
-mod included_txs;
+pub mod epoch;
+mod events;
+mod evidence;
+mod history;
 pub mod jmt;
 mod meta;
 mod module;
 mod pages;
+mod receipts;
 pub mod round_state;
+mod schema;
 mod site;
 mod site_kv;
+mod site_kv_index;
+mod storage_usage;
 pub mod vote_state;
 
+use history::HISTORY_TRACKED_CFS;
 use parking_lot::Mutex;
 use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
@@ -0,0 +1,69 @@
+use super::{BatchDb, Db, cf};
+use vastrum_shared_types::{
+    borsh::BorshExt, crypto::sha256::Sha256Digest, limits::VALIDITY_WINDOW,
+    types::execution::receipt::TxReceipt,
+};
+
+impl Db {
+    pub fn check_tx_inclusion_state(&self, tx_hash: Sha256Digest) -> bool {
+        let key = tx_hash.encode();
+        self.get(cf::RECEIPT, key).is_some()
+    }
+
+    pub fn read_tx_receipt(&self, tx_hash: Sha256Digest) -> Option<TxReceipt> {
+        let key = tx_hash.encode();
+        let value = self.get(cf::RECEIPT, key)?;
+        TxReceipt::decode(&value).ok()
+    }
+
+    /// Height at which tx expired from the local mempool without being included
+    pub fn read_tx_expired_height(&self, tx_hash: Sha256Digest) -> Option<u64> {
+        let key = tx_hash.encode();
+        let value = self.get(cf::EXPIRED_TXS, key)?;
+        u64::decode(&value).ok()
+    }
+
+    /// Records txs that expired at height and forgets those that expired VALIDITY_WINDOW earlier
+    pub fn set_txs_as_expired(&self, tx_hashes: &[Sha256Digest], height: u64) {
+        for tx_hash in tx_hashes {
+            self.put(cf::EXPIRED_TXS, tx_hash.encode(), height.encode());
+        }
+        if !tx_hashes.is_empty() {
+            self.put(cf::EXPIRED_TXS_PRUNE_INDEX, height.to_be_bytes(), tx_hashes.encode());
+        }
+        self.prune_expired_txs(height);
+    }
+
+    fn prune_expired_txs(&self, height: u64) {
+        let Some(pruned_height) = height.checked_sub(VALIDITY_WINDOW) else {
+            return;
+        };
+        let idx_key = pruned_height.to_be_bytes();
+        if let Some(data) = self.get(cf::EXPIRED_TXS_PRUNE_INDEX, idx_key) {
+            if let Ok(tx_hashes) = Vec::<Sha256Digest>::decode(&data) {
+                for tx_hash in tx_hashes {
+                    self.delete(cf::EXPIRED_TXS, tx_hash.encode());
+                }
+            }
+            self.delete(cf::EXPIRED_TXS_PRUNE_INDEX, idx_key);
+        }
+    }
+}
+
+impl BatchDb {
+    pub fn check_tx_inclusion_state(&self, tx_hash: Sha256Digest) -> bool {
+        let key = tx_hash.encode();
+        self.get(cf::RECEIPT, key).is_some()
+    }
+
+    pub fn read_tx_receipt(&self, tx_hash: Sha256Digest) -> Option<TxReceipt> {
+        let key = tx_hash.encode();
+        let value = self.get(cf::RECEIPT, key)?;
+        TxReceipt::decode(&value).ok()
+    }
+
+    pub fn write_tx_receipt(&self, tx_hash: Sha256Digest, receipt: &TxReceipt) {
+        let key = tx_hash.encode();
+        self.put(cf::RECEIPT, key, receipt.encode());
+    }
+}
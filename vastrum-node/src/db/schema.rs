@@ -0,0 +1,28 @@
+//databases written before this schema store blocks, transactions and certificates in older encodings
+//they can not be upgraded in place, the node data directory has to be removed and the chain resynced
+//bump on every change to the stored encodings, there are no migrations between versions
+const SCHEMA_VERSION: u64 = 1;
+
+const META_SCHEMA_VERSION: &[u8] = b"schema_version";
+
+impl Db {
+    /// Stamps a fresh database with the current schema and refuses one written by another schema
+    /// a database without finalized blocks has nothing stored in an old encoding
+    pub fn check_schema_version(&self) {
+        let is_fresh = self.read_latest_finalized_height() == 0;
+        let schema_version = self.get(cf::META, META_SCHEMA_VERSION);
+        let schema_version = schema_version.map(|bytes| u64::decode(&bytes).unwrap());
+        match schema_version {
+            Some(SCHEMA_VERSION) => {}
+            None if is_fresh => self.put(cf::META, META_SCHEMA_VERSION, SCHEMA_VERSION.encode()),
+            _ => panic!(
+                "database at {} has schema {schema_version:?}, this node needs {SCHEMA_VERSION}, \
+                 remove it to resync the chain",
+                self.data_path.display()
+            ),
+        }
+    }
+}
+
+use super::{Db, cf};
+use vastrum_shared_types::borsh::BorshExt;
@@ -7,7 +7,7 @@ impl Db {
         let key = site_id.encode();
         let res = self.get(cf::SITE, key);
         if let Some(res) = res {
-            return Some(SiteData::decode(&res).unwrap());
+            return Some(SiteData::decode_versioned(&res).expect("corrupt site data"));
         } else {
             return None;
         }
@@ -25,7 +25,7 @@ impl BatchDb {
         let key = site_id.encode();
         let res = self.get(cf::SITE, key);
         if let Some(res) = res {
-            return Some(SiteData::decode(&res).unwrap());
+            return Some(SiteData::decode_versioned(&res).expect("corrupt site data"));
         } else {
             return None;
         }
@@ -0,0 +1,82 @@
+//sitekv is keyed by key hash, so it can not be walked in key order
+//this index keeps site_id + plain key for every stored kv entry, updated next to sitekv like storage usage
+pub(super) fn index_key(site_id: Sha256Digest, key: &str) -> Vec<u8> {
+    return [site_id.to_bytes().as_slice(), key.as_bytes()].concat();
+}
+
+fn key_from_index_key(index_key: &[u8]) -> String {
+    return String::from_utf8_lossy(&index_key[32..]).into_owned();
+}
+
+//a start outside the prefix range is clamped to its edge
+//0xff never appears in utf8, so prefix + 0xff sorts after every key with that prefix
+fn scan_from(prefix: &[u8], start: Option<Vec<u8>>, reverse: bool) -> Vec<u8> {
+    let prefix_end = [prefix, &[0xff]].concat();
+    match (start, reverse) {
+        (Some(start), false) => return start.max(prefix.to_vec()),
+        (Some(start), true) => return start.min(prefix_end),
+        (None, false) => return prefix.to_vec(),
+        (None, true) => return prefix_end,
+    }
+}
+
+impl Db {
+    /// Keys of a site starting with prefix in byte order, start is inclusive
+    pub fn scan_kv_keys(
+        &self,
+        site_id: Sha256Digest,
+        prefix: &str,
+        start: Option<&str>,
+        limit: usize,
+        reverse: bool,
+    ) -> Vec<String> {
+        let prefix = index_key(site_id, prefix);
+        let from = scan_from(&prefix, start.map(|start| index_key(site_id, start)), reverse);
+        let entries = self.scan_prefix(cf::SITE_KV_INDEX, &prefix, &from, reverse, limit);
+        return entries.iter().map(|entry| key_from_index_key(&entry.key)).collect();
+    }
+
+    pub(super) fn track_kv_index_write(&self, site_id: Sha256Digest, key: &str, exists: bool) {
+        if exists {
+            self.put(cf::SITE_KV_INDEX, index_key(site_id, key), vec![]);
+        } else {
+            self.delete(cf::SITE_KV_INDEX, index_key(site_id, key));
+        }
+    }
+}
+
+impl BatchDb {
+    /// Entries of a site with keys starting with prefix in byte order, start is inclusive
+    /// At most limit index entries are examined, keys without a value are skipped
+    pub fn scan_kv(
+        &self,
+        site_id: Sha256Digest,
+        prefix: &str,
+        start: Option<&str>,
+        limit: usize,
+        reverse: bool,
+    ) -> Vec<(String, Vec<u8>)> {
+        let prefix = index_key(site_id, prefix);
+        let from = scan_from(&prefix, start.map(|start| index_key(site_id, start)), reverse);
+        let mut entries = vec![];
+        for entry in self.scan_prefix(cf::SITE_KV_INDEX, &prefix, &from, reverse, limit) {
+            let key = key_from_index_key(&entry.key);
+            if let Some(value) = self.read_kv(&key, site_id) {
+                entries.push((key, value));
+            }
+        }
+        return entries;
+    }
+
+    //goes through the same layers as the kv write itself, so rollbacks restore the index too
+    pub(super) fn track_kv_index_write(&self, site_id: Sha256Digest, key: &str, exists: bool) {
+        if exists {
+            self.put(cf::SITE_KV_INDEX, index_key(site_id, key), vec![]);
+        } else {
+            self.delete(cf::SITE_KV_INDEX, index_key(site_id, key));
+        }
+    }
+}
+
+use super::{BatchDb, Db, cf};
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
@@ -0,0 +1,84 @@
+//usage is the sum of encoded storage key + value bytes over every sitekv entry of a site,
+//plus the kv index key each entry keeps
+//kept as a running counter next to sitekv so quota checks never scan the site
+fn entry_size(keys_len: usize, value_len: Option<usize>) -> u64 {
+    match value_len {
+        Some(value_len) => (keys_len + value_len) as u64,
+        None => 0,
+    }
+}
+
+fn entry_keys_len(site_id: Sha256Digest, key: &str, storage_key: &[u8]) -> usize {
+    return storage_key.len() + index_key(site_id, key).len();
+}
+
+fn decode_usage(bytes: Option<Vec<u8>>) -> u64 {
+    return bytes.map(|bytes| u64::decode(&bytes).unwrap()).unwrap_or(0);
+}
+
+//shared by Db and BatchDb, None if the write leaves usage unchanged
+fn usage_after_write(
+    usage: u64,
+    keys_len: usize,
+    old_value_len: Option<usize>,
+    new_value_len: Option<usize>,
+) -> Option<u64> {
+    let old_size = entry_size(keys_len, old_value_len);
+    let new_size = entry_size(keys_len, new_value_len);
+    if old_size == new_size {
+        return None;
+    }
+    return Some((usage + new_size).saturating_sub(old_size));
+}
+
+impl Db {
+    pub fn read_storage_usage(&self, site_id: Sha256Digest) -> u64 {
+        return decode_usage(self.get(cf::SITE_STORAGE, site_id.encode()));
+    }
+
+    pub(super) fn track_storage_write(
+        &self,
+        site_id: Sha256Digest,
+        key: &str,
+        storage_key: &[u8],
+        new_value: Option<&[u8]>,
+    ) {
+        let keys_len = entry_keys_len(site_id, key, storage_key);
+        let old_value_len = self.get(cf::SITE_KV, storage_key).map(|v| v.len());
+        let usage = self.read_storage_usage(site_id);
+        if let Some(usage) =
+            usage_after_write(usage, keys_len, old_value_len, new_value.map(|v| v.len()))
+        {
+            self.put(cf::SITE_STORAGE, site_id.encode(), usage.encode());
+        }
+    }
+}
+
+impl BatchDb {
+    /// Bytes the site holds in sitekv, including writes pending in this batch
+    pub fn read_storage_usage(&self, site_id: Sha256Digest) -> u64 {
+        return decode_usage(self.get(cf::SITE_STORAGE, site_id.encode()));
+    }
+
+    //goes through the same layers as the kv write itself, so rollbacks restore the usage too
+    pub(super) fn track_storage_write(
+        &self,
+        site_id: Sha256Digest,
+        key: &str,
+        storage_key: &[u8],
+        new_value: Option<&[u8]>,
+    ) {
+        let keys_len = entry_keys_len(site_id, key, storage_key);
+        let old_value_len = self.get(cf::SITE_KV, storage_key).map(|v| v.len());
+        let usage = self.read_storage_usage(site_id);
+        if let Some(usage) =
+            usage_after_write(usage, keys_len, old_value_len, new_value.map(|v| v.len()))
+        {
+            self.put(cf::SITE_STORAGE, site_id.encode(), usage.encode());
+        }
+    }
+}
+
+use super::site_kv_index::index_key;
+use super::{BatchDb, Db, cf};
+use vastrum_shared_types::{borsh::BorshExt, crypto::sha256::Sha256Digest};
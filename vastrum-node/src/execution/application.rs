@@ -1,10 +1,14 @@
 impl Execution {
-    pub fn execute_call_tx(&self, calldata: Vec<u8>, module_cache: &HashMap<PathBuf, Module>) {
+    pub fn execute_call_tx(
+        &self,
+        calldata: Vec<u8>,
+        module_cache: &HashMap<PathBuf, Module>,
+    ) -> ExecutionOutcome {
         let Ok(site_call) = borsh::from_slice::<SiteCall>(&calldata) else {
             tracing::warn!("failed to decode SiteCall");
-            return;
+            return ExecutionOutcome::failed("failed to decode SiteCall");
         };
-        self.call_site(site_call.site_id, site_call.calldata, module_cache);
+        return self.call_site(site_call.site_id, site_call.calldata, module_cache);
     }
 
     fn call_site(
@@ -12,31 +16,34 @@ impl Execution {
         site_id: Sha256Digest,
         calldata: Vec<u8>,
         module_cache: &HashMap<PathBuf, Module>,
-    ) {
+    ) -> ExecutionOutcome {
         let Some(site_data) = self.db.read_site(site_id) else {
             tracing::warn!("site not found: {site_id:?}");
-            return;
+            return ExecutionOutcome::failed(format!("site not found: {site_id}"));
         };
         let module_file_path = self.db.calculate_module_file_path(site_data.module_id);
         let Some(module) = self.load_module(&module_file_path, module_cache) else {
-            return;
+            return ExecutionOutcome::failed("failed to load site module");
         };
         //incase tx fails revert state changes writen to db by this tx
         self.db.begin_revertable();
-        let result = self.vastrum_host.execute_call(
+        let execution = self.vastrum_host.execute_call(
             &module,
             calldata,
             site_id,
             self.message_sender,
-            self.block_timestamp,
+            self.tx_context,
+            self.fuel_limit,
             self.db.clone(),
         );
-        if let Err(e) = result {
+        let outcome = ExecutionOutcome::from_contract(execution);
+        if let Some(error) = &outcome.error {
             self.db.rollback_revertable();
-            tracing::warn!("execute_call failed: {e:?}");
+            tracing::warn!("execute_call failed: {error}");
         } else {
             self.db.commit_revertable();
         }
+        return outcome;
     }
 
     fn load_module(
@@ -60,10 +67,10 @@ impl Execution {
         }
     }
 
-    pub fn register_domain(&self, calldata: Vec<u8>) {
+    pub fn register_domain(&self, calldata: Vec<u8>) -> ExecutionOutcome {
         let Ok(domain_data) = DomainData::decode(&calldata) else {
             tracing::warn!("failed to decode DomainData");
-            return;
+            return ExecutionOutcome::failed("failed to decode DomainData");
         };
         // Reject domain names that look like a site_id
         //TODO: enable this to prevent registering site_ids as domains
@@ -71,72 +78,148 @@ impl Execution {
         //     tracing::warn!("domain name rejected: looks like a site_id");
         //     return;
         // }
-        println!("deploying domain {domain_data:#?}");
-        let current_domain = self.db.read_domain(&domain_data.domain_name);
-        println!("current domain {current_domain:#?}");
-        if current_domain.is_none() {
-            println!("writing domain data");
-            self.db.write_domain(domain_data);
+        if self.db.read_domain(&domain_data.domain_name).is_some() {
+            return ExecutionOutcome::failed("domain already registered");
         }
+        self.db.write_domain(domain_data);
+        return ExecutionOutcome::default();
     }
 
     /// Upload new wasm, create a site, and call its constructor
-    pub fn execute_deploy_new_module_tx(&self, calldata: Vec<u8>, tx_hash: Sha256Digest) {
+    pub fn execute_deploy_new_module_tx(
+        &self,
+        calldata: Vec<u8>,
+        tx_hash: Sha256Digest,
+    ) -> ExecutionOutcome {
         let Ok(deploy) = borsh::from_slice::<DeployNewModuleCall>(&calldata) else {
             tracing::warn!("failed to decode DeployNewModuleCall");
-            return;
+            return ExecutionOutcome::failed("failed to decode DeployNewModuleCall");
         };
         let Some(module_id) = self.add_new_module_to_wasm_store(&deploy.wasm_data) else {
-            return;
+            return ExecutionOutcome::failed("failed to compile wasm module");
         };
-        self.deploy_site(module_id, deploy.constructor_calldata, tx_hash);
+        return self.deploy_site(module_id, deploy.constructor_calldata, deploy.admin, tx_hash);
     }
 
     /// Store contract wasm bytecode, but dont deploy a site
-    pub fn execute_add_module_tx(&self, calldata: Vec<u8>) {
-        let _ = self.add_new_module_to_wasm_store(&calldata);
+    pub fn execute_add_module_tx(&self, calldata: Vec<u8>) -> ExecutionOutcome {
+        if self.add_new_module_to_wasm_store(&calldata).is_none() {
+            return ExecutionOutcome::failed("failed to compile wasm module");
+        }
+        return ExecutionOutcome::default();
     }
 
     /// Create a new site from an already stored wasm module and call its constructor
-    pub fn execute_deploy_stored_module_tx(&self, calldata: Vec<u8>, tx_hash: Sha256Digest) {
+    pub fn execute_deploy_stored_module_tx(
+        &self,
+        calldata: Vec<u8>,
+        tx_hash: Sha256Digest,
+    ) -> ExecutionOutcome {
         let Ok(deploy) = borsh::from_slice::<DeployStoredModuleCall>(&calldata) else {
             tracing::warn!("failed to decode DeployStoredModuleCall");
-            return;
+            return ExecutionOutcome::failed("failed to decode DeployStoredModuleCall");
+        };
+        return self.deploy_site(
+            deploy.module_id,
+            deploy.constructor_calldata,
+            deploy.admin,
+            tx_hash,
+        );
+    }
+
+    /// Point a site to an already stored module and run its migrate entry point, admin only
+    pub fn execute_upgrade_site_tx(&self, calldata: Vec<u8>) -> ExecutionOutcome {
+        let Ok(upgrade) = borsh::from_slice::<UpgradeSiteCall>(&calldata) else {
+            tracing::warn!("failed to decode UpgradeSiteCall");
+            return ExecutionOutcome::failed("failed to decode UpgradeSiteCall");
+        };
+        let Some(site_data) = self.db.read_site(upgrade.site_id) else {
+            return ExecutionOutcome::failed(format!("site not found: {}", upgrade.site_id));
+        };
+        let Some(admin) = site_data.admin else {
+            return ExecutionOutcome::failed("site has no admin and cannot be upgraded");
+        };
+        if admin != self.message_sender {
+            return ExecutionOutcome::failed("upgrade not signed by the site admin");
+        }
+        let module_file_path = self.db.calculate_module_file_path(upgrade.module_id);
+        if !module_file_path.exists() {
+            tracing::warn!("module file not found: {:?}", upgrade.module_id);
+            return ExecutionOutcome::failed(format!("module not found: {}", upgrade.module_id));
+        }
+        //incase migrate fails the site keeps its old module
+        self.db.begin_revertable();
+
+        self.db.write_site(SiteData { module_id: upgrade.module_id, ..site_data });
+
+        let execution = self.vastrum_host.execute_migrate(
+            &module_file_path,
+            upgrade.migrate_calldata.clone(),
+            upgrade.site_id,
+            self.message_sender,
+            self.tx_context,
+            self.fuel_limit,
+            self.db.clone(),
+        );
+        let mut outcome = match execution {
+            Some(execution) => ExecutionOutcome::from_contract(execution),
+            None if upgrade.migrate_calldata.is_empty() => ExecutionOutcome::default(),
+            None => ExecutionOutcome::failed("module has no migrate entry point"),
         };
-        self.deploy_site(deploy.module_id, deploy.constructor_calldata, tx_hash);
+        if let Some(error) = &outcome.error {
+            self.db.rollback_revertable();
+            tracing::warn!("upgrade_site failed: {error}");
+        } else {
+            self.db.commit_revertable();
+            //upgrade history, provable through the site's event chain
+            let upgraded = SiteUpgraded {
+                previous_module_id: site_data.module_id,
+                module_id: upgrade.module_id,
+            };
+            outcome.events.push(EmittedEvent {
+                site_id: upgrade.site_id,
+                topics: vec![site_upgraded_topic()],
+                data: upgraded.encode(),
+            });
+        }
+        return outcome;
     }
 
     fn deploy_site(
         &self,
         module_id: Sha256Digest,
         constructor_calldata: Vec<u8>,
+        admin: Option<ed25519::PublicKey>,
         site_id: Sha256Digest,
-    ) {
+    ) -> ExecutionOutcome {
         let module_file_path = self.db.calculate_module_file_path(module_id);
         if !module_file_path.exists() {
             tracing::warn!("module file not found: {module_id:?}");
-            return;
+            return ExecutionOutcome::failed(format!("module not found: {module_id}"));
         }
         //incase tx fails revert state changes writen to db by this tx
         self.db.begin_revertable();
 
-        let site_data = SiteData { site_id, module_id };
+        let site_data = SiteData { site_id, module_id, admin };
         self.db.write_site(site_data);
 
-        let result = self.vastrum_host.execute_construct(
+        let execution = self.vastrum_host.execute_construct(
             &module_file_path,
             constructor_calldata,
             site_id,
             self.message_sender,
-            self.block_timestamp,
+            self.tx_context,
+            self.fuel_limit,
             self.db.clone(),
         );
-        if let Err(e) = result {
+        let outcome = ExecutionOutcome::from_contract(execution);
+        if let Some(error) = &outcome.error {
             self.db.rollback_revertable();
-            tracing::warn!("execute_construct failed: {e:?}");
-            return;
+            tracing::warn!("execute_construct failed: {error}");
+        } else {
+            self.db.commit_revertable();
         }
-        self.db.commit_revertable();
+        return outcome;
     }
 
     fn add_new_module_to_wasm_store(&self, wasm_data: &[u8]) -> Option<Sha256Digest> {
@@ -165,16 +248,23 @@ impl Execution {
 
 use super::{
     execution::Execution,
-    types::{compiled_module::CompiledModule, sitedata::SiteData},
+    types::{compiled_module::CompiledModule, outcome::ExecutionOutcome, sitedata::SiteData},
+    wasmhost::hostbindings::EmittedEvent,
 };
 use std::{collections::HashMap, path::PathBuf};
 use vastrum_shared_types::{
     borsh::BorshExt,
-    crypto::sha256::{Sha256Digest, sha256_hash},
+    crypto::{
+        ed25519,
+        sha256::{Sha256Digest, sha256_hash},
+    },
     limits::MAX_WASM_MODULE_SIZE,
-    types::application::{
-        deploy_new_module::DeployNewModuleCall, deploy_stored_module::DeployStoredModuleCall,
-        domaindata::DomainData, sitecall::SiteCall,
+    types::{
+        application::{
+            deploy_new_module::DeployNewModuleCall, deploy_stored_module::DeployStoredModuleCall,
+            domaindata::DomainData, sitecall::SiteCall, upgrade_site::UpgradeSiteCall,
+        },
+        execution::event::{SiteUpgraded, site_upgraded_topic},
     },
 };
 use wasmtime::Module;
@@ -2,21 +2,32 @@ pub struct Execution {
     seen_pow_hash: HashSet<Sha256Digest>,
     seen_pow_hash_by_height: HashMap<u64, Vec<Sha256Digest>>,
     current_block_height: u64,
+    pow_difficulty: u8,
     pub vastrum_host: VastrumHost,
-    pub block_timestamp: u64,
+    pub tx_context: TxContext,
     pub message_sender: ed25519::PublicKey,
+    /// Fuel limit declared by the transaction being executed
+    pub fuel_limit: u64,
     pub db: Arc<BatchDb>,
+    pub chain_id: u64,
     state_tree: StateTree,
+    //events of the block being executed by site and topic, written once the block is done
+    #[cfg(not(madsim))]
+    block_events: BTreeMap<(Sha256Digest, Sha256Digest), Vec<ContractEvent>>,
 }
 impl Execution {
     #[cfg(not(madsim))]
     pub fn execute_block(&mut self, finalized: FinalizedBlock) {
         self.current_block_height = finalized.block.height;
-        self.block_timestamp = finalized.block.timestamp;
+        self.tx_context.block_height = finalized.block.height;
+        self.tx_context.block_timestamp = finalized.block.timestamp;
+        self.tx_context.previous_block_hash = finalized.block.previous_block_hash;
+        self.pow_difficulty = finalized.block.pow_difficulty;
 
         let txs = &finalized.block.transactions;
+        let block_hash = finalized.block.calculate_hash();
 
-        let all_signatures_valid = parallel_batch_verifier::verify_signatures(txs);
+        let all_signatures_valid = parallel_batch_verifier::verify_signatures(txs, self.chain_id);
         let all_pow_valid = self.verify_all_pow(txs);
         let all_gas_limits_valid = Self::verify_all_gas(txs);
         let all_txs_valid_in_block = all_signatures_valid && all_pow_valid && all_gas_limits_valid;
@@ -33,19 +44,32 @@ impl Execution {
                 self.mark_pow_as_spent(decoded_tx.pow_hash);
                 let Some(transaction_data) = decoded_tx.transaction_data else {
                     tracing::warn!("failed to decompress transaction calldata");
+                    let outcome = ExecutionOutcome::failed("failed to decode transaction data");
+                    let receipt = outcome.into_receipt(self.current_block_height);
+                    self.db.write_tx_receipt(decoded_tx.tx_hash, &receipt);
                     continue;
                 };
                 self.message_sender = decoded_tx.pub_key;
+                self.fuel_limit = decoded_tx.fuel_limit;
+                self.tx_context.tx_hash = decoded_tx.tx_hash;
+                self.tx_context.random_seed = derive_random_seed(
+                    block_hash,
+                    &finalized.block.grandparent_finalization,
+                    decoded_tx.tx_hash,
+                );
                 self.execute_transaction(transaction_data, decoded_tx.tx_hash, &module_cache);
             }
         }
+        self.write_block_events();
         self.prune_spent_pow_hashes();
+        self.advance_epoch(finalized.block.height);
         //comment out for benchmark
         indexer::index_finalized_block(&self.db, &finalized);
         self.db.write_block(finalized.clone());
         self.db.write_latest_height(finalized.block.height);
         self.db.write_keyvalue_history_to_db(finalized.block.height, KV_RETENTION_WINDOW);
         self.state_tree.write_state_updates_to_jmt_proof_db(&self.db, finalized.block.height);
+        self.db.persist_epoch_handoff(finalized.block.height);
         self.db.commit();
     }
     #[cfg(not(madsim))]
@@ -57,20 +81,50 @@ impl Execution {
     ) {
         let calldata = transaction_data.calldata;
 
+        let mut outcome = ExecutionOutcome::default();
         if transaction_data.transaction_type == TransactionType::Call {
-            self.execute_call_tx(calldata, module_cache);
+            outcome = self.execute_call_tx(calldata, module_cache);
         } else if transaction_data.transaction_type == TransactionType::DeployNewModule {
-            self.execute_deploy_new_module_tx(calldata, tx_hash);
+            outcome = self.execute_deploy_new_module_tx(calldata, tx_hash);
         } else if transaction_data.transaction_type == TransactionType::AddModule {
-            self.execute_add_module_tx(calldata);
+            outcome = self.execute_add_module_tx(calldata);
         } else if transaction_data.transaction_type == TransactionType::DeployStoredModule {
-            self.execute_deploy_stored_module_tx(calldata, tx_hash);
+            outcome = self.execute_deploy_stored_module_tx(calldata, tx_hash);
+        } else if transaction_data.transaction_type == TransactionType::UpgradeSite {
+            outcome = self.execute_upgrade_site_tx(calldata);
         } else if transaction_data.transaction_type == TransactionType::RegisterDomain {
-            self.register_domain(calldata);
+            outcome = self.register_domain(calldata);
+        } else if transaction_data.transaction_type == TransactionType::RegisterValidator {
+            outcome = self.register_validator(calldata);
+        } else if transaction_data.transaction_type == TransactionType::ExitValidator {
+            outcome = self.exit_validator();
+        } else if transaction_data.transaction_type == TransactionType::Evidence {
+            outcome = self.process_evidence(calldata);
         }
 
+        self.write_events(tx_hash, std::mem::take(&mut outcome.events));
         //comment out for benchmark
-        self.db.set_tx_as_included(tx_hash);
+        let receipt = outcome.into_receipt(self.current_block_height);
+        self.db.write_tx_receipt(tx_hash, &receipt);
+    }
+
+    #[cfg(not(madsim))]
+    fn write_events(&mut self, tx_hash: Sha256Digest, events: Vec<EmittedEvent>) {
+        //an event is stored under each of its topics
+        for EmittedEvent { site_id, topics, data } in events {
+            let unique_topics: BTreeSet<Sha256Digest> = topics.iter().copied().collect();
+            let event = ContractEvent { tx_hash, topics, data };
+            for topic in unique_topics {
+                self.block_events.entry((site_id, topic)).or_default().push(event.clone());
+            }
+        }
+    }
+
+    #[cfg(not(madsim))]
+    fn write_block_events(&mut self) {
+        for ((site_id, topic), events) in std::mem::take(&mut self.block_events) {
+            self.db.write_event_block(site_id, topic, self.current_block_height, events);
+        }
     }
 
     #[cfg(not(madsim))]
@@ -128,12 +182,11 @@ impl Execution {
                 return false;
             }
         }
-        return true;
+        return total_fuel_limit(transactions) <= MAX_BLOCK_FUEL;
     }
 
     fn verify_pow_threshold(&self, transaction: &Transaction) -> bool {
-        let pow_hash = transaction.calculate_pow_hash();
-        return pow_hash < self.pow_threshold();
+        return transaction.meets_pow_difficulty(self.pow_difficulty);
     }
 
     fn verify_pow_not_spent(&self, transaction: &Transaction) -> bool {
@@ -167,34 +220,55 @@ impl Execution {
         self.seen_pow_hash_by_height.retain(|&height, _| height >= expired_height);
     }
 
-    fn pow_threshold(&self) -> Sha256Digest {
-        return Sha256Digest::from([
-            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
-            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
-        ]);
-    }
-
     pub fn new(db: Arc<Db>) -> Execution {
         return Execution {
             seen_pow_hash: HashSet::new(),
             seen_pow_hash_by_height: HashMap::new(),
             current_block_height: 0,
+            pow_difficulty: 0,
             vastrum_host: VastrumHost::new(),
-            block_timestamp: 0,
+            tx_context: TxContext::default(),
             message_sender: ed25519::PublicKey::default(),
+            fuel_limit: 0,
             db: BatchDb::new(db),
+            chain_id: genesis_chain_id(),
             state_tree: StateTree::new(),
+            #[cfg(not(madsim))]
+            block_events: BTreeMap::new(),
         };
     }
 
     pub fn latest_state_root(&self) -> Sha256Digest {
         self.state_tree.latest_state_root()
     }
+
+    /// State root a block at height commits to, None if that height is not executed yet
+    pub fn state_root_for_block(&self, height: u64) -> Option<Sha256Digest> {
+        let version = height.saturating_sub(STATE_ROOT_DELAY);
+        //genesis state is empty
+        if version == 0 {
+            return Some(Sha256Digest::default());
+        }
+        return self.db.read_state_root_at(version);
+    }
+
+    pub fn current_block_height(&self) -> u64 {
+        self.current_block_height
+    }
+
+    /// Pow difficulty of the latest executed block
+    pub fn pow_difficulty(&self) -> u8 {
+        self.pow_difficulty
+    }
     #[cfg(madsim)]
     pub fn execute_block(&mut self, finalized: FinalizedBlock) {
         self.current_block_height = finalized.block.height;
-        self.block_timestamp = finalized.block.timestamp;
+        self.tx_context.block_height = finalized.block.height;
+        self.tx_context.block_timestamp = finalized.block.timestamp;
+        self.tx_context.previous_block_hash = finalized.block.previous_block_hash;
+        self.pow_difficulty = finalized.block.pow_difficulty;
         self.prune_spent_pow_hashes();
+        self.advance_epoch(finalized.block.height);
         indexer::index_finalized_block(&self.db, &finalized);
         self.db.write_block(finalized.clone());
         self.db.write_latest_height(finalized.block.height);
@@ -223,25 +297,39 @@ impl Execution {
             }
         }
 
+        let pow_difficulty =
+            db.read_block(latest_finalized_height).map(|f| f.block.pow_difficulty).unwrap_or(0);
+
         let state_tree = StateTree::restore(&db);
         Execution {
             seen_pow_hash,
             seen_pow_hash_by_height,
             current_block_height: latest_finalized_height,
+            pow_difficulty,
             vastrum_host: VastrumHost::new(),
-            block_timestamp: 0,
+            tx_context: TxContext::default(),
             message_sender: ed25519::PublicKey::default(),
+            fuel_limit: 0,
             state_tree,
             db: BatchDb::new(db),
+            chain_id: genesis_chain_id(),
+            #[cfg(not(madsim))]
+            block_events: BTreeMap::new(),
         }
     }
 }
 
+/// Sum of declared fuel limits, a block may not exceed MAX_BLOCK_FUEL
+pub fn total_fuel_limit(transactions: &[Transaction]) -> u64 {
+    return transactions.iter().map(|tx| tx.fuel_limit).fold(0, u64::saturating_add);
+}
+
 #[cfg(not(madsim))]
 struct DecodedTx {
     tx_hash: Sha256Digest,
     pow_hash: Sha256Digest,
     pub_key: ed25519::PublicKey,
+    fuel_limit: u64,
     transaction_data: Option<TransactionData>,
 }
 
@@ -262,6 +350,7 @@ fn decompress_and_decode_transactions(transactions: &[Transaction]) -> Vec<Decod
                 tx_hash: tx.calculate_txhash(),
                 pow_hash: tx.calculate_pow_hash(),
                 pub_key: tx.pub_key,
+                fuel_limit: tx.fuel_limit,
                 transaction_data,
             }
         })
@@ -272,11 +361,13 @@ use crate::block_indexer::indexer;
 use crate::{
     consensus::types::FinalizedBlock,
     db::{BatchDb, Db},
+    execution::types::tx_context::TxContext,
     execution::wasmhost::host::VastrumHost,
 };
 use vastrum_shared_types::{
     crypto::{ed25519, sha256::Sha256Digest},
-    limits::{KV_RETENTION_WINDOW, VALIDITY_WINDOW},
+    genesis::genesis_chain_id,
+    limits::{KV_RETENTION_WINDOW, MAX_BLOCK_FUEL, STATE_ROOT_DELAY, VALIDITY_WINDOW},
     types::execution::transaction::Transaction,
 };
 use std::{
@@ -286,6 +377,9 @@ use std::{
 #[cfg(not(madsim))]
 use {
     super::parallel_batch_verifier,
+    super::types::outcome::ExecutionOutcome,
+    super::types::tx_context::derive_random_seed,
+    super::wasmhost::hostbindings::EmittedEvent,
     rayon::prelude::*,
     vastrum_shared_types::{
         transactioning::compression::decompress_calldata,
@@ -293,7 +387,9 @@ use {
             sitecall::SiteCall,
             transactiondata::{TransactionData, TransactionType},
         },
+        types::execution::event::ContractEvent,
     },
+    std::collections::{BTreeMap, BTreeSet},
     std::path::PathBuf,
     wasmtime::Module,
 };
@@ -4,13 +4,34 @@ fn make_tx(seed: u64, nonce: u64, recent_block_height: u64) -> Transaction {
         transaction_type: TransactionType::DeployNewModule,
         calldata: vec![1, 2, 3],
     };
-    let compressed = compress_calldata(&td.encode());
+    return signed_tx(&private_key, &td, nonce, recent_block_height);
+}
+
+fn signed_tx(
+    private_key: &ed25519::PrivateKey,
+    tx_data: &TransactionData,
+    nonce: u64,
+    recent_block_height: u64,
+) -> Transaction {
+    let chain_id = genesis_chain_id();
+    let compressed = compress_calldata(&tx_data.encode());
+    let signing_data = TransactionSigningData {
+        version: TRANSACTION_VERSION,
+        chain_id,
+        calldata_hash: sha256::sha256_hash(&compressed),
+        nonce,
+        recent_block_height,
+        fuel_limit: 0,
+    };
     Transaction {
+        version: TRANSACTION_VERSION,
+        chain_id,
         pub_key: private_key.public_key(),
-        signature: private_key.sign_hash(sha256::sha256_hash(&compressed)),
+        signature: private_key.sign_hash(signing_data.calculate_hash()),
         calldata: compressed,
-        nonce: nonce,
-        recent_block_height: recent_block_height,
+        nonce,
+        recent_block_height,
+        fuel_limit: 0,
     }
 }
 
@@ -22,8 +43,10 @@ fn write_block_with_txs(db: &Arc<Db>, height: u64, txs: Vec<Transaction>) {
         previous_block_hash: Sha256Digest::from_u64(0),
         timestamp: 0,
         previous_block_state_root: Sha256Digest::default(),
+        pow_difficulty: 0,
+        grandparent_finalization: BlockFinalization::default(),
     };
-    batch.write_block(FinalizedBlock { block, votes: BTreeMap::new(), round: 0 });
+    batch.write_block(FinalizedBlock { block, votes: CertificateVotes::default(), round: 0 });
     batch.write_latest_height(height);
     batch.commit();
 }
@@ -69,8 +92,10 @@ fn test_restore_from_disk_prunes_outside_validity_window() {
             previous_block_hash: Sha256Digest::from_u64(0),
             timestamp: 0,
             previous_block_state_root: Sha256Digest::default(),
+            pow_difficulty: 0,
+            grandparent_finalization: BlockFinalization::default(),
         };
-        batch.write_block(FinalizedBlock { block, votes: BTreeMap::new(), round: 0 });
+        batch.write_block(FinalizedBlock { block, votes: CertificateVotes::default(), round: 0 });
         batch.commit();
     }
     {
@@ -82,8 +107,11 @@ fn test_restore_from_disk_prunes_outside_validity_window() {
                 previous_block_hash: Sha256Digest::from_u64(0),
                 timestamp: 0,
                 previous_block_state_root: Sha256Digest::default(),
+                pow_difficulty: 0,
+                grandparent_finalization: BlockFinalization::default(),
             };
-            batch.write_block(FinalizedBlock { block, votes: BTreeMap::new(), round: 0 });
+            let finalized = FinalizedBlock { block, votes: CertificateVotes::default(), round: 0 };
+            batch.write_block(finalized);
         }
         batch.commit();
     }
@@ -115,18 +143,11 @@ fn verify_tx() {
         transaction_type: TransactionType::DeployNewModule,
         calldata: module_data,
     };
-    let compressed = compress_calldata(&deploy_website_tx_data.encode());
-
-    let deploy_website_tx = Transaction {
-        pub_key: private_key.public_key(),
-        signature: private_key.sign_hash(sha256::sha256_hash(&compressed)),
-        calldata: compressed,
-        nonce: 0,
-        recent_block_height: 0,
-    };
+    let deploy_website_tx = signed_tx(&private_key, &deploy_website_tx_data, 0, 0);
 
     let execution = Execution::new(db);
 
+    assert!(deploy_website_tx.verify_signature());
     assert!(execution.verify_pow(&deploy_website_tx));
 }
 
@@ -147,19 +168,213 @@ fn verify_call_tx() {
         .to_vec(),
     };
 
-    let compressed = compress_calldata(&create_post_tx_data.encode());
-    let create_post_tx = Transaction {
-        pub_key: private_key.public_key(),
-        signature: private_key.sign_hash(sha256::sha256_hash(&compressed)),
-        calldata: compressed,
-        nonce: 2,
-        recent_block_height: 0,
-    };
+    let create_post_tx = signed_tx(&private_key, &create_post_tx_data, 2, 0);
 
     let execution = Execution::new(db);
+    assert!(create_post_tx.verify_signature());
     assert!(execution.verify_pow(&create_post_tx));
 }
 
+#[test]
+fn test_call_to_missing_site_reports_failure() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-missing-site-call")));
+    let execution = Execution::new(db);
+    let site_call = SiteCall { site_id: Sha256Digest::from_u64(0), calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    let receipt = outcome.into_receipt(5);
+    assert_eq!(receipt.height, 5);
+    let TxOutcome::Failure { message } = receipt.outcome else {
+        panic!("call to missing site should fail");
+    };
+    assert!(message.starts_with("site not found"));
+}
+
+#[test]
+fn test_infinite_loop_runs_out_of_fuel() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-out-of-fuel")));
+    let execution = Execution::new(db);
+    let wat = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "__alloc") (param i32) (result i32) i32.const 0)
+            (func (export "makecall") (param i32 i32) (loop $spin br $spin)))
+    "#;
+    let compiled = execution.vastrum_host.compile_module(wat.as_bytes()).unwrap();
+    let module = unsafe { Module::deserialize(execution.vastrum_host.engine(), compiled) }.unwrap();
+
+    let fuel_limit = 50_000;
+    let contract_execution = execution.vastrum_host.execute_call(
+        &module,
+        vec![],
+        Sha256Digest::from_u64(1),
+        ed25519::PublicKey::default(),
+        TxContext::default(),
+        fuel_limit,
+        execution.db.clone(),
+    );
+    let receipt = ExecutionOutcome::from_contract(contract_execution).into_receipt(1);
+    assert_eq!(receipt.fuel_used, fuel_limit);
+    let TxOutcome::Failure { message } = receipt.outcome else {
+        panic!("infinite loop should run out of fuel");
+    };
+    assert!(message.contains("fuel"), "unexpected failure message: {message}");
+}
+
+//writes "k" = [1] to its own storage, then calls into target if set
+fn site_wat(target: Option<[u8; 32]>, trap: bool) -> String {
+    let call = match target {
+        Some(site_id) => {
+            let escaped: String = site_id.iter().map(|b| format!("\\{b:02x}")).collect();
+            format!(
+                r#"(data (i32.const 0) "{escaped}\00\00\00\00")
+                (func $call
+                    (call $call_site (i32.const 0) (i32.const 36) (i32.const 128) (i32.const 132)))"#
+            )
+        }
+        None => "(func $call)".to_string(),
+    };
+    let end = if trap { "unreachable" } else { "" };
+    return format!(
+        r#"(module
+            (import "vastrum" "call_site" (func $call_site (param i32 i32 i32 i32)))
+            (import "vastrum" "kv_insert" (func $kv_insert (param i32 i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 64) "\01\00\00\00k\01\00\00\00\01")
+            {call}
+            (func (export "__alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "makecall") (param i32 i32)
+                (call $kv_insert (i32.const 64) (i32.const 10))
+                (call $call)
+                {end}))"#
+    );
+}
+
+fn deploy_wat_site(execution: &Execution, site_id: Sha256Digest, wat: &str) {
+    let module_id = sha256::sha256_hash(wat.as_bytes());
+    let data = execution.vastrum_host.compile_module(wat.as_bytes()).unwrap();
+    execution.db.write_module(CompiledModule { key: module_id, data });
+    execution.db.write_site(SiteData { site_id, module_id, admin: None });
+}
+
+#[test]
+fn test_failed_site_call_reverts_only_callee() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-site-call-revert")));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let caller = Sha256Digest::from([0xaa; 32]);
+    let callee = Sha256Digest::from([0xbb; 32]);
+    deploy_wat_site(&execution, caller, &site_wat(Some(callee.to_bytes()), false));
+    deploy_wat_site(&execution, callee, &site_wat(None, true));
+
+    let site_call = SiteCall { site_id: caller, calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    assert_eq!(outcome.error, None);
+    assert_eq!(outcome.usage.host_calls.call_site, 1);
+    assert_eq!(execution.db.read_kv("k", caller), Some(vec![1]));
+    assert_eq!(execution.db.read_kv("k", callee), None);
+}
+
+#[test]
+fn test_site_call_writes_revert_with_caller() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-site-call-nested")));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let caller = Sha256Digest::from([0xaa; 32]);
+    let callee = Sha256Digest::from([0xbb; 32]);
+    deploy_wat_site(&execution, caller, &site_wat(Some(callee.to_bytes()), true));
+    deploy_wat_site(&execution, callee, &site_wat(None, false));
+
+    let site_call = SiteCall { site_id: caller, calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    assert!(outcome.error.is_some());
+    assert_eq!(execution.db.read_kv("k", caller), None);
+    assert_eq!(execution.db.read_kv("k", callee), None);
+}
+
+#[test]
+fn test_reentrant_site_call_rejected() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-site-call-reentry")));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let site_id = Sha256Digest::from([0xaa; 32]);
+    deploy_wat_site(&execution, site_id, &site_wat(Some(site_id.to_bytes()), false));
+
+    let site_call = SiteCall { site_id, calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    //rejected call is reported to the caller, which carries on
+    assert_eq!(outcome.error, None);
+    assert_eq!(outcome.usage.host_calls.call_site, 1);
+    assert_eq!(execution.db.read_kv("k", site_id), Some(vec![1]));
+}
+
+//writes "k" = [1] to its own storage, then passes 42u32 to set_return_data or revert
+fn returning_wat(host_fn: &str) -> String {
+    return format!(
+        r#"(module
+            (import "vastrum" "kv_insert" (func $kv_insert (param i32 i32)))
+            (import "vastrum" "{host_fn}" (func $return (param i32 i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\2a\00\00\00")
+            (data (i32.const 64) "\01\00\00\00k\01\00\00\00\01")
+            (func (export "__alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "makecall") (param i32 i32)
+                (call $kv_insert (i32.const 64) (i32.const 10))
+                (call $return (i32.const 0) (i32.const 4))))"#
+    );
+}
+
+#[test]
+fn test_return_data_recorded_in_receipt() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-return-data")));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let site_id = Sha256Digest::from([0xaa; 32]);
+    deploy_wat_site(&execution, site_id, &returning_wat("set_return_data"));
+
+    let site_call = SiteCall { site_id, calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    let receipt = outcome.into_receipt(1);
+    assert!(receipt.is_success());
+    assert_eq!(u32::decode(&receipt.return_data).unwrap(), 42);
+    assert_eq!(execution.db.read_kv("k", site_id), Some(vec![1]));
+}
+
+#[test]
+fn test_revert_keeps_error_and_reverts_writes() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-revert")));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let site_id = Sha256Digest::from([0xaa; 32]);
+    deploy_wat_site(&execution, site_id, &returning_wat("revert"));
+
+    let site_call = SiteCall { site_id, calldata: vec![] };
+    let outcome = execution.execute_call_tx(site_call.encode(), &HashMap::new());
+    let receipt = outcome.into_receipt(1);
+    let TxOutcome::Failure { message } = &receipt.outcome else {
+        panic!("revert should fail the call");
+    };
+    assert_eq!(message, "method returned an error");
+    assert_eq!(u32::decode(&receipt.return_data).unwrap(), 42);
+    assert_eq!(execution.db.read_kv("k", site_id), None);
+}
+
+#[test]
+fn test_block_over_fuel_limit_rejected() {
+    let mut txs = Vec::new();
+    for seed in 0..=(MAX_BLOCK_FUEL / MAX_TX_FUEL_LIMIT) {
+        let mut tx = make_tx(seed, 0, 0);
+        tx.fuel_limit = MAX_TX_FUEL_LIMIT;
+        txs.push(tx);
+    }
+    assert!(!Execution::verify_all_gas(&txs));
+    txs.pop();
+    assert!(Execution::verify_all_gas(&txs));
+
+    let mut tx = make_tx(0, 0, 0);
+    tx.fuel_limit = MAX_TX_FUEL_LIMIT + 1;
+    assert!(!Execution::verify_all_gas(&[tx]));
+}
+
 #[test]
 fn test_pow_hash_rejected_after_spent() {
     let db = Arc::new(Db::open_fresh(
@@ -227,21 +442,605 @@ fn test_pow_replay_rejected_at_boundary() {
     );
 }
 
+#[test]
+fn test_pow_below_block_difficulty_rejected() {
+    let db = Arc::new(Db::open_fresh(
+        std::env::temp_dir().join("vastrum-test-pow-below-block-difficulty-rejected"),
+    ));
+    let mut execution = Execution::new(db);
+    execution.pow_difficulty = 8;
+
+    let private_key = ed25519::PrivateKey::from_seed(0xeee);
+    let unmined_nonce =
+        (0..).find(|&nonce| !make_tx(0xeee, nonce, 0).meets_pow_difficulty(8)).unwrap();
+    let mined_nonce = mine_pow_nonce(private_key.public_key(), 0, 0, 8).unwrap();
+
+    assert!(!execution.verify_pow(&make_tx(0xeee, unmined_nonce, 0)), "pow below difficulty");
+    assert!(execution.verify_pow(&make_tx(0xeee, mined_nonce, 0)), "mined pow should be accepted");
+}
+
+#[test]
+fn test_state_root_for_block_lags_execution() {
+    let db = Arc::new(Db::open_fresh(
+        std::env::temp_dir().join("vastrum-test-state-root-for-block-lags-execution"),
+    ));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db);
+    for height in 1..=3 {
+        let block = Block {
+            height,
+            transactions: vec![],
+            previous_block_hash: Sha256Digest::from_u64(0),
+            timestamp: 0,
+            previous_block_state_root: Sha256Digest::default(),
+            pow_difficulty: 0,
+            grandparent_finalization: BlockFinalization::default(),
+        };
+        let finalized = FinalizedBlock { block, votes: CertificateVotes::default(), round: 0 };
+        execution.execute_block(finalized);
+    }
+
+    let delayed_height = 3 + STATE_ROOT_DELAY;
+    assert_eq!(execution.state_root_for_block(STATE_ROOT_DELAY), Some(Sha256Digest::default()));
+    assert_eq!(execution.state_root_for_block(delayed_height), Some(execution.latest_state_root()));
+    assert_eq!(execution.state_root_for_block(delayed_height + 1), None, "height 4 not executed");
+}
+
+fn write_genesis_validator_set(db: &Db, seeds: &[u64]) {
+    let mut validators = Vec::new();
+    for seed in seeds {
+        let key = ed25519::PrivateKey::from_seed(*seed).public_key();
+        validators.push(PersistedValidator { pub_key: key, p2p_key: key, stake: 100 });
+    }
+    let validator_set = PersistedValidatorSet { validators };
+    db.write_epoch_validator_set(0, &validator_set);
+    db.write_pending_validator_set(&validator_set);
+}
+
+fn register_validator_calldata(
+    new_validator: &ed25519::PrivateKey,
+    approver_seeds: &[u64],
+    stake: u64,
+) -> Vec<u8> {
+    let approval = ValidatorRegistrationApproval {
+        validator_pub_key: new_validator.public_key(),
+        p2p_key: new_validator.public_key(),
+        stake,
+        epoch: 0,
+    };
+    let mut approvals = Vec::new();
+    for (validator_index, seed) in approver_seeds.iter().enumerate() {
+        let approver = ed25519::PrivateKey::from_seed(*seed);
+        approvals.push((validator_index as u64, approver.sign_hash(approval.calculate_hash())));
+    }
+    RegisterValidatorCall { p2p_key: new_validator.public_key(), stake, epoch: 0, approvals }
+        .encode()
+}
+
+#[test]
+fn test_register_validator_takes_effect_at_epoch_boundary() {
+    let db = Arc::new(Db::open_fresh(
+        std::env::temp_dir().join("vastrum-test-register-validator-epoch-boundary"),
+    ));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db);
+    let new_validator = ed25519::PrivateKey::from_seed(4);
+
+    execution.message_sender = new_validator.public_key();
+    execution.register_validator(register_validator_calldata(&new_validator, &[1, 2], 50));
+
+    let snapshot_height = EPOCH_LENGTH - 1 - STATE_ROOT_DELAY;
+    execution.advance_epoch(snapshot_height - 1);
+    assert!(execution.db.read_epoch_validator_set(1).is_none(), "epoch 1 written too early");
+
+    execution.advance_epoch(snapshot_height);
+    let epoch_1 = execution.db.read_epoch_validator_set(1).unwrap();
+    assert_eq!(epoch_1.validators.len(), 4);
+    assert_eq!(epoch_1.validators[3].pub_key, new_validator.public_key());
+    assert_eq!(execution.db.read_epoch_validator_set(0).unwrap().validators.len(), 3);
+}
+
+#[test]
+fn test_register_validator_without_quorum_rejected() {
+    let db = Arc::new(Db::open_fresh(
+        std::env::temp_dir().join("vastrum-test-register-validator-without-quorum"),
+    ));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db);
+    let new_validator = ed25519::PrivateKey::from_seed(4);
+
+    execution.message_sender = new_validator.public_key();
+    let outcome =
+        execution.register_validator(register_validator_calldata(&new_validator, &[1], 50));
+    assert_eq!(outcome.error.as_deref(), Some("validator registration lacks 2/3 approval"));
+
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3, "1/3 approval should not register validator");
+}
+
+#[test]
+fn test_register_validator_stake_capped() {
+    let db = Arc::new(Db::open_fresh(
+        std::env::temp_dir().join("vastrum-test-register-validator-stake-cap"),
+    ));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db);
+    let new_validator = ed25519::PrivateKey::from_seed(4);
+
+    execution.message_sender = new_validator.public_key();
+    let calldata = register_validator_calldata(&new_validator, &[1, 2, 3], 150);
+    assert!(execution.register_validator(calldata).error.is_some());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3, "a third of the next epoch's stake is over the cap");
+
+    let calldata = register_validator_calldata(&new_validator, &[1, 2, 3], 149);
+    assert!(execution.register_validator(calldata).error.is_none());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 4);
+}
+
+#[cfg(not(madsim))]
+fn execute_empty_blocks(execution: &mut Execution, heights: std::ops::RangeInclusive<u64>) {
+    for height in heights {
+        execute_finalized_block(execution, height, vec![]);
+    }
+}
+
+//finalized by validator seeds 1, 2 and 3
+#[cfg(not(madsim))]
+fn execute_finalized_block(execution: &mut Execution, height: u64, transactions: Vec<Transaction>) {
+    let block = Block {
+        height,
+        transactions,
+        previous_block_hash: Sha256Digest::from_u64(0),
+        timestamp: 0,
+        previous_block_state_root: execution.state_root_for_block(height).unwrap(),
+        pow_difficulty: 0,
+        grandparent_finalization: BlockFinalization::default(),
+    };
+    let round = 0;
+    let mut votes = BTreeMap::new();
+    for (validator_index, seed) in [1, 2, 3].into_iter().enumerate() {
+        let validator = ed25519::PrivateKey::from_seed(seed);
+        let finalize = VoteType::Finalize(block.calculate_hash());
+        let validator_index = validator_index as u64;
+        let vote = ValidatorVote::create_signed(
+            finalize,
+            height,
+            round,
+            validator_index,
+            execution.chain_id,
+            &validator,
+        );
+        votes.insert(validator_index, vote.signature);
+    }
+    let votes = CertificateVotes::from_votes(&votes);
+    execution.execute_block(FinalizedBlock { block, votes, round });
+}
+
+//state proofs are not generated under madsim
+#[cfg(not(madsim))]
+#[test]
+fn test_epoch_handoff_proves_next_validator_set() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-epoch-handoff")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db.clone());
+    let new_validator = ed25519::PrivateKey::from_seed(4);
+    execution.message_sender = new_validator.public_key();
+    execution.register_validator(register_validator_calldata(&new_validator, &[1, 2], 50));
+
+    execute_empty_blocks(&mut execution, 1..=EPOCH_LENGTH - 2);
+    assert!(db.read_epoch_validator_set(1).is_some(), "snapshot before the last block");
+    assert!(db.read_epoch_handoff(1).is_none(), "handoff written before the last block");
+
+    execute_empty_blocks(&mut execution, EPOCH_LENGTH - 1..=EPOCH_LENGTH - 1);
+    let handoff = db.read_epoch_handoff(1).unwrap();
+    assert_eq!(handoff.epoch, 1);
+    assert_eq!(handoff.state_proof.block_header.height, EPOCH_LENGTH - 1);
+    assert_eq!(handoff.validator_set, db.read_epoch_validator_set(1).unwrap());
+
+    let genesis_set = db.read_epoch_validator_set(0).unwrap();
+    let genesis = EpochValidators::from_validator_set(0, &genesis_set);
+    let mut known = KnownValidatorSets::new(genesis);
+    let mut forged = handoff.clone();
+    forged.validator_set.validators.pop();
+    assert!(known.add_handoff(&forged, execution.chain_id).is_err(), "set not in state");
+    assert!(known.add_handoff(&handoff, execution.chain_id + 1).is_err(), "other chain");
+
+    known.add_handoff(&handoff, execution.chain_id).unwrap();
+    let epoch_1 = known.get(1).unwrap();
+    assert_eq!(epoch_1.validators.len(), 4);
+    assert_eq!(epoch_1.validators[&3].pub_key, new_validator.public_key().to_bytes());
+    assert_eq!(epoch_1.total_stake, 350);
+}
+
+#[cfg(not(madsim))]
+#[test]
+fn test_tx_receipt_proof_verifies() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-receipt-proof")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db.clone());
+    let site_call = SiteCall { site_id: Sha256Digest::from_u64(0), calldata: vec![] };
+    let tx_data =
+        TransactionData { transaction_type: TransactionType::Call, calldata: site_call.encode() };
+    let tx = signed_tx(&ed25519::PrivateKey::from_seed(9), &tx_data, 0, 0);
+    let tx_hash = tx.calculate_txhash();
+
+    execute_finalized_block(&mut execution, 1, vec![tx]);
+    let payload = GetTxReceiptPayload { tx_hash };
+    let GetTxReceiptResult::Err(e) = get_tx_receipt(&db, payload.clone()) else {
+        panic!("receipt proven before its state root is committed");
+    };
+    assert_eq!(e, ProvedReadError::ProofUnavailable);
+
+    execute_empty_blocks(&mut execution, 2..=1 + STATE_ROOT_DELAY);
+    let GetTxReceiptResult::Ok(response) = get_tx_receipt(&db, payload) else {
+        panic!("receipt should be provable");
+    };
+    assert_eq!(response.receipt.height, 1);
+    assert!(matches!(response.receipt.outcome, TxOutcome::Failure { .. }));
+
+    let genesis = EpochValidators::from_validator_set(0, &db.read_epoch_validator_set(0).unwrap());
+    let chain_id = execution.chain_id;
+    let verify = |response: &GetTxReceiptResponse, tx_hash, chain_id| {
+        verify_receipt_proof(
+            response,
+            tx_hash,
+            chain_id,
+            &genesis.validators,
+            genesis.total_stake,
+            0,
+        )
+    };
+    verify(&response, tx_hash, chain_id).unwrap();
+    assert!(verify(&response, Sha256Digest::from_u64(1), chain_id).is_err(), "other tx");
+    assert!(verify(&response, tx_hash, chain_id + 1).is_err(), "other chain");
+    let mut tampered = response.clone();
+    tampered.receipt.fuel_used += 1;
+    assert!(verify(&tampered, tx_hash, chain_id).is_err(), "receipt not in state");
+}
+
+#[test]
+fn test_epoch_states_follow_current_height() {
+    let db = Db::open_fresh(std::env::temp_dir().join("vastrum-test-load-epoch-states"));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut epoch_states = BTreeMap::new();
+    assert!(load_epoch_states(&db, 1, &mut epoch_states));
+    assert!(!load_epoch_states(&db, 2, &mut epoch_states), "nothing new to load");
+
+    let new_validator_p2p_key = ed25519::PrivateKey::from_seed(4).public_key();
+    let mut epoch_1 = db.read_epoch_validator_set(0).unwrap();
+    epoch_1.validators.push(PersistedValidator {
+        pub_key: new_validator_p2p_key,
+        p2p_key: new_validator_p2p_key,
+        stake: 100,
+    });
+    db.write_epoch_validator_set(1, &epoch_1);
+    assert!(load_epoch_states(&db, EPOCH_LENGTH - 1, &mut epoch_states), "next epoch loaded");
+    assert_eq!(epoch_states.keys().copied().collect::<Vec<_>>(), vec![0, 1]);
+    assert!(validator_p2p_keys(&epoch_states).contains(&new_validator_p2p_key));
+
+    assert!(load_epoch_states(&db, EPOCH_LENGTH, &mut epoch_states), "previous epoch dropped");
+    assert_eq!(epoch_states.keys().copied().collect::<Vec<_>>(), vec![1]);
+    assert_eq!(epoch_states[&1].total_validator_stake, 400);
+}
+
+#[test]
+fn test_exit_validator_removed_from_pending_set() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-exit-validator")));
+    write_genesis_validator_set(&db, &[1, 2]);
+    let mut execution = Execution::new(db);
+
+    execution.message_sender = ed25519::PrivateKey::from_seed(1).public_key();
+    assert!(execution.exit_validator().error.is_none());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 1);
+    assert_eq!(pending.validators[0].pub_key, ed25519::PrivateKey::from_seed(2).public_key());
+
+    execution.message_sender = ed25519::PrivateKey::from_seed(2).public_key();
+    assert_eq!(execution.exit_validator().error.as_deref(), Some("last validator can not exit"));
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 1, "last validator should not be able to exit");
+}
+
+#[test]
+fn test_register_taken_domain_fails() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-taken-domain")));
+    let execution = Execution::new(db);
+    let domain_name = "example".to_string();
+
+    let first = DomainData { site_id: Sha256Digest::from_u64(1), domain_name: domain_name.clone() };
+    assert!(execution.register_domain(first.encode()).error.is_none());
+
+    let second =
+        DomainData { site_id: Sha256Digest::from_u64(2), domain_name: domain_name.clone() };
+    let outcome = execution.register_domain(second.encode());
+    assert_eq!(outcome.error.as_deref(), Some("domain already registered"));
+    assert_eq!(execution.db.read_domain(&domain_name).unwrap().site_id, Sha256Digest::from_u64(1));
+}
+
+#[test]
+fn test_double_vote_evidence_removes_offender() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-double-vote")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let mut execution = Execution::new(db);
+    let offender = ed25519::PrivateKey::from_seed(2);
+
+    let chain_id = execution.chain_id;
+
+    let finalize = VoteType::Finalize(sha256::sha256_hash(b"a"));
+    let first = ValidatorVote::create_signed(finalize, 1, 0, 1, chain_id, &offender);
+    let second = ValidatorVote::create_signed(VoteType::Skip, 1, 0, 1, chain_id, &offender);
+    let evidence = Evidence::DoubleVote(DoubleVoteEvidence { first, second });
+
+    execution.message_sender = ed25519::PrivateKey::from_seed(1).public_key();
+    assert!(execution.process_evidence(evidence.encode()).error.is_none());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 2);
+    assert!(pending.validators.iter().all(|v| v.pub_key != offender.public_key()));
+    assert!(execution.db.check_evidence_included(evidence.calculate_hash()));
+
+    let outcome = execution.process_evidence(evidence.encode());
+    assert_eq!(outcome.error.as_deref(), Some("evidence already included"));
+}
+
+#[test]
+fn test_non_conflicting_votes_rejected_as_evidence() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-no-double-vote")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let execution = Execution::new(db);
+    let validator = ed25519::PrivateKey::from_seed(2);
+
+    //justify then skip in the same round is allowed
+    let chain_id = execution.chain_id;
+    let justify = VoteType::Justify(sha256::sha256_hash(b"a"));
+    let first = ValidatorVote::create_signed(justify, 1, 0, 1, chain_id, &validator);
+    let second = ValidatorVote::create_signed(VoteType::Skip, 1, 0, 1, chain_id, &validator);
+    let evidence = Evidence::DoubleVote(DoubleVoteEvidence { first, second });
+
+    assert!(execution.process_evidence(evidence.encode()).error.is_some());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3);
+}
+
+#[test]
+fn test_evidence_signed_for_other_chain_rejected() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-other-chain-vote")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    let execution = Execution::new(db);
+    let offender = ed25519::PrivateKey::from_seed(2);
+    let other_chain_id = execution.chain_id + 1;
+
+    let finalize = VoteType::Finalize(sha256::sha256_hash(b"a"));
+    let first = ValidatorVote::create_signed(finalize, 1, 0, 1, other_chain_id, &offender);
+    let second = ValidatorVote::create_signed(VoteType::Skip, 1, 0, 1, other_chain_id, &offender);
+    let evidence = Evidence::DoubleVote(DoubleVoteEvidence { first, second });
+
+    assert!(execution.process_evidence(evidence.encode()).error.is_some());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3, "votes from another chain must not slash");
+}
+
+fn double_proposal(proposer: &ed25519::PrivateKey, height: u64, chain_id: u64) -> Evidence {
+    let propose = |previous_block_hash| {
+        let block = Block {
+            height,
+            transactions: vec![],
+            previous_block_hash,
+            timestamp: 0,
+            previous_block_state_root: Sha256Digest::default(),
+            pow_difficulty: 0,
+            grandparent_finalization: BlockFinalization::default(),
+        };
+        let proposal =
+            Proposal::create_signed(0, ProposalType::Proposal, block, chain_id, proposer);
+        return proposal.header();
+    };
+    let first = propose(Sha256Digest::from_u64(1));
+    let second = propose(Sha256Digest::from_u64(2));
+    let leader = proposer.public_key();
+    return Evidence::DoubleProposal(DoubleProposalEvidence { leader, first, second });
+}
+
+#[test]
+fn test_double_proposal_only_slashes_slot_leader() {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join("vastrum-test-double-proposal")));
+    write_genesis_validator_set(&db, &[1, 2, 3]);
+    write_block_with_txs(&db, 1, vec![]);
+    let execution = Execution::new(db.clone());
+    let chain_id = execution.chain_id;
+
+    //leader of height 2 is seeded by previous_block_hash of block 1
+    let epoch_state = EpochState::from_validator_set(&db.read_epoch_validator_set(0).unwrap());
+    let leader = slot_leader(&epoch_state, Sha256Digest::from_u64(0), 2, 0).unwrap().pub_key;
+    let seeds = [1, 2, 3].map(ed25519::PrivateKey::from_seed);
+    let leader_key = seeds.iter().find(|key| key.public_key() == leader).unwrap();
+    let non_leader_key = seeds.iter().find(|key| key.public_key() != leader).unwrap();
+
+    let evidence = double_proposal(non_leader_key, 2, chain_id);
+    assert!(execution.process_evidence(evidence.encode()).error.is_some());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3, "non leader can not propose");
+    assert!(!execution.db.check_evidence_included(evidence.calculate_hash()));
+
+    //without the block before the slot the leader is unknown
+    let evidence = double_proposal(leader_key, 3, chain_id);
+    assert!(execution.process_evidence(evidence.encode()).error.is_some());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 3);
+
+    let evidence = double_proposal(leader_key, 2, chain_id);
+    assert!(execution.process_evidence(evidence.encode()).error.is_none());
+    let pending = execution.db.read_pending_validator_set().unwrap();
+    assert_eq!(pending.validators.len(), 2);
+    assert!(pending.validators.iter().all(|v| v.pub_key != leader));
+    assert!(execution.db.check_evidence_included(evidence.calculate_hash()));
+}
+
+//writes "k" = [2] when migrated to, traps instead if trap is set
+fn migrate_wat(trap: bool) -> String {
+    let end = if trap { "unreachable" } else { "" };
+    return format!(
+        r#"(module
+            (import "vastrum" "kv_insert" (func $kv_insert (param i32 i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 64) "\01\00\00\00k\01\00\00\00\02")
+            (func (export "__alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "makecall") (param i32 i32))
+            (func (export "migrate") (param i32 i32)
+                (call $kv_insert (i32.const 64) (i32.const 10))
+                {end}))"#
+    );
+}
+
+fn store_wat_module(execution: &Execution, wat: &str) -> Sha256Digest {
+    let module_id = sha256::sha256_hash(wat.as_bytes());
+    let data = execution.vastrum_host.compile_module(wat.as_bytes()).unwrap();
+    execution.db.write_module(CompiledModule { key: module_id, data });
+    return module_id;
+}
+
+fn upgradeable_site(name: &str, admin: Option<ed25519::PublicKey>) -> (Execution, Sha256Digest) {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join(name)));
+    let mut execution = Execution::new(db);
+    execution.fuel_limit = DEFAULT_TX_FUEL_LIMIT;
+    let site_id = Sha256Digest::from([0xaa; 32]);
+    let module_id = store_wat_module(&execution, &site_wat(None, false));
+    execution.db.write_site(SiteData { site_id, module_id, admin });
+    return (execution, site_id);
+}
+
+#[test]
+fn test_upgrade_site_runs_migrate() {
+    let admin = ed25519::PrivateKey::from_seed(1).public_key();
+    let (mut execution, site_id) = upgradeable_site("vastrum-test-upgrade-site", Some(admin));
+    let old_module_id = execution.db.read_site(site_id).unwrap().module_id;
+    let module_id = store_wat_module(&execution, &migrate_wat(false));
+
+    execution.message_sender = admin;
+    let upgrade = UpgradeSiteCall { site_id, module_id, migrate_calldata: vec![] };
+    let outcome = execution.execute_upgrade_site_tx(upgrade.encode());
+    assert_eq!(outcome.error, None);
+    assert_eq!(execution.db.read_site(site_id).unwrap().module_id, module_id);
+    assert_eq!(execution.db.read_kv("k", site_id), Some(vec![2]));
+
+    let [event] = outcome.events.as_slice() else { panic!("expected one upgrade event") };
+    assert_eq!(event.site_id, site_id);
+    assert_eq!(event.topics, vec![site_upgraded_topic()]);
+    let upgraded = SiteUpgraded::decode(&event.data).unwrap();
+    assert_eq!(upgraded, SiteUpgraded { previous_module_id: old_module_id, module_id });
+}
+
+#[test]
+fn test_upgrade_site_requires_admin() {
+    let admin = ed25519::PrivateKey::from_seed(1).public_key();
+    let (mut execution, site_id) = upgradeable_site("vastrum-test-upgrade-not-admin", Some(admin));
+    let old_module_id = execution.db.read_site(site_id).unwrap().module_id;
+    let module_id = store_wat_module(&execution, &migrate_wat(false));
+
+    execution.message_sender = ed25519::PrivateKey::from_seed(2).public_key();
+    let upgrade = UpgradeSiteCall { site_id, module_id, migrate_calldata: vec![] };
+    let outcome = execution.execute_upgrade_site_tx(upgrade.encode());
+    assert!(outcome.error.is_some());
+    assert_eq!(execution.db.read_site(site_id).unwrap().module_id, old_module_id);
+
+    let (mut execution, site_id) = upgradeable_site("vastrum-test-upgrade-immutable", None);
+    let module_id = store_wat_module(&execution, &migrate_wat(false));
+    execution.message_sender = admin;
+    let upgrade = UpgradeSiteCall { site_id, module_id, migrate_calldata: vec![] };
+    let outcome = execution.execute_upgrade_site_tx(upgrade.encode());
+    assert!(outcome.error.is_some(), "sites deployed without admin are immutable");
+}
+
+#[test]
+fn test_failed_migrate_keeps_old_module() {
+    let admin = ed25519::PrivateKey::from_seed(1).public_key();
+    let (mut execution, site_id) = upgradeable_site("vastrum-test-upgrade-trap", Some(admin));
+    let old_module_id = execution.db.read_site(site_id).unwrap().module_id;
+    let module_id = store_wat_module(&execution, &migrate_wat(true));
+
+    execution.message_sender = admin;
+    let upgrade = UpgradeSiteCall { site_id, module_id, migrate_calldata: vec![] };
+    let outcome = execution.execute_upgrade_site_tx(upgrade.encode());
+    assert!(outcome.error.is_some());
+    assert_eq!(execution.db.read_site(site_id).unwrap().module_id, old_module_id);
+    assert_eq!(execution.db.read_kv("k", site_id), None);
+}
+
+#[test]
+fn test_random_seed_mixes_grandparent_votes() {
+    let block_hash = Sha256Digest::from_u64(1);
+    let tx_hash = Sha256Digest::from_u64(2);
+    let finalization = |signers: &[u64], round: u64| {
+        let vote_hash = sha256::sha256_hash(b"finalize");
+        let votes: BTreeMap<u64, ed25519::Signature> = signers
+            .iter()
+            .map(|&index| (index, ed25519::PrivateKey::from_seed(index).sign_hash(vote_hash)))
+            .collect();
+        BlockFinalization { round, votes: CertificateVotes::from_votes(&votes) }
+    };
+    let grandparent = finalization(&[0, 1, 2], 0);
+    let seed = derive_random_seed(block_hash, &grandparent, tx_hash);
+    assert_eq!(seed, derive_random_seed(block_hash, &finalization(&[0, 1, 2], 0), tx_hash));
+
+    assert_ne!(seed, derive_random_seed(block_hash, &finalization(&[0, 1, 3], 0), tx_hash));
+    assert_ne!(seed, derive_random_seed(block_hash, &finalization(&[0, 1, 2], 1), tx_hash));
+    assert_ne!(seed, derive_random_seed(block_hash, &BlockFinalization::default(), tx_hash));
+    assert_ne!(seed, derive_random_seed(block_hash, &grandparent, Sha256Digest::from_u64(3)));
+    assert_ne!(seed, derive_random_seed(Sha256Digest::from_u64(3), &grandparent, tx_hash));
+}
+
 use crate::{
-    consensus::types::{Block, FinalizedBlock},
-    db::{BatchDb, Db},
-    execution::execution::Execution,
+    consensus::{
+        evidence::{DoubleProposalEvidence, DoubleVoteEvidence, Evidence},
+        types::{Block, FinalizedBlock, Proposal, ProposalType, ValidatorVote, VoteType},
+        validator_state_machine::{EpochState, load_epoch_states, slot_leader, validator_p2p_keys},
+    },
+    db::{
+        BatchDb, Db,
+        epoch::{PersistedValidator, PersistedValidatorSet},
+    },
+    execution::{
+        execution::Execution,
+        types::{
+            compiled_module::CompiledModule,
+            outcome::ExecutionOutcome,
+            sitedata::SiteData,
+            tx_context::{TxContext, derive_random_seed},
+        },
+    },
+    rpc::handlers::get_tx_receipt,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
 };
 use vastrum_shared_types::{
     borsh::BorshExt,
     crypto::{ed25519, sha256, sha256::Sha256Digest},
-    transactioning::compression::compress_calldata,
+    genesis::genesis_chain_id,
+    limits::{
+        DEFAULT_TX_FUEL_LIMIT, EPOCH_LENGTH, MAX_BLOCK_FUEL, MAX_TX_FUEL_LIMIT, STATE_ROOT_DELAY,
+    },
+    proof_verification::{EpochValidators, KnownValidatorSets, verify_receipt_proof},
+    transactioning::{compression::compress_calldata, pow::mine_pow_nonce},
     types::{
         application::{
+            domaindata::DomainData,
             sitecall::SiteCall,
             transactiondata::{TransactionData, TransactionType},
+            upgrade_site::UpgradeSiteCall,
+            validator_registration::{RegisterValidatorCall, ValidatorRegistrationApproval},
+        },
+        consensus::{BlockFinalization, CertificateVotes},
+        execution::{
+            event::{SiteUpgraded, site_upgraded_topic},
+            receipt::TxOutcome,
+            transaction::{TRANSACTION_VERSION, Transaction, TransactionSigningData},
+        },
+        rpc::types::{
+            GetTxReceiptPayload, GetTxReceiptResponse, GetTxReceiptResult, ProvedReadError,
         },
-        execution::transaction::Transaction,
     },
 };
-use std::{collections::BTreeMap, sync::Arc};
+use wasmtime::Module;
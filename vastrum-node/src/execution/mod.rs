@@ -1,7 +1,9 @@
 pub mod application;
 pub mod execution;
+pub mod simulation;
 #[cfg(not(madsim))]
 mod parallel_batch_verifier;
 mod state_tree;
 pub mod types;
+mod validator_registry;
 pub mod wasmhost;
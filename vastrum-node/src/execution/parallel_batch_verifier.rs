@@ -1,10 +1,18 @@
 use rayon::prelude::*;
-use vastrum_shared_types::{borsh::BorshExt, types::execution::transaction::Transaction};
+use vastrum_shared_types::{
+    borsh::BorshExt,
+    types::execution::transaction::{TRANSACTION_VERSION, Transaction},
+};
 
-pub fn verify_signatures(transactions: &[Transaction]) -> bool {
+pub fn verify_signatures(transactions: &[Transaction], chain_id: u64) -> bool {
     if transactions.is_empty() {
         return true;
     }
+    let all_for_this_chain =
+        transactions.iter().all(|tx| tx.version == TRANSACTION_VERSION && tx.chain_id == chain_id);
+    if !all_for_this_chain {
+        return false;
+    }
     let chunk_size = 16;
 
     let all_signatures_valid = transactions.par_chunks(chunk_size).all(|chunk| {
@@ -12,7 +20,7 @@ pub fn verify_signatures(transactions: &[Transaction]) -> bool {
         for transaction in chunk {
             let verification_key = transaction.pub_key.verifying_key();
             let signature = transaction.signature.inner();
-            let hash = transaction.calculate_calldata_hash().encode();
+            let hash = transaction.calculate_signing_hash().encode();
             verifier.queue((verification_key, signature, &hash));
         }
         verifier.verify(rand_core_06::OsRng).is_ok()
@@ -0,0 +1,120 @@
+/// Runs site calls against finalized state in a throwaway batch, for view calls and tx simulation
+pub struct Simulator {
+    db: Arc<Db>,
+    vastrum_host: VastrumHost,
+    permits: Arc<Semaphore>,
+}
+
+impl Simulator {
+    pub fn new(db: Arc<Db>) -> Simulator {
+        let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_SIMULATIONS));
+        return Simulator { db, vastrum_host: VastrumHost::new(), permits };
+    }
+
+    /// Runs the call on the blocking pool so rpc tasks are not stalled,
+    /// rejected as busy while MAX_CONCURRENT_SIMULATIONS calls are already running
+    pub async fn simulate_call_blocking(
+        self: Arc<Self>,
+        payload: SimulateCallPayload,
+    ) -> SimulateCallResult {
+        let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+            return SimulateCallResult::Err(SimulateCallError::Busy);
+        };
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            return self.simulate_call(payload);
+        });
+        return task.await.expect("simulation task panicked");
+    }
+
+    pub fn simulate_call(&self, payload: SimulateCallPayload) -> SimulateCallResult {
+        let current_height = self.db.read_latest_finalized_height();
+        let height = match payload.height_lock {
+            Some(h) if h <= current_height => h,
+            _ => current_height,
+        };
+        if height + KV_RETENTION_WINDOW < current_height {
+            return SimulateCallResult::Err(SimulateCallError::OutsideRetentionWindow);
+        }
+
+        //never committed, every write is dropped with the batch
+        let batch = BatchDb::new_at_height(self.db.clone(), height);
+        let Some(site_data) = batch.read_site(payload.site_id) else {
+            return SimulateCallResult::Err(SimulateCallError::SiteNotFound);
+        };
+        let module_file_path = batch.calculate_module_file_path(site_data.module_id);
+        let engine = self.vastrum_host.engine();
+        let Ok(module) = (unsafe { Module::deserialize_file(engine, &module_file_path) }) else {
+            return SimulateCallResult::Err(SimulateCallError::ModuleUnavailable);
+        };
+        //no tx hash before the call is sent, the real tx hash and seed will differ
+        let tx_context = match self.db.read_block(height) {
+            Some(finalized) => TxContext {
+                block_height: height,
+                block_timestamp: finalized.block.timestamp,
+                previous_block_hash: finalized.block.previous_block_hash,
+                tx_hash: Sha256Digest::default(),
+                random_seed: derive_random_seed(
+                    finalized.block.calculate_hash(),
+                    &finalized.block.grandparent_finalization,
+                    Sha256Digest::default(),
+                ),
+            },
+            None => TxContext { block_height: height, ..TxContext::default() },
+        };
+
+        let (execution, trace) = self.vastrum_host.simulate_call(
+            &module,
+            payload.calldata,
+            payload.site_id,
+            payload.message_sender.unwrap_or_default(),
+            tx_context,
+            SIMULATION_FUEL_LIMIT,
+            batch.clone(),
+        );
+        let reverted = match &execution.result {
+            Ok(()) => false,
+            Err(e) => e.downcast_ref::<ContractRevert>().is_some(),
+        };
+        let mut writes = vec![];
+        if execution.result.is_ok() {
+            for (storage_key, value) in batch.pending_kv_writes() {
+                let Ok(decoded) = SiteKvStorageKey::decode(&storage_key) else { continue };
+                let key = trace.kv_keys.get(&storage_key).cloned().unwrap_or_default();
+                writes.push(SimulatedWrite { site_id: decoded.site_id, key, value });
+            }
+        }
+
+        let receipt = ExecutionOutcome::from_contract(execution).into_receipt(height);
+        return SimulateCallResult::Ok(SimulateCallResponse {
+            height,
+            outcome: receipt.outcome,
+            reverted,
+            return_data: receipt.return_data,
+            fuel_used: receipt.fuel_used,
+            logs: trace.logs,
+            writes,
+        });
+    }
+}
+
+use super::types::tx_context::{TxContext, derive_random_seed};
+use super::{types::outcome::ExecutionOutcome, wasmhost::host::VastrumHost};
+use crate::db::{BatchDb, Db};
+use crate::utils::limits::{MAX_CONCURRENT_SIMULATIONS, SIMULATION_FUEL_LIMIT};
+use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::limits::KV_RETENTION_WINDOW;
+use vastrum_shared_types::types::rpc::types::{
+    SimulateCallError, SimulateCallPayload, SimulateCallResponse, SimulateCallResult,
+    SimulatedWrite,
+};
+use vastrum_shared_types::types::storage::SiteKvStorageKey;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use vastrum_bindings_host::ContractRevert;
+use wasmtime::Module;
+
+#[cfg(test)]
+#[path = "simulation_tests.rs"]
+mod tests;
@@ -0,0 +1,112 @@
+use super::*;
+
+//writes "k" = [1], logs "hi" and returns 42u32
+const VIEW_WAT: &str = r#"
+    (module
+        (import "vastrum" "kv_insert" (func $kv_insert (param i32 i32)))
+        (import "vastrum" "log" (func $log (param i32 i32)))
+        (import "vastrum" "set_return_data" (func $set_return_data (param i32 i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "\2a\00\00\00")
+        (data (i32.const 32) "\02\00\00\00hi")
+        (data (i32.const 64) "\01\00\00\00k\01\00\00\00\01")
+        (func (export "__alloc") (param i32) (result i32) i32.const 1024)
+        (func (export "makecall") (param i32 i32)
+            (call $kv_insert (i32.const 64) (i32.const 10))
+            (call $log (i32.const 32) (i32.const 6))
+            (call $set_return_data (i32.const 0) (i32.const 4))))
+"#;
+
+//same write, after marking the call as a view like #[view] methods do
+const WRITING_VIEW_WAT: &str = r#"
+    (module
+        (import "vastrum" "enter_view" (func $enter_view))
+        (import "vastrum" "kv_insert" (func $kv_insert (param i32 i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 64) "\01\00\00\00k\01\00\00\00\01")
+        (func (export "__alloc") (param i32) (result i32) i32.const 1024)
+        (func (export "makecall") (param i32 i32)
+            (call $enter_view)
+            (call $kv_insert (i32.const 64) (i32.const 10))))
+"#;
+
+fn deploy_view_site(name: &str, wat: &str) -> (Arc<Db>, Simulator, Sha256Digest) {
+    let db = Arc::new(Db::open_fresh(std::env::temp_dir().join(name)));
+    let simulator = Simulator::new(db.clone());
+    let site_id = Sha256Digest::from([0xaa; 32]);
+    let module_id = sha256_hash(wat.as_bytes());
+    let data = simulator.vastrum_host.compile_module(wat.as_bytes()).unwrap();
+    db.write_module(CompiledModule { key: module_id, data });
+    db.write_site(SiteData { site_id, module_id, admin: None });
+    return (db, simulator, site_id);
+}
+
+#[test]
+fn test_simulated_call_reports_result_without_persisting() {
+    let (db, simulator, site_id) = deploy_view_site("vastrum-test-simulate-call", VIEW_WAT);
+    let payload =
+        SimulateCallPayload { site_id, calldata: vec![], message_sender: None, height_lock: None };
+    let SimulateCallResult::Ok(response) = simulator.simulate_call(payload) else {
+        panic!("simulation should run");
+    };
+    assert_eq!(response.outcome, TxOutcome::Success);
+    assert!(!response.reverted);
+    assert_eq!(u32::decode(&response.return_data).unwrap(), 42);
+    assert_eq!(response.logs, vec!["hi".to_string()]);
+    assert_eq!(response.writes, vec![SimulatedWrite { site_id, key: "k".into(), value: vec![1] }]);
+    assert!(response.fuel_used > 0);
+    assert_eq!(db.read_kv("k", site_id), None);
+}
+
+#[test]
+fn test_view_cannot_change_state() {
+    let (_db, simulator, site_id) =
+        deploy_view_site("vastrum-test-simulate-view", WRITING_VIEW_WAT);
+    let payload =
+        SimulateCallPayload { site_id, calldata: vec![], message_sender: None, height_lock: None };
+    let SimulateCallResult::Ok(response) = simulator.simulate_call(payload) else {
+        panic!("simulation should run");
+    };
+    let TxOutcome::Failure { message } = response.outcome else {
+        panic!("write in a view should trap");
+    };
+    assert_eq!(message, "view methods cannot change state");
+    assert!(response.writes.is_empty());
+}
+
+#[test]
+fn test_simulation_outside_retention_window_rejected() {
+    let (db, simulator, site_id) = deploy_view_site("vastrum-test-simulate-retention", VIEW_WAT);
+    db.write_latest_height(KV_RETENTION_WINDOW + 10);
+    let payload = SimulateCallPayload {
+        site_id,
+        calldata: vec![],
+        message_sender: None,
+        height_lock: Some(1),
+    };
+    assert_eq!(
+        simulator.simulate_call(payload),
+        SimulateCallResult::Err(SimulateCallError::OutsideRetentionWindow)
+    );
+}
+
+#[tokio::test]
+async fn test_simulation_rejected_when_pool_is_full() {
+    let (_db, simulator, site_id) = deploy_view_site("vastrum-test-simulate-busy", VIEW_WAT);
+    let simulator = Arc::new(simulator);
+    let payload =
+        SimulateCallPayload { site_id, calldata: vec![], message_sender: None, height_lock: None };
+
+    let permits = simulator.permits.clone();
+    let held = permits.try_acquire_many_owned(MAX_CONCURRENT_SIMULATIONS as u32).unwrap();
+    let result = simulator.clone().simulate_call_blocking(payload.clone()).await;
+    assert_eq!(result, SimulateCallResult::Err(SimulateCallError::Busy));
+
+    drop(held);
+    let result = simulator.simulate_call_blocking(payload).await;
+    assert!(matches!(result, SimulateCallResult::Ok(_)));
+}
+
+use crate::execution::types::{compiled_module::CompiledModule, sitedata::SiteData};
+use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
+use vastrum_shared_types::types::execution::receipt::TxOutcome;
@@ -1,2 +1,4 @@
 pub mod compiled_module;
+pub mod outcome;
 pub mod sitedata;
+pub mod tx_context;
@@ -0,0 +1,50 @@
+/// What executing one transaction did, recorded as its receipt
+#[derive(Default, Debug)]
+pub struct ExecutionOutcome {
+    pub error: Option<String>,
+    pub usage: HostUsage,
+    pub return_data: Vec<u8>,
+    /// Empty if the transaction failed
+    pub events: Vec<EmittedEvent>,
+}
+
+impl ExecutionOutcome {
+    pub fn failed(message: impl Into<String>) -> ExecutionOutcome {
+        ExecutionOutcome { error: Some(message.into()), ..ExecutionOutcome::default() }
+    }
+
+    pub fn from_contract(execution: ContractExecution) -> ExecutionOutcome {
+        let ContractExecution { result, usage, return_data, events } = execution;
+        return match result {
+            Ok(()) => ExecutionOutcome { error: None, usage, return_data, events },
+            Err(e) => {
+                let error = Some(failure_message(&e));
+                ExecutionOutcome { error, usage, return_data, events: Vec::new() }
+            }
+        };
+    }
+
+    pub fn into_receipt(self, height: u64) -> TxReceipt {
+        let outcome = match self.error {
+            None => TxOutcome::Success,
+            Some(message) => TxOutcome::Failure {
+                message: message.chars().take(MAX_RECEIPT_MESSAGE_LEN).collect(),
+            },
+        };
+        return TxReceipt {
+            height,
+            outcome,
+            fuel_used: self.usage.fuel_used,
+            kv_bytes_written: self.usage.kv_bytes_written,
+            host_calls: self.usage.host_calls,
+            return_data: self.return_data,
+        };
+    }
+}
+
+use crate::execution::wasmhost::{
+    host::ContractExecution,
+    hostbindings::{EmittedEvent, HostUsage, failure_message},
+};
+use crate::utils::limits::MAX_RECEIPT_MESSAGE_LEN;
+use vastrum_shared_types::types::execution::receipt::{TxOutcome, TxReceipt};
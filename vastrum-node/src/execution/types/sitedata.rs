@@ -2,9 +2,28 @@
 pub struct SiteData {
     pub site_id: Sha256Digest,
     pub module_id: Sha256Digest,
+    pub admin: Option<ed25519::PublicKey>,
+}
+
+//layout written before sites had an admin, exactly 64 bytes while the current layout is at least 65
+#[derive(BorshDeserialize)]
+struct SiteDataV0 {
+    site_id: Sha256Digest,
+    module_id: Sha256Digest,
+}
+
+impl SiteData {
+    /// Decode either layout, sites stored before admins existed have no admin and are immutable
+    pub fn decode_versioned(bytes: &[u8]) -> Option<SiteData> {
+        if let Ok(site_data) = borsh::from_slice::<SiteData>(bytes) {
+            return Some(site_data);
+        }
+        let SiteDataV0 { site_id, module_id } = borsh::from_slice(bytes).ok()?;
+        return Some(SiteData { site_id, module_id, admin: None });
+    }
 }
 
 use borsh::{BorshDeserialize, BorshSerialize};
 #[allow(unused_imports)]
 use vastrum_shared_types::borsh::*;
-use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::crypto::{ed25519, sha256::Sha256Digest};
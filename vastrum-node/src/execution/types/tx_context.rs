@@ -0,0 +1,32 @@
+//prefixed so the seed never equals another hash over the same block and tx hash
+const RANDOM_SEED_DOMAIN: &[u8] = b"vastrum.random_seed";
+
+/// Block and transaction a contract runs in, shared by every site called in the tx
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TxContext {
+    pub block_height: u64,
+    pub block_timestamp: u64,
+    pub previous_block_hash: Sha256Digest,
+    pub tx_hash: Sha256Digest,
+    /// Per tx entropy, see derive_random_seed
+    pub random_seed: Sha256Digest,
+}
+
+/// Seed of a tx, unknown to the tx sender since the block hash commits to the other txs in the block.
+/// The grandparent finalization votes tie it to signatures that only exist once that block finalized.
+/// The proposer has those votes before proposing, so it can still grind the seed over tx order,
+/// timestamp and which signers of the certificate to include, or withhold the block.
+pub fn derive_random_seed(
+    block_hash: Sha256Digest,
+    grandparent_finalization: &BlockFinalization,
+    tx_hash: Sha256Digest,
+) -> Sha256Digest {
+    let mut data = RANDOM_SEED_DOMAIN.to_vec();
+    data.extend_from_slice(&block_hash.to_bytes());
+    data.extend_from_slice(&grandparent_finalization.calculate_hash().to_bytes());
+    data.extend_from_slice(&tx_hash.to_bytes());
+    return sha256_hash(&data);
+}
+
+use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
+use vastrum_shared_types::types::consensus::BlockFinalization;
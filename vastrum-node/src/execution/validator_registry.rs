@@ -0,0 +1,168 @@
+impl Execution {
+    /// Add tx signer to the pending validator set, requires approval of 2/3 of the current epoch stake
+    pub fn register_validator(&self, calldata: Vec<u8>) -> ExecutionOutcome {
+        let Ok(call) = RegisterValidatorCall::decode(&calldata) else {
+            tracing::warn!("failed to decode RegisterValidatorCall");
+            return ExecutionOutcome::failed("failed to decode RegisterValidatorCall");
+        };
+        let current_epoch = epoch_for_height(self.current_block_height());
+        if call.epoch != current_epoch {
+            tracing::warn!("validator registration approved for epoch {}, not current", call.epoch);
+            return ExecutionOutcome::failed("validator registration approved for another epoch");
+        }
+        if call.stake == 0 {
+            return ExecutionOutcome::failed("validator stake is zero");
+        }
+        let Some(epoch_validator_set) = self.db.read_epoch_validator_set(current_epoch) else {
+            return ExecutionOutcome::failed("no validator set for the current epoch");
+        };
+        let Some(mut pending) = self.db.read_pending_validator_set() else {
+            return ExecutionOutcome::failed("no pending validator set");
+        };
+        let validator_pub_key = self.message_sender;
+        let already_registered = pending.validators.iter().any(|v| v.pub_key == validator_pub_key);
+        if already_registered {
+            return ExecutionOutcome::failed("validator already registered");
+        }
+        //there is no native token to bond, stake is voting weight granted by the 2/3 approval
+        //capped so a single registration stays below a third of the next epoch's stake
+        let pending_stake: u64 = pending.validators.iter().map(|v| v.stake).sum();
+        let exceeds_stake_cap = call.stake.saturating_mul(2) >= pending_stake;
+        if exceeds_stake_cap {
+            tracing::warn!("validator registration stake over cap: {validator_pub_key}");
+            return ExecutionOutcome::failed("validator stake over cap");
+        }
+
+        let approval = ValidatorRegistrationApproval {
+            validator_pub_key,
+            p2p_key: call.p2p_key,
+            stake: call.stake,
+            epoch: call.epoch,
+        };
+        let is_approved = verify_approvals(&epoch_validator_set, &call.approvals, approval);
+        if !is_approved {
+            tracing::warn!("validator registration lacks 2/3 approval: {validator_pub_key}");
+            return ExecutionOutcome::failed("validator registration lacks 2/3 approval");
+        }
+
+        pending.validators.push(PersistedValidator {
+            pub_key: validator_pub_key,
+            p2p_key: call.p2p_key,
+            stake: call.stake,
+        });
+        self.db.write_pending_validator_set(&pending);
+        return ExecutionOutcome::default();
+    }
+
+    /// Remove tx signer from the pending validator set, last validator can not exit
+    pub fn exit_validator(&self) -> ExecutionOutcome {
+        let Some(mut pending) = self.db.read_pending_validator_set() else {
+            return ExecutionOutcome::failed("no pending validator set");
+        };
+        let validator_pub_key = self.message_sender;
+        let is_registered = pending.validators.iter().any(|v| v.pub_key == validator_pub_key);
+        if !is_registered {
+            return ExecutionOutcome::failed("not a pending validator");
+        }
+        let is_last_validator = pending.validators.len() <= 1;
+        if is_last_validator {
+            return ExecutionOutcome::failed("last validator can not exit");
+        }
+        pending.validators.retain(|v| v.pub_key != validator_pub_key);
+        self.db.write_pending_validator_set(&pending);
+        return ExecutionOutcome::default();
+    }
+
+    /// Slash validator proven to have equivocated by removing it from the pending validator set
+    pub fn process_evidence(&self, calldata: Vec<u8>) -> ExecutionOutcome {
+        let Ok(evidence) = Evidence::decode(&calldata) else {
+            tracing::warn!("failed to decode Evidence");
+            return ExecutionOutcome::failed("failed to decode Evidence");
+        };
+        let evidence_hash = evidence.calculate_hash();
+        if self.db.check_evidence_included(evidence_hash) {
+            return ExecutionOutcome::failed("evidence already included");
+        }
+        let evidence_epoch = epoch_for_height(evidence.height());
+        let Some(validator_set) = self.db.read_epoch_validator_set(evidence_epoch) else {
+            return ExecutionOutcome::failed("no validator set for the evidence epoch");
+        };
+        let epoch_state = EpochState::from_validator_set(&validator_set);
+        //evidence is included in a block after its slot, so height - 1 is already finalized
+        let previous_block = evidence.height().checked_sub(1).and_then(|h| self.db.read_block(h));
+        let leader_seed = previous_block.map(|previous| previous.block.previous_block_hash);
+        let Some(offender) = evidence.verify(&epoch_state, leader_seed, self.chain_id) else {
+            tracing::warn!("invalid {} evidence", evidence.kind());
+            return ExecutionOutcome::failed(format!("invalid {} evidence", evidence.kind()));
+        };
+        //checked before marking the evidence included, a failed tx leaves no state behind
+        let Some(mut pending) = self.db.read_pending_validator_set() else {
+            return ExecutionOutcome::failed("no pending validator set");
+        };
+        let is_last_validator = pending.validators.len() <= 1;
+        if is_last_validator {
+            return ExecutionOutcome::failed("last validator can not be removed");
+        }
+        self.db.set_evidence_as_included(evidence_hash, self.current_block_height());
+        pending.validators.retain(|v| v.pub_key != offender.pub_key);
+        self.db.write_pending_validator_set(&pending);
+        return ExecutionOutcome::default();
+    }
+
+    /// Snapshot the pending validator set as the next epoch, STATE_ROOT_DELAY blocks before the
+    /// last block of the epoch so that block commits to the next set and can prove it to clients
+    /// changes after the snapshot take effect an epoch later
+    pub fn advance_epoch(&self, height: u64) {
+        let is_snapshot_height = (height + 1 + STATE_ROOT_DELAY) % EPOCH_LENGTH == 0;
+        if !is_snapshot_height {
+            return;
+        }
+        let Some(pending) = self.db.read_pending_validator_set() else {
+            return;
+        };
+        let next_epoch = epoch_for_height(height + 1 + STATE_ROOT_DELAY);
+        self.db.write_epoch_validator_set(next_epoch, &pending);
+    }
+}
+
+//double approval protection enforced through BTreeMap<ValidatorIndex, Signature>
+//so each validator index counts at most once
+fn verify_approvals(
+    validator_set: &PersistedValidatorSet,
+    approvals: &[(u64, ed25519::Signature)],
+    approval: ValidatorRegistrationApproval,
+) -> bool {
+    let approvals: BTreeMap<u64, ed25519::Signature> = approvals.iter().copied().collect();
+    let hash = approval.calculate_hash();
+
+    let total_stake: u64 = validator_set.validators.iter().map(|v| v.stake).sum();
+    let threshold = (total_stake * 2) / 3;
+    let mut approved_stake = 0;
+    for (validator_index, signature) in approvals {
+        let Some(validator) = validator_set.validators.get(validator_index as usize) else {
+            continue;
+        };
+        if validator.pub_key.verify_sig(hash, signature) {
+            approved_stake += validator.stake;
+        }
+    }
+    return approved_stake > 0 && approved_stake >= threshold;
+}
+
+use super::{execution::Execution, types::outcome::ExecutionOutcome};
+use crate::{
+    consensus::{
+        evidence::Evidence,
+        validator_state_machine::{EpochState, epoch_for_height},
+    },
+    db::epoch::{PersistedValidator, PersistedValidatorSet},
+};
+use std::collections::BTreeMap;
+use vastrum_shared_types::{
+    borsh::BorshExt,
+    crypto::ed25519,
+    limits::{EPOCH_LENGTH, STATE_ROOT_DELAY},
+    types::application::validator_registration::{
+        RegisterValidatorCall, ValidatorRegistrationApproval,
+    },
+};
@@ -12,6 +12,9 @@ pub fn common_config() -> wasmtime::Config {
     //https://docs.wasmtime.dev/examples-deterministic-wasm-execution.html
     config.cranelift_nan_canonicalization(true);
 
+    //deterministic execution budget, epoch interruption would depend on wall clock time
+    config.consume_fuel(true);
+
     // Disable unused WASM features (reduces code size + JIT validation overhead)
     // Note: reference_types and multi_value are required by Rust-compiled WASM
     config.relaxed_simd_deterministic(true);
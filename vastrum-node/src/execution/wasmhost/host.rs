@@ -1,6 +1,15 @@
+/// Result of a contract invocation together with what it used of the host
+pub struct ContractExecution {
+    pub result: Result<()>,
+    pub usage: HostUsage,
+    /// Borsh encoded return value, or error if the method returned Err
+    pub return_data: Vec<u8>,
+    pub events: Vec<EmittedEvent>,
+}
+
 pub struct VastrumHost {
     engine: Engine,
-    linker: Linker<HostState>,
+    linker: Arc<Linker<HostState>>,
 }
 
 impl VastrumHost {
@@ -8,7 +17,8 @@ impl VastrumHost {
         &self,
         site_id: Sha256Digest,
         message_sender: ed25519::PublicKey,
-        block_timestamp: u64,
+        tx_context: TxContext,
+        fuel_limit: u64,
         db: Arc<BatchDb>,
     ) -> Store<HostState> {
         let mut store = Store::new(
@@ -16,18 +26,37 @@ impl VastrumHost {
             HostState::new(
                 site_id,
                 message_sender,
-                block_timestamp,
-                StoreLimitsBuilder::new()
-                    .memory_size(vastrum_shared_types::limits::MAX_WASM_MEMORY)
-                    .instances(10)
-                    .build(),
+                tx_context,
+                TxLimits::new(
+                    StoreLimitsBuilder::new()
+                        .memory_size(MAX_WASM_MEMORY)
+                        //every cross site call instantiates the callee in this store
+                        .instances(MAX_SITE_CALLS_PER_TX + 1)
+                        .memories(MAX_SITE_CALLS_PER_TX + 1)
+                        .tables(MAX_SITE_CALLS_PER_TX + 1)
+                        .build(),
+                ),
                 db,
+                self.linker.clone(),
             ),
         );
         store.limiter(|state| &mut state.limits);
+        store.set_fuel(fuel_limit).expect("fuel is enabled in common_config");
         store
     }
 
+    fn collect_execution(
+        store: &mut Store<HostState>,
+        result: Result<()>,
+        fuel_limit: u64,
+    ) -> ContractExecution {
+        let mut usage = std::mem::take(&mut store.data_mut().usage);
+        usage.fuel_used = fuel_limit - store.get_fuel().unwrap_or(0);
+        let return_data = std::mem::take(&mut store.data_mut().return_data);
+        let events = std::mem::take(&mut store.data_mut().events);
+        return ContractExecution { result, usage, return_data, events };
+    }
+
     pub fn engine(&self) -> &Engine {
         &self.engine
     }
@@ -38,12 +67,33 @@ impl VastrumHost {
         calldata: Vec<u8>,
         site_id: Sha256Digest,
         message_sender: ed25519::PublicKey,
-        block_timestamp: u64,
+        tx_context: TxContext,
+        fuel_limit: u64,
+        db: Arc<BatchDb>,
+    ) -> ContractExecution {
+        let mut store = self.make_store(site_id, message_sender, tx_context, fuel_limit, db);
+        let result =
+            vastrum_bindings_host::call_contract(&self.linker, &mut store, module, &calldata);
+        return Self::collect_execution(&mut store, result, fuel_limit);
+    }
+
+    /// Same as execute_call but records logs and the key names written
+    pub fn simulate_call(
+        &self,
+        module: &Module,
+        calldata: Vec<u8>,
+        site_id: Sha256Digest,
+        message_sender: ed25519::PublicKey,
+        tx_context: TxContext,
+        fuel_limit: u64,
         db: Arc<BatchDb>,
-    ) -> Result<()> {
-        let mut store = self.make_store(site_id, message_sender, block_timestamp, db);
-        vastrum_bindings_host::call_contract(&self.linker, &mut store, module, &calldata)?;
-        Ok(())
+    ) -> (ContractExecution, SimulationTrace) {
+        let mut store = self.make_store(site_id, message_sender, tx_context, fuel_limit, db);
+        store.data_mut().trace = Some(SimulationTrace::default());
+        let result =
+            vastrum_bindings_host::call_contract(&self.linker, &mut store, module, &calldata);
+        let trace = store.data_mut().trace.take().unwrap_or_default();
+        return (Self::collect_execution(&mut store, result, fuel_limit), trace);
     }
 
     pub fn execute_construct(
@@ -52,18 +102,64 @@ impl VastrumHost {
         constructor_params: Vec<u8>,
         site_id: Sha256Digest,
         message_sender: ed25519::PublicKey,
-        block_timestamp: u64,
+        tx_context: TxContext,
+        fuel_limit: u64,
         db: Arc<BatchDb>,
-    ) -> Result<()> {
-        let module = unsafe { Module::deserialize_file(&self.engine, module_file_path)? };
-        let mut store = self.make_store(site_id, message_sender, block_timestamp, db);
-        vastrum_bindings_host::construct_contract(
+    ) -> ContractExecution {
+        let module = match unsafe { Module::deserialize_file(&self.engine, module_file_path) } {
+            Ok(module) => module,
+            Err(e) => {
+                return ContractExecution {
+                    result: Err(e),
+                    usage: HostUsage::default(),
+                    return_data: Vec::new(),
+                    events: Vec::new(),
+                };
+            }
+        };
+        let mut store = self.make_store(site_id, message_sender, tx_context, fuel_limit, db);
+        let result = vastrum_bindings_host::construct_contract(
             &self.linker,
             &mut store,
             &module,
             &constructor_params,
-        )?;
-        Ok(())
+        );
+        return Self::collect_execution(&mut store, result, fuel_limit);
+    }
+
+    /// Runs the migrate entry point of the module, None if the module has none
+    pub fn execute_migrate(
+        &self,
+        module_file_path: &std::path::Path,
+        migrate_params: Vec<u8>,
+        site_id: Sha256Digest,
+        message_sender: ed25519::PublicKey,
+        tx_context: TxContext,
+        fuel_limit: u64,
+        db: Arc<BatchDb>,
+    ) -> Option<ContractExecution> {
+        let module = match unsafe { Module::deserialize_file(&self.engine, module_file_path) } {
+            Ok(module) => module,
+            Err(e) => {
+                return Some(ContractExecution {
+                    result: Err(e),
+                    usage: HostUsage::default(),
+                    return_data: Vec::new(),
+                    events: Vec::new(),
+                });
+            }
+        };
+        if !vastrum_bindings_host::has_migrate_entry_point(&module) {
+            return None;
+        }
+        let mut store = self.make_store(site_id, message_sender, tx_context, fuel_limit, db);
+        let result = vastrum_bindings_host::migrate_contract(
+            &self.linker,
+            &mut store,
+            &module,
+            &migrate_params,
+        );
+        return Some(Self::collect_execution(&mut store, result, fuel_limit));
     }
 
     pub fn compile_module(&self, wasm_data: &[u8]) -> Result<Vec<u8>> {
@@ -76,11 +172,16 @@ impl VastrumHost {
         let engine = Engine::new(&config).unwrap();
         let mut linker = Linker::new(&engine);
         vastrum_bindings_host::add_to_linker(&mut linker).unwrap();
-        VastrumHost { engine, linker }
+        VastrumHost { engine, linker: Arc::new(linker) }
     }
 }
-use super::{config::common_config, hostbindings::HostState};
+use super::{
+    config::common_config,
+    hostbindings::{EmittedEvent, HostState, HostUsage, SimulationTrace, TxLimits},
+};
 use crate::db::BatchDb;
+use crate::execution::types::tx_context::TxContext;
 use vastrum_shared_types::crypto::{ed25519, sha256::Sha256Digest};
+use vastrum_shared_types::limits::{MAX_SITE_CALLS_PER_TX, MAX_WASM_MEMORY};
 use std::sync::Arc;
 use wasmtime::{Engine, Linker, Module, Result, Store, StoreLimitsBuilder};
@@ -1,36 +1,224 @@
 pub struct HostState {
     pub site_id: Sha256Digest,
     pub message_sender: ed25519::PublicKey,
-    pub block_timestamp: u64,
-    pub limits: StoreLimits,
+    pub tx_context: TxContext,
+    pub limits: TxLimits,
     pub db: Arc<BatchDb>,
+    pub usage: HostUsage,
+    /// Borsh encoded return value, or error if the method returned Err
+    pub return_data: Vec<u8>,
+    /// Events of the sites that did not revert, written to the block once the tx succeeds
+    pub events: Vec<EmittedEvent>,
+    /// Only set when simulating a call, block execution does not record logs
+    pub trace: Option<SimulationTrace>,
+    linker: Arc<Linker<HostState>>,
+    //contexts of the calling sites while a cross site call runs, innermost last
+    call_stack: Vec<SiteCallFrame>,
+    site_calls: usize,
+    //set by a #[view] method, called sites inherit it and it is restored when they return
+    in_view: bool,
+}
+
+struct SiteCallFrame {
+    site_id: Sha256Digest,
+    message_sender: ed25519::PublicKey,
+    return_data: Vec<u8>,
+    //events emitted before the call, the callees are dropped if it fails
+    events_len: usize,
+    in_view: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmittedEvent {
+    pub site_id: Sha256Digest,
+    pub topics: Vec<Sha256Digest>,
+    pub data: Vec<u8>,
+}
+
+/// Logs and written key names of a simulated call
+#[derive(Default, Debug)]
+pub struct SimulationTrace {
+    pub logs: Vec<String>,
+    //encoded SiteKvStorageKey to the key name the contract wrote it under
+    pub kv_keys: HashMap<Vec<u8>, String>,
+}
+
+/// Host side accounting of one contract invocation, reported in the tx receipt
+#[derive(Default, Debug)]
+pub struct HostUsage {
+    pub fuel_used: u64,
+    pub kv_bytes_written: u64,
+    pub host_calls: HostCallCounts,
+}
+
+/// Message recorded for a failed contract invocation, the guest panic message if it panicked
+pub fn failure_message(error: &wasmtime::Error) -> String {
+    if let Some(panic) = error.downcast_ref::<ContractPanic>() {
+        return panic.message.clone();
+    }
+    return error.root_cause().to_string();
+}
+
+/// Store limits of one transaction, wasmtime caps each memory on its own
+/// so the memories of all instances in the store are capped in total as well
+pub struct TxLimits {
+    store_limits: StoreLimits,
+    memory_bytes: usize,
+}
+
+impl TxLimits {
+    pub fn new(store_limits: StoreLimits) -> TxLimits {
+        TxLimits { store_limits, memory_bytes: 0 }
+    }
+}
+
+impl ResourceLimiter for TxLimits {
+    //also called with current 0 when an instance creates its memory
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        let memory_bytes = self.memory_bytes - current + desired;
+        if memory_bytes > MAX_TX_WASM_MEMORY {
+            return Ok(false);
+        }
+        if !self.store_limits.memory_growing(current, desired, maximum)? {
+            return Ok(false);
+        }
+        self.memory_bytes = memory_bytes;
+        return Ok(true);
+    }
+
+    fn table_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        return self.store_limits.table_growing(current, desired, maximum);
+    }
+
+    fn instances(&self) -> usize {
+        return self.store_limits.instances();
+    }
+
+    fn tables(&self) -> usize {
+        return self.store_limits.tables();
+    }
+
+    fn memories(&self) -> usize {
+        return self.store_limits.memories();
+    }
 }
 
 impl HostState {
     pub fn new(
         site_id: Sha256Digest,
         message_sender: ed25519::PublicKey,
-        block_timestamp: u64,
-        limits: StoreLimits,
+        tx_context: TxContext,
+        limits: TxLimits,
         db: Arc<BatchDb>,
+        linker: Arc<Linker<HostState>>,
     ) -> HostState {
-        HostState { site_id, message_sender, block_timestamp, limits, db }
+        HostState {
+            site_id,
+            message_sender,
+            tx_context,
+            limits,
+            db,
+            usage: HostUsage::default(),
+            return_data: Vec::new(),
+            events: Vec::new(),
+            trace: None,
+            linker,
+            call_stack: Vec::new(),
+            site_calls: 0,
+            in_view: false,
+        }
+    }
+
+    fn resolve_site_call(&self, site_id: Sha256Digest) -> Result<Module, String> {
+        if self.call_stack.len() >= MAX_SITE_CALL_DEPTH {
+            return Err(format!("site call depth limit of {MAX_SITE_CALL_DEPTH} reached"));
+        }
+        if self.site_calls >= MAX_SITE_CALLS_PER_TX {
+            return Err(format!("site call limit of {MAX_SITE_CALLS_PER_TX} per tx reached"));
+        }
+        //contract state is loaded on entry and saved on exit, re-entry would overwrite it
+        let on_call_stack =
+            site_id == self.site_id || self.call_stack.iter().any(|frame| frame.site_id == site_id);
+        if on_call_stack {
+            return Err(format!("re-entrant call to site {site_id}"));
+        }
+        let Some(site_data) = self.db.read_site(site_id) else {
+            return Err(format!("site not found: {site_id}"));
+        };
+        let module_file_path = self.db.calculate_module_file_path(site_data.module_id);
+        return match unsafe { Module::deserialize_file(self.linker.engine(), &module_file_path) } {
+            Ok(module) => Ok(module),
+            Err(e) => Err(format!("failed to load site module: {e}")),
+        };
     }
 }
 
 impl HostRuntime for HostState {
-    fn message_sender(&self) -> Vec<u8> {
+    fn message_sender(&mut self) -> Vec<u8> {
+        self.usage.host_calls.message_sender += 1;
         let sender: Ed25519PublicKey = self.message_sender.into();
 
         let response = GetMessageSenderResponse { sender };
         return response.encode();
     }
 
-    fn block_time(&self) -> u64 {
-        return self.block_timestamp;
+    fn block_time(&mut self) -> u64 {
+        self.usage.host_calls.block_time += 1;
+        return self.tx_context.block_timestamp;
+    }
+
+    fn block_height(&mut self) -> u64 {
+        self.usage.host_calls.block_height += 1;
+        return self.tx_context.block_height;
+    }
+
+    fn previous_block_hash(&mut self) -> Vec<u8> {
+        self.usage.host_calls.previous_block_hash += 1;
+        let digest = self.tx_context.previous_block_hash.to_bytes();
+        return GetDigestResponse { digest }.encode();
+    }
+
+    fn tx_hash(&mut self) -> Vec<u8> {
+        self.usage.host_calls.tx_hash += 1;
+        let digest = self.tx_context.tx_hash.to_bytes();
+        return GetDigestResponse { digest }.encode();
+    }
+
+    fn site_id(&mut self) -> Vec<u8> {
+        self.usage.host_calls.site_id += 1;
+        let digest = self.site_id.to_bytes();
+        return GetDigestResponse { digest }.encode();
+    }
+
+    fn module_id(&mut self) -> Vec<u8> {
+        self.usage.host_calls.module_id += 1;
+        //read on every call, a migrating site already sees the module it is upgraded to
+        let module_id = self.db.read_site(self.site_id).map(|site| site.module_id);
+        let digest = module_id.unwrap_or_default().to_bytes();
+        return GetDigestResponse { digest }.encode();
+    }
+
+    fn random_seed(&mut self) -> Vec<u8> {
+        self.usage.host_calls.random_seed += 1;
+        //mixed with the site id so sites called in the same tx draw independent seeds
+        let mut data = self.tx_context.random_seed.to_bytes().to_vec();
+        data.extend_from_slice(&self.site_id.to_bytes());
+        let seed = sha256_hash(&data).to_bytes();
+        return GetRandomSeedResponse { seed }.encode();
     }
 
     fn register_static_route(&mut self, args: &[u8]) {
+        self.usage.host_calls.register_static_route += 1;
         let Ok(RegisterStaticRouteCall { route, brotli_html_content }) = borsh::from_slice(args)
         else {
             tracing::warn!("failed to decode RegisterStaticRouteCall");
@@ -40,19 +228,43 @@ impl HostRuntime for HostState {
         self.db.write_page(page);
     }
 
-    fn kv_insert(&mut self, args: &[u8]) {
+    fn kv_insert(&mut self, args: &[u8]) -> wasmtime::Result<()> {
+        self.usage.host_calls.kv_insert += 1;
         let Ok(KeyValueInsertCall { key, value }) = borsh::from_slice(args) else {
             tracing::warn!("failed to decode KeyValueInsert");
-            return;
+            return Ok(());
         };
+        self.usage.kv_bytes_written += (key.len() + value.len()) as u64;
+        if let Some(trace) = &mut self.trace {
+            let storage_key = SiteKvStorageKey::new(self.site_id, &key).encode();
+            trace.kv_keys.insert(storage_key, key.clone());
+        }
+        let usage_before = self.db.read_storage_usage(self.site_id);
         if value.is_empty() {
             self.db.delete_kv(&key, self.site_id);
         } else {
             self.db.write_kv(&key, value, self.site_id);
         }
+        //the trap reverts the write along with the rest of the call
+        let usage_after = self.db.read_storage_usage(self.site_id);
+        if usage_after > usage_before {
+            let quota = genesis_site_storage_quota();
+            if usage_after > quota {
+                return Err(wasmtime::Error::msg(format!(
+                    "site storage quota of {quota} bytes exceeded"
+                )));
+            }
+        }
+        return Ok(());
     }
 
-    fn kv_get(&self, args: &[u8]) -> Vec<u8> {
+    fn storage_usage(&mut self) -> u64 {
+        self.usage.host_calls.storage_usage += 1;
+        return self.db.read_storage_usage(self.site_id);
+    }
+
+    fn kv_get(&mut self, args: &[u8]) -> Vec<u8> {
+        self.usage.host_calls.kv_get += 1;
         let Ok(KeyValueReadCall { key }) = borsh::from_slice(args) else {
             tracing::warn!("failed to decode KeyValueRead");
             return KeyValueReadResponse { value: vec![] }.encode();
@@ -62,22 +274,157 @@ impl HostRuntime for HostState {
         return response.encode();
     }
 
+    fn kv_scan(&mut self, call: KeyValueScanCall, reverse: bool) -> Vec<u8> {
+        self.usage.host_calls.kv_scan += 1;
+        let KeyValueScanCall { prefix, start, limit } = call;
+        let entries =
+            self.db.scan_kv(self.site_id, &prefix, start.as_deref(), limit as usize, reverse);
+        return KeyValueScanResponse { entries }.encode();
+    }
+
     fn log(&mut self, args: &[u8]) {
+        self.usage.host_calls.log += 1;
         let Ok(LogCall { message }) = borsh::from_slice(args) else {
             tracing::warn!("failed to decode Log");
             return;
         };
+        if let Some(trace) = &mut self.trace {
+            trace.logs.push(message.clone());
+        }
         tracing::info!(site_id = ?self.site_id, "{}", message);
     }
+
+    fn emit_event(&mut self, args: &[u8]) -> wasmtime::Result<u64> {
+        self.usage.host_calls.emit_event += 1;
+        let Ok(EmitEventCall { topics, data }) = borsh::from_slice(args) else {
+            return Err(wasmtime::Error::msg("failed to decode EmitEventCall"));
+        };
+        if topics.is_empty() || topics.len() > MAX_EVENT_TOPICS {
+            return Err(wasmtime::Error::msg(format!(
+                "event needs between 1 and {MAX_EVENT_TOPICS} topics"
+            )));
+        }
+        if data.len() > MAX_EVENT_DATA_SIZE {
+            return Err(wasmtime::Error::msg("event data too large"));
+        }
+        if self.events.len() >= MAX_EVENTS_PER_TX {
+            return Err(wasmtime::Error::msg(format!(
+                "event limit of {MAX_EVENTS_PER_TX} per tx reached"
+            )));
+        }
+        let topics: Vec<Sha256Digest> = topics.into_iter().map(Sha256Digest::from).collect();
+        //upgrade history must only come from the node
+        if topics.contains(&site_upgraded_topic()) {
+            return Err(wasmtime::Error::msg("event topic is reserved"));
+        }
+        let copies = topics.iter().collect::<BTreeSet<_>>().len() as u64;
+        self.events.push(EmittedEvent { site_id: self.site_id, topics, data });
+        return Ok(copies);
+    }
+
+    fn set_return_data(&mut self, data: Vec<u8>) {
+        self.return_data = data;
+    }
+
+    fn enter_view(&mut self) {
+        self.in_view = true;
+    }
+
+    fn in_view(&self) -> bool {
+        return self.in_view;
+    }
+
+    fn record_precompile_call(&mut self) {
+        self.usage.host_calls.precompiles += 1;
+    }
+
+    fn linker(&self) -> Arc<Linker<HostState>> {
+        return self.linker.clone();
+    }
+
+    fn enter_site_call(&mut self, args: &[u8]) -> Result<SiteCallTarget, Vec<u8>> {
+        self.usage.host_calls.call_site += 1;
+        let Ok(CallSiteCall { site_id, calldata }) = borsh::from_slice(args) else {
+            tracing::warn!("failed to decode CallSiteCall");
+            let error = CallSiteError::Failed("failed to decode CallSiteCall".into());
+            return Err(CallSiteResponse { result: Err(error) }.encode());
+        };
+        let site_id = Sha256Digest::from(site_id);
+        let module = match self.resolve_site_call(site_id) {
+            Ok(module) => module,
+            Err(message) => {
+                let error = CallSiteError::Failed(message);
+                return Err(CallSiteResponse { result: Err(error) }.encode());
+            }
+        };
+        self.site_calls += 1;
+        self.call_stack.push(SiteCallFrame {
+            site_id: self.site_id,
+            message_sender: self.message_sender,
+            return_data: std::mem::take(&mut self.return_data),
+            events_len: self.events.len(),
+            in_view: self.in_view,
+        });
+        //the called site sees the calling site as message sender
+        self.message_sender = ed25519::PublicKey::try_from_bytes(self.site_id.to_bytes()).unwrap();
+        self.site_id = site_id;
+        //nested in the callers scope so the callees writes revert with the caller
+        self.db.begin_revertable();
+        return Ok(SiteCallTarget { module, calldata });
+    }
+
+    fn exit_site_call(&mut self, result: wasmtime::Result<()>) -> Vec<u8> {
+        let frame = self.call_stack.pop().expect("exit_site_call without matching enter");
+        let callee_site_id = std::mem::replace(&mut self.site_id, frame.site_id);
+        self.message_sender = frame.message_sender;
+        self.in_view = frame.in_view;
+        let return_data = std::mem::replace(&mut self.return_data, frame.return_data);
+        if result.is_err() {
+            self.events.truncate(frame.events_len);
+        }
+        let result = match result {
+            Ok(()) => {
+                self.db.commit_revertable();
+                Ok(return_data)
+            }
+            Err(e) if e.downcast_ref::<ContractRevert>().is_some() => {
+                self.db.rollback_revertable();
+                Err(CallSiteError::Reverted(return_data))
+            }
+            Err(e) => {
+                self.db.rollback_revertable();
+                let message = failure_message(&e);
+                tracing::warn!(site_id = ?callee_site_id, "site call failed: {message}");
+                Err(CallSiteError::Failed(message))
+            }
+        };
+        return CallSiteResponse { result }.encode();
+    }
 }
 use crate::db::BatchDb;
+use crate::execution::types::tx_context::TxContext;
 use vastrum_runtime_shared::{
-    Ed25519PublicKey, GetMessageSenderResponse, KeyValueInsertCall, KeyValueReadCall,
-    KeyValueReadResponse, LogCall, RegisterStaticRouteCall,
+    CallSiteCall, CallSiteError, CallSiteResponse, Ed25519PublicKey, EmitEventCall,
+    GetDigestResponse, GetMessageSenderResponse, GetRandomSeedResponse, KeyValueInsertCall,
+    KeyValueReadCall, KeyValueReadResponse, KeyValueScanCall, KeyValueScanResponse, LogCall,
+    RegisterStaticRouteCall,
 };
 use vastrum_shared_types::borsh::BorshExt;
-use vastrum_shared_types::crypto::{ed25519, sha256::Sha256Digest};
-use vastrum_shared_types::types::storage::Page;
+use vastrum_shared_types::crypto::ed25519;
+use vastrum_shared_types::crypto::sha256::{Sha256Digest, sha256_hash};
+use vastrum_shared_types::genesis::genesis_site_storage_quota;
+use vastrum_shared_types::limits::{
+    MAX_EVENT_DATA_SIZE, MAX_EVENT_TOPICS, MAX_EVENTS_PER_TX, MAX_SITE_CALL_DEPTH,
+    MAX_SITE_CALLS_PER_TX, MAX_TX_WASM_MEMORY,
+};
+use vastrum_shared_types::types::execution::event::site_upgraded_topic;
+use vastrum_shared_types::types::execution::receipt::HostCallCounts;
+use vastrum_shared_types::types::storage::{Page, SiteKvStorageKey};
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
-use vastrum_bindings_host::HostRuntime;
-use wasmtime::StoreLimits;
+use vastrum_bindings_host::{ContractPanic, ContractRevert, HostRuntime, SiteCallTarget};
+use wasmtime::{Linker, Module, ResourceLimiter, StoreLimits};
+
+#[cfg(test)]
+#[path = "hostbindings_tests.rs"]
+mod tests;
@@ -0,0 +1,30 @@
+use super::*;
+use vastrum_shared_types::limits::MAX_WASM_MEMORY;
+use wasmtime::StoreLimitsBuilder;
+
+const MB: usize = 1024 * 1024;
+
+fn tx_limits() -> TxLimits {
+    TxLimits::new(StoreLimitsBuilder::new().memory_size(MAX_WASM_MEMORY).build())
+}
+
+#[test]
+fn test_memory_capped_per_memory() {
+    let mut limits = tx_limits();
+    assert!(!limits.memory_growing(0, MAX_WASM_MEMORY + 1, None).unwrap());
+    assert!(limits.memory_growing(0, MAX_WASM_MEMORY, None).unwrap());
+}
+
+#[test]
+fn test_memory_capped_across_instances() {
+    let mut limits = tx_limits();
+    assert!(limits.memory_growing(0, 100 * MB, None).unwrap());
+    //growing counts only the difference
+    assert!(limits.memory_growing(100 * MB, 200 * MB, None).unwrap());
+    assert!(limits.memory_growing(0, MAX_WASM_MEMORY, None).unwrap(), "second instance");
+
+    let remaining = MAX_TX_WASM_MEMORY - 200 * MB - MAX_WASM_MEMORY;
+    assert!(!limits.memory_growing(0, remaining + MB, None).unwrap(), "over the total");
+    assert!(limits.memory_growing(0, remaining, None).unwrap(), "rejected growth not counted");
+    assert!(!limits.memory_growing(200 * MB, 200 * MB + MB, None).unwrap());
+}
@@ -13,11 +13,19 @@ pub async fn start_localnet() {
         run_rpc_node: true,
         genesis_epoch_state: genesis_epoch_state(),
         rpc_nodes: vec![rpc_node],
+        #[cfg(madsim)]
+        byzantine_mode: consensus::byzantine::ByzantineMode::Honest,
+        round_timeout: RoundTimeoutConfig::default(),
+        chain_id: genesis_chain_id(),
     };
     ValidatorStateMachine::start_node(db, config).await;
 }
 
-pub async fn start_node_production(keystore_path: PathBuf, run_rpc: bool) {
+pub async fn start_node_production(
+    keystore_path: PathBuf,
+    run_rpc: bool,
+    round_timeout: RoundTimeoutConfig,
+) {
     utils::logging::setup_logging();
     let keystore = Keystore::load_or_create(&keystore_path);
     let db = Arc::new(Db::open(Db::default_path()));
@@ -27,6 +35,10 @@ pub async fn start_node_production(keystore_path: PathBuf, run_rpc: bool) {
         run_rpc_node: run_rpc,
         genesis_epoch_state: genesis_epoch_state(),
         rpc_nodes: genesis_rpc_nodes(),
+        #[cfg(madsim)]
+        byzantine_mode: consensus::byzantine::ByzantineMode::Honest,
+        round_timeout,
+        chain_id: genesis_chain_id(),
     };
     ValidatorStateMachine::start_node(db, config).await;
 }
@@ -47,9 +59,11 @@ use crate::{
         generate_localnet, genesis_bootstrap_peers, genesis_epoch_state, genesis_rpc_nodes,
     },
 };
+use consensus::round_timer::RoundTimeoutConfig;
 use consensus::validator_state_machine::{NodeConfig, ValidatorStateMachine};
 use db::Db;
 use std::{path::PathBuf, sync::Arc};
 use vastrum_shared_types::frontend::frontend_data::RpcNodeEndpoint;
+use vastrum_shared_types::genesis::genesis_chain_id;
 use vastrum_shared_types::ports::WEBRTC_PORT;
 use vastrum_webrtc_direct_server::local_network_ip;
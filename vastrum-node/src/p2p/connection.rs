@@ -92,6 +92,7 @@ pub fn spawn_connection_actor(
             _ = writer_task(encrypted_writer, write_rx) => {}
             _ = reader_task(
                 encrypted_reader,
+                remote_p2p_key,
                 reader_pending,
                 reader_write_tx,
                 inbound_tx,
@@ -116,6 +117,7 @@ async fn writer_task(mut writer: EncryptedWriter, mut write_rx: mpsc::Receiver<V
 
 async fn reader_task(
     mut reader: EncryptedReader,
+    remote_p2p_key: ed25519::PublicKey,
     pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Response>>>>,
     write_tx: mpsc::Sender<Vec<u8>>,
     inbound_tx: mpsc::UnboundedSender<InboundMessage>,
@@ -136,7 +138,11 @@ async fn reader_task(
         match message {
             Message::Request { id, payload } => {
                 let handle = ResponseHandle::new(write_tx.clone(), id);
-                let _ = inbound_tx.send(InboundMessage { payload, respond: Some(handle) });
+                let _ = inbound_tx.send(InboundMessage {
+                    from: remote_p2p_key,
+                    payload,
+                    respond: Some(handle),
+                });
             }
             Message::Response { id, payload } => {
                 if let Some(sender) = pending.lock().remove(&id) {
@@ -144,7 +150,11 @@ async fn reader_task(
                 }
             }
             Message::Statement { payload } => {
-                let _ = inbound_tx.send(InboundMessage { payload, respond: None });
+                let _ = inbound_tx.send(InboundMessage {
+                    from: remote_p2p_key,
+                    payload,
+                    respond: None,
+                });
             }
             Message::Ping { nonce } => {
                 let pong = Message::Pong { nonce };
@@ -3,6 +3,7 @@ pub async fn handshake_dial(
     local_key: &ed25519::PrivateKey,
     remote_key: ed25519::PublicKey,
     local_port: u16,
+    chain_id: u64,
 ) -> eyre::Result<HandshakeResult> {
     tokio::time::timeout(Duration::from_secs(15), async {
         let socket = TcpStream::connect(addr).await?;
@@ -14,6 +15,7 @@ pub async fn handshake_dial(
         let ephemeral_public = ephemeral_private.public_key();
 
         let content = HandshakeRequestContent {
+            chain_id,
             from_p2p_key: local_key.public_key(),
             unix_timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
             to_p2p_key: remote_key,
@@ -38,6 +40,9 @@ pub async fn handshake_dial(
         if response.content.request_hash != content_hash {
             return Err(eyre::eyre!("invalid request hash"));
         }
+        if response.content.chain_id != chain_id {
+            return Err(eyre::eyre!("peer is on chain {}", response.content.chain_id));
+        }
 
         let (encrypted_reader, encrypted_writer) = derive_encrypted_transport(
             ephemeral_private,
@@ -62,6 +67,7 @@ pub async fn handshake_listen(
     socket: TcpStream,
     local_key: &ed25519::PrivateKey,
     local_port: u16,
+    chain_id: u64,
 ) -> eyre::Result<HandshakeResult> {
     tokio::time::timeout(Duration::from_secs(10), async {
         socket.set_nodelay(true).ok();
@@ -79,6 +85,9 @@ pub async fn handshake_listen(
         if request.content.to_p2p_key != local_key.public_key() {
             return Err(eyre::eyre!("invalid to_p2p_key"));
         }
+        if request.content.chain_id != chain_id {
+            return Err(eyre::eyre!("peer is on chain {}", request.content.chain_id));
+        }
         validate_signature_and_timestamp(
             request.content.from_p2p_key,
             request_content_hash,
@@ -87,6 +96,7 @@ pub async fn handshake_listen(
         )?;
 
         let response_content = HandshakeResponseContent {
+            chain_id,
             unix_timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
             listening_port: local_port,
             request_hash: request_content_hash,
@@ -160,6 +170,7 @@ pub struct HandshakeResult {
 
 #[derive(BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
 struct HandshakeRequestContent {
+    chain_id: u64,
     from_p2p_key: ed25519::PublicKey,
     unix_timestamp: u64,
     to_p2p_key: ed25519::PublicKey,
@@ -180,6 +191,7 @@ struct HandshakeRequest {
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 struct HandshakeResponseContent {
+    chain_id: u64,
     unix_timestamp: u64,
     listening_port: u16,
     request_hash: Sha256Digest,
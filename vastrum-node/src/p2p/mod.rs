@@ -5,4 +5,5 @@ pub mod networking;
 pub mod peer_manager;
 pub mod transport;
 pub mod transport_cipher;
+pub mod tx_gossip;
 pub mod types;
@@ -1,6 +1,6 @@
 pub struct Networking {
     peer_manager: Arc<PeerManager>,
-    transaction_tx: UnboundedSender<Transaction>,
+    tx_gossip: Arc<TxGossip>,
 }
 
 impl Networking {
@@ -13,13 +13,17 @@ impl Networking {
     pub fn broadcast_certificate(&self, cert: Certificate) {
         self.peer_manager.broadcast_statement_to_all_peers(AppPayload::Certificate(cert));
     }
+    pub fn broadcast_evidence(&self, evidence: &Evidence) {
+        self.peer_manager.broadcast_statement_to_all_peers(AppPayload::Evidence(evidence.clone()));
+    }
 
     async fn handle_received_messages(
         mut message_rx: UnboundedReceiver<AppInboundMessage>,
         vote_tx: UnboundedSender<ValidatorVote>,
         proposal_tx: UnboundedSender<Proposal>,
         cert_tx: UnboundedSender<Certificate>,
-        transaction_tx: UnboundedSender<Transaction>,
+        evidence_tx: UnboundedSender<Evidence>,
+        tx_gossip: Arc<TxGossip>,
         current_round_for_sync: Arc<RwLock<RoundSyncStateExternal>>,
         db: Arc<Db>,
     ) {
@@ -45,12 +49,20 @@ impl Networking {
                         );
                     }
                 }
-                AppPayload::TransactionGossip(transaction) => {
-                    Self::ingest_transaction(transaction, &transaction_tx);
+                AppPayload::TxAnnouncement(announcement) => {
+                    tx_gossip.handle_announcement(msg.from, announcement);
+                }
+                AppPayload::GetTransactionsReq(request) => {
+                    if let Some(respond) = msg.respond {
+                        tx_gossip.handle_request(msg.from, request, respond);
+                    }
                 }
                 AppPayload::Certificate(cert) => {
                     let _ = cert_tx.send(cert);
                 }
+                AppPayload::Evidence(evidence) => {
+                    let _ = evidence_tx.send(evidence);
+                }
             }
         }
     }
@@ -61,18 +73,6 @@ impl Networking {
     fn handle_proposal(proposal: Proposal, proposal_tx: &UnboundedSender<Proposal>) {
         let _ = proposal_tx.send(proposal);
     }
-    fn ingest_transaction(
-        transaction: Transaction,
-        transaction_tx: &UnboundedSender<Transaction>,
-    ) -> bool {
-        let valid_signature = transaction.verify_signature();
-        let valid_gas = transaction.verify_gas();
-        if !valid_signature || !valid_gas {
-            return false;
-        }
-        let _ = transaction_tx.send(transaction);
-        return true;
-    }
 
     pub async fn get_slot(&self, height: u64) -> Option<GetSlotReply> {
         let response = self
@@ -120,39 +120,35 @@ impl Networking {
         });
     }
 
+    /// Validator peers get the validator connection tier, updated when a new epoch is loaded
+    pub fn set_validator_p2p_keys(&self, validator_p2p_keys: HashSet<ed25519::PublicKey>) {
+        self.peer_manager.set_validator_p2p_keys(validator_p2p_keys);
+    }
+
+    //peers are sent the hash in the next announcement batch and pull the body if they lack it
     pub fn broadcast_transaction(&self, transaction: Transaction) {
-        let was_valid_transaction =
-            Self::ingest_transaction(transaction.clone(), &self.transaction_tx);
-        if !was_valid_transaction {
-            return;
-        }
-        let payload = AppPayload::TransactionGossip(transaction);
-        self.peer_manager.broadcast_statement_to_all_peers(payload);
+        self.tx_gossip.submit(transaction);
     }
     pub async fn start(
         vote_tx: UnboundedSender<ValidatorVote>,
         proposal_tx: UnboundedSender<Proposal>,
         cert_tx: UnboundedSender<Certificate>,
+        evidence_tx: UnboundedSender<Evidence>,
         transaction_tx: UnboundedSender<Transaction>,
         p2p_key: ed25519::PrivateKey,
         peers: Vec<KnownPeer>,
         current_round_for_sync: Arc<RwLock<RoundSyncStateExternal>>,
         db: Arc<Db>,
-        epoch_state: &EpochState,
+        validator_p2p_keys: HashSet<ed25519::PublicKey>,
+        chain_id: u64,
     ) -> Arc<Networking> {
         let (inbound_tx, inbound_rx) = mpsc::unbounded_channel::<AppInboundMessage>();
 
-        let mut validator_p2p_keys = HashSet::new();
-        for v in epoch_state.validator_data.values() {
-            validator_p2p_keys.insert(v.p2p_key);
-        }
-
-        let peer_manager = PeerManager::start(inbound_tx, p2p_key, peers, validator_p2p_keys).await;
+        let peer_manager =
+            PeerManager::start(inbound_tx, p2p_key, peers, validator_p2p_keys, chain_id).await;
 
-        let net = Arc::new(Networking {
-            peer_manager: peer_manager.clone(),
-            transaction_tx: transaction_tx.clone(),
-        });
+        let tx_gossip = TxGossip::start(peer_manager.clone(), transaction_tx, chain_id);
+        let net = Arc::new(Networking { peer_manager, tx_gossip: tx_gossip.clone() });
 
         tokio::spawn(async move {
             Networking::handle_received_messages(
@@ -160,7 +156,8 @@ impl Networking {
                 vote_tx,
                 proposal_tx,
                 cert_tx,
-                transaction_tx,
+                evidence_tx,
+                tx_gossip,
                 current_round_for_sync,
                 db,
             )
@@ -172,12 +169,13 @@ impl Networking {
 }
 
 use crate::{
+    consensus::evidence::Evidence,
     consensus::types::{Certificate, Proposal, RoundSyncStateExternal, ValidatorVote},
-    consensus::validator_state_machine::EpochState,
     db::Db,
     p2p::{
         connection::ResponseHandle,
         peer_manager::{KnownPeer, PeerManager},
+        tx_gossip::TxGossip,
         types::{
             app_types::{GetRoundReply, GetRoundRequest, GetSlotReply, GetSlotRequest},
             messages::AppInboundMessage,
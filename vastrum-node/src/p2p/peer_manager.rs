@@ -9,7 +9,9 @@ pub struct PeerManager {
     local_p2p_key: ed25519::PrivateKey,
     handshake_rate_limiter: Mutex<HandshakeRateLimiter>,
     release_tx: mpsc::UnboundedSender<ed25519::PublicKey>,
-    validator_p2p_keys: HashSet<ed25519::PublicKey>,
+    //validators of the current and next epoch, replaced when an epoch starts
+    validator_p2p_keys: Mutex<HashSet<ed25519::PublicKey>>,
+    chain_id: u64,
 }
 impl PeerManager {
     pub async fn start(
@@ -17,6 +19,7 @@ impl PeerManager {
         local_p2p_key: ed25519::PrivateKey,
         initial_peers: Vec<KnownPeer>,
         validator_p2p_keys: HashSet<ed25519::PublicKey>,
+        chain_id: u64,
     ) -> Arc<PeerManager> {
         let (connection_inbound_tx, connection_inbound_rx) = mpsc::unbounded_channel();
         let (release_tx, release_rx) = mpsc::unbounded_channel();
@@ -27,7 +30,8 @@ impl PeerManager {
             local_p2p_key,
             handshake_rate_limiter: Mutex::new(HandshakeRateLimiter::new()),
             release_tx,
-            validator_p2p_keys,
+            validator_p2p_keys: Mutex::new(validator_p2p_keys),
+            chain_id,
         });
 
         pm.add_known_peers(initial_peers, EndpointSource::Bootstrap);
@@ -59,9 +63,13 @@ impl PeerManager {
                     else {
                         return;
                     };
-                    let Ok(result) =
-                        handshake_listen(socket, &pm.local_p2p_key, vastrum_shared_types::ports::P2P_PORT)
-                            .await
+                    let Ok(result) = handshake_listen(
+                        socket,
+                        &pm.local_p2p_key,
+                        vastrum_shared_types::ports::P2P_PORT,
+                        pm.chain_id,
+                    )
+                    .await
                     else {
                         return;
                     };
@@ -154,6 +162,7 @@ impl PeerManager {
             &self.local_p2p_key,
             target_p2p_key,
             vastrum_shared_types::ports::P2P_PORT,
+            self.chain_id,
         )
         .await;
 
@@ -197,6 +206,39 @@ impl PeerManager {
         });
     }
 
+    pub fn send_statement_to_peer(&self, peer: &ed25519::PublicKey, payload: AppPayload) {
+        let Some(sender) = self.connected_peer(peer) else { return };
+        let _ = sender.send_statement(NetworkPayload::App(payload).encode());
+    }
+
+    pub async fn send_request_to_peer(
+        &self,
+        peer: &ed25519::PublicKey,
+        payload: AppPayload,
+    ) -> Option<Response> {
+        let sender = self.connected_peer(peer)?;
+        sender.send_request(NetworkPayload::App(payload).encode(), 3).await.ok()
+    }
+
+    pub fn connected_peer_keys(&self) -> Vec<ed25519::PublicKey> {
+        let peers = self.peers.lock();
+        let mut connected = Vec::new();
+        for record in peers.values() {
+            if record.connection_status.is_connected() {
+                connected.push(record.p2p_key);
+            }
+        }
+        return connected;
+    }
+
+    fn connected_peer(&self, peer: &ed25519::PublicKey) -> Option<PeerSender> {
+        let peers = self.peers.lock();
+        if let ConnectionStatus::Connected(sender, _) = &peers.get(peer)?.connection_status {
+            return Some(sender.clone());
+        }
+        return None;
+    }
+
     fn connected_peers(&self) -> Vec<PeerSender> {
         let peers = self.peers.lock();
         let mut connected = Vec::new();
@@ -260,8 +302,12 @@ impl PeerManager {
         self.local_p2p_key.public_key()
     }
 
+    pub fn set_validator_p2p_keys(&self, validator_p2p_keys: HashSet<ed25519::PublicKey>) {
+        *self.validator_p2p_keys.lock() = validator_p2p_keys;
+    }
+
     fn get_peer_tier(&self, p2p_key: &ed25519::PublicKey) -> PeerTier {
-        if self.validator_p2p_keys.contains(p2p_key) {
+        if self.validator_p2p_keys.lock().contains(p2p_key) {
             return PeerTier::Validator;
         } else {
             return PeerTier::Normal;
@@ -312,8 +358,11 @@ impl PeerManager {
                         pm.handle_get_peers_request(msg.respond);
                     }
                     NetworkPayload::App(app_payload) => {
-                        let _ = app_tx
-                            .send(AppInboundMessage { payload: app_payload, respond: msg.respond });
+                        let _ = app_tx.send(AppInboundMessage {
+                            from: msg.from,
+                            payload: app_payload,
+                            respond: msg.respond,
+                        });
                     }
                 }
             }
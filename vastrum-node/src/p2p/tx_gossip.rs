@@ -0,0 +1,299 @@
+/// Announce/request transaction gossip
+//peers learn new tx hashes in batches and pull only the bodies they lack
+//per peer known sets stop the same transaction being announced or sent to a peer twice
+pub struct TxGossip {
+    peer_manager: Arc<PeerManager>,
+    transaction_tx: UnboundedSender<Transaction>,
+    chain_id: u64,
+    state: Mutex<GossipState>,
+}
+
+#[derive(Default)]
+struct GossipState {
+    //bodies served to peers that request them, oldest evicted first
+    cache: HashMap<Sha256Digest, CachedTransaction>,
+    cache_order: VecDeque<Sha256Digest>,
+    cache_size: usize,
+    known_by_peer: HashMap<ed25519::PublicKey, KnownTxs>,
+    requested: HashSet<Sha256Digest>,
+    pending_announcements: Vec<Sha256Digest>,
+}
+
+struct CachedTransaction {
+    transaction: Transaction,
+    size: usize,
+}
+
+//tx hashes a peer has announced or been sent, oldest forgotten first
+#[derive(Default)]
+struct KnownTxs {
+    hashes: HashSet<Sha256Digest>,
+    order: VecDeque<Sha256Digest>,
+}
+
+impl TxGossip {
+    pub fn start(
+        peer_manager: Arc<PeerManager>,
+        transaction_tx: UnboundedSender<Transaction>,
+        chain_id: u64,
+    ) -> Arc<TxGossip> {
+        let gossip = Arc::new(TxGossip {
+            peer_manager,
+            transaction_tx,
+            chain_id,
+            state: Mutex::new(GossipState::default()),
+        });
+        gossip.start_announce_loop();
+        return gossip;
+    }
+
+    /// Transaction submitted to this node, announced to peers if valid and not already gossiped
+    pub fn submit(&self, transaction: Transaction) -> bool {
+        let tx_hash = transaction.calculate_txhash();
+        if self.state.lock().cache.contains_key(&tx_hash) {
+            return false;
+        }
+        if !self.ingest_transaction(transaction.clone()) {
+            return false;
+        }
+        self.state.lock().add(tx_hash, transaction);
+        return true;
+    }
+
+    pub fn handle_announcement(
+        self: &Arc<Self>,
+        from: ed25519::PublicKey,
+        announcement: TxAnnouncement,
+    ) {
+        let wanted = self.state.lock().wanted(from, announcement.tx_hashes);
+        if wanted.is_empty() {
+            return;
+        }
+        let gossip = self.clone();
+        tokio::spawn(async move {
+            gossip.fetch_transactions(from, wanted).await;
+        });
+    }
+
+    pub fn handle_request(
+        &self,
+        from: ed25519::PublicKey,
+        request: GetTransactionsRequest,
+        respond: ResponseHandle,
+    ) {
+        let transactions = self.state.lock().reply(from, &request.tx_hashes);
+        respond.respond(GetTransactionsReply { transactions }.encode());
+    }
+
+    //a reply stops once full, so the hashes it did not reach are requested again from the same peer
+    //hashes the peer does not have are released so a later announcement can fetch them elsewhere
+    async fn fetch_transactions(&self, from: ed25519::PublicKey, tx_hashes: Vec<Sha256Digest>) {
+        let mut remaining = tx_hashes.clone();
+        while !remaining.is_empty() {
+            let request = GetTransactionsRequest { tx_hashes: remaining.clone() };
+            let response = self
+                .peer_manager
+                .send_request_to_peer(&from, AppPayload::GetTransactionsReq(request));
+            let reply = response.await.and_then(|r| GetTransactionsReply::decode(&r.payload).ok());
+            let Some(reply) = reply else { break };
+
+            let requested: HashSet<&Sha256Digest> = remaining.iter().collect();
+            let mut delivered = HashSet::new();
+            for transaction in reply.transactions {
+                let tx_hash = transaction.calculate_txhash();
+                //only accept bodies that were asked for
+                if !requested.contains(&tx_hash) {
+                    continue;
+                }
+                delivered.insert(tx_hash);
+                if self.ingest_transaction(transaction.clone()) {
+                    self.state.lock().add(tx_hash, transaction);
+                }
+            }
+            remaining = unreached_hashes(remaining, &delivered);
+        }
+        self.state.lock().release(&tx_hashes);
+    }
+
+    fn ingest_transaction(&self, transaction: Transaction) -> bool {
+        let same_chain = transaction.chain_id == self.chain_id;
+        let valid_signature = transaction.verify_signature();
+        let valid_gas = transaction.verify_gas();
+        if !same_chain || !valid_signature || !valid_gas {
+            return false;
+        }
+        let _ = self.transaction_tx.send(transaction);
+        return true;
+    }
+
+    fn start_announce_loop(self: &Arc<Self>) {
+        let gossip = self.clone();
+        tokio::spawn(async move {
+            loop {
+                sleep(TX_ANNOUNCE_INTERVAL).await;
+                gossip.announce_pending();
+            }
+        });
+    }
+
+    fn announce_pending(&self) {
+        let connected = self.peer_manager.connected_peer_keys();
+        let announcements = self.state.lock().announcements(connected);
+        for (peer, announcement) in announcements {
+            self.peer_manager
+                .send_statement_to_peer(&peer, AppPayload::TxAnnouncement(announcement));
+        }
+    }
+}
+
+//reply follows request order and skips bodies the peer lacks, hashes past the last delivered one were not reached
+//nothing delivered means the peer has none of the rest
+fn unreached_hashes(
+    requested: Vec<Sha256Digest>,
+    delivered: &HashSet<Sha256Digest>,
+) -> Vec<Sha256Digest> {
+    let Some(last_delivered) = requested.iter().rposition(|h| delivered.contains(h)) else {
+        return vec![];
+    };
+    return requested[last_delivered + 1..].to_vec();
+}
+
+impl GossipState {
+    //announced hashes this node neither has nor is already fetching, marked as being fetched
+    fn wanted(
+        &mut self,
+        from: ed25519::PublicKey,
+        tx_hashes: Vec<Sha256Digest>,
+    ) -> Vec<Sha256Digest> {
+        let mut wanted = vec![];
+        let known = self.known_by_peer.entry(from).or_default();
+        for tx_hash in tx_hashes.into_iter().take(MAX_TX_ANNOUNCEMENT_HASHES) {
+            known.insert(tx_hash);
+            let have_or_fetching =
+                self.cache.contains_key(&tx_hash) || self.requested.contains(&tx_hash);
+            if !have_or_fetching {
+                self.requested.insert(tx_hash);
+                wanted.push(tx_hash);
+            }
+        }
+        return wanted;
+    }
+
+    fn release(&mut self, tx_hashes: &[Sha256Digest]) {
+        for tx_hash in tx_hashes {
+            self.requested.remove(tx_hash);
+        }
+    }
+
+    //cached bodies in request order, stops before the reply outgrows MAX_TRANSACTION_SIZE
+    //the first body always fits since no transaction is larger
+    fn reply(&mut self, from: ed25519::PublicKey, tx_hashes: &[Sha256Digest]) -> Vec<Transaction> {
+        let mut transactions = vec![];
+        let known = self.known_by_peer.entry(from).or_default();
+        let mut reply_size = 0;
+        for tx_hash in tx_hashes.iter().take(MAX_TX_ANNOUNCEMENT_HASHES) {
+            let Some(cached) = self.cache.get(tx_hash) else { continue };
+            if reply_size + cached.size > MAX_TRANSACTION_SIZE {
+                break;
+            }
+            reply_size += cached.size;
+            known.insert(*tx_hash);
+            transactions.push(cached.transaction.clone());
+        }
+        return transactions;
+    }
+
+    //pending hashes each connected peer does not know yet, forgets peers that disconnected
+    fn announcements(
+        &mut self,
+        connected: Vec<ed25519::PublicKey>,
+    ) -> Vec<(ed25519::PublicKey, TxAnnouncement)> {
+        let mut announcements = vec![];
+        self.known_by_peer.retain(|peer, _| connected.contains(peer));
+
+        let pending = std::mem::take(&mut self.pending_announcements);
+        if pending.is_empty() {
+            return announcements;
+        }
+        for peer in connected {
+            let known = self.known_by_peer.entry(peer).or_default();
+            let mut tx_hashes = vec![];
+            for tx_hash in &pending {
+                if !known.contains(tx_hash) {
+                    known.insert(*tx_hash);
+                    tx_hashes.push(*tx_hash);
+                }
+            }
+            for chunk in tx_hashes.chunks(MAX_TX_ANNOUNCEMENT_HASHES) {
+                announcements.push((peer, TxAnnouncement { tx_hashes: chunk.to_vec() }));
+            }
+        }
+        return announcements;
+    }
+
+    fn add(&mut self, tx_hash: Sha256Digest, transaction: Transaction) {
+        if self.cache.contains_key(&tx_hash) {
+            return;
+        }
+        let size = transaction.encode().len();
+        self.cache.insert(tx_hash, CachedTransaction { transaction, size });
+        self.cache_order.push_back(tx_hash);
+        self.cache_size += size;
+        self.pending_announcements.push(tx_hash);
+
+        while self.cache_size > MAX_GOSSIP_CACHE_SIZE {
+            let Some(oldest) = self.cache_order.pop_front() else { break };
+            if let Some(evicted) = self.cache.remove(&oldest) {
+                self.cache_size -= evicted.size;
+            }
+        }
+    }
+}
+
+impl KnownTxs {
+    fn insert(&mut self, tx_hash: Sha256Digest) {
+        if !self.hashes.insert(tx_hash) {
+            return;
+        }
+        self.order.push_back(tx_hash);
+        if self.order.len() > MAX_KNOWN_TXS_PER_PEER {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+    }
+    fn contains(&self, tx_hash: &Sha256Digest) -> bool {
+        self.hashes.contains(tx_hash)
+    }
+}
+
+use crate::{
+    p2p::{
+        connection::ResponseHandle,
+        peer_manager::PeerManager,
+        types::{
+            app_types::{GetTransactionsReply, GetTransactionsRequest, TxAnnouncement},
+            payload::AppPayload,
+        },
+    },
+    utils::limits::{
+        MAX_GOSSIP_CACHE_SIZE, MAX_KNOWN_TXS_PER_PEER, MAX_TX_ANNOUNCEMENT_HASHES,
+        TX_ANNOUNCE_INTERVAL,
+    },
+};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+use tokio::{sync::mpsc::UnboundedSender, time::sleep};
+use vastrum_shared_types::{
+    borsh::BorshExt,
+    crypto::{ed25519, sha256::Sha256Digest},
+    limits::MAX_TRANSACTION_SIZE,
+    types::execution::transaction::Transaction,
+};
+
+#[cfg(test)]
+#[path = "tx_gossip_tests.rs"]
+mod tests;
@@ -0,0 +1,129 @@
+use super::*;
+use vastrum_shared_types::crypto::sha256::sha256_hash;
+use vastrum_shared_types::limits::DEFAULT_TX_FUEL_LIMIT;
+use vastrum_shared_types::transactioning::transaction_generator::{
+    TxEnvelope, build_call_transaction,
+};
+
+fn tx(seed: u64, calldata: Vec<u8>) -> Transaction {
+    build_call_transaction(
+        Sha256Digest::default(),
+        calldata,
+        ed25519::PrivateKey::from_seed(seed),
+        TxEnvelope {
+            nonce: 0,
+            recent_block_height: 0,
+            chain_id: 0,
+            pow_difficulty: 0,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
+    )
+}
+
+//incompressible calldata so the encoded transaction stays about len bytes
+fn large_tx(seed: u64, len: usize) -> Transaction {
+    let mut calldata = Vec::with_capacity(len);
+    let mut i = 0u64;
+    while calldata.len() < len {
+        calldata.extend_from_slice(&sha256_hash(&(seed, i).encode()).to_bytes());
+        i += 1;
+    }
+    calldata.truncate(len);
+    return tx(seed, calldata);
+}
+
+fn peer(seed: u64) -> ed25519::PublicKey {
+    return ed25519::PrivateKey::from_seed(seed).public_key();
+}
+
+fn cached(state: &mut GossipState, transaction: Transaction) -> Sha256Digest {
+    let tx_hash = transaction.calculate_txhash();
+    state.add(tx_hash, transaction);
+    return tx_hash;
+}
+
+#[test]
+fn test_announced_hashes_fetched_once() {
+    let mut state = GossipState::default();
+    let have = cached(&mut state, tx(1, vec![1]));
+    let missing = tx(2, vec![2]).calculate_txhash();
+
+    let wanted = state.wanted(peer(1), vec![have, missing]);
+    assert_eq!(wanted, vec![missing], "cached hash should not be fetched");
+    assert!(state.wanted(peer(2), vec![missing]).is_empty(), "already being fetched");
+
+    //released after the fetch, a later announcement can fetch it elsewhere
+    state.release(&wanted);
+    assert_eq!(state.wanted(peer(2), vec![missing]), vec![missing]);
+}
+
+#[test]
+fn test_announcements_skip_known_hashes() {
+    let mut state = GossipState::default();
+    let tx_hash = cached(&mut state, tx(1, vec![1]));
+    state.wanted(peer(1), vec![tx_hash]);
+
+    let announcements = state.announcements(vec![peer(1), peer(2)]);
+    assert_eq!(announcements.len(), 1);
+    assert_eq!(announcements[0].0, peer(2), "peer that announced it already knows it");
+    assert_eq!(announcements[0].1.tx_hashes, vec![tx_hash]);
+
+    assert!(state.announcements(vec![peer(1), peer(2)]).is_empty(), "announced only once");
+}
+
+#[test]
+fn test_reply_only_cached_and_marks_known() {
+    let mut state = GossipState::default();
+    let have = cached(&mut state, tx(1, vec![1]));
+    let missing = tx(2, vec![2]).calculate_txhash();
+    state.pending_announcements.clear();
+
+    let reply = state.reply(peer(1), &[missing, have]);
+    assert_eq!(reply.len(), 1);
+    assert_eq!(reply[0].calculate_txhash(), have);
+
+    //peer was sent the body so it is not announced to it afterwards
+    state.pending_announcements.push(have);
+    assert!(state.announcements(vec![peer(1)]).is_empty());
+}
+
+#[test]
+fn test_truncated_reply_is_paged() {
+    let mut state = GossipState::default();
+    let size = MAX_TRANSACTION_SIZE * 2 / 5;
+    let hashes: Vec<Sha256Digest> =
+        (1..=3).map(|i| cached(&mut state, large_tx(i, size))).collect();
+
+    let first_page = state.reply(peer(1), &hashes);
+    assert_eq!(first_page.len(), 2, "third body would outgrow the reply");
+    let delivered: HashSet<Sha256Digest> =
+        first_page.iter().map(|tx| tx.calculate_txhash()).collect();
+
+    let remaining = unreached_hashes(hashes.clone(), &delivered);
+    assert_eq!(remaining, vec![hashes[2]]);
+    let second_page = state.reply(peer(1), &remaining);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].calculate_txhash(), hashes[2]);
+}
+
+#[test]
+fn test_unreached_hashes() {
+    let hashes: Vec<Sha256Digest> = (1..=4).map(Sha256Digest::from_u64).collect();
+    let delivered = HashSet::from([hashes[1]]);
+    assert_eq!(unreached_hashes(hashes.clone(), &delivered), hashes[2..].to_vec());
+    assert!(unreached_hashes(hashes.clone(), &HashSet::new()).is_empty());
+    let last_delivered = HashSet::from([hashes[3]]);
+    assert!(unreached_hashes(hashes, &last_delivered).is_empty());
+}
+
+#[test]
+fn test_add_dedups() {
+    let mut state = GossipState::default();
+    let transaction = tx(1, vec![1]);
+    let size = transaction.encode().len();
+    cached(&mut state, transaction.clone());
+    cached(&mut state, transaction);
+    assert_eq!(state.pending_announcements.len(), 1);
+    assert_eq!(state.cache_order.len(), 1);
+    assert_eq!(state.cache_size, size);
+}
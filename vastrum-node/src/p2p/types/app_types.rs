@@ -19,6 +19,21 @@ pub struct GetRoundReply {
     pub cert: Option<Certificate>,
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TxAnnouncement {
+    pub tx_hashes: Vec<Sha256Digest>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GetTransactionsRequest {
+    pub tx_hashes: Vec<Sha256Digest>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GetTransactionsReply {
+    pub transactions: Vec<Transaction>,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
 pub struct GetPeersReply {
     pub peers: Vec<KnownPeer>,
@@ -29,3 +44,5 @@ use crate::p2p::peer_manager::KnownPeer;
 use borsh::{BorshDeserialize, BorshSerialize};
 #[allow(unused_imports)]
 use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::types::execution::transaction::Transaction;
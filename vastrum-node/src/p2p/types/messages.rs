@@ -12,11 +12,13 @@ pub struct Response {
 }
 
 pub struct InboundMessage {
+    pub from: ed25519::PublicKey,
     pub payload: Vec<u8>,
     pub respond: Option<ResponseHandle>,
 }
 
 pub struct AppInboundMessage {
+    pub from: ed25519::PublicKey,
     pub payload: AppPayload,
     pub respond: Option<ResponseHandle>,
 }
@@ -26,3 +28,4 @@ use crate::p2p::types::payload::AppPayload;
 use borsh::{BorshDeserialize, BorshSerialize};
 #[allow(unused_imports)]
 use vastrum_shared_types::borsh::*;
+use vastrum_shared_types::crypto::ed25519;
@@ -2,9 +2,11 @@ use borsh::{BorshDeserialize, BorshSerialize};
 #[allow(unused_imports)]
 use vastrum_shared_types::borsh::*;
 
+use crate::consensus::evidence::Evidence;
 use crate::consensus::types::{Certificate, Proposal, ValidatorVote};
-use crate::p2p::types::app_types::{GetRoundRequest, GetSlotRequest};
-use vastrum_shared_types::types::execution::transaction::Transaction;
+use crate::p2p::types::app_types::{
+    GetRoundRequest, GetSlotRequest, GetTransactionsRequest, TxAnnouncement,
+};
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum NetworkPayload {
@@ -18,6 +20,8 @@ pub enum AppPayload {
     Proposal(Proposal),
     GetSlotReq(GetSlotRequest),
     GetRoundReq(GetRoundRequest),
-    TransactionGossip(Transaction),
+    TxAnnouncement(TxAnnouncement),
+    GetTransactionsReq(GetTransactionsRequest),
     Certificate(Certificate),
+    Evidence(Evidence),
 }
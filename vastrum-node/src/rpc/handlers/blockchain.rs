@@ -18,8 +18,8 @@ pub fn get_page(db: &Db, payload: GetPagePayload) -> GetPageResult {
     let proof_height = db.read_latest_finalized_height();
 
     let storage_key = PageStorageKey::new(site_id, &actual_path).encode();
-    //state hash is delayed 1 block,
-    let state_proof_height = proof_height.saturating_sub(1);
+    //state hash is delayed STATE_ROOT_DELAY blocks
+    let state_proof_height = proof_height.saturating_sub(STATE_ROOT_DELAY);
     match db.generate_state_proof("page", &storage_key, state_proof_height) {
         Some(state_proof) => GetPageResult::Ok(PageResponse {
             brotli_html_content,
@@ -32,15 +32,18 @@ pub fn get_page(db: &Db, payload: GetPagePayload) -> GetPageResult {
 }
 
 pub fn get_latest_block_height(db: &Db) -> GetLatestBlockHeightResponse {
-    GetLatestBlockHeightResponse { height: db.read_latest_finalized_height() }
+    let height = db.read_latest_finalized_height();
+    //difficulty the next block requires
+    let pow_difficulty = db.read_block(height).map(|f| f.block.next_pow_difficulty()).unwrap_or(0);
+    GetLatestBlockHeightResponse { height, chain_id: genesis_chain_id(), pow_difficulty }
 }
 
 pub fn get_key_value(db: &Db, payload: GetKeyValuePayload) -> GetKeyValueResult {
     let current_height = db.read_latest_finalized_height();
-    //can only prove current_height -1, if request is above this, then clamp it down to latest provable height
+    //can only prove current_height - STATE_ROOT_DELAY, if request is above this, then clamp it down to latest provable height
     let height = match payload.height_lock {
-        Some(h) if h < current_height => h,
-        _ => current_height.saturating_sub(1),
+        Some(h) if h + STATE_ROOT_DELAY <= current_height => h,
+        _ => current_height.saturating_sub(STATE_ROOT_DELAY),
     };
 
     let does_not_have_height_in_db = height + KV_RETENTION_WINDOW < current_height;
@@ -78,12 +81,162 @@ pub fn get_tx_hash_inclusion_state(
     GetTxHashIsIncludedResponse { included }
 }
 
+/// Inclusion is checked first, a transaction can still be pending locally after another node included it
+pub fn get_tx_status(
+    db: &Db,
+    mempool: &SharedMempool,
+    payload: GetTxStatusPayload,
+) -> GetTxStatusResponse {
+    let tx_hash = payload.tx_hash;
+    let status = if let Some(receipt) = db.read_tx_receipt(tx_hash) {
+        if receipt.is_success() {
+            TxStatus::Included { height: receipt.height }
+        } else {
+            TxStatus::Failed { height: receipt.height }
+        }
+    } else if mempool.read().contains(&tx_hash) {
+        TxStatus::Pending
+    } else if db.read_tx_expired_height(tx_hash).is_some() {
+        TxStatus::Expired
+    } else {
+        TxStatus::Unknown
+    };
+    GetTxStatusResponse { status }
+}
+
+/// Receipt is proven against the latest provable state root, it never changes once written
+pub fn get_tx_receipt(db: &Db, payload: GetTxReceiptPayload) -> GetTxReceiptResult {
+    let Some(receipt) = db.read_tx_receipt(payload.tx_hash) else {
+        return GetTxReceiptResult::Err(ProvedReadError::ReceiptNotFound);
+    };
+    let current_height = db.read_latest_finalized_height();
+    let height = current_height.saturating_sub(STATE_ROOT_DELAY);
+    if receipt.height > height {
+        return GetTxReceiptResult::Err(ProvedReadError::ProofUnavailable);
+    }
+    let state_proof = db.generate_state_proof(cf::RECEIPT, &payload.tx_hash.encode(), height);
+    match state_proof {
+        Some(state_proof) => {
+            GetTxReceiptResult::Ok(Box::new(GetTxReceiptResponse { receipt, state_proof }))
+        }
+        None => GetTxReceiptResult::Err(ProvedReadError::ProofUnavailable),
+    }
+}
+
+/// Validator set of an epoch with the proof persisted when the previous epoch ended
+pub fn get_epoch_handoff(db: &Db, payload: GetEpochHandoffPayload) -> GetEpochHandoffResult {
+    match db.read_epoch_handoff(payload.epoch) {
+        Some(handoff) => GetEpochHandoffResult::Ok(Box::new(handoff)),
+        None => GetEpochHandoffResult::Err(ProvedReadError::EpochHandoffNotFound),
+    }
+}
+
+/// Event blocks of a site topic newest first, proven against the latest provable state root
+/// The first block above the range or the topic head proves the range is complete
+pub fn get_events(db: &Db, payload: GetEventsPayload) -> GetEventsResult {
+    let GetEventsPayload { site_id, topic, from_height, to_height } = payload;
+    let current_height = db.read_latest_finalized_height();
+    let state_height = current_height.saturating_sub(STATE_ROOT_DELAY);
+    let to_height = to_height.min(state_height);
+
+    let head_key = event_head_key(site_id, topic);
+    let Some(state_proof) = db.generate_state_proof(cf::EVENT, &head_key, state_height) else {
+        return GetEventsResult::Err(ProvedReadError::ProofUnavailable);
+    };
+    let head = db.latest_event_height(site_id, topic, state_height);
+
+    let mut anchor = None;
+    if let Some(height) = db.next_event_height(site_id, topic, to_height, state_height) {
+        let Some(proved) = prove_event_block(db, site_id, topic, height, state_height) else {
+            return GetEventsResult::Err(ProvedReadError::ProofUnavailable);
+        };
+        anchor = Some(proved);
+    }
+
+    let mut next_height = match &anchor {
+        Some(anchor) => anchor.block.prev_height,
+        None => head,
+    };
+    let mut page_bytes = state_proof.encode().len() + anchor.encode().len();
+    let mut blocks = Vec::new();
+    while let Some(height) = next_height {
+        if height < from_height || blocks.len() >= MAX_EVENT_BLOCKS_PAGE {
+            break;
+        }
+        let Some(proved) = prove_event_block(db, site_id, topic, height, state_height) else {
+            return GetEventsResult::Err(ProvedReadError::ProofUnavailable);
+        };
+        page_bytes += proved.encode().len();
+        //a page always has a block, otherwise the caller could not continue below it
+        if page_bytes > MAX_EVENTS_PAGE_BYTES && !blocks.is_empty() {
+            break;
+        }
+        next_height = proved.block.prev_height;
+        blocks.push(proved);
+    }
+    GetEventsResult::Ok(Box::new(GetEventsResponse {
+        to_height,
+        head,
+        anchor,
+        blocks,
+        state_proof,
+    }))
+}
+
+fn prove_event_block(
+    db: &Db,
+    site_id: Sha256Digest,
+    topic: Sha256Digest,
+    height: u64,
+    state_height: u64,
+) -> Option<ProvedEventBlock> {
+    let block = db.read_event_block(site_id, topic, height)?;
+    let key = event_block_key(site_id, topic, height);
+    let proof = db.generate_key_proof(cf::EVENT, &key, state_height)?;
+    return Some(ProvedEventBlock { height, block, proof });
+}
+
+pub fn get_pending_transactions(
+    mempool: &SharedMempool,
+    payload: GetPendingTransactionsPayload,
+) -> GetPendingTransactionsResponse {
+    let limit = payload.limit.min(MAX_PENDING_TXS_PAGE) as usize;
+    let offset = usize::try_from(payload.offset).unwrap_or(usize::MAX);
+    let mempool = mempool.read();
+    let total = mempool.transaction_count() as u64;
+    let transactions = mempool.pending_page(offset, limit);
+    GetPendingTransactionsResponse { total, transactions }
+}
+
+/// Runs against finalized state, the node discards everything the call writes
+pub async fn simulate_call(
+    simulator: &Arc<Simulator>,
+    payload: SimulateCallPayload,
+) -> SimulateCallResult {
+    simulator.clone().simulate_call_blocking(payload).await
+}
+
 pub fn resolve_domain(db: &Db, payload: ResolveDomainRequest) -> ResolveDomainResponse {
     let domain = db.read_domain(&payload.domain);
     let site_id = domain.map(|d| d.site_id);
     ResolveDomainResponse { site_id }
 }
 
+/// Latest usage, unproven
+pub fn get_storage_usage(db: &Db, payload: GetStorageUsagePayload) -> GetStorageUsageResponse {
+    let used_bytes = db.read_storage_usage(payload.site_id);
+    GetStorageUsageResponse { used_bytes, quota_bytes: genesis_site_storage_quota() }
+}
+
+/// Latest keys of a site in order, unproven since the key index is not part of the state tree
+/// Values can be read and proven per key with get_key_value
+pub fn scan_keys(db: &Db, payload: ScanKeysPayload) -> ScanKeysResponse {
+    let ScanKeysPayload { site_id, prefix, start, limit, reverse } = payload;
+    let limit = limit.min(MAX_KV_SCAN_LIMIT) as usize;
+    let keys = db.scan_kv_keys(site_id, &prefix, start.as_deref(), limit, reverse);
+    ScanKeysResponse { keys }
+}
+
 fn resolve_route(db: &Db, site_id: Sha256Digest, path: &str) -> Option<(String, Vec<u8>)> {
     //path has registed route for path
     if let Some(page) = db.read_page(site_id, path) {
@@ -102,20 +255,33 @@ fn resolve_route(db: &Db, site_id: Sha256Digest, path: &str) -> Option<(String,
     }
 }
 
-use crate::{db::Db, p2p::networking::Networking};
+use crate::{
+    consensus::mempool::SharedMempool,
+    db::{Db, cf},
+    execution::simulation::Simulator,
+    p2p::networking::Networking,
+    utils::limits::{MAX_EVENT_BLOCKS_PAGE, MAX_EVENTS_PAGE_BYTES, MAX_PENDING_TXS_PAGE},
+};
+use std::sync::Arc;
 use vastrum_shared_types::borsh::BorshExt;
-use vastrum_shared_types::limits::KV_RETENTION_WINDOW;
-use vastrum_shared_types::types::storage::PageStorageKey;
+use vastrum_shared_types::genesis::{genesis_chain_id, genesis_site_storage_quota};
+use vastrum_shared_types::limits::{KV_RETENTION_WINDOW, MAX_KV_SCAN_LIMIT, STATE_ROOT_DELAY};
+use vastrum_shared_types::types::storage::{PageStorageKey, event_block_key, event_head_key};
 use vastrum_shared_types::{
     crypto::sha256::Sha256Digest,
     types::{
         execution::transaction::Transaction,
         rpc::types::{
-            GetKeyValuePayload, GetKeyValueResponse, GetKeyValueResult,
-            GetLatestBlockHeightResponse, GetPagePayload, GetPageResult, GetSiteIDIsDeployed,
-            GetSiteIDIsDeployedResponse, GetTxHashIsIncluded, GetTxHashIsIncludedResponse,
-            PageResponse, ProvedReadError, ResolveDomainRequest, ResolveDomainResponse,
-            SubmitTransactionPayload,
+            GetEpochHandoffPayload, GetEpochHandoffResult, GetEventsPayload, GetEventsResponse,
+            GetEventsResult, GetKeyValuePayload, GetKeyValueResponse, GetKeyValueResult,
+            GetLatestBlockHeightResponse, GetPagePayload, GetPageResult,
+            GetPendingTransactionsPayload, GetPendingTransactionsResponse, GetSiteIDIsDeployed,
+            GetSiteIDIsDeployedResponse, GetStorageUsagePayload, GetStorageUsageResponse,
+            GetTxHashIsIncluded, GetTxHashIsIncludedResponse, GetTxReceiptPayload,
+            GetTxReceiptResponse, GetTxReceiptResult, GetTxStatusPayload, GetTxStatusResponse,
+            PageResponse, ProvedEventBlock, ProvedReadError, ResolveDomainRequest,
+            ResolveDomainResponse, ScanKeysPayload, ScanKeysResponse, SimulateCallPayload,
+            SimulateCallResult, SubmitTransactionPayload, TxStatus,
         },
     },
 };
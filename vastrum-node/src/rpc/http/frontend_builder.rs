@@ -5,51 +5,75 @@ pub struct FrontendAssets;
 
 #[derive(Clone)]
 pub struct Frontend {
+    pub index: Arc<RwLock<IndexHtml>>,
+    pub compressed_assets: Arc<HashMap<String, (Vec<u8>, String)>>,
+}
+
+/// index.html with injected frontend data, rebuilt when a new validator epoch starts
+pub struct IndexHtml {
+    pub validators_epoch: u64,
     pub html: String,
     pub compressed_html: Arc<Vec<u8>>,
-    pub compressed_assets: Arc<HashMap<String, (Vec<u8>, String)>>,
 }
 
 /// Build the frontend HTML (with injected frontend data) and pre-compress all assets with brotli.
-pub async fn build_frontend(rpc_nodes: Vec<RpcNodeEndpoint>, epoch_state: &EpochState) -> Frontend {
-    let (html, compressed_html) = build_index_html(rpc_nodes, epoch_state).await;
+pub async fn build_frontend(rpc_nodes: Vec<RpcNodeEndpoint>, db: &Db) -> Frontend {
+    let index = build_index_html(rpc_nodes, current_epoch_validators(db)).await;
     let compressed_assets = brotli_compress_static_assets();
-    Frontend { html, compressed_html, compressed_assets }
+    Frontend { index: Arc::new(RwLock::new(index)), compressed_assets }
+}
+
+/// Page loads verify proofs against the injected validators, keep them at the current epoch
+pub async fn refresh_index_html_on_epoch_change(
+    frontend: Frontend,
+    rpc_nodes: Vec<RpcNodeEndpoint>,
+    db: Arc<Db>,
+) {
+    loop {
+        tokio::time::sleep(FRONTEND_REFRESH_INTERVAL).await;
+        let validators = current_epoch_validators(&db);
+        let is_current = frontend.index.read().validators_epoch == validators.epoch;
+        if is_current {
+            continue;
+        }
+        let index = build_index_html(rpc_nodes.clone(), validators).await;
+        *frontend.index.write() = index;
+    }
+}
+
+fn current_epoch_validators(db: &Db) -> EpochValidators {
+    let epoch = epoch_for_height(db.read_latest_finalized_height() + 1);
+    let validator_set = db.read_epoch_validator_set(epoch).expect("current epoch validator set");
+    return EpochValidators::from_validator_set(epoch, &validator_set);
 }
 
 async fn build_index_html(
     rpc_nodes: Vec<RpcNodeEndpoint>,
-    epoch_state: &EpochState,
-) -> (String, Arc<Vec<u8>>) {
+    validators: EpochValidators,
+) -> IndexHtml {
     let file = FrontendAssets::get("index.html").unwrap();
     let raw_html = String::from_utf8_lossy(&file.data);
     let helios_checkpoint = fetch_finalized_checkpoint().await;
-    let html = inject_frontend_data(&raw_html, rpc_nodes, helios_checkpoint, epoch_state);
+    let validators_epoch = validators.epoch;
+    let html = inject_frontend_data(&raw_html, rpc_nodes, helios_checkpoint, validators);
     let compressed_html = Arc::new(brotli_compress(html.as_bytes()));
-    (html, compressed_html)
+    IndexHtml { validators_epoch, html, compressed_html }
 }
 
 fn inject_frontend_data(
     html: &str,
     rpc_nodes: Vec<RpcNodeEndpoint>,
     helios_checkpoint: String,
-    epoch_state: &EpochState,
+    validators: EpochValidators,
 ) -> String {
-    let mut genesis_validators = HashMap::new();
-    for v in epoch_state.validator_data.values() {
-        genesis_validators.insert(
-            v.validator_index,
-            ValidatorInfo {
-                validator_index: v.validator_index,
-                pub_key: v.pub_key.to_bytes(),
-                stake: v.stake,
-            },
-        );
-    }
-    let total_validator_stake = epoch_state.total_validator_stake;
-
-    let frontend_data =
-        FrontendData { rpc_nodes, helios_checkpoint, genesis_validators, total_validator_stake };
+    let frontend_data = FrontendData {
+        chain_id: genesis_chain_id(),
+        rpc_nodes,
+        helios_checkpoint,
+        validators_epoch: validators.epoch,
+        validators: validators.validators,
+        total_validator_stake: validators.total_stake,
+    };
     let encoded = serde_json::to_string(&frontend_data).unwrap();
     html.replace("</head>", &format!(r#"<script type="application/json" id="__frontendData">{encoded}</script></head>"#))
 }
@@ -70,8 +94,13 @@ fn brotli_compress_static_assets() -> Arc<HashMap<String, (Vec<u8>, String)>> {
 }
 
 use super::helios_checkpoint::fetch_finalized_checkpoint;
-use crate::consensus::validator_state_machine::EpochState;
+use crate::consensus::validator_state_machine::epoch_for_height;
+use crate::db::Db;
+use crate::utils::limits::FRONTEND_REFRESH_INTERVAL;
+use parking_lot::RwLock;
 use vastrum_shared_types::compression::brotli::brotli_compress;
-use vastrum_shared_types::frontend::frontend_data::{FrontendData, RpcNodeEndpoint, ValidatorInfo};
+use vastrum_shared_types::frontend::frontend_data::{FrontendData, RpcNodeEndpoint};
+use vastrum_shared_types::genesis::genesis_chain_id;
+use vastrum_shared_types::proof_verification::EpochValidators;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -1,28 +1,40 @@
 pub async fn start_http_server(
     db: Arc<Db>,
     networking: Arc<Networking>,
+    mempool: SharedMempool,
+    simulator: Arc<Simulator>,
     rpc_nodes: Vec<RpcNodeEndpoint>,
-    epoch_state: &EpochState,
 ) -> eyre::Result<()> {
     let addr = format!("0.0.0.0:{HTTP_RPC_PORT}");
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    let rpc = RPCHttpServer::new(networking, db);
-    let app = rpc.router(rpc_nodes, epoch_state).await;
+    let rpc = RPCHttpServer::new(networking, db, mempool, simulator);
+    let app = rpc.router(rpc_nodes).await;
 
     axum::serve(listener, app).await?;
     Ok(())
 }
 
 impl RPCHttpServer {
-    async fn router(&self, rpc_nodes: Vec<RpcNodeEndpoint>, epoch_state: &EpochState) -> Router {
+    async fn router(&self, rpc_nodes: Vec<RpcNodeEndpoint>) -> Router {
         let cors = CorsLayer::new()
             .allow_origin(Any)
             .allow_methods(Any)
             .allow_headers(Any);
 
-        let frontend = frontend_builder::build_frontend(rpc_nodes, epoch_state).await;
-        let state = AppState { networking: self.networking.clone(), db: self.db.clone(), frontend };
+        let frontend = frontend_builder::build_frontend(rpc_nodes.clone(), &self.db).await;
+        tokio::spawn(frontend_builder::refresh_index_html_on_epoch_change(
+            frontend.clone(),
+            rpc_nodes,
+            self.db.clone(),
+        ));
+        let state = AppState {
+            networking: self.networking.clone(),
+            db: self.db.clone(),
+            mempool: self.mempool.clone(),
+            simulator: self.simulator.clone(),
+            frontend,
+        };
         return Router::new()
             .route("/submit/", post(RPCHttpServer::submit_transaction))
             .route("/page/", post(RPCHttpServer::get_page))
@@ -30,7 +42,15 @@ impl RPCHttpServer {
             .route("/getkeyvalue/", post(RPCHttpServer::get_key_value))
             .route("/getsiteidisdeployed/", post(RPCHttpServer::get_site_id_is_deployed))
             .route("/gettxhashinclusionstate/", post(RPCHttpServer::get_tx_hash_inclusion_state))
+            .route("/gettxstatus/", post(RPCHttpServer::get_tx_status))
+            .route("/gettxreceipt/", post(RPCHttpServer::get_tx_receipt))
+            .route("/getevents/", post(RPCHttpServer::get_events))
+            .route("/getepochhandoff/", post(RPCHttpServer::get_epoch_handoff))
+            .route("/getpendingtransactions/", post(RPCHttpServer::get_pending_transactions))
+            .route("/simulatecall/", post(RPCHttpServer::simulate_call))
             .route("/resolvedomain/", post(RPCHttpServer::resolve_domain))
+            .route("/getstorageusage/", post(RPCHttpServer::get_storage_usage))
+            .route("/scankeys/", post(RPCHttpServer::scan_keys))
             .route("/ethexecutionrpc", any(RPCHttpServer::eth_execution_rpc))
             .route("/ethexecutionrpc/{*path}", any(RPCHttpServer::eth_execution_rpc))
             .route("/ethconsensusrpc", any(RPCHttpServer::eth_consensus_rpc))
@@ -80,12 +100,60 @@ impl RPCHttpServer {
     ) -> impl IntoResponse {
         Json(handlers::get_tx_hash_inclusion_state(&state.db, input))
     }
+    async fn get_tx_status(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetTxStatusPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_tx_status(&state.db, &state.mempool, input))
+    }
+    async fn get_tx_receipt(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetTxReceiptPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_tx_receipt(&state.db, input))
+    }
+    async fn get_epoch_handoff(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetEpochHandoffPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_epoch_handoff(&state.db, input))
+    }
+    async fn get_events(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetEventsPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_events(&state.db, input))
+    }
+    async fn get_pending_transactions(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetPendingTransactionsPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_pending_transactions(&state.mempool, input))
+    }
+    async fn simulate_call(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<SimulateCallPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::simulate_call(&state.simulator, input).await)
+    }
     async fn resolve_domain(
         State(state): State<AppState>,
         axum::Json(input): axum::Json<ResolveDomainRequest>,
     ) -> impl IntoResponse {
         Json(handlers::resolve_domain(&state.db, input))
     }
+    async fn get_storage_usage(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<GetStorageUsagePayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::get_storage_usage(&state.db, input))
+    }
+    async fn scan_keys(
+        State(state): State<AppState>,
+        axum::Json(input): axum::Json<ScanKeysPayload>,
+    ) -> impl IntoResponse {
+        Json(handlers::scan_keys(&state.db, input))
+    }
     async fn borsh_rpc(
         State(state): State<AppState>,
         body: axum::body::Bytes,
@@ -93,7 +161,8 @@ impl RPCHttpServer {
         let Ok(request) = borsh::from_slice::<RpcRequest>(&body) else {
             return StatusCode::BAD_REQUEST.into_response();
         };
-        let result = route(&request, &state.db, &state.networking).await;
+        let result =
+            route(&request, &state.db, &state.networking, &state.mempool, &state.simulator).await;
         match result {
             Some(rpc_body) => {
                 let response = RpcResponse { id: request.id, body: rpc_body };
@@ -128,8 +197,13 @@ impl RPCHttpServer {
         //if does not match asset serve index.html for all other paths
         serve_index_html(&state, accepts_brotli)
     }
-    pub fn new(networking: Arc<Networking>, db: Arc<Db>) -> RPCHttpServer {
-        return RPCHttpServer { networking, db };
+    pub fn new(
+        networking: Arc<Networking>,
+        db: Arc<Db>,
+        mempool: SharedMempool,
+        simulator: Arc<Simulator>,
+    ) -> RPCHttpServer {
+        return RPCHttpServer { networking, db, mempool, simulator };
     }
 }
 
@@ -141,6 +215,7 @@ fn client_accepts_brotli_compression(headers: &HeaderMap) -> bool {
 }
 
 fn serve_index_html(state: &AppState, accepts_brotli: bool) -> Response {
+    let index = state.frontend.index.read();
     if accepts_brotli {
         (
             [
@@ -148,13 +223,13 @@ fn serve_index_html(state: &AppState, accepts_brotli: bool) -> Response {
                 (header::CONTENT_ENCODING, "br".to_string()),
                 (header::CACHE_CONTROL, "no-store".to_string()),
             ],
-            (*state.frontend.compressed_html).clone(),
+            (*index.compressed_html).clone(),
         )
             .into_response()
     } else {
         (
             [(header::CACHE_CONTROL, "no-store".to_string())],
-            axum::response::Html(state.frontend.html.clone()),
+            axum::response::Html(index.html.clone()),
         )
             .into_response()
     }
@@ -182,18 +257,23 @@ fn serve_static_asset(state: &AppState, path: &str, accepts_brotli: bool) -> Opt
 pub struct RPCHttpServer {
     networking: Arc<Networking>,
     db: Arc<Db>,
+    mempool: SharedMempool,
+    simulator: Arc<Simulator>,
 }
 #[derive(Clone)]
 struct AppState {
     networking: Arc<Networking>,
     db: Arc<Db>,
+    mempool: SharedMempool,
+    simulator: Arc<Simulator>,
     frontend: frontend_builder::Frontend,
 }
 
 use super::frontend_builder;
 use crate::{
-    consensus::validator_state_machine::EpochState,
+    consensus::mempool::SharedMempool,
     db::Db,
+    execution::simulation::Simulator,
     p2p::networking::Networking,
     rpc::{handlers, webrtc_direct::router::route},
 };
@@ -211,7 +291,9 @@ use tower_http::cors::{Any, CorsLayer};
 use vastrum_shared_types::borsh::BorshExt;
 use vastrum_shared_types::frontend::frontend_data::RpcNodeEndpoint;
 use vastrum_shared_types::types::rpc::types::{
-    GetKeyValuePayload, GetPagePayload, GetSiteIDIsDeployed, GetTxHashIsIncluded,
-    ResolveDomainRequest, RpcRequest, RpcResponse, SubmitTransactionPayload,
+    GetEpochHandoffPayload, GetEventsPayload, GetKeyValuePayload, GetPagePayload,
+    GetPendingTransactionsPayload, GetSiteIDIsDeployed, GetStorageUsagePayload,
+    GetTxHashIsIncluded, GetTxReceiptPayload, GetTxStatusPayload, ResolveDomainRequest, RpcRequest,
+    RpcResponse, ScanKeysPayload, SimulateCallPayload, SubmitTransactionPayload,
 };
 use vastrum_shared_types::{limits::MAX_RPC_BODY_SIZE, ports::HTTP_RPC_PORT};
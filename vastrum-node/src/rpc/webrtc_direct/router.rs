@@ -1,6 +1,12 @@
 /// Route a WebRTC RPC request to the appropriate handler.
 /// Returns `Some(body)` to send a response, `None` for fire-and-forget.
-pub async fn route(request: &RpcRequest, db: &Db, networking: &Networking) -> Option<RpcBody> {
+pub async fn route(
+    request: &RpcRequest,
+    db: &Db,
+    networking: &Networking,
+    mempool: &SharedMempool,
+    simulator: &Arc<Simulator>,
+) -> Option<RpcBody> {
     match request.route.as_str() {
         "page" => {
             let Ok(payload) = borsh::from_slice::<GetPagePayload>(&request.body) else {
@@ -41,6 +47,49 @@ pub async fn route(request: &RpcRequest, db: &Db, networking: &Networking) -> Op
             let inclusion = handlers::get_tx_hash_inclusion_state(db, payload);
             return Some(RpcBody::Success(inclusion.encode()));
         }
+        "gettxstatus" => {
+            let Ok(payload) = borsh::from_slice::<GetTxStatusPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let status = handlers::get_tx_status(db, mempool, payload);
+            return Some(RpcBody::Success(status.encode()));
+        }
+        "gettxreceipt" => {
+            let Ok(payload) = borsh::from_slice::<GetTxReceiptPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let receipt = handlers::get_tx_receipt(db, payload);
+            return Some(RpcBody::Success(receipt.encode()));
+        }
+        "getevents" => {
+            let Ok(payload) = borsh::from_slice::<GetEventsPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let events = handlers::get_events(db, payload);
+            return Some(RpcBody::Success(events.encode()));
+        }
+        "getepochhandoff" => {
+            let Ok(payload) = borsh::from_slice::<GetEpochHandoffPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let handoff = handlers::get_epoch_handoff(db, payload);
+            return Some(RpcBody::Success(handoff.encode()));
+        }
+        "getpendingtransactions" => {
+            let Ok(payload) = borsh::from_slice::<GetPendingTransactionsPayload>(&request.body)
+            else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let pending = handlers::get_pending_transactions(mempool, payload);
+            return Some(RpcBody::Success(pending.encode()));
+        }
+        "simulatecall" => {
+            let Ok(payload) = borsh::from_slice::<SimulateCallPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let result = handlers::simulate_call(simulator, payload).await;
+            return Some(RpcBody::Success(result.encode()));
+        }
         "resolvedomain" => {
             let Ok(payload) = borsh::from_slice::<ResolveDomainRequest>(&request.body) else {
                 return Some(RpcBody::Error("invalid payload".into()));
@@ -48,6 +97,20 @@ pub async fn route(request: &RpcRequest, db: &Db, networking: &Networking) -> Op
             let resolved = handlers::resolve_domain(db, payload);
             return Some(RpcBody::Success(resolved.encode()));
         }
+        "getstorageusage" => {
+            let Ok(payload) = borsh::from_slice::<GetStorageUsagePayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let usage = handlers::get_storage_usage(db, payload);
+            return Some(RpcBody::Success(usage.encode()));
+        }
+        "scankeys" => {
+            let Ok(payload) = borsh::from_slice::<ScanKeysPayload>(&request.body) else {
+                return Some(RpcBody::Error("invalid payload".into()));
+            };
+            let keys = handlers::scan_keys(db, payload);
+            return Some(RpcBody::Success(keys.encode()));
+        }
         "ethproxy" => {
             let Ok(payload) = borsh::from_slice::<EthProxyRequest>(&request.body) else {
                 return Some(RpcBody::Error("invalid payload".into()));
@@ -67,11 +130,17 @@ pub async fn route(request: &RpcRequest, db: &Db, networking: &Networking) -> Op
     }
 }
 
-use crate::{db::Db, p2p::networking::Networking, rpc::handlers};
+use crate::{
+    consensus::mempool::SharedMempool, db::Db, execution::simulation::Simulator,
+    p2p::networking::Networking, rpc::handlers,
+};
+use std::sync::Arc;
 use vastrum_shared_types::{
     borsh::BorshExt,
     types::rpc::types::{
-        EthProxyRequest, GetKeyValuePayload, GetPagePayload, GetSiteIDIsDeployed,
-        GetTxHashIsIncluded, ResolveDomainRequest, RpcBody, RpcRequest, SubmitTransactionPayload,
+        EthProxyRequest, GetEpochHandoffPayload, GetEventsPayload, GetKeyValuePayload,
+        GetPagePayload, GetPendingTransactionsPayload, GetSiteIDIsDeployed, GetStorageUsagePayload,
+        GetTxHashIsIncluded, GetTxReceiptPayload, GetTxStatusPayload, ResolveDomainRequest,
+        RpcBody, RpcRequest, ScanKeysPayload, SimulateCallPayload, SubmitTransactionPayload,
     },
 };
@@ -3,6 +3,8 @@ pub struct RpcChannel {
     pub writer: FramedWriter,
     pub db: Arc<Db>,
     pub networking: Arc<Networking>,
+    pub mempool: SharedMempool,
+    pub simulator: Arc<Simulator>,
 }
 impl RpcChannel {
     pub async fn run(mut self) {
@@ -17,8 +19,10 @@ impl RpcChannel {
             let writer = self.writer.clone();
             let db = self.db.clone();
             let networking = self.networking.clone();
+            let mempool = self.mempool.clone();
+            let simulator = self.simulator.clone();
             tokio::spawn(async move {
-                Self::handle_request(request, writer, &db, &networking).await;
+                Self::handle_request(request, writer, &db, &networking, &mempool, &simulator).await;
             });
         }
     }
@@ -28,8 +32,10 @@ impl RpcChannel {
         writer: FramedWriter,
         db: &Db,
         networking: &Networking,
+        mempool: &SharedMempool,
+        simulator: &Arc<Simulator>,
     ) {
-        let Some(body) = router::route(&request, db, networking).await else {
+        let Some(body) = router::route(&request, db, networking, mempool, simulator).await else {
             return;
         };
         let response = RpcResponse { id: request.id, body };
@@ -39,7 +45,10 @@ impl RpcChannel {
 }
 
 use super::router;
-use crate::{db::Db, p2p::networking::Networking};
+use crate::{
+    consensus::mempool::SharedMempool, db::Db, execution::simulation::Simulator,
+    p2p::networking::Networking,
+};
 use vastrum_shared_types::{
     borsh::BorshExt,
     limits::MAX_RPC_BODY_SIZE,
@@ -1,6 +1,8 @@
 pub async fn start_webrtc_server(
     db: Arc<Db>,
     networking: Arc<Networking>,
+    mempool: SharedMempool,
+    simulator: Arc<Simulator>,
     dtls_key: DtlsKey,
 ) -> eyre::Result<()> {
     let listen_addr: std::net::SocketAddr = format!("0.0.0.0:{WEBRTC_PORT}").parse().unwrap();
@@ -10,8 +12,14 @@ pub async fn start_webrtc_server(
     tokio::spawn(async move {
         while let Some((conn, _addr)) = server.accept().await {
             let (reader, writer) = conn.split();
-            let channel =
-                RpcChannel { reader, writer, db: db.clone(), networking: networking.clone() };
+            let channel = RpcChannel {
+                reader,
+                writer,
+                db: db.clone(),
+                networking: networking.clone(),
+                mempool: mempool.clone(),
+                simulator: simulator.clone(),
+            };
             tokio::spawn(channel.run());
         }
     });
@@ -20,7 +28,10 @@ pub async fn start_webrtc_server(
 }
 
 use super::rpc_channel::RpcChannel;
-use crate::{db::Db, p2p::networking::Networking};
+use crate::{
+    consensus::mempool::SharedMempool, db::Db, execution::simulation::Simulator,
+    p2p::networking::Networking,
+};
 use vastrum_shared_types::ports::WEBRTC_PORT;
 use std::sync::Arc;
 use vastrum_webrtc_direct_server::{DtlsKey, WebRtcServer};
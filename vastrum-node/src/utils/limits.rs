@@ -1,14 +1,31 @@
 use std::time::Duration;
+use vastrum_shared_types::limits::MAX_RPC_BODY_SIZE;
 
 pub const MAX_PROXY_BODY_SIZE: usize = 10 * 1024 * 1024; // 10MB
 
 pub const MAX_MEMPOOL_SIZE: usize = 100 * 1024 * 1024; // 100MB
+pub const MAX_MEMPOOL_TXS_PER_SENDER: usize = 64;
+//extra pow bits a full mempool requires of new transactions, scaled linearly from half full
+pub const MEMPOOL_ADMISSION_BITS: u32 = 8;
+pub const MAX_PENDING_TXS_PAGE: u64 = 1000;
+//each event block in a response carries its own merkle proof
+pub const MAX_EVENT_BLOCKS_PAGE: usize = 100;
+//borsh size a page of event blocks stops at, well under MAX_RPC_BODY_SIZE as json encodes bytes larger
+pub const MAX_EVENTS_PAGE_BYTES: usize = MAX_RPC_BODY_SIZE / 4;
+//simulated calls are unauthenticated, kept to a small multiple of DEFAULT_TX_FUEL_LIMIT
+pub const SIMULATION_FUEL_LIMIT: u64 = 20_000_000;
+//simulations run on the blocking pool, calls over this many at once are rejected as busy
+pub const MAX_CONCURRENT_SIMULATIONS: usize = 4;
+//served frontend data is rebuilt with the validators of a new epoch
+pub const FRONTEND_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
 
 pub const MAX_ROUND_LOOKAHEAD: u64 = 100;
 pub const MAX_SLOT_LOOKAHEAD: u64 = 100;
 
+//initial round timeout, adapts between min and max based on skipped rounds and observed latency
 pub const ROUND_TIMEOUT: Duration = Duration::from_secs(3);
-pub const LONG_ROUND_TIMEOUT: Duration = Duration::from_secs(12);
+pub const MIN_ROUND_TIMEOUT: Duration = Duration::from_secs(2);
+pub const MAX_ROUND_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub const MAX_FRAME_SIZE: usize = 5 * 1024 * 1024; // 5MB
 pub const MAX_INBOUND_VALIDATORS: usize = 10_000;
@@ -16,3 +33,12 @@ pub const MAX_OUTBOUND_VALIDATORS: usize = 10_000;
 pub const MAX_INBOUND_NORMAL: usize = 20;
 pub const MAX_OUTBOUND_NORMAL: usize = 20;
 pub const MAX_PEER_RECORDS: usize = 100;
+
+//transactions are announced by hash in batches, peers pull only the bodies they lack
+pub const TX_ANNOUNCE_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_TX_ANNOUNCEMENT_HASHES: usize = 4096;
+pub const MAX_KNOWN_TXS_PER_PEER: usize = 65_536;
+pub const MAX_GOSSIP_CACHE_SIZE: usize = 64 * 1024 * 1024; // 64MB
+
+//failure messages are committed to state in tx receipts
+pub const MAX_RECEIPT_MESSAGE_LEN: usize = 512;
@@ -8,7 +8,7 @@ const PACKET_LOSS_10_PERCENT: f64 = 0.10;
 const NETWORK_LATENCY_80_TO_250_MS: Range<Duration> = ms(80)..ms(250);
 const PACKET_LOSS_HALF_PERCENT: f64 = 0.005;
 
-//latency above round timeout (3s), proposals will always arrive too late and nodes will always skip
+//latency above initial round timeout (3s), proposals arrive too late until round timeout backs off
 const NETWORK_LATENCY_ABOVE_ROUND_TIMEOUT: Range<Duration> = ms(4000)..ms(6000);
 
 #[madsim::test]
@@ -23,6 +23,19 @@ async fn five_nodes_reach_consensus() {
     assert_chain_consistency(&[node_1, node_2, node_3, node_4, node_5]);
 }
 
+#[madsim::test]
+async fn network_stays_live_with_latency_above_initial_round_timeout() {
+    let [node_1, node_2, node_3, node_4, node_5] = start_madsim_localnet();
+    set_network_conditions(NETWORK_LATENCY_ABOVE_ROUND_TIMEOUT, 0.0);
+
+    //round timeout should back off until rounds can finalize, then stay above observed latency
+    tokio::time::sleep(Duration::from_secs(900)).await;
+
+    assert!(node_1.latest_finalized_height() >= 20, "network didn't adapt round timeout");
+
+    assert_chain_consistency(&[node_1, node_2, node_3, node_4, node_5]);
+}
+
 #[madsim::test]
 async fn three_live_two_offline_network_does_not_reach_consensus() {
     let [node_1, node_2, _node_3, _node_4, node_5] = start_madsim_localnet();
@@ -175,6 +188,29 @@ async fn block_production_rate_happy_path_network() {
     assert_chain_consistency(&[node_1, node_2, node_4, node_5]);
 }
 
+#[madsim::test]
+async fn pipelined_blocks_commit_to_delayed_state_root() {
+    let [node_1, node_2, node_3, node_4, node_5] = start_madsim_localnet();
+    set_network_conditions(NETWORK_LATENCY_80_TO_250_MS, 0.0);
+
+    tokio::time::sleep(Duration::from_secs(120)).await;
+
+    //next height leader proposes on the justified block, so without faults every height
+    //finalizes in round 0 with the root executed STATE_ROOT_DELAY blocks earlier
+    let latest_height = node_1.latest_finalized_height();
+    assert!(latest_height > KV_RETENTION_WINDOW, "network didn't reach consensus");
+    let retained_versions = latest_height - KV_RETENTION_WINDOW + STATE_ROOT_DELAY..=latest_height;
+    for height in retained_versions {
+        let finalized = node_1.db.read_block(height).unwrap();
+        assert_eq!(finalized.round, 0, "height {height} needed a skip round");
+        let committed_root = node_1.db.read_state_root_at(height - STATE_ROOT_DELAY);
+        let block_root = finalized.block.previous_block_state_root;
+        assert_eq!(Some(block_root), committed_root, "height {height}");
+    }
+
+    assert_chain_consistency(&[node_1, node_2, node_3, node_4, node_5]);
+}
+
 #[madsim::test]
 async fn block_production_rate_degraded_network() {
     let [node_1, node_2, node_3, node_4, node_5] = start_madsim_localnet();
@@ -306,9 +342,11 @@ async fn write_ahead_log_vote_recovery_after_all_nodes_crash_works() {
 //using disk written consensus round certs
 #[madsim::test]
 async fn mid_round_cert_recovery_after_all_nodes_crash_mid_round_works() {
-    let [node_1, node_2, node_3, node_4, node_5] = start_madsim_localnet();
+    let fixed_timeout = RoundTimeoutConfig::fixed(Duration::from_secs(3));
+    let [node_1, node_2, node_3, node_4, node_5] =
+        start_madsim_localnet_with_config([ByzantineMode::Honest; 5], fixed_timeout, [None; 5]);
 
-    //latency > round timeout (3s) so proposals always arrive too late
+    //latency > fixed round timeout (3s) so proposals always arrive too late
     //nodes skip every round, no block is ever justified or finalized
     set_network_conditions(NETWORK_LATENCY_ABOVE_ROUND_TIMEOUT, 0.0);
 
@@ -355,6 +393,110 @@ async fn mid_round_cert_recovery_after_all_nodes_crash_mid_round_works() {
     assert_chain_consistency(&[node_1, node_2, node_3, node_4, node_5]);
 }
 
+#[madsim::test]
+async fn leader_proposing_conflicting_blocks_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::ConflictingProposals);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+    assert_evidence_included(&honest_nodes[0]);
+}
+
+#[madsim::test]
+async fn double_voting_validator_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::DoubleVote);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+    assert_evidence_included(&honest_nodes[0]);
+}
+
+#[madsim::test]
+async fn validator_voting_for_unseen_blocks_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::VoteForUnseenBlocks);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    //remaining 4 honest nodes have 400/500 stake and should still progress
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+}
+
+#[madsim::test]
+async fn node_withholding_certificates_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::WithholdCertificates);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+}
+
+#[madsim::test]
+async fn node_replaying_old_proposals_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::ReplayOldProposals);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+}
+
+#[madsim::test]
+async fn byzantine_node_during_partitions_does_not_break_safety() {
+    let nodes = start_madsim_localnet_with_byzantine_node(ByzantineMode::DoubleVote);
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+    let honest_nodes = &nodes[..4];
+
+    //partition one honest node at a time, leaving the byzantine node as deciding vote
+    for i in 0..4 {
+        honest_nodes[i].block_all_network_comms();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_honest_nodes_never_conflict(honest_nodes);
+        honest_nodes[i].unblock_all_network_comms();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    }
+
+    assert_node_is_finalizing_blocks(&honest_nodes[0]).await;
+
+    assert_honest_nodes_never_conflict(honest_nodes);
+    assert_chain_consistency(honest_nodes);
+}
+
+#[madsim::test]
+async fn node_on_other_chain_is_isolated_from_network() {
+    let mut chain_ids = [None; 5];
+    chain_ids[4] = Some(genesis_chain_id() + 1);
+    let [node_1, node_2, node_3, node_4, node_5] = start_madsim_localnet_with_config(
+        [ByzantineMode::Honest; 5],
+        RoundTimeoutConfig::default(),
+        chain_ids,
+    );
+    set_network_conditions(NETWORK_LATENCY_200_TO_500_MS, PACKET_LOSS_10_PERCENT);
+
+    //remaining 4 nodes have 400/500 stake and should still progress
+    assert_node_is_finalizing_blocks(&node_1).await;
+
+    //handshake with node 5 is rejected, it never receives or counts any votes
+    assert_eq!(node_5.latest_finalized_height(), 0, "node on other chain finalized blocks");
+
+    assert_chain_consistency(&[node_1, node_2, node_3, node_4]);
+}
+
 #[derive(Clone)]
 struct SimNode {
     db: Arc<Db>,
@@ -382,10 +524,7 @@ impl SimNode {
         self.db.read_latest_finalized_height()
     }
 
-    fn block_hash_at(
-        &self,
-        height: u64,
-    ) -> Option<vastrum_shared_types::crypto::sha256::Sha256Digest> {
+    fn block_hash_at(&self, height: u64) -> Option<Sha256Digest> {
         self.db.read_block(height).map(|fc| fc.block.calculate_hash())
     }
 
@@ -394,6 +533,25 @@ impl SimNode {
     }
 }
 fn start_madsim_localnet<const AMOUNT_OF_NODES: usize>() -> [SimNode; AMOUNT_OF_NODES] {
+    start_madsim_localnet_with_config(
+        [ByzantineMode::Honest; AMOUNT_OF_NODES],
+        RoundTimeoutConfig::default(),
+        [None; AMOUNT_OF_NODES],
+    )
+}
+
+//last node runs the byzantine mode, the first 4 are honest
+fn start_madsim_localnet_with_byzantine_node(mode: ByzantineMode) -> [SimNode; 5] {
+    let mut modes = [ByzantineMode::Honest; 5];
+    modes[4] = mode;
+    start_madsim_localnet_with_config(modes, RoundTimeoutConfig::default(), [None; 5])
+}
+
+fn start_madsim_localnet_with_config<const AMOUNT_OF_NODES: usize>(
+    modes: [ByzantineMode; AMOUNT_OF_NODES],
+    round_timeout: RoundTimeoutConfig,
+    chain_id_overrides: [Option<u64>; AMOUNT_OF_NODES],
+) -> [SimNode; AMOUNT_OF_NODES] {
     assert!(AMOUNT_OF_NODES == 5);
     let handle = Handle::current();
     let (testnet, epoch_state) = generate_sim_test_network(AMOUNT_OF_NODES as u64);
@@ -404,6 +562,8 @@ fn start_madsim_localnet<const AMOUNT_OF_NODES: usize>() -> [SimNode; AMOUNT_OF_
         let ip = test_node.endpoint.ip();
         let test_node = test_node.clone();
         let epoch_state = epoch_state.clone();
+        let byzantine_mode = modes[i];
+        let chain_id = chain_id_overrides[i].unwrap_or(genesis_chain_id());
 
         let db = Arc::new(Db::new());
         let db_for_test = db.clone();
@@ -423,6 +583,9 @@ fn start_madsim_localnet<const AMOUNT_OF_NODES: usize>() -> [SimNode; AMOUNT_OF_
                         run_rpc_node: false,
                         genesis_epoch_state: epoch_state,
                         rpc_nodes: vec![],
+                        byzantine_mode,
+                        round_timeout,
+                        chain_id,
                     };
                     ValidatorStateMachine::start_node(db, config).await;
                 }
@@ -469,6 +632,51 @@ fn assert_chain_consistency(nodes: &[SimNode]) {
     }
 }
 
+//safety: no two honest nodes may ever finalize different blocks at the same height
+//unlike assert_chain_consistency this does not require nodes to have progressed
+fn assert_honest_nodes_never_conflict(honest_nodes: &[SimNode]) {
+    let max_height = honest_nodes.iter().map(|n| n.latest_finalized_height()).max().unwrap();
+    for height in 1..=max_height {
+        let mut finalized: Option<(usize, Sha256Digest)> = None;
+        for (i, node) in honest_nodes.iter().enumerate() {
+            let Some(hash) = node.block_hash_at(height) else {
+                continue;
+            };
+            match finalized {
+                None => finalized = Some((i, hash)),
+                Some((first_node, first_hash)) => assert_eq!(
+                    first_hash,
+                    hash,
+                    "safety violation at height {}: node {} finalized {:?}, node {} finalized {:?}",
+                    height,
+                    first_node + 1,
+                    first_hash,
+                    i + 1,
+                    hash
+                ),
+            }
+        }
+    }
+}
+
+fn assert_evidence_included(node: &SimNode) {
+    for height in 1..=node.latest_finalized_height() {
+        let block = node.db.read_block(height).unwrap();
+        for tx in &block.block.transactions {
+            let Ok(calldata) = decompress_calldata(&tx.calldata) else {
+                continue;
+            };
+            let Ok(tx_data) = TransactionData::decode(&calldata) else {
+                continue;
+            };
+            if tx_data.transaction_type == TransactionType::Evidence {
+                return;
+            }
+        }
+    }
+    panic!("no equivocation evidence was included in a finalized block");
+}
+
 async fn assert_block_production_rate(node: &SimNode, duration_secs: u64, min_blocks_per_sec: f64) {
     let height_before = node.latest_finalized_height();
     tokio::time::sleep(Duration::from_secs(duration_secs)).await;
@@ -558,9 +766,17 @@ use std::net::{Ipv4Addr, SocketAddr};
 use std::ops::Range;
 use std::sync::Arc;
 use std::time::Duration;
+use vastrum_node::consensus::byzantine::ByzantineMode;
+use vastrum_node::consensus::round_timer::RoundTimeoutConfig;
 use vastrum_node::consensus::validator_state_machine::{
     EpochState, NodeConfig, ValidatorStateMachine,
 };
 use vastrum_node::db::Db;
 use vastrum_node::keystore::keyset::Keystore;
 use vastrum_node::p2p::peer_manager::KnownPeer;
+use vastrum_shared_types::borsh::BorshExt;
+use vastrum_shared_types::crypto::sha256::Sha256Digest;
+use vastrum_shared_types::genesis::genesis_chain_id;
+use vastrum_shared_types::limits::{KV_RETENTION_WINDOW, STATE_ROOT_DELAY};
+use vastrum_shared_types::transactioning::compression::decompress_calldata;
+use vastrum_shared_types::types::application::transactiondata::{TransactionData, TransactionType};
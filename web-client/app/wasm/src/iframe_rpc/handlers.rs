@@ -51,6 +51,36 @@ pub async fn get_tx_hash_is_included(
     return Ok(GetTXHashIsConfirmedResponse { is_finalized });
 }
 
+pub async fn handle_get_tx_status(params: GetTxStatusRequest) -> Result<GetTxStatusResponse> {
+    let status = get_tx_status(params.tx_hash).await?;
+    return Ok(GetTxStatusResponse { status });
+}
+
+pub async fn handle_get_tx_receipt(params: GetTxReceiptRequest) -> Result<GetTxReceiptResponse> {
+    let receipt = get_tx_receipt(params.tx_hash).await?;
+    return Ok(GetTxReceiptResponse { receipt });
+}
+
+//simulated as the site account, the same sender make_authenticated_call signs with
+pub async fn handle_simulate_call(params: SimulateCallRequest) -> Result<SimulateCallResponse> {
+    let site_id = get_current_site_id()?;
+    let message_sender = keystore::get_site_private_key()?.public_key();
+    return simulate_call(site_id, params.call_data, message_sender).await;
+}
+
+pub async fn handle_get_events(params: GetEventsRequest) -> Result<GetEventsResponse> {
+    let site_id = get_current_site_id()?;
+    let events = get_events(site_id, params.topic, params.from_height, params.to_height).await?;
+    return Ok(GetEventsResponse { events });
+}
+
+pub async fn handle_scan_keys(params: ScanKeysRequest) -> Result<ScanKeysResponse> {
+    let site_id = get_current_site_id()?;
+    let keys =
+        scan_keys(site_id, params.prefix, params.start, params.limit, params.reverse).await?;
+    return Ok(ScanKeysResponse { keys });
+}
+
 pub async fn handle_eth_rpc_request(params: GetEthRPCRequest) -> GetEthRPCResponse {
     let res = send_eth_rpc_to_worker(params.request).await;
     let eth_rpc_response = EthRPCResponse { value_json: res };
@@ -74,9 +104,14 @@ pub async fn update_current_path(params: UpdateCurrentPath) -> Result<UpdateCurr
 
 use crate::crypto::keystore;
 use crate::helios::worker::send_eth_rpc_to_worker;
+use crate::networking::rpc::get_events;
 use crate::networking::rpc::get_key_value_with_height;
 use crate::networking::rpc::get_latest_block_height;
 use crate::networking::rpc::get_tx_hash_inclusion_state;
+use crate::networking::rpc::get_tx_receipt;
+use crate::networking::rpc::get_tx_status;
+use crate::networking::rpc::scan_keys;
+use crate::networking::rpc::simulate_call;
 use crate::networking::rpc::submit_authenticated_call;
 use crate::networking::rpc::submit_call;
 use crate::utils::error::Result;
@@ -84,4 +119,5 @@ use crate::utils::site_id::get_current_site_id;
 use vastrum_shared_types::borsh::BorshExt;
 use vastrum_shared_types::crypto::sha256::sha256_hash;
 use vastrum_shared_types::iframerpc::types::*;
+use vastrum_shared_types::types::rpc::types::SimulateCallResponse;
 use wasm_bindgen::JsValue;
@@ -126,6 +126,31 @@ async fn handle_request_methods(request: &RpcRequest) -> Result<String> {
             let res = handlers::get_site_private_key(params).await?;
             Ok(serde_json::to_string(&res).unwrap())
         }
+        RpcMethod::GetTxStatus => {
+            let params = serde_json::from_str(&request.params)?;
+            let res = handlers::handle_get_tx_status(params).await?;
+            Ok(serde_json::to_string(&res).unwrap())
+        }
+        RpcMethod::GetTxReceipt => {
+            let params = serde_json::from_str(&request.params)?;
+            let res = handlers::handle_get_tx_receipt(params).await?;
+            Ok(serde_json::to_string(&res).unwrap())
+        }
+        RpcMethod::SimulateCall => {
+            let params = serde_json::from_str(&request.params)?;
+            let res = handlers::handle_simulate_call(params).await?;
+            Ok(serde_json::to_string(&res).unwrap())
+        }
+        RpcMethod::GetEvents => {
+            let params = serde_json::from_str(&request.params)?;
+            let res = handlers::handle_get_events(params).await?;
+            Ok(serde_json::to_string(&res).unwrap())
+        }
+        RpcMethod::ScanKeys => {
+            let params = serde_json::from_str(&request.params)?;
+            let res = handlers::handle_scan_keys(params).await?;
+            Ok(serde_json::to_string(&res).unwrap())
+        }
         RpcMethod::OpenExternalUrl => {
             let params: OpenExternalUrlRequest = serde_json::from_str(&request.params)?;
             if !params.url.starts_with("https://") {
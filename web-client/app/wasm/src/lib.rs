@@ -6,7 +6,7 @@ pub async fn main() -> Result<(), String> {
 
     Ok(())
 }
-/// Read __frontendData injected by the node into the frontend, contains rpc endpoints and genesis epoch state
+/// Read __frontendData injected by the node into the frontend, contains rpc endpoints and current validators
 pub fn read_frontend_data() -> vastrum_shared_types::frontend::frontend_data::FrontendData {
     let document = web_sys::window().unwrap().document().unwrap();
     let element = document.get_element_by_id("__frontendData").unwrap();
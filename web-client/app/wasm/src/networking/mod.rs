@@ -1,3 +1,4 @@
 pub mod connection;
 pub mod rpc;
 mod transport;
+mod validator_sets;
@@ -16,12 +16,14 @@ pub async fn get_key_value_with_height(
     };
 
     let data = read_frontend_data();
+    let validators = epoch_validators(response.state_proof.block_header.height).await?;
     proof_verification::verify_keyvalue_proof(
         &response,
         site_id,
         &key,
-        &data.genesis_validators,
-        data.total_validator_stake,
+        data.chain_id,
+        &validators.validators,
+        validators.total_stake,
         (js_sys::Date::now() / 1000.0) as u64,
     )?;
 
@@ -30,14 +32,21 @@ pub async fn get_key_value_with_height(
 
 pub async fn submit_call(site_id: Sha256Digest, call_data: Vec<u8>) -> Result<Sha256Digest> {
     let private_key = generate_private_key();
-    let recent_block_height = get_latest_block_height().await?;
+    let latest = get_latest_block().await?;
+
+    let nonce = mine_pow_nonce_yielding(&private_key, latest.height, latest.pow_difficulty).await?;
 
     let transaction = build_call_transaction(
         site_id,
         call_data,
-        get_random_u64(),
         private_key,
-        recent_block_height,
+        TxEnvelope {
+            nonce,
+            recent_block_height: latest.height,
+            chain_id: read_frontend_data().chain_id,
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
     );
     let payload = SubmitTransactionPayload { transaction_bytes: transaction.encode() };
     send_fire_and_forget("submit", &payload.encode()).await?;
@@ -50,14 +59,22 @@ pub async fn submit_authenticated_call(
     call_data: Vec<u8>,
     account_private_key: ed25519::PrivateKey,
 ) -> Result<Sha256Digest> {
-    let recent_block_height = get_latest_block_height().await?;
+    let latest = get_latest_block().await?;
+
+    let nonce =
+        mine_pow_nonce_yielding(&account_private_key, latest.height, latest.pow_difficulty).await?;
 
     let transaction = build_call_transaction(
         site_id,
         call_data,
-        get_random_u64(),
         account_private_key,
-        recent_block_height,
+        TxEnvelope {
+            nonce,
+            recent_block_height: latest.height,
+            chain_id: read_frontend_data().chain_id,
+            pow_difficulty: latest.pow_difficulty,
+            fuel_limit: DEFAULT_TX_FUEL_LIMIT,
+        },
     );
     let payload = SubmitTransactionPayload { transaction_bytes: transaction.encode() };
     send_fire_and_forget("submit", &payload.encode()).await?;
@@ -65,10 +82,41 @@ pub async fn submit_authenticated_call(
     return Ok(tx_hash);
 }
 
+//mining at MAX_POW_DIFFICULTY takes minutes, so hand control back to the browser between batches
+const POW_ATTEMPTS_PER_YIELD: u64 = 4096;
+
+async fn mine_pow_nonce_yielding(
+    private_key: &ed25519::PrivateKey,
+    recent_block_height: u64,
+    pow_difficulty: u8,
+) -> Result<u64> {
+    let mut miner = PowMiner::new(
+        private_key.public_key(),
+        recent_block_height,
+        get_random_u64(),
+        pow_difficulty,
+    );
+    loop {
+        match miner.step(POW_ATTEMPTS_PER_YIELD) {
+            PowStep::Found(nonce) => return Ok(nonce),
+            PowStep::Pending => TimeoutFuture::new(0).await,
+            PowStep::Exhausted => return Err(WasmErr::PowExhausted),
+        }
+    }
+}
+
 pub async fn get_latest_block_height() -> Result<u64> {
+    return Ok(get_latest_block().await?.height);
+}
+
+async fn get_latest_block() -> Result<GetLatestBlockHeightResponse> {
     let resp = send_request("getlatestblockheight", &[]).await?;
     let response: GetLatestBlockHeightResponse = borsh::from_slice(&resp)?;
-    return Ok(response.height);
+    let expected = read_frontend_data().chain_id;
+    if response.chain_id != expected {
+        return Err(WasmErr::WrongNetwork { expected, got: response.chain_id });
+    }
+    return Ok(response);
 }
 
 pub async fn get_tx_hash_inclusion_state(tx_hash: Sha256Digest) -> Result<bool> {
@@ -78,6 +126,109 @@ pub async fn get_tx_hash_inclusion_state(tx_hash: Sha256Digest) -> Result<bool>
     return Ok(res.included);
 }
 
+pub async fn get_tx_status(tx_hash: Sha256Digest) -> Result<TxStatus> {
+    let payload = GetTxStatusPayload { tx_hash };
+    let resp = send_request("gettxstatus", &payload.encode()).await?;
+    let res: GetTxStatusResponse = borsh::from_slice(&resp)?;
+    return Ok(res.status);
+}
+
+pub async fn get_tx_receipt(tx_hash: Sha256Digest) -> Result<TxReceipt> {
+    let payload = GetTxReceiptPayload { tx_hash };
+    let resp = send_request("gettxreceipt", &payload.encode()).await?;
+    let result: GetTxReceiptResult = borsh::from_slice(&resp)?;
+    let response = match result {
+        GetTxReceiptResult::Ok(r) => *r,
+        GetTxReceiptResult::Err(e) => return Err(WasmErr::RpcError(format!("{e:?}"))),
+    };
+    let validators = epoch_validators(response.state_proof.block_header.height).await?;
+    proof_verification::verify_receipt_proof(
+        &response,
+        tx_hash,
+        read_frontend_data().chain_id,
+        &validators.validators,
+        validators.total_stake,
+        (js_sys::Date::now() / 1000.0) as u64,
+    )?;
+    return Ok(response.receipt);
+}
+
+pub async fn simulate_call(
+    site_id: Sha256Digest,
+    calldata: Vec<u8>,
+    message_sender: ed25519::PublicKey,
+) -> Result<SimulateCallResponse> {
+    let payload = SimulateCallPayload {
+        site_id,
+        calldata,
+        message_sender: Some(message_sender),
+        height_lock: None,
+    };
+    let resp = send_request("simulatecall", &payload.encode()).await?;
+    let result: SimulateCallResult = borsh::from_slice(&resp)?;
+    match result {
+        SimulateCallResult::Ok(response) => Ok(response),
+        SimulateCallResult::Err(e) => Err(WasmErr::RpcError(format!("{e:?}"))),
+    }
+}
+
+//pages come newest first, each continuing below the previous one
+pub async fn get_events(
+    site_id: Sha256Digest,
+    topic: Sha256Digest,
+    from_height: u64,
+    to_height: u64,
+) -> Result<Vec<BlockEvent>> {
+    let data = read_frontend_data();
+    let mut pages = Vec::new();
+    let mut page_to_height = Some(to_height);
+    while let Some(to_height) = page_to_height {
+        let payload = GetEventsPayload { site_id, topic, from_height, to_height };
+        let resp = send_request("getevents", &payload.encode()).await?;
+        let result: GetEventsResult = borsh::from_slice(&resp)?;
+        let response = match result {
+            GetEventsResult::Ok(r) => *r,
+            GetEventsResult::Err(e) => return Err(WasmErr::RpcError(format!("{e:?}"))),
+        };
+        let validators = epoch_validators(response.state_proof.block_header.height).await?;
+        page_to_height = proof_verification::verify_events_proof(
+            &response,
+            &payload,
+            data.chain_id,
+            &validators.validators,
+            validators.total_stake,
+            (js_sys::Date::now() / 1000.0) as u64,
+        )?;
+        pages.push(response.into_events());
+    }
+    return Ok(pages.into_iter().rev().flatten().collect());
+}
+
+//the key list is not proven, callers read values through get_key_value_with_height
+pub async fn scan_keys(
+    site_id: Sha256Digest,
+    prefix: String,
+    start: Option<String>,
+    limit: u32,
+    reverse: bool,
+) -> Result<Vec<String>> {
+    let payload = ScanKeysPayload { site_id, prefix, start, limit, reverse };
+    let resp = send_request("scankeys", &payload.encode()).await?;
+    let response: ScanKeysResponse = borsh::from_slice(&resp)?;
+    return Ok(response.keys);
+}
+
+/// Validator set of an epoch, verified by the caller against the previous epoch's validators
+pub async fn get_epoch_handoff(epoch: u64) -> Result<EpochHandoff> {
+    let payload = GetEpochHandoffPayload { epoch };
+    let resp = send_request("getepochhandoff", &payload.encode()).await?;
+    let result: GetEpochHandoffResult = borsh::from_slice(&resp)?;
+    match result {
+        GetEpochHandoffResult::Ok(handoff) => Ok(*handoff),
+        GetEpochHandoffResult::Err(e) => Err(WasmErr::RpcError(format!("{e:?}"))),
+    }
+}
+
 pub async fn eth_proxy(url: String, method: String, body: Vec<u8>) -> Result<EthProxyResponse> {
     let payload = EthProxyRequest { url, method, body };
     let resp = send_request("ethproxy", &payload.encode()).await?;
@@ -108,10 +259,12 @@ pub async fn get_page(page_path: String, site_identifier: String) -> Result<JSPa
     };
 
     let data = read_frontend_data();
+    let validators = epoch_validators(response.state_proof.block_header.height).await?;
     proof_verification::verify_page_proof(
         &response,
-        &data.genesis_validators,
-        data.total_validator_stake,
+        data.chain_id,
+        &validators.validators,
+        validators.total_stake,
         (js_sys::Date::now() / 1000.0) as u64,
     )?;
 
@@ -149,20 +302,32 @@ use crate::{
         site_id::set_current_site_id,
     },
 };
+use gloo_timers::future::TimeoutFuture;
 use serde::{Deserialize, Serialize};
 use vastrum_shared_types::proof_verification;
 use vastrum_shared_types::{
     borsh::BorshExt,
     compression::brotli::brotli_decompress_html,
     crypto::{ed25519, sha256::Sha256Digest},
+    limits::DEFAULT_TX_FUEL_LIMIT,
     ports::HTTP_RPC_PORT,
-    transactioning::transaction_generator::build_call_transaction,
+    transactioning::{
+        pow::{PowMiner, PowStep},
+        transaction_generator::{TxEnvelope, build_call_transaction},
+    },
+    types::execution::event::BlockEvent,
+    types::execution::receipt::TxReceipt,
     types::rpc::types::{
-        EthProxyRequest, EthProxyResponse, GetKeyValuePayload, GetKeyValueResponse,
-        GetKeyValueResult, GetLatestBlockHeightResponse, GetPagePayload, GetPageResult,
-        GetTxHashIsIncluded, GetTxHashIsIncludedResponse, SubmitTransactionPayload,
+        EpochHandoff, EthProxyRequest, EthProxyResponse, GetEpochHandoffPayload,
+        GetEpochHandoffResult, GetEventsPayload, GetEventsResult, GetKeyValuePayload,
+        GetKeyValueResponse, GetKeyValueResult, GetLatestBlockHeightResponse, GetPagePayload,
+        GetPageResult, GetTxHashIsIncluded, GetTxHashIsIncludedResponse, GetTxReceiptPayload,
+        GetTxReceiptResult, GetTxStatusPayload, GetTxStatusResponse, ScanKeysPayload,
+        ScanKeysResponse, SimulateCallPayload, SimulateCallResponse, SimulateCallResult,
+        SubmitTransactionPayload, TxStatus,
     },
 };
 use tsify::Tsify;
 
 use super::connection::start_webrtc_connection;
+use super::validator_sets::epoch_validators;
@@ -0,0 +1,47 @@
+thread_local! {
+    //starts at the validators in frontend data, extended through verified epoch handoffs
+    static VALIDATOR_SETS: RefCell<Option<KnownValidatorSets>> = const { RefCell::new(None) };
+}
+
+/// Validators of the epoch block_height is in, walking epoch handoffs from the latest known epoch
+pub async fn epoch_validators(block_height: u64) -> Result<EpochValidators> {
+    let epoch = epoch_for_height(block_height);
+    loop {
+        let next_missing = with_validator_sets(|sets| sets.next_missing_epoch(epoch))?;
+        let Some(next_epoch) = next_missing else {
+            return Ok(with_validator_sets(|sets| sets.get(epoch).unwrap().clone()));
+        };
+        let handoff = get_epoch_handoff(next_epoch).await?;
+        let chain_id = read_frontend_data().chain_id;
+        with_validator_sets(|sets| {
+            //a concurrent call may have added it while this one was fetching
+            if sets.latest_epoch() >= handoff.epoch {
+                return Ok(());
+            }
+            return sets.add_handoff(&handoff, chain_id);
+        })?;
+    }
+}
+
+fn with_validator_sets<T>(f: impl FnOnce(&mut KnownValidatorSets) -> T) -> T {
+    VALIDATOR_SETS.with(|sets| {
+        let mut sets = sets.borrow_mut();
+        return f(sets.get_or_insert_with(frontend_validator_sets));
+    })
+}
+
+fn frontend_validator_sets() -> KnownValidatorSets {
+    let data = read_frontend_data();
+    return KnownValidatorSets::new(EpochValidators {
+        epoch: data.validators_epoch,
+        validators: data.validators,
+        total_stake: data.total_validator_stake,
+    });
+}
+
+use super::rpc::get_epoch_handoff;
+use crate::{read_frontend_data, utils::error::Result};
+use std::cell::RefCell;
+use vastrum_shared_types::proof_verification::{
+    EpochValidators, KnownValidatorSets, epoch_for_height,
+};
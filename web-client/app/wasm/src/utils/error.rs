@@ -38,6 +38,12 @@ pub enum WasmErr {
     #[error("payload too large")]
     PayloadTooLarge,
 
+    #[error("no pow nonce found within attempt budget")]
+    PowExhausted,
+
+    #[error("RPC node is on chain {got}, expected chain {expected}")]
+    WrongNetwork { expected: u64, got: u64 },
+
     #[error(transparent)]
     ProofVerification(#[from] vastrum_shared_types::proof_verification::ProofVerificationError),
 